@@ -37,6 +37,8 @@ use tempfile::TempDir;
 mod format;
 pub use format::{format_files, format_src, FormatMode};
 
+mod test_report;
+
 pub const CMD_BUILD: &str = "build";
 pub const CMD_RUN: &str = "run";
 pub const CMD_DEV: &str = "dev";
@@ -47,12 +49,17 @@ pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_TEST: &str = "test";
 pub const CMD_GLUE: &str = "glue";
+pub const CMD_VENDOR: &str = "vendor";
+pub const CMD_BUNDLE: &str = "bundle";
 pub const CMD_GEN_STUB_LIB: &str = "gen-stub-lib";
 pub const CMD_PREPROCESS_HOST: &str = "preprocess-host";
+pub const CMD_EXPECT_CHILD: &str = "expect-child";
 
 pub const FLAG_EMIT_LLVM_IR: &str = "emit-llvm-ir";
 pub const FLAG_PROFILING: &str = "profiling";
 pub const FLAG_BUNDLE: &str = "bundle";
+pub const FLAG_VERIFY: &str = "verify";
+pub const FLAG_COMPRESSION: &str = "compression";
 pub const FLAG_DEV: &str = "dev";
 pub const FLAG_OPTIMIZE: &str = "optimize";
 pub const FLAG_MAX_THREADS: &str = "max-threads";
@@ -68,13 +75,31 @@ pub const FLAG_STDIN: &str = "stdin";
 pub const FLAG_STDOUT: &str = "stdout";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
 pub const FLAG_OUTPUT: &str = "output";
+pub const FLAG_FILTER: &str = "filter";
+pub const FLAG_LIST: &str = "list";
+pub const FLAG_FORMAT: &str = "format";
+pub const FLAG_TIMEOUT: &str = "timeout";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_DIR: &str = "GLUE_DIR";
 pub const GLUE_SPEC: &str = "GLUE_SPEC";
 pub const DIRECTORY_OR_FILES: &str = "DIRECTORY_OR_FILES";
+pub const EXPECT_DYLIB: &str = "EXPECT_DYLIB";
+pub const EXPECT_NAME: &str = "EXPECT_NAME";
+pub const EXPECT_REPORT: &str = "EXPECT_REPORT";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
 
+/// The environment variable which can be set to a directory of package tarballs,
+/// e.g. for build machines without internet access. See [RocCacheDir::Mirrored].
+pub const ROC_PACKAGE_MIRROR_ENV_VAR: &str = "ROC_PACKAGE_MIRROR";
+
+/// Returns the directory set in the ROC_PACKAGE_MIRROR environment variable, if any.
+pub fn package_mirror_dir() -> Option<PathBuf> {
+    env::var_os(ROC_PACKAGE_MIRROR_ENV_VAR)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
 const VERSION: &str = include_str!("../../../version.txt");
 const DEFAULT_GENERATED_DOCS_DIR: &str = "generated-docs";
 
@@ -225,6 +250,34 @@ pub fn build_app() -> Command {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(
+                Arg::new(FLAG_FILTER)
+                    .long(FLAG_FILTER)
+                    .help("Only run the `expect`s in the module with this name, or whose name contains this text\n(An `expect` is named after the module and the top-level definition it follows, e.g. `Str.concat`. This flag can be given more than once.)")
+                    .action(ArgAction::Append)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_LIST)
+                    .long(FLAG_LIST)
+                    .help("List the `expect`s that would run, without running them")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_TIMEOUT)
+                    .long(FLAG_TIMEOUT)
+                    .help("Stop any `expect` that runs for longer than this many seconds, and count it as failed")
+                    .value_parser(value_parser!(u64))
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_FORMAT)
+                    .long(FLAG_FORMAT)
+                    .help("Print the results in a machine-readable format instead")
+                    .value_parser(PossibleValuesParser::new(["junit", "json"]))
+                    .required(false),
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file for the main module")
@@ -309,6 +362,42 @@ pub fn build_app() -> Command {
                     .default_value(DEFAULT_ROC_FILENAME),
             )
             )
+        .subcommand(Command::new(CMD_BUNDLE)
+            .about("Create a reproducible archive of a package or platform, so others can add it as a HTTPS dependency")
+            .arg(
+                Arg::new(FLAG_COMPRESSION)
+                    .long(FLAG_COMPRESSION)
+                    .help("The kind of archive to create")
+                    .value_parser([".tar", ".tar.gz", ".tar.br"])
+                    .default_value(".tar.br")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_VERIFY)
+                    .long(FLAG_VERIFY)
+                    .help("Instead of creating an archive, check that the archive at this URL or path matches the hash in its name, and contains every module its root module needs")
+                    .value_parser(value_parser!(String))
+                    .conflicts_with(ROC_FILE)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The package or platform's main .roc file")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
+        .subcommand(Command::new(CMD_VENDOR)
+            .about(concatcp!("Copy an app’s packages into a vendor directory next to it, and write a ", roc_packaging::vendor::LOCKFILE_NAME, " file listing them"))
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file of the app whose packages should be vendored")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
         .subcommand(
             Command::new(CMD_DOCS)
                 .about("Generate documentation for a Roc package")
@@ -383,6 +472,13 @@ pub fn build_app() -> Command {
                     .required(false),
             )
         )
+        .subcommand(Command::new(CMD_EXPECT_CHILD)
+            .about("Run a single `expect` on behalf of `roc test`")
+            .hide(true)
+            .arg(Arg::new(EXPECT_DYLIB).value_parser(value_parser!(PathBuf)).required(true))
+            .arg(Arg::new(EXPECT_NAME).required(true))
+            .arg(Arg::new(EXPECT_REPORT).value_parser(value_parser!(PathBuf)).required(true))
+        )
         .arg(flag_optimize)
         .arg(flag_max_threads)
         .arg(flag_opt_size)
@@ -417,22 +513,198 @@ fn opt_level_from_flags(matches: &ArgMatches) -> OptLevel {
     }
 }
 
-#[cfg(windows)]
-pub fn test(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
-    todo!("running tests does not work on windows right now")
+/// Bundle the given package or platform into an archive next to it, named by its hash.
+pub fn bundle(path: &Path, compression: Compression) -> io::Result<i32> {
+    let start_time = Instant::now();
+
+    // Print a note of advice. This is mainly here because brotli takes so long but produces
+    // such smaller output files; the idea is to encourage people to wait for brotli,
+    // so that downloads go faster. The compression only happens once, but the network
+    // transfer and decompression will happen many more times!
+    match compression {
+        Compression::Brotli => {
+            println!("Compressing with Brotli at maximum quality level…\n\n(Note: Brotli compression can take awhile! Bundling as .tar.gz takes less time, but usually produces a significantly larger output file. Brotli is generally worth the up-front wait if this is a file people will be downloading!)\n");
+        }
+        Compression::Gzip => {
+            println!("Compressing with gzip at minimum quality…\n\n(Note: Gzip usually runs faster than Brotli but typically produces significantly larger output files. Consider bundling as .tar.br if this is a file people will be downloading!)\n");
+        }
+        Compression::Uncompressed => {
+            println!("Building .tar archive without compression…\n\n(Note: Compression takes more time to run but typically produces much smaller output files. Consider bundling as .tar.br if this is a file people will be downloading!)\n");
+        }
+    }
+
+    let bundle = match roc_packaging::tarball::build(path, compression) {
+        Ok(bundle) => bundle,
+        Err(err) => {
+            eprintln!("I couldn't bundle {}:\n\n    {err}\n", path.display());
+
+            return Ok(1);
+        }
+    };
+    let total_time_ms = start_time.elapsed().as_millis();
+    let total_time = if total_time_ms > 1000 {
+        format!("{}s {}ms", total_time_ms / 1000, total_time_ms % 1000)
+    } else {
+        format!("{total_time_ms} ms")
+    };
+    let filename = bundle.filename;
+    let created_path = path.with_file_name(&filename);
+
+    println!("These files were included in the archive:\n");
+    print_bundled_files(&bundle.files);
+
+    println!(
+        "\nBundled \x1B[33m{}\x1B[39m and its dependent files into the following archive in {total_time}:\n\n\t\x1B[33m{}\x1B[39m\n\nTo distribute this archive as a package, upload this to some URL and then add it as a dependency with:\n\n\t\x1B[32m\"https://your-url-goes-here/{filename}\"\x1B[39m\n",
+        path.to_string_lossy(),
+        created_path.to_string_lossy()
+    );
+
+    Ok(0)
+}
+
+/// Check that the bundle at the given URL or path has the hash in its name,
+/// and that it includes every module its root module needs.
+pub fn verify_bundle(url_or_path: &str) -> io::Result<i32> {
+    let verification = match roc_packaging::tarball::verify(url_or_path) {
+        Ok(verification) => verification,
+        Err(problem) => {
+            let report =
+                roc_reporting::report::to_https_problem_report_string(url_or_path, problem);
+
+            eprint!("{report}");
+
+            return Ok(1);
+        }
+    };
+
+    println!("These files are in the archive:\n");
+    print_bundled_files(&verification.files);
+    println!();
+
+    if verification.expected_hash == verification.actual_hash {
+        println!(
+            "\x1B[32m✓\x1B[39m The archive's contents match its hash: {}",
+            verification.actual_hash
+        );
+    } else {
+        println!(
+            "\x1B[31m✗\x1B[39m The archive's contents don't match its hash!\n\n    expected: {}\n    actual:   {}",
+            verification.expected_hash, verification.actual_hash
+        );
+    }
+
+    if verification.missing.is_empty() {
+        println!("\x1B[32m✓\x1B[39m The archive contains every module its root module needs.");
+    } else {
+        println!("\x1B[31m✗\x1B[39m These modules are needed, but missing from the archive:\n");
+        print_bundled_files(&verification.missing);
+    }
+
+    Ok(if verification.is_ok() { 0 } else { 1 })
+}
+
+fn print_bundled_files(files: &[roc_packaging::tarball::BundledFile]) {
+    let width = files
+        .iter()
+        .map(|file| file.archive_path.len())
+        .max()
+        .unwrap_or(0);
+
+    for file in files {
+        println!(
+            "    \x1B[33m{:width$}\x1B[39m  {}",
+            file.archive_path, file.reason
+        );
+    }
 }
 
-#[cfg(not(windows))]
-pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
+/// Copy all the packages the given app depends on into a vendor directory next to it,
+/// and write a lock file listing them. Once the lock file exists, `roc_load` loads packages
+/// from the vendor directory, and never downloads anything.
+pub fn vendor(roc_file_path: &Path, roc_cache_dir: RocCacheDir<'_>) -> io::Result<i32> {
+    use roc_packaging::dependencies::DependencyProblem;
+    use roc_packaging::vendor::{vendor_packages, LOCKFILE_NAME};
+
+    match vendor_packages(roc_cache_dir, roc_file_path) {
+        Ok(lockfile) => {
+            let count = lockfile.urls().count();
+
+            println!(
+                "Vendored \x1B[32m{count}\x1B[39m {} into {}, and wrote {LOCKFILE_NAME}.",
+                if count == 1 { "package" } else { "packages" },
+                lockfile.vendor_dir().display()
+            );
+
+            Ok(0)
+        }
+        Err(DependencyProblem::Install(url, problem)) => {
+            let report = roc_reporting::report::to_https_problem_report_string(&url, problem);
+
+            eprint!("{report}");
+
+            Ok(1)
+        }
+        Err(DependencyProblem::InvalidHeader(path)) => {
+            eprintln!(
+                "I couldn't parse the header of {}. Run `roc check` on it for details.",
+                path.display()
+            );
+
+            Ok(1)
+        }
+        Err(DependencyProblem::IoErr(path, err)) => {
+            eprintln!("I couldn't read or write {}: {err}", path.display());
+
+            Ok(1)
+        }
+        Err(DependencyProblem::FsExtraErr(err)) => {
+            eprintln!("I couldn't copy a package into the vendor directory: {err}");
+
+            Ok(1)
+        }
+        Err(DependencyProblem::Version(problem)) => {
+            let report = roc_reporting::report::to_version_problem_report_string(problem);
+
+            eprint!("{report}");
+
+            Ok(1)
+        }
+    }
+}
+
+pub fn test(
+    matches: &ArgMatches,
+    triple: Triple,
+    roc_cache_dir: RocCacheDir<'_>,
+) -> io::Result<i32> {
     use roc_build::program::report_problems_monomorphized;
     use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadMonomorphizedError};
-    use roc_packaging::cache;
+    use roc_module::symbol::Symbol;
+    use roc_problem::Severity;
+    use roc_region::all::Region;
+    use roc_repl_expect::child::ChildMode;
+    use roc_repl_expect::run::ExpectRunOptions;
+    use roc_reporting::report::RenderTarget;
     use roc_target::TargetInfo;
+    use std::time::Duration;
+    use test_report::{
+        describe_expects, render_json_report, render_junit_report, render_listing, ExpectInfo,
+        TestFormat,
+    };
 
     let start_time = Instant::now();
     let arena = Bump::new();
     let opt_level = opt_level_from_flags(matches);
 
+    let format = TestFormat::from_flag(matches.get_one::<String>(FLAG_FORMAT));
+    let list_only = matches.get_flag(FLAG_LIST);
+
+    // A JUnit report is made of test results, which --list doesn't produce.
+    if list_only && format == TestFormat::Junit {
+        eprintln!("\nThe --list flag can't be combined with --format junit. You can use --format json to list the expects in a machine-readable format instead.\n");
+        process::exit(1);
+    }
+
     let threading = match matches.get_one::<usize>(FLAG_MAX_THREADS) {
         None => Threading::AllAvailable,
         Some(0) => user_error!("cannot build with at most 0 threads"),
@@ -480,12 +752,8 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         threading,
         exec_mode: ExecutionMode::Test,
     };
-    let load_result = roc_load::load_and_monomorphize(
-        arena,
-        path.to_path_buf(),
-        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
-        load_config,
-    );
+    let load_result =
+        roc_load::load_and_monomorphize(arena, path.to_path_buf(), roc_cache_dir, load_config);
 
     let mut loaded = match load_result {
         Ok(loaded) => loaded,
@@ -496,13 +764,65 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
             return handle_error_module(module, start_time.elapsed(), path.as_os_str(), false);
         }
     };
+
+    // Warnings are printed to stdout, where they would end up in the middle of the report.
+    if format.is_machine_readable() || list_only {
+        for problems in loaded.can_problems.values_mut() {
+            problems.retain(|problem| problem.severity() != Severity::Warning);
+        }
+
+        for problems in loaded.type_problems.values_mut() {
+            problems.retain(|problem| problem.severity() != Severity::Warning);
+        }
+    }
+
     let problems = report_problems_monomorphized(&mut loaded);
 
+    let expect_infos = describe_expects(
+        (loaded.toplevel_expects.pure.iter()).chain(loaded.toplevel_expects.fx.iter()),
+        &loaded.toplevel_expects.preceding_defs,
+        &loaded.sources,
+        &loaded.interns,
+    );
+
+    let filters: Vec<&String> = matches
+        .get_many::<String>(FLAG_FILTER)
+        .unwrap_or_default()
+        .collect();
+    let is_excluded = |symbol: &Symbol, _: &Region| {
+        let info = expect_infos.get(symbol).unwrap();
+
+        !filters.is_empty() && !filters.iter().any(|filter| info.matches(filter))
+    };
+
+    loaded
+        .toplevel_expects
+        .pure
+        .drain_filter(is_excluded)
+        .for_each(drop);
+    loaded
+        .toplevel_expects
+        .fx
+        .drain_filter(is_excluded)
+        .for_each(drop);
+
+    if list_only {
+        let mut selected: Vec<&ExpectInfo> = (loaded.toplevel_expects.pure.keys())
+            .chain(loaded.toplevel_expects.fx.keys())
+            .map(|symbol| expect_infos.get(symbol).unwrap())
+            .collect();
+        selected.sort_by_key(|info| (&info.path, info.region.start.line));
+
+        print!("{}", render_listing(&selected, format));
+
+        return Ok(0);
+    }
+
     let mut expectations = std::mem::take(&mut loaded.expectations);
 
     let interns = loaded.interns.clone();
 
-    let (lib, expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
+    let (dylib, expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
         arena,
         target.clone(),
         loaded,
@@ -527,26 +847,71 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     let arena = &bumpalo::Bump::new();
     let interns = arena.alloc(interns);
 
-    let mut writer = std::io::stdout();
+    // Machine-readable formats include the rendered failures in the report instead.
+    let (mut writer, render_target): (Box<dyn io::Write>, _) = if format.is_machine_readable() {
+        (Box::new(io::sink()), RenderTarget::Generic)
+    } else {
+        (Box::new(io::stdout()), RenderTarget::ColorTerminal)
+    };
 
-    let (failed, passed) = roc_repl_expect::run::run_toplevel_expects(
+    // Each expect runs in a process of its own, so this limits the number of processes.
+    let max_workers = match threading {
+        Threading::AllAvailable => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        Threading::Single => 1,
+        Threading::AtMost(n) => n,
+    };
+    let options = ExpectRunOptions {
+        max_workers,
+        timeout: matches
+            .get_one::<u64>(FLAG_TIMEOUT)
+            .map(|secs| Duration::from_secs(*secs)),
+        // Children started in the portable mode run `roc expect-child`.
+        child_mode: ChildMode::for_host(env::current_exe()?, vec![CMD_EXPECT_CHILD.into()]),
+    };
+
+    let outcomes = roc_repl_expect::run::run_toplevel_expects(
         &mut writer,
-        roc_reporting::report::RenderTarget::ColorTerminal,
+        render_target,
         arena,
         interns,
         &layout_interner.into_global(),
-        &lib,
+        &dylib,
         &mut expectations,
         expects,
+        options,
     )
     .unwrap();
 
     let total_time = start_time.elapsed();
+    let (failed, passed) = roc_repl_expect::run::count_outcomes(&outcomes);
 
-    if failed == 0 && passed == 0 {
-        // TODO print this in a more nicely formatted way!
-        println!("No expectations were found.");
+    let results: Vec<_> = outcomes
+        .into_iter()
+        .map(|outcome| (expect_infos.get(&outcome.symbol).unwrap(), outcome))
+        .collect();
+
+    match format {
+        TestFormat::Junit => print!("{}", render_junit_report(&results, total_time)),
+        TestFormat::Json => println!("{}", render_json_report(&results, total_time)),
+        TestFormat::Pretty if failed == 0 && passed == 0 => {
+            // TODO print this in a more nicely formatted way!
+            println!("No expectations were found.");
+        }
+        TestFormat::Pretty => {
+            let failed_color = if failed == 0 {
+                32 // green
+            } else {
+                31 // red
+            };
+
+            println!(
+                "\n\x1B[{failed_color}m{failed}\x1B[39m failed and \x1B[32m{passed}\x1B[39m passed in {} ms.\n",
+                total_time.as_millis(),
+            );
+        }
+    }
 
+    if failed == 0 && passed == 0 {
         // If no tests ran, treat that as an error. This is perhaps
         // briefly annoying at the very beginning of a project when
         // you actually have zero tests, but it can save you from
@@ -554,21 +919,22 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         // running tests altogether!
         Ok(2)
     } else {
-        let failed_color = if failed == 0 {
-            32 // green
-        } else {
-            31 // red
-        };
-
-        println!(
-            "\n\x1B[{failed_color}m{failed}\x1B[39m failed and \x1B[32m{passed}\x1B[39m passed in {} ms.\n",
-            total_time.as_millis(),
-        );
-
         Ok((failed > 0) as i32)
     }
 }
 
+/// Runs a single `expect` in a child process started by `roc test`, and writes what happened to
+/// the report file that `roc test` reads once the child exits.
+pub fn expect_child(matches: &ArgMatches) -> io::Result<i32> {
+    let dylib_path = matches.get_one::<PathBuf>(EXPECT_DYLIB).unwrap();
+    let expect_name = matches.get_one::<String>(EXPECT_NAME).unwrap();
+    let report_path = matches.get_one::<PathBuf>(EXPECT_REPORT).unwrap();
+
+    roc_repl_expect::child::run_spawned_child(dylib_path, expect_name, report_path)?;
+
+    Ok(0)
+}
+
 /// Find the element of `options` with the smallest edit distance to
 /// `reference`. Returns a tuple containing the element and the distance, or
 /// `None` if the `options` `Vec` is empty.
@@ -638,46 +1004,13 @@ pub fn build(
         }
 
         if config == BuildConfig::BuildOnly && matches.contains_id(FLAG_BUNDLE) {
-            let start_time = Instant::now();
-
             let compression =
                 Compression::try_from(matches.get_one::<String>(FLAG_BUNDLE).unwrap().as_str())
                     .unwrap();
 
-            // Print a note of advice. This is mainly here because brotli takes so long but produces
-            // such smaller output files; the idea is to encourage people to wait for brotli,
-            // so that downloads go faster. The compression only happens once, but the network
-            // transfer and decompression will happen many more times!
-            match compression {
-                Compression::Brotli => {
-                    println!("Compressing with Brotli at maximum quality level…\n\n(Note: Brotli compression can take awhile! Using --{FLAG_BUNDLE} .tar.gz takes less time, but usually produces a significantly larger output file. Brotli is generally worth the up-front wait if this is a file people will be downloading!)\n");
-                }
-                Compression::Gzip => {
-                    println!("Compressing with gzip at minimum quality…\n\n(Note: Gzip usually runs faster than Brotli but typically produces significantly larger output files. Consider using --{FLAG_BUNDLE} .tar.br if this is a file people will be downloading!)\n");
-                }
-                Compression::Uncompressed => {
-                    println!("Building .tar archive without compression…\n\n(Note: Compression takes more time to run but typically produces much smaller output files. Consider using --{FLAG_BUNDLE} .tar.br if this is a file people will be downloading!)\n");
-                }
-            }
-
             // Rather than building an executable or library, we're building
             // a tarball so this code can be distributed via a HTTPS
-            let filename = roc_packaging::tarball::build(path, compression)?;
-            let total_time_ms = start_time.elapsed().as_millis();
-            let total_time = if total_time_ms > 1000 {
-                format!("{}s {}ms", total_time_ms / 1000, total_time_ms % 1000)
-            } else {
-                format!("{total_time_ms} ms")
-            };
-            let created_path = path.with_file_name(&filename);
-
-            println!(
-                "\nBundled \x1B[33m{}\x1B[39m and its dependent files into the following archive in {total_time}:\n\n\t\x1B[33m{}\x1B[39m\n\nTo distribute this archive as a package, upload this to some URL and then add it as a dependency with:\n\n\t\x1B[32m\"https://your-url-goes-here/{filename}\"\x1B[39m\n",
-                path.to_string_lossy(),
-                created_path.to_string_lossy()
-            );
-
-            return Ok(0);
+            return bundle(path, compression);
         }
     }
 
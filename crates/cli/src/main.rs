@@ -3,11 +3,12 @@ use bumpalo::Bump;
 use roc_build::link::LinkType;
 use roc_build::program::{check_file, CodeGenBackend};
 use roc_cli::{
-    build_app, format_files, format_src, test, BuildConfig, FormatMode, CMD_BUILD, CMD_CHECK,
-    CMD_DEV, CMD_DOCS, CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_PREPROCESS_HOST, CMD_REPL,
-    CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_LIB,
-    FLAG_NO_LINK, FLAG_OUTPUT, FLAG_STDIN, FLAG_STDOUT, FLAG_TARGET, FLAG_TIME, GLUE_DIR,
-    GLUE_SPEC, ROC_FILE,
+    build_app, bundle, expect_child, format_files, format_src, package_mirror_dir, test, vendor,
+    verify_bundle, BuildConfig, FormatMode, CMD_BUILD, CMD_BUNDLE, CMD_CHECK, CMD_DEV, CMD_DOCS,
+    CMD_EXPECT_CHILD, CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_PREPROCESS_HOST, CMD_REPL,
+    CMD_RUN, CMD_TEST, CMD_VENDOR, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_COMPRESSION,
+    FLAG_DEV, FLAG_LIB, FLAG_NO_LINK, FLAG_OUTPUT, FLAG_STDIN, FLAG_STDOUT, FLAG_TARGET, FLAG_TIME,
+    FLAG_VERIFY, GLUE_DIR, GLUE_SPEC, ROC_FILE,
 };
 use roc_docs::generate_docs_html;
 use roc_error_macros::user_error;
@@ -15,6 +16,7 @@ use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
 use roc_load::{FunctionKind, LoadingProblem, Threading};
 use roc_packaging::cache::{self, RocCacheDir};
+use roc_packaging::tarball::Compression;
 use roc_target::{get_target_triple_str, Target};
 use std::fs::{self, FileType};
 use std::io::{self, Read, Write};
@@ -42,6 +44,16 @@ fn main() -> io::Result<()> {
         .collect();
     let matches = app.get_matches();
 
+    let cache_dir = cache::roc_cache_dir();
+    let mirror_dir = package_mirror_dir();
+    let roc_cache_dir = match &mirror_dir {
+        Some(mirror_dir) => RocCacheDir::Mirrored {
+            cache_dir: &cache_dir,
+            mirror_dir,
+        },
+        None => RocCacheDir::Persistent(&cache_dir),
+    };
+
     let exit_code = match matches.subcommand() {
         None => {
             if matches.contains_id(ROC_FILE) {
@@ -51,7 +63,7 @@ fn main() -> io::Result<()> {
                     BuildConfig::BuildAndRunIfNoErrors,
                     Triple::host(),
                     None,
-                    RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                    roc_cache_dir,
                     LinkType::Executable,
                 )
             } else {
@@ -66,7 +78,7 @@ fn main() -> io::Result<()> {
                     BuildConfig::BuildAndRun,
                     Triple::host(),
                     None,
-                    RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                    roc_cache_dir,
                     LinkType::Executable,
                 )
             } else {
@@ -77,13 +89,14 @@ fn main() -> io::Result<()> {
         }
         Some((CMD_TEST, matches)) => {
             if matches.contains_id(ROC_FILE) {
-                test(matches, Triple::host())
+                test(matches, Triple::host(), roc_cache_dir)
             } else {
                 eprintln!("What .roc file do you want to test? Specify it at the end of the `roc test` command.");
 
                 Ok(1)
             }
         }
+        Some((CMD_EXPECT_CHILD, matches)) => expect_child(matches),
         Some((CMD_DEV, matches)) => {
             if matches.contains_id(ROC_FILE) {
                 build(
@@ -92,7 +105,7 @@ fn main() -> io::Result<()> {
                     BuildConfig::BuildAndRunIfNoErrors,
                     Triple::host(),
                     None,
-                    RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                    roc_cache_dir,
                     LinkType::Executable,
                 )
             } else {
@@ -129,7 +142,7 @@ fn main() -> io::Result<()> {
             let function_kind = FunctionKind::LambdaSet;
             roc_linker::generate_stub_lib(
                 input_path,
-                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                roc_cache_dir,
                 &target.to_triple(),
                 function_kind,
             );
@@ -144,12 +157,8 @@ fn main() -> io::Result<()> {
 
             let triple = target.to_triple();
             let function_kind = FunctionKind::LambdaSet;
-            let (platform_path, stub_lib, stub_dll_symbols) = roc_linker::generate_stub_lib(
-                &input_path,
-                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
-                &triple,
-                function_kind,
-            );
+            let (platform_path, stub_lib, stub_dll_symbols) =
+                roc_linker::generate_stub_lib(&input_path, roc_cache_dir, &triple, function_kind);
 
             // TODO: pipeline the executable location through here.
             // Currently it is essentally hardcoded as platform_path/dynhost.
@@ -186,7 +195,7 @@ fn main() -> io::Result<()> {
                 BuildConfig::BuildOnly,
                 target.to_triple(),
                 out_path,
-                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                roc_cache_dir,
                 link_type,
             )?)
         }
@@ -206,7 +215,7 @@ fn main() -> io::Result<()> {
                 &arena,
                 roc_file_path.to_owned(),
                 emit_timings,
-                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                roc_cache_dir,
                 threading,
             ) {
                 Ok((problems, total_time)) => {
@@ -250,6 +259,23 @@ fn main() -> io::Result<()> {
                 }
             }
         }
+        Some((CMD_BUNDLE, matches)) => match matches.get_one::<String>(FLAG_VERIFY) {
+            Some(url_or_path) => verify_bundle(url_or_path),
+            None => {
+                let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+                let compression = matches.get_one::<String>(FLAG_COMPRESSION).unwrap();
+
+                bundle(
+                    roc_file_path,
+                    Compression::try_from(compression.as_str()).unwrap(),
+                )
+            }
+        },
+        Some((CMD_VENDOR, matches)) => {
+            let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+
+            vendor(roc_file_path, roc_cache_dir)
+        }
         Some((CMD_REPL, _)) => Ok(roc_repl_cli::main()),
         Some((CMD_DOCS, matches)) => {
             let root_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
@@ -0,0 +1,287 @@
+//! Naming, filtering and reporting the top-level `expect`s that `roc test` runs.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use roc_collections::{MutMap, VecMap};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_region::all::{LineColumnRegion, LineInfo, Region};
+use roc_repl_expect::run::ExpectOutcome;
+use serde_json::json;
+
+/// How `roc test` reports its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestFormat {
+    /// Human-readable output, with failures rendered as they happen.
+    Pretty,
+    Junit,
+    Json,
+}
+
+impl TestFormat {
+    pub fn from_flag(flag: Option<&String>) -> Self {
+        match flag.map(|s| s.as_str()) {
+            None => TestFormat::Pretty,
+            Some("junit") => TestFormat::Junit,
+            Some("json") => TestFormat::Json,
+            Some(other) => unreachable!("clap only allows junit or json, got {other}"),
+        }
+    }
+
+    pub fn is_machine_readable(self) -> bool {
+        self != TestFormat::Pretty
+    }
+}
+
+/// What a top-level expect is called, and where it is.
+#[derive(Debug)]
+pub struct ExpectInfo {
+    pub module: String,
+    /// The top-level definition the expect follows, which is usually the one it tests.
+    pub def: Option<String>,
+    pub path: PathBuf,
+    pub region: LineColumnRegion,
+}
+
+impl ExpectInfo {
+    /// The name used for `--filter` and in reports, e.g. `Str.concat`. Expects that do not
+    /// follow a definition are named after their line instead, e.g. `Str:12`.
+    pub fn name(&self) -> String {
+        match &self.def {
+            Some(def) => format!("{}.{}", self.module, def),
+            None => format!("{}:{}", self.module, self.region.start.line + 1),
+        }
+    }
+
+    /// Whether the expect is in the module named `filter`, or its name contains `filter`.
+    pub fn matches(&self, filter: &str) -> bool {
+        self.module == filter || self.name().contains(filter)
+    }
+
+    fn location(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.path.display(),
+            self.region.start.line + 1,
+            self.region.start.column + 1
+        )
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "name": self.name(),
+            "module": self.module,
+            "def": self.def,
+            "path": self.path,
+            "region": {
+                "start": { "line": self.region.start.line + 1, "column": self.region.start.column + 1 },
+                "end": { "line": self.region.end.line + 1, "column": self.region.end.column + 1 },
+            },
+        })
+    }
+}
+
+/// Names every expect in `expects`, after the def in `preceding_defs` it follows.
+pub fn describe_expects<'a>(
+    expects: impl IntoIterator<Item = (&'a Symbol, &'a Region)>,
+    preceding_defs: &VecMap<Symbol, Symbol>,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    interns: &Interns,
+) -> VecMap<Symbol, ExpectInfo> {
+    let mut line_infos: MutMap<ModuleId, LineInfo> = MutMap::default();
+
+    expects
+        .into_iter()
+        .map(|(symbol, region)| {
+            let module_id = symbol.module_id();
+            let (path, source) = &sources[&module_id];
+            let line_info = line_infos
+                .entry(module_id)
+                .or_insert_with(|| LineInfo::new(source));
+
+            let info = ExpectInfo {
+                module: interns.module_name(module_id).as_str().to_string(),
+                def: (preceding_defs.get(symbol)).map(|def| def.as_str(interns).to_string()),
+                path: path.clone(),
+                region: line_info.convert_region(*region),
+            };
+
+            (*symbol, info)
+        })
+        .collect()
+}
+
+/// The output of `roc test --list`.
+pub fn render_listing(expects: &[&ExpectInfo], format: TestFormat) -> String {
+    match format {
+        TestFormat::Json => {
+            let expects: Vec<_> = expects.iter().map(|info| info.to_json()).collect();
+            serde_json::to_string_pretty(&expects).unwrap()
+        }
+        TestFormat::Junit => unreachable!("`roc test` rejects --list with --format junit"),
+        TestFormat::Pretty => {
+            let mut out = String::new();
+            for info in expects {
+                writeln!(out, "{}\t{}", info.name(), info.location()).unwrap();
+            }
+            out
+        }
+    }
+}
+
+pub fn render_json_report(
+    results: &[(&ExpectInfo, ExpectOutcome)],
+    total_time: Duration,
+) -> String {
+    let failed = count_failures(results);
+    let passed = results.len() - failed;
+
+    let expects: Vec<_> = results
+        .iter()
+        .map(|(info, outcome)| {
+            let mut value = info.to_json();
+            value["status"] = json!(if outcome.passed() { "passed" } else { "failed" });
+            value["duration_ms"] = json!(outcome.duration.as_secs_f64() * 1000.0);
+            value["failure"] = json!(outcome.failure.as_deref().map(strip_ansi_codes));
+            value
+        })
+        .collect();
+
+    let report = json!({
+        "passed": passed,
+        "failed": failed,
+        "duration_ms": total_time.as_secs_f64() * 1000.0,
+        "expects": expects,
+    });
+
+    serde_json::to_string_pretty(&report).unwrap()
+}
+
+pub fn render_junit_report(
+    results: &[(&ExpectInfo, ExpectOutcome)],
+    total_time: Duration,
+) -> String {
+    let failed = count_failures(results);
+
+    // One test suite per module, in the order the modules' expects were run.
+    let mut suites: VecMap<&str, Vec<&(&ExpectInfo, ExpectOutcome)>> = VecMap::default();
+    for result in results {
+        suites
+            .get_or_insert(result.0.module.as_str(), Vec::new)
+            .push(result);
+    }
+
+    let mut out = String::new();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        out,
+        r#"<testsuites name="roc test" tests="{}" failures="{}" time="{:.3}">"#,
+        results.len(),
+        failed,
+        total_time.as_secs_f64()
+    )
+    .unwrap();
+
+    for (module, results) in suites.iter() {
+        let suite_failures = results
+            .iter()
+            .filter(|(_, outcome)| !outcome.passed())
+            .count();
+        let suite_time: Duration = results.iter().map(|(_, outcome)| outcome.duration).sum();
+
+        writeln!(
+            out,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" time="{:.3}">"#,
+            xml_escape(module),
+            results.len(),
+            suite_failures,
+            suite_time.as_secs_f64()
+        )
+        .unwrap();
+
+        for (info, outcome) in results.iter() {
+            write!(
+                out,
+                r#"    <testcase name="{}" classname="{}" file="{}" line="{}" time="{:.3}""#,
+                xml_escape(&info.name()),
+                xml_escape(module),
+                xml_escape(&info.path.display().to_string()),
+                info.region.start.line + 1,
+                outcome.duration.as_secs_f64()
+            )
+            .unwrap();
+
+            match &outcome.failure {
+                None => writeln!(out, "/>").unwrap(),
+                Some(failure) => {
+                    writeln!(out, ">").unwrap();
+                    writeln!(
+                        out,
+                        r#"      <failure message="expect failed at {}">{}</failure>"#,
+                        xml_escape(&info.location()),
+                        xml_escape(&strip_ansi_codes(failure))
+                    )
+                    .unwrap();
+                    writeln!(out, "    </testcase>").unwrap();
+                }
+            }
+        }
+
+        writeln!(out, "  </testsuite>").unwrap();
+    }
+
+    writeln!(out, "</testsuites>").unwrap();
+
+    out
+}
+
+fn count_failures(results: &[(&ExpectInfo, ExpectOutcome)]) -> usize {
+    results
+        .iter()
+        .filter(|(_, outcome)| !outcome.passed())
+        .count()
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Not allowed in XML 1.0 documents at all.
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Failures are rendered without colors for machine-readable formats, but a roc_panic message
+/// may still contain escape codes of its own.
+fn strip_ansi_codes(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip a CSI sequence, e.g. `\x1b[31m`.
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
@@ -575,39 +575,207 @@ mod cli_run {
             TestCliCommands::Dev,
         );
 
+        let expected_test_output = indoc!(
+            r#"
+            This expectation failed:
+
+             6│>  expect
+             7│>      a = 1
+             8│>      b = 2
+             9│>
+            10│>      a == b
+
+            When it failed, these variables had these values:
+
+            a : Num *
+            a = 1
+
+            b : Num *
+            b = 2
+
+
+
+            1 failed and 0 passed in <ignored for test> ms."#
+        );
+
         test_roc_app(
             "crates/cli_testing_examples/expects",
             "expects.roc",
             &[],
             &[],
             &[],
-            indoc!(
-                r#"
-                This expectation failed:
+            expected_test_output,
+            UseValgrind::Yes,
+            TestCliCommands::Test,
+        );
 
-                 6│>  expect
-                 7│>      a = 1
-                 8│>      b = 2
-                 9│>
-                10│>      a == b
+        // The portable way of running each expect in a child process, which is what Windows uses.
+        test_roc_app(
+            "crates/cli_testing_examples/expects",
+            "expects.roc",
+            &[],
+            &[],
+            &[(roc_repl_expect::child::ChildMode::ENV_VAR, "spawn")],
+            expected_test_output,
+            UseValgrind::Yes,
+            TestCliCommands::Test,
+        );
+    }
 
-                When it failed, these variables had these values:
+    const FILTER_FLAG: &str = concatcp!("--", roc_cli::FLAG_FILTER);
+    const LIST_FLAG: &str = concatcp!("--", roc_cli::FLAG_LIST);
+    const FORMAT_FLAG: &str = concatcp!("--", roc_cli::FLAG_FORMAT);
+
+    fn roc_test_filters(flags: &[&str]) -> Out {
+        let path = file_path_from_root("crates/cli_testing_examples/expects", "Filters.roc");
+
+        run_roc(
+            iter::once(CMD_TEST)
+                .chain(flags.iter().copied())
+                .chain(iter::once(path.to_str().unwrap())),
+            &[],
+            &[],
+        )
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_list() {
+        let out = roc_test_filters(&[LIST_FLAG]);
+        assert!(out.status.success(), "{}", out.stderr);
+
+        // Each expect is named after the def it follows, and listed with its location.
+        let listing: Vec<(&str, &str)> = out
+            .stdout
+            .lines()
+            .map(|line| line.split_once('\t').unwrap())
+            .collect();
+        let names: Vec<&str> = listing.iter().map(|(name, _)| *name).collect();
+
+        assert_eq!(names, ["Filters.double", "Filters.double", "Filters.shout"]);
+        assert!(
+            listing[0].1.ends_with("Filters.roc:7:1"),
+            "{}",
+            listing[0].1
+        );
+        assert!(
+            listing[2].1.ends_with("Filters.roc:13:1"),
+            "{}",
+            listing[2].1
+        );
+    }
 
-                a : Num *
-                a = 1
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_list_json() {
+        let out = roc_test_filters(&[LIST_FLAG, FILTER_FLAG, "shout", FORMAT_FLAG, "json"]);
+        assert!(out.status.success(), "{}", out.stderr);
 
-                b : Num *
-                b = 2
+        let listing: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+        let expects = listing.as_array().unwrap();
 
+        assert_eq!(expects.len(), 1);
+        assert_eq!(expects[0]["name"], "Filters.shout");
+        assert_eq!(expects[0]["def"], "shout");
+        assert_eq!(expects[0]["region"]["start"]["line"], 13);
+    }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_list_junit_is_rejected() {
+        let out = roc_test_filters(&[LIST_FLAG, FORMAT_FLAG, "junit"]);
 
-                1 failed and 0 passed in <ignored for test> ms."#
-            ),
-            UseValgrind::Yes,
-            TestCliCommands::Test,
+        assert!(!out.status.success());
+        assert!(out.stdout.is_empty(), "{}", out.stdout);
+        assert!(
+            out.stderr.contains("can't be combined with --format junit"),
+            "{}",
+            out.stderr
         );
     }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_filter() {
+        let out = roc_test_filters(&[FILTER_FLAG, "shout"]);
+        assert!(out.status.success(), "{}", out.stderr);
+        assert!(
+            strip_colors(&out.stdout).contains("0 failed and 1 passed"),
+            "{}",
+            out.stdout
+        );
+
+        // Filtering by module name runs every expect in it, including the failing one.
+        let out = roc_test_filters(&[FILTER_FLAG, "Filters"]);
+        assert!(!out.status.success());
+        assert!(
+            strip_colors(&out.stdout).contains("1 failed and 2 passed"),
+            "{}",
+            out.stdout
+        );
+
+        // Nothing matches, which is an error like having no expects at all.
+        let out = roc_test_filters(&[FILTER_FLAG, "nothing"]);
+        assert!(!out.status.success());
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_format_json() {
+        let out = roc_test_filters(&[FORMAT_FLAG, "json"]);
+        assert!(!out.status.success());
+
+        let report: serde_json::Value = serde_json::from_str(&out.stdout).unwrap();
+
+        assert_eq!(report["passed"], 2);
+        assert_eq!(report["failed"], 1);
+
+        let statuses: Vec<(&str, &str)> = report["expects"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|expect| {
+                (
+                    expect["name"].as_str().unwrap(),
+                    expect["status"].as_str().unwrap(),
+                )
+            })
+            .collect();
+
+        assert!(statuses.contains(&("Filters.double", "failed")));
+        assert!(statuses.contains(&("Filters.double", "passed")));
+        assert!(statuses.contains(&("Filters.shout", "passed")));
+
+        let failed = report["expects"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|expect| expect["status"] == "failed")
+            .unwrap();
+        let failure = failed["failure"].as_str().unwrap();
+
+        // Failures are rendered without colors.
+        assert!(failure.contains("expect double 3 == 7"), "{failure}");
+        assert!(!failure.contains('\x1b'), "{failure}");
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn test_format_junit() {
+        let out = roc_test_filters(&[FORMAT_FLAG, "junit"]);
+        assert!(!out.status.success());
+
+        let report = out.stdout;
+
+        assert!(report.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(report.contains(r#"<testsuites name="roc test" tests="3" failures="1""#));
+        assert!(report.contains(r#"<testsuite name="Filters" tests="3" failures="1""#));
+        assert!(report.contains(r#"<testcase name="Filters.shout" classname="Filters""#));
+        assert_eq!(report.matches("<failure ").count(), 1);
+        assert!(report.contains("expect double 3 == 7"), "{report}");
+        assert!(report.trim_end().ends_with("</testsuites>"));
+    }
+
     #[test]
     #[cfg_attr(
         windows,
@@ -0,0 +1,13 @@
+interface Filters
+    exposes [double, shout]
+    imports []
+
+double = \x -> x * 2
+
+expect double 2 == 4
+
+expect double 3 == 7
+
+shout = \s -> Str.concat s "!"
+
+expect shout "hi" == "hi!"
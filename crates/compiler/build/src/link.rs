@@ -26,17 +26,20 @@ pub enum LinkingStrategy {
 }
 
 /// input_paths can include the host as well as the app. e.g. &["host.o", "roc_app.o"]
+/// The native linkers keep whatever debug info the inputs have. For wasm, we strip it
+/// unless `emit_debug_info` is set.
 pub fn link(
     target: &Triple,
     output_path: PathBuf,
     input_paths: &[&str],
     link_type: LinkType,
+    emit_debug_info: bool,
 ) -> io::Result<(Child, PathBuf)> {
     match target {
         Triple {
             architecture: Architecture::Wasm32,
             ..
-        } => link_wasm32(target, output_path, input_paths, link_type),
+        } => link_wasm32(target, output_path, input_paths, link_type, emit_debug_info),
         Triple {
             operating_system: OperatingSystem::Linux,
             ..
@@ -1200,7 +1203,15 @@ fn link_wasm32(
     output_path: PathBuf,
     input_paths: &[&str],
     _link_type: LinkType,
+    emit_debug_info: bool,
 ) -> io::Result<(Child, PathBuf)> {
+    // Without -fstrip, the output keeps its name section and the DWARF sections from the app
+    let strip_flag = if emit_debug_info {
+        "-fno-strip"
+    } else {
+        "-fstrip"
+    };
+
     let child = zig()
         // .env_clear()
         // .env("PATH", &env_path)
@@ -1218,7 +1229,7 @@ fn link_wasm32(
             &format!("glue::{}", find_zig_glue_path().to_str().unwrap()),
             "--deps",
             "glue",
-            "-fstrip",
+            strip_flag,
             "-O",
             "ReleaseSmall",
             // useful for debugging
@@ -1282,12 +1293,24 @@ pub fn llvm_module_to_dylib(
     target: &Triple,
     opt_level: OptLevel,
 ) -> Result<Library, Error> {
+    let dir = tempfile::tempdir().unwrap();
+
+    llvm_module_to_dylib_in(module, target, opt_level, dir.path()).map(|(lib, _)| lib)
+}
+
+/// Like [llvm_module_to_dylib], but the dylib is written into `dir`, and stays there for other
+/// processes to load. Returns the path of the dylib along with the loaded library.
+pub fn llvm_module_to_dylib_in(
+    module: &inkwell::module::Module,
+    target: &Triple,
+    opt_level: OptLevel,
+    dir: &Path,
+) -> Result<(Library, PathBuf), Error> {
     use crate::target::{self, convert_opt_level};
     use inkwell::targets::{FileType, RelocMode};
 
-    let dir = tempfile::tempdir().unwrap();
     let filename = PathBuf::from("Test.roc");
-    let file_path = dir.path().join(filename);
+    let file_path = dir.join(filename);
     let mut app_o_file = file_path;
 
     app_o_file.set_file_name("app.o");
@@ -1307,6 +1330,7 @@ pub fn llvm_module_to_dylib(
         app_o_file.clone(),
         &[app_o_file.to_str().unwrap()],
         LinkType::Dylib,
+        false,
     )
     .unwrap();
 
@@ -1328,14 +1352,14 @@ pub fn llvm_module_to_dylib(
         // to be okay.
         loop {
             match unsafe { Library::new(path) } {
-                Ok(lib) => return Ok(lib),
+                Ok(lib) => return Ok((lib, dylib_path)),
                 Err(Error::DlOpen { .. }) => continue,
                 Err(other) => return Err(other),
             }
         }
     }
 
-    unsafe { Library::new(path) }
+    unsafe { Library::new(path) }.map(|lib| (lib, dylib_path))
 }
 
 pub fn preprocess_host_wasm32(host_input_path: &Path, preprocessed_host_path: &Path) {
@@ -113,6 +113,7 @@ pub fn gen_from_mono_module<'a>(
             preprocessed_host_path,
             wasm_dev_stack_bytes,
             AssemblyBackendMode::Binary, // dummy value, unused in practice
+            debug,
         ),
         CodeGenBackend::Assembly(backend_mode) => gen_from_mono_module_dev(
             arena,
@@ -121,6 +122,7 @@ pub fn gen_from_mono_module<'a>(
             preprocessed_host_path,
             wasm_dev_stack_bytes,
             backend_mode,
+            debug,
         ),
         CodeGenBackend::Llvm(backend_mode) => gen_from_mono_module_llvm(
             arena,
@@ -198,6 +200,11 @@ fn gen_from_mono_module_llvm<'a>(
     let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
     let (mpm, _fpm) = roc_gen_llvm::llvm::build::construct_optimization_passes(module, opt_level);
 
+    // Only worth computing when the debug info is kept
+    let source_locations = emit_debug_info.then(|| {
+        roc_mono::source_locations::SourceLocations::new(&loaded.sources, loaded.symbol_regions)
+    });
+
     // Compile and add all the Procs before adding main
     let env = roc_gen_llvm::llvm::build::Env {
         arena,
@@ -216,6 +223,7 @@ fn gen_from_mono_module_llvm<'a>(
             .keys()
             .copied()
             .collect(),
+        source_locations: source_locations.as_ref(),
     };
 
     // does not add any externs for this mode (we have a host) but cleans up some functions around
@@ -425,6 +433,7 @@ fn gen_from_mono_module_dev<'a>(
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
     backend_mode: AssemblyBackendMode,
+    emit_debug_info: bool,
 ) -> GenFromMono<'a> {
     use target_lexicon::Architecture;
 
@@ -434,9 +443,10 @@ fn gen_from_mono_module_dev<'a>(
             loaded,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
+            emit_debug_info,
         ),
         Architecture::X86_64 | Architecture::Aarch64(_) => {
-            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode)
+            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode, emit_debug_info)
         }
         _ => todo!(),
     }
@@ -450,12 +460,13 @@ pub fn gen_from_mono_module_dev<'a>(
     _host_input_path: &Path,
     _wasm_dev_stack_bytes: Option<u32>,
     backend_mode: AssemblyBackendMode,
+    emit_debug_info: bool,
 ) -> GenFromMono<'a> {
     use target_lexicon::Architecture;
 
     match target.architecture {
         Architecture::X86_64 | Architecture::Aarch64(_) => {
-            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode)
+            gen_from_mono_module_dev_assembly(arena, loaded, target, backend_mode, emit_debug_info)
         }
         _ => todo!(),
     }
@@ -467,6 +478,7 @@ fn gen_from_mono_module_dev_wasm32<'a>(
     loaded: MonomorphizedModule<'a>,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    emit_debug_info: bool,
 ) -> GenFromMono<'a> {
     let all_code_gen_start = Instant::now();
     let MonomorphizedModule {
@@ -474,6 +486,8 @@ fn gen_from_mono_module_dev_wasm32<'a>(
         procedures,
         mut interns,
         mut layout_interner,
+        sources,
+        symbol_regions,
         ..
     } = loaded;
 
@@ -489,6 +503,8 @@ fn gen_from_mono_module_dev_wasm32<'a>(
         module_id,
         exposed_to_host,
         stack_bytes: wasm_dev_stack_bytes.unwrap_or(roc_gen_wasm::Env::DEFAULT_STACK_BYTES),
+        source_locations: emit_debug_info
+            .then(|| roc_mono::source_locations::SourceLocations::new(&sources, symbol_regions)),
     };
 
     let host_bytes = std::fs::read(preprocessed_host_path).unwrap_or_else(|_| {
@@ -540,6 +556,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
     loaded: MonomorphizedModule<'a>,
     target: &target_lexicon::Triple,
     backend_mode: AssemblyBackendMode,
+    emit_debug_info: bool,
 ) -> GenFromMono<'a> {
     let all_code_gen_start = Instant::now();
 
@@ -551,6 +568,8 @@ fn gen_from_mono_module_dev_assembly<'a>(
         mut interns,
         exposed_to_host,
         mut layout_interner,
+        sources,
+        symbol_regions,
         ..
     } = loaded;
 
@@ -560,6 +579,8 @@ fn gen_from_mono_module_dev_assembly<'a>(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: backend_mode,
+        source_locations: emit_debug_info
+            .then(|| roc_mono::source_locations::SourceLocations::new(&sources, symbol_regions)),
     };
 
     let module_object =
@@ -1029,8 +1050,14 @@ fn build_loaded_file<'a>(
                 inputs.push(builtins_host_tempfile.path().to_str().unwrap());
             }
 
-            let (mut child, _) = link(target, output_exe_path.clone(), &inputs, link_type)
-                .map_err(|_| todo!("gracefully handle `ld` failing to spawn."))?;
+            let (mut child, _) = link(
+                target,
+                output_exe_path.clone(),
+                &inputs,
+                link_type,
+                code_gen_options.emit_debug_info,
+            )
+            .map_err(|_| todo!("gracefully handle `ld` failing to spawn."))?;
 
             let exit_status = child
                 .wait()
@@ -1230,6 +1230,17 @@ pub fn toF64(arg: RocDec) callconv(.C) f64 {
     return @call(.always_inline, RocDec.toF64, .{arg});
 }
 
+// Every Dec value is within the range of both f32 and f64
+pub fn toF32Checked(arg: RocDec) callconv(.C) num_.ToFloatCheckedResult(f32) {
+    const value = @call(.always_inline, RocDec.toF64, .{arg});
+    return .{ .value = @as(f32, @floatCast(value)), .out_of_bounds = false };
+}
+
+pub fn toF64Checked(arg: RocDec) callconv(.C) num_.ToFloatCheckedResult(f64) {
+    const value = @call(.always_inline, RocDec.toF64, .{arg});
+    return .{ .value = value, .out_of_bounds = false };
+}
+
 pub fn exportFromInt(comptime T: type, comptime name: []const u8) void {
     comptime var f = struct {
         fn func(self: T) callconv(.C) i128 {
@@ -5,3 +5,4 @@
 pub const dec = @import("dec.zig");
 pub const list = @import("list.zig");
 pub const str = @import("str.zig");
+pub const utils = @import("utils.zig");
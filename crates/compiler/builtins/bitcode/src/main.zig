@@ -45,6 +45,8 @@ comptime {
     exportDecFn(dec.subSaturatedC, "sub_saturated");
     exportDecFn(dec.tanC, "tan");
     exportDecFn(dec.toF64, "to_f64");
+    exportDecFn(dec.toF32Checked, "to_f32_checked");
+    exportDecFn(dec.toF64Checked, "to_f64_checked");
     exportDecFn(dec.toI128, "to_i128");
     exportDecFn(dec.toStr, "to_str");
 
@@ -94,6 +96,10 @@ comptime {
 
     exportNumFn(num.shiftRightZeroFillI128, "shift_right_zero_fill.i128");
     exportNumFn(num.shiftRightZeroFillU128, "shift_right_zero_fill.u128");
+    exportNumFn(num.shiftLeftI128, "shift_left.i128");
+    exportNumFn(num.shiftLeftU128, "shift_left.u128");
+    exportNumFn(num.shiftRightI128, "shift_right.i128");
+    exportNumFn(num.shiftRightU128, "shift_right.u128");
 
     exportNumFn(num.compareI128, "compare.i128");
     exportNumFn(num.compareU128, "compare.u128");
@@ -124,6 +130,8 @@ comptime {
     inline for (INTEGERS, 0..) |T, i| {
         num.exportPow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".pow_int.");
         num.exportDivCeil(T, ROC_BUILTINS ++ "." ++ NUM ++ ".div_ceil.");
+        num.exportDivTrunc(T, ROC_BUILTINS ++ "." ++ NUM ++ ".div_trunc.");
+        num.exportRem(T, ROC_BUILTINS ++ "." ++ NUM ++ ".rem.");
 
         num.exportRound(f32, T, ROC_BUILTINS ++ "." ++ NUM ++ ".round_f32.");
         num.exportRound(f64, T, ROC_BUILTINS ++ "." ++ NUM ++ ".round_f64.");
@@ -131,20 +139,29 @@ comptime {
         num.exportFloor(f64, T, ROC_BUILTINS ++ "." ++ NUM ++ ".floor_f64.");
         num.exportCeiling(f32, T, ROC_BUILTINS ++ "." ++ NUM ++ ".ceiling_f32.");
         num.exportCeiling(f64, T, ROC_BUILTINS ++ "." ++ NUM ++ ".ceiling_f64.");
+        num.exportToFloat(T, f32, ROC_BUILTINS ++ "." ++ NUM ++ ".to_f32.");
+        num.exportToFloat(T, f64, ROC_BUILTINS ++ "." ++ NUM ++ ".to_f64.");
+        num.exportToFloatChecked(T, f32, ROC_BUILTINS ++ "." ++ NUM ++ ".to_f32_checked.");
+        num.exportToFloatChecked(T, f64, ROC_BUILTINS ++ "." ++ NUM ++ ".to_f64_checked.");
 
         num.exportAddWithOverflow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_with_overflow.");
         num.exportAddOrPanic(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_or_panic.");
         num.exportAddSaturatedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_saturated.");
+        num.exportAddWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_wrapped.");
 
         num.exportSubWithOverflow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_with_overflow.");
         num.exportSubOrPanic(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_or_panic.");
         num.exportSubSaturatedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_saturated.");
+        num.exportSubWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_wrapped.");
 
         num.exportMulWithOverflow(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_with_overflow.");
         num.exportMulOrPanic(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_or_panic.");
         num.exportMulSaturatedInt(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_saturated.");
         num.exportMulWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".mul_wrapped.");
 
+        num.exportNegateWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".negate_wrapped.");
+        num.exportAbsWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".abs_wrapped.");
+
         num.exportIsMultipleOf(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_multiple_of.");
 
         num.exportCountLeadingZeroBits(T, ROC_BUILTINS ++ "." ++ NUM ++ ".count_leading_zero_bits.");
@@ -181,6 +198,9 @@ comptime {
         num.exportIsNan(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_nan.");
         num.exportIsInfinite(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_infinite.");
         num.exportIsFinite(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_finite.");
+
+        num.exportToFloatChecked(T, f32, ROC_BUILTINS ++ "." ++ NUM ++ ".to_f32_checked.");
+        num.exportToFloatChecked(T, f64, ROC_BUILTINS ++ "." ++ NUM ++ ".to_f64_checked.");
     }
 }
 
@@ -102,6 +102,8 @@ Dict k v := {
     metadata : List I8,
     dataIndices : List Nat,
     data : List (k, v),
+    # Keys are hashed with this. Storing it lets a host hash keys the same way.
+    seed : U64,
 } where k implements Hash & Eq
     implements [
         Eq {
@@ -149,6 +151,7 @@ empty = \{} ->
         metadata: emptyMetadata,
         dataIndices: emptyDataIndices,
         data: [],
+        seed: pseudoSeed {},
     }
 
 ## Return a dictionary with space allocated for a number of entries. This
@@ -175,6 +178,7 @@ withCapacity = \size ->
             metadata: List.repeat emptySlot cap,
             dataIndices: List.repeat 0 cap,
             data: List.withCapacity cap,
+            seed: pseudoSeed {},
         }
 
 containingPowerOfTwo : U64 -> U64
@@ -263,7 +267,7 @@ isEmpty = \@Dict { data } ->
 ## expect Dict.len clearSongs == 0
 ## ```
 clear : Dict k v -> Dict k v where k implements Hash & Eq
-clear = \@Dict { metadata, dataIndices, data } ->
+clear = \@Dict { metadata, dataIndices, data, seed } ->
     cap = List.len dataIndices
 
     # Only clear large allocations.
@@ -276,6 +280,7 @@ clear = \@Dict { metadata, dataIndices, data } ->
             dataIndices,
             # use takeFirst to keep around the capacity.
             data: List.takeFirst data 0,
+            seed,
         }
 
 ## Convert each value in the dictionary to something new, by calling a conversion
@@ -400,9 +405,9 @@ dropIf = \dict, predicate ->
 ## expect Dict.get dictionary 2000 == Err KeyNotFound
 ## ```
 get : Dict k v, k -> Result v [KeyNotFound] where k implements Hash & Eq
-get = \@Dict { metadata, dataIndices, data }, key ->
+get = \@Dict { metadata, dataIndices, data, seed }, key ->
     hashKey =
-        createLowLevelHasher PseudoRandSeed
+        createLowLevelHasher (WithSeed seed)
         |> Hash.hash key
         |> complete
     h1Key = h1 hashKey
@@ -428,9 +433,9 @@ get = \@Dict { metadata, dataIndices, data }, key ->
 ##     |> Bool.isEq Bool.true
 ## ```
 contains : Dict k v, k -> Bool where k implements Hash & Eq
-contains = \@Dict { metadata, dataIndices, data }, key ->
+contains = \@Dict { metadata, dataIndices, data, seed }, key ->
     hashKey =
-        createLowLevelHasher PseudoRandSeed
+        createLowLevelHasher (WithSeed seed)
         |> Hash.hash key
         |> complete
     h1Key = h1 hashKey
@@ -453,9 +458,9 @@ contains = \@Dict { metadata, dataIndices, data }, key ->
 ##     |> Bool.isEq (Ok 12)
 ## ```
 insert : Dict k v, k, v -> Dict k v where k implements Hash & Eq
-insert = \@Dict { metadata, dataIndices, data }, key, value ->
+insert = \@Dict { metadata, dataIndices, data, seed }, key, value ->
     hashKey =
-        createLowLevelHasher PseudoRandSeed
+        createLowLevelHasher (WithSeed seed)
         |> Hash.hash key
         |> complete
     h1Key = h1 hashKey
@@ -470,6 +475,7 @@ insert = \@Dict { metadata, dataIndices, data }, key, value ->
                 metadata,
                 dataIndices,
                 data: List.set data dataIndex (key, value),
+                seed,
             }
 
         Err NotFound ->
@@ -481,6 +487,7 @@ insert = \@Dict { metadata, dataIndices, data }, key, value ->
                             metadata,
                             dataIndices,
                             data,
+                            seed,
                         }
                     )
 
@@ -497,10 +504,10 @@ insert = \@Dict { metadata, dataIndices, data }, key, value ->
 ##     |> Bool.isEq 0
 ## ```
 remove : Dict k v, k -> Dict k v where k implements Hash & Eq
-remove = \@Dict { metadata, dataIndices, data }, key ->
+remove = \@Dict { metadata, dataIndices, data, seed }, key ->
     # TODO: change this from swap remove to tombstone and test is performance is still good.
     hashKey =
-        createLowLevelHasher PseudoRandSeed
+        createLowLevelHasher (WithSeed seed)
         |> Hash.hash key
         |> complete
     h1Key = h1 hashKey
@@ -517,12 +524,13 @@ remove = \@Dict { metadata, dataIndices, data }, key ->
                     metadata: List.set metadata index deletedSlot,
                     dataIndices,
                     data: List.dropLast data 1,
+                    seed,
                 }
             else
-                swapAndUpdateDataIndex (@Dict { metadata, dataIndices, data }) index last
+                swapAndUpdateDataIndex (@Dict { metadata, dataIndices, data, seed }) index last
 
         Err NotFound ->
-            @Dict { metadata, dataIndices, data }
+            @Dict { metadata, dataIndices, data, seed }
 
 ## Insert or remove a value for a specified key. This function enables a
 ## performance optimization for the use case of providing a default when a value
@@ -692,10 +700,10 @@ removeAll = \xs, ys ->
     walk ys xs (\state, k, _ -> remove state k)
 
 swapAndUpdateDataIndex : Dict k v, Nat, Nat -> Dict k v where k implements Hash & Eq
-swapAndUpdateDataIndex = \@Dict { metadata, dataIndices, data }, removedIndex, lastIndex ->
+swapAndUpdateDataIndex = \@Dict { metadata, dataIndices, data, seed }, removedIndex, lastIndex ->
     (key, _) = listGetUnsafe data lastIndex
     hashKey =
-        createLowLevelHasher PseudoRandSeed
+        createLowLevelHasher (WithSeed seed)
         |> Hash.hash key
         |> complete
     h1Key = h1 hashKey
@@ -717,6 +725,7 @@ swapAndUpdateDataIndex = \@Dict { metadata, dataIndices, data }, removedIndex, l
                 # Update index of swaped element.
                 dataIndices: List.set dataIndices index dataIndex,
                 data: nextData,
+                seed,
             }
 
         Err NotFound ->
@@ -724,7 +733,7 @@ swapAndUpdateDataIndex = \@Dict { metadata, dataIndices, data }, removedIndex, l
             crash "unreachable state in dict swapAndUpdateDataIndex hit. Definitely a standard library bug."
 
 insertNotFoundHelper : Dict k v, k, v, U64, I8 -> Dict k v
-insertNotFoundHelper = \@Dict { metadata, dataIndices, data }, key, value, h1Key, h2Key ->
+insertNotFoundHelper = \@Dict { metadata, dataIndices, data, seed }, key, value, h1Key, h2Key ->
     probe = newProbe h1Key (div8 (List.len metadata))
     index = nextEmptyOrDeletedHelper metadata probe 0
     dataIndex = List.len data
@@ -734,6 +743,7 @@ insertNotFoundHelper = \@Dict { metadata, dataIndices, data }, key, value, h1Key
         metadata: List.set metadata index h2Key,
         dataIndices: List.set dataIndices index dataIndex,
         data: nextData,
+        seed,
     }
 
 nextEmptyOrDeletedHelper : List I8, Probe, Nat -> Nat
@@ -786,26 +796,27 @@ findIndexHelper = \metadata, dataIndices, data, h2Key, key, probe, offset ->
 # If we aren't to the load factor yet, just ignore this.
 # The container must have an updated size including any elements about to be inserted.
 maybeRehash : Dict k v -> Dict k v where k implements Hash & Eq
-maybeRehash = \@Dict { metadata, dataIndices, data } ->
+maybeRehash = \@Dict { metadata, dataIndices, data, seed } ->
     cap = List.len dataIndices
     maxLoadCap =
         # This is 7/8 * capacity, which is the max load factor.
         Num.subWrap cap (Num.shiftRightZfBy cap 3)
 
     if (List.len data + 1) > maxLoadCap then
-        rehash (@Dict { metadata, dataIndices, data })
+        rehash (@Dict { metadata, dataIndices, data, seed })
     else
-        @Dict { metadata, dataIndices, data }
+        @Dict { metadata, dataIndices, data, seed }
 
 # TODO: switch rehash to iterate data and eventually clear out tombstones as well.
 rehash : Dict k v -> Dict k v where k implements Hash & Eq
-rehash = \@Dict { metadata, dataIndices, data } ->
+rehash = \@Dict { metadata, dataIndices, data, seed } ->
     newLen = 2 * List.len dataIndices
     newDict =
         @Dict {
             metadata: List.repeat emptySlot newLen,
             dataIndices: List.repeat 0 newLen,
             data,
+            seed,
         }
 
     rehashHelper newDict metadata dataIndices data 0
@@ -832,9 +843,9 @@ rehashHelper = \dict, oldMetadata, oldDataIndices, oldData, index ->
             dict
 
 insertForRehash : Dict k v, k, Nat -> Dict k v where k implements Hash & Eq
-insertForRehash = \@Dict { metadata, dataIndices, data }, key, dataIndex ->
+insertForRehash = \@Dict { metadata, dataIndices, data, seed }, key, dataIndex ->
     hashKey =
-        createLowLevelHasher PseudoRandSeed
+        createLowLevelHasher (WithSeed seed)
         |> Hash.hash key
         |> complete
     h1Key = h1 hashKey
@@ -846,6 +857,7 @@ insertForRehash = \@Dict { metadata, dataIndices, data }, key, dataIndex ->
         metadata: List.set metadata index h2Key,
         dataIndices: List.set dataIndices index dataIndex,
         data,
+        seed,
     }
 
 emptySlot : I8
@@ -286,21 +286,33 @@ pub const NUM_SQRT: IntrinsicName = float_intrinsic!("roc_builtins.num.sqrt");
 
 pub const NUM_POW_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.pow_int");
 pub const NUM_DIV_CEIL: IntrinsicName = int_intrinsic!("roc_builtins.num.div_ceil");
+pub const NUM_DIV_TRUNC: IntrinsicName = int_intrinsic!("roc_builtins.num.div_trunc");
+pub const NUM_REM: IntrinsicName = int_intrinsic!("roc_builtins.num.rem");
 pub const NUM_CEILING_F32: IntrinsicName = int_intrinsic!("roc_builtins.num.ceiling_f32");
 pub const NUM_CEILING_F64: IntrinsicName = int_intrinsic!("roc_builtins.num.ceiling_f64");
 pub const NUM_FLOOR_F32: IntrinsicName = int_intrinsic!("roc_builtins.num.floor_f32");
 pub const NUM_FLOOR_F64: IntrinsicName = int_intrinsic!("roc_builtins.num.floor_f64");
 pub const NUM_ROUND_F32: IntrinsicName = int_intrinsic!("roc_builtins.num.round_f32");
 pub const NUM_ROUND_F64: IntrinsicName = int_intrinsic!("roc_builtins.num.round_f64");
+pub const NUM_TO_F32: IntrinsicName = int_intrinsic!("roc_builtins.num.to_f32");
+pub const NUM_TO_F64: IntrinsicName = int_intrinsic!("roc_builtins.num.to_f64");
+pub const NUM_TO_F32_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.to_f32_checked");
+pub const NUM_TO_F64_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.to_f64_checked");
+pub const NUM_TO_F32_CHECKED_FLOAT: IntrinsicName =
+    float_intrinsic!("roc_builtins.num.to_f32_checked");
+pub const NUM_TO_F64_CHECKED_FLOAT: IntrinsicName =
+    float_intrinsic!("roc_builtins.num.to_f64_checked");
 
 pub const NUM_ADD_OR_PANIC_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_or_panic");
 pub const NUM_ADD_SATURATED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_saturated");
+pub const NUM_ADD_WRAP_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_wrapped");
 pub const NUM_ADD_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_with_overflow");
 pub const NUM_ADD_CHECKED_FLOAT: IntrinsicName =
     float_intrinsic!("roc_builtins.num.add_with_overflow");
 
 pub const NUM_SUB_OR_PANIC_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_or_panic");
 pub const NUM_SUB_SATURATED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_saturated");
+pub const NUM_SUB_WRAP_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_wrapped");
 pub const NUM_SUB_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_with_overflow");
 pub const NUM_SUB_CHECKED_FLOAT: IntrinsicName =
     float_intrinsic!("roc_builtins.num.sub_with_overflow");
@@ -312,10 +324,15 @@ pub const NUM_MUL_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.
 pub const NUM_MUL_CHECKED_FLOAT: IntrinsicName =
     float_intrinsic!("roc_builtins.num.mul_with_overflow");
 
+pub const NUM_NEGATE_WRAP_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.negate_wrapped");
+pub const NUM_ABS_WRAP_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.abs_wrapped");
+
 pub const NUM_IS_MULTIPLE_OF: IntrinsicName = int_intrinsic!("roc_builtins.num.is_multiple_of");
 
 pub const NUM_SHIFT_RIGHT_ZERO_FILL: IntrinsicName =
     int_intrinsic!("roc_builtins.num.shift_right_zero_fill");
+pub const NUM_SHIFT_LEFT: IntrinsicName = int_intrinsic!("roc_builtins.num.shift_left");
+pub const NUM_SHIFT_RIGHT: IntrinsicName = int_intrinsic!("roc_builtins.num.shift_right");
 
 pub const NUM_COMPARE: IntrinsicName = int_intrinsic!("roc_builtins.num.compare");
 pub const NUM_LESS_THAN: IntrinsicName = int_intrinsic!("roc_builtins.num.less_than");
@@ -419,6 +436,9 @@ pub const DEC_SUB_OR_PANIC: &str = "roc_builtins.dec.sub_or_panic";
 pub const DEC_SUB_SATURATED: &str = "roc_builtins.dec.sub_saturated";
 pub const DEC_SUB_WITH_OVERFLOW: &str = "roc_builtins.dec.sub_with_overflow";
 pub const DEC_TAN: &str = "roc_builtins.dec.tan";
+pub const DEC_TO_F64: &str = "roc_builtins.dec.to_f64";
+pub const DEC_TO_F32_CHECKED: &str = "roc_builtins.dec.to_f32_checked";
+pub const DEC_TO_F64_CHECKED: &str = "roc_builtins.dec.to_f64_checked";
 pub const DEC_TO_I128: &str = "roc_builtins.dec.to_i128";
 pub const DEC_TO_STR: &str = "roc_builtins.dec.to_str";
 
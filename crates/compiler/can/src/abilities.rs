@@ -122,6 +122,15 @@ pub struct ImplKey {
     pub ability_member: Symbol,
 }
 
+impl ImplKey {
+    pub fn remap_symbols(self, remap: &impl Fn(Symbol) -> Symbol) -> Self {
+        Self {
+            opaque: remap(self.opaque),
+            ability_member: remap(self.ability_member),
+        }
+    }
+}
+
 /// Fully-resolved implementation of an ability member for an opaque type.
 /// This is only fully known after type solving of the owning module.
 #[derive(Clone, Debug)]
@@ -484,6 +493,55 @@ impl IAbilitiesStore<Resolved> {
     pub fn deserialize(bytes: &[u8]) -> (Self, usize) {
         serialize::deserialize(bytes)
     }
+
+    /// Replace every symbol in the store with `remap(symbol)`, like [Subs::remap_symbols].
+    ///
+    /// [Subs::remap_symbols]: roc_types::subs::Subs::remap_symbols
+    pub fn remap_symbols(&mut self, remap: &impl Fn(Symbol) -> Symbol) {
+        self.members_of_ability = std::mem::take(&mut self.members_of_ability)
+            .into_iter()
+            .map(|(ability, members)| (remap(ability), members.into_iter().map(remap).collect()))
+            .collect();
+
+        self.specialization_to_root = std::mem::take(&mut self.specialization_to_root)
+            .into_iter()
+            .map(|(symbol, impl_key)| (remap(symbol), impl_key.remap_symbols(remap)))
+            .collect();
+
+        self.ability_members = std::mem::take(&mut self.ability_members)
+            .into_iter()
+            .map(|(member, mut data)| {
+                data.parent_ability = remap(data.parent_ability);
+
+                (remap(member), data)
+            })
+            .collect();
+
+        self.declared_implementations = std::mem::take(&mut self.declared_implementations)
+            .into_iter()
+            .map(|(impl_key, member_impl)| {
+                let member_impl = match member_impl {
+                    MemberImpl::Impl(symbol) => MemberImpl::Impl(remap(symbol)),
+                    MemberImpl::Error => MemberImpl::Error,
+                };
+
+                (impl_key.remap_symbols(remap), member_impl)
+            })
+            .collect();
+
+        self.specializations = std::mem::take(&mut self.specializations)
+            .into_iter()
+            .map(|(symbol, mut specialization)| {
+                specialization.symbol = remap(specialization.symbol);
+
+                (remap(symbol), specialization)
+            })
+            .collect();
+
+        for symbol in self.resolved_specializations.values_mut() {
+            *symbol = remap(*symbol);
+        }
+    }
 }
 
 pub use serialize::deserialize_solved_implementations;
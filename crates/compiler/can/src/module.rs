@@ -1271,6 +1271,31 @@ impl TypeState {
         Ok(written_subs + written_ab + written_solved_impls)
     }
 
+    /// Give every module the types refer to the ID `remap` returns for it, e.g. because they
+    /// were read from a cache written by a build which assigned module IDs in another order.
+    pub fn remap_module_ids(&mut self, remap: impl Fn(ModuleId) -> ModuleId) {
+        let remap_symbol =
+            |symbol: Symbol| Symbol::new(remap(symbol.module_id()), symbol.ident_id());
+
+        self.subs.remap_symbols(&remap_symbol);
+        self.abilities.remap_symbols(&remap_symbol);
+
+        for (symbol, _) in self.exposed_vars_by_symbol.iter_mut() {
+            *symbol = remap_symbol(*symbol);
+        }
+
+        self.solved_implementations = std::mem::take(&mut self.solved_implementations)
+            .into_iter()
+            .map(|(impl_key, mut resolved_impl)| {
+                if let ResolvedImpl::Impl(specialization) = &mut resolved_impl {
+                    specialization.symbol = remap_symbol(specialization.symbol);
+                }
+
+                (impl_key.remap_symbols(&remap_symbol), resolved_impl)
+            })
+            .collect();
+    }
+
     pub fn deserialize(bytes: &[u8]) -> (Self, usize) {
         let ((subs, exposed_vars_by_symbol), len_subs) = Subs::deserialize(bytes);
         let bytes = &bytes[len_subs..];
@@ -164,7 +164,7 @@ pub fn walk_decls<V: Visitor>(visitor: &mut V, decls: &Declarations) {
     }
 }
 
-fn walk_decl<V: Visitor>(visitor: &mut V, decl: DeclarationInfo<'_>) {
+pub fn walk_decl<V: Visitor>(visitor: &mut V, decl: DeclarationInfo<'_>) {
     use DeclarationInfo::*;
 
     match decl {
@@ -628,6 +628,119 @@ pub fn walk_record_destruct<V: Visitor>(visitor: &mut V, destruct: &RecordDestru
     }
 }
 
+/// Every symbol bound by a pattern in `decls`, along with the region of the pattern binding it.
+/// This covers top-level definitions as well as nested definitions, function arguments and the
+/// patterns of `when` branches.
+pub fn find_bound_symbols(decls: &Declarations) -> Vec<(Symbol, Region)> {
+    let mut collector = BoundSymbols { found: Vec::new() };
+    collector.visit_decls(decls);
+    collector.found
+}
+
+/// Where a symbol that is bound locally is in scope: the function whose argument it is, the
+/// definition that binds it along with the expression that follows, or the `when` branch whose
+/// pattern binds it. Symbols defined at the top level are in scope throughout their module, and
+/// [None] is returned for them.
+pub fn find_binding_scope(symbol: Symbol, decls: &Declarations) -> Option<Region> {
+    let mut visitor = Finder {
+        symbol,
+        scope: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.scope;
+
+    struct Finder {
+        symbol: Symbol,
+        scope: Option<Region>,
+    }
+
+    impl Finder {
+        fn binds(&self, loc_pattern: &Loc<Pattern>) -> bool {
+            symbols_introduced_from_pattern(loc_pattern).any(|symbol| symbol.value == self.symbol)
+        }
+    }
+
+    impl Visitor for Finder {
+        fn should_visit(&mut self, _region: Region) -> bool {
+            self.scope.is_none()
+        }
+
+        fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+            if let DeclarationInfo::Function { function, .. } = &decl {
+                if function
+                    .value
+                    .arguments
+                    .iter()
+                    .any(|(_, _, argument)| self.binds(argument))
+                {
+                    self.scope = Some(decl.region());
+                }
+            }
+
+            if self.should_visit(decl.region()) {
+                walk_decl(self, decl);
+            }
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            if !self.should_visit(region) {
+                return;
+            }
+
+            let binding_region = match expr {
+                Expr::Closure(ClosureData { arguments, .. }) => arguments
+                    .iter()
+                    .any(|(_, _, argument)| self.binds(argument))
+                    .then_some(region),
+                Expr::LetNonRec(def, _) => self.binds(&def.loc_pattern).then_some(region),
+                Expr::LetRec(defs, _, _) => defs
+                    .iter()
+                    .any(|def| self.binds(&def.loc_pattern))
+                    .then_some(region),
+                Expr::When { branches, .. } => branches
+                    .iter()
+                    .find(|branch| branch.patterns.iter().any(|p| self.binds(&p.pattern)))
+                    .map(|branch| branch.region()),
+                _ => None,
+            };
+
+            match binding_region {
+                Some(binding_region) => self.scope = Some(binding_region),
+                None => walk_expr(self, expr, var),
+            }
+        }
+    }
+}
+
+struct BoundSymbols {
+    found: Vec<(Symbol, Region)>,
+}
+
+impl Visitor for BoundSymbols {
+    fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+        match pattern {
+            Pattern::Identifier(symbol)
+            | Pattern::Shadowed(_, _, symbol)
+            | Pattern::AbilityMemberSpecialization { ident: symbol, .. }
+            | Pattern::As(_, symbol) => self.found.push((*symbol, region)),
+            _ => {}
+        }
+
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+        match destruct.typ {
+            DestructType::Required | DestructType::Optional(..) => {
+                self.found.push((destruct.symbol, region))
+            }
+            DestructType::Guard(..) => {}
+        }
+
+        walk_record_destruct(self, destruct);
+    }
+}
+
 struct TypeAtVisitor {
     region: Region,
     typ: Option<Variable>,
@@ -963,3 +1076,142 @@ pub fn find_declaration(symbol: Symbol, decls: &'_ Declarations) -> Option<Found
         }
     }
 }
+
+/// How a symbol appears at a region found by [find_symbol_occurrences].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccurrenceKind {
+    /// `symbol` is bound by an identifier pattern; the region is exactly the symbol.
+    Definition,
+    /// The region is exactly the symbol, possibly qualified by its module name.
+    Exact,
+    /// `{ symbol }` in a record literal, where the symbol is also the name of the field.
+    PunnedField,
+    /// `pattern as symbol`; the symbol is at the end of the region.
+    AsPattern,
+    /// `{ symbol }` or `{ symbol ? default }` in a record destructure, where the symbol is also
+    /// the name of the destructured field.
+    RecordDestruct { optional: bool },
+    /// `{ symbol & field: value }`; the symbol is near the start of the region.
+    RecordUpdate,
+    /// The region is the type in `symbol : type`; the symbol precedes it.
+    Annotation,
+}
+
+/// Finds every region where `symbol` is defined, annotated or referenced.
+pub fn find_symbol_occurrences(
+    symbol: Symbol,
+    decls: &Declarations,
+) -> Vec<(Region, OccurrenceKind)> {
+    let mut visitor = Finder {
+        symbol,
+        found: Vec::new(),
+    };
+
+    for (loc_symbol, annotation) in decls.symbols.iter().zip(decls.annotations.iter()) {
+        match annotation {
+            Some(annotation) if loc_symbol.value == symbol => {
+                visitor
+                    .found
+                    .push((annotation.region, OccurrenceKind::Annotation));
+            }
+            _ => {}
+        }
+    }
+
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder {
+        symbol: Symbol,
+        found: Vec<(Region, OccurrenceKind)>,
+    }
+
+    impl Finder {
+        fn find_punned_fields<'a>(&mut self, fields: impl Iterator<Item = &'a Field>) {
+            for field in fields {
+                // `{ x }` desugars to `{ x: x }` where both the field and its value have the
+                // region of the label.
+                let is_punned = match field.loc_expr.value {
+                    Expr::Var(symbol, _) => {
+                        symbol == self.symbol && field.region == field.loc_expr.region
+                    }
+                    _ => false,
+                };
+
+                if is_punned {
+                    self.found.push((field.region, OccurrenceKind::PunnedField));
+                }
+            }
+        }
+    }
+
+    impl Visitor for Finder {
+        fn visit_def(&mut self, def: &Def) {
+            if let (Pattern::Identifier(s), Some(annotation)) =
+                (&def.loc_pattern.value, &def.annotation)
+            {
+                if *s == self.symbol {
+                    self.found
+                        .push((annotation.region, OccurrenceKind::Annotation));
+                }
+            }
+
+            walk_def(self, def);
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            match expr {
+                Expr::Var(symbol, _) | Expr::AbilityMember(symbol, _, _)
+                    if *symbol == self.symbol =>
+                {
+                    let already_found_as_punned_field =
+                        self.found.contains(&(region, OccurrenceKind::PunnedField));
+
+                    if !already_found_as_punned_field {
+                        self.found.push((region, OccurrenceKind::Exact));
+                    }
+                }
+                Expr::Record { fields, .. } => self.find_punned_fields(fields.values()),
+                Expr::RecordUpdate {
+                    symbol, updates, ..
+                } => {
+                    if *symbol == self.symbol {
+                        self.found.push((region, OccurrenceKind::RecordUpdate));
+                    }
+
+                    self.find_punned_fields(updates.values());
+                }
+                _ => {}
+            }
+
+            walk_expr(self, expr, var);
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            match pattern {
+                Pattern::Identifier(symbol)
+                | Pattern::AbilityMemberSpecialization { ident: symbol, .. }
+                    if *symbol == self.symbol =>
+                {
+                    self.found.push((region, OccurrenceKind::Definition));
+                }
+                Pattern::As(_, symbol) if *symbol == self.symbol => {
+                    self.found.push((region, OccurrenceKind::AsPattern));
+                }
+                _ => {}
+            }
+
+            walk_pattern(self, pattern);
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            if destruct.symbol == self.symbol {
+                let optional = matches!(destruct.typ, DestructType::Optional(..));
+                self.found
+                    .push((region, OccurrenceKind::RecordDestruct { optional }));
+            }
+
+            walk_record_destruct(self, destruct);
+        }
+    }
+}
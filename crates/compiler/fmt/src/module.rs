@@ -9,8 +9,9 @@ use roc_parse::ast::{Collection, Header, Module, Spaced, Spaces};
 use roc_parse::header::{
     AppHeader, ExposedName, ExposesKeyword, GeneratesKeyword, HostedHeader, ImportsEntry,
     ImportsKeyword, InterfaceHeader, Keyword, KeywordItem, ModuleName, PackageEntry, PackageHeader,
-    PackageKeyword, PackageName, PackagesKeyword, PlatformHeader, PlatformRequires,
-    ProvidesKeyword, ProvidesTo, RequiresKeyword, To, ToKeyword, TypedIdent, WithKeyword,
+    PackageKeyword, PackageName, PackageVersion, PackagesKeyword, PlatformHeader, PlatformRequires,
+    ProvidesKeyword, ProvidesTo, RequiresKeyword, To, ToKeyword, TypedIdent, VersionKeyword,
+    WithKeyword,
 };
 use roc_parse::ident::UppercaseIdent;
 use roc_region::all::Loc;
@@ -75,6 +76,7 @@ keywords! {
     RequiresKeyword,
     ProvidesKeyword,
     ToKeyword,
+    VersionKeyword,
 }
 
 impl<V: Formattable> Formattable for Option<V> {
@@ -231,6 +233,11 @@ pub fn fmt_package_header<'a>(buf: &mut Buf, header: &'a PackageHeader<'a>) {
 
     fmt_package_name(buf, header.name.value, indent);
 
+    if let Some(version) = &header.version {
+        version.keyword.format(buf, indent);
+        fmt_package_version(buf, version.item.value, indent);
+    }
+
     header.exposes.keyword.format(buf, indent);
     fmt_exposes(buf, header.exposes.item, indent);
     header.packages.keyword.format(buf, indent);
@@ -294,6 +301,13 @@ fn fmt_package_name(buf: &mut Buf, name: PackageName, indent: u16) {
     buf.push('"');
 }
 
+fn fmt_package_version(buf: &mut Buf, version: PackageVersion, indent: u16) {
+    buf.indent(indent);
+    buf.push('"');
+    buf.push_str_allow_spaces(version.as_str());
+    buf.push('"');
+}
+
 impl<'a, T: Formattable> Formattable for Spaced<'a, T> {
     fn is_multiline(&self) -> bool {
         use Spaced::*;
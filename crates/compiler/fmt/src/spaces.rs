@@ -10,8 +10,8 @@ use roc_parse::{
     },
     header::{
         AppHeader, ExposedName, HostedHeader, ImportsEntry, InterfaceHeader, KeywordItem,
-        ModuleName, PackageEntry, PackageHeader, PackageName, PlatformHeader, PlatformRequires,
-        ProvidesTo, To, TypedIdent,
+        ModuleName, PackageEntry, PackageHeader, PackageName, PackageVersion, PlatformHeader,
+        PlatformRequires, ProvidesTo, To, TypedIdent,
     },
     ident::{BadIdent, UppercaseIdent},
 };
@@ -296,6 +296,7 @@ impl<'a> RemoveSpaces<'a> for Module<'a> {
             Header::Package(header) => Header::Package(PackageHeader {
                 before_name: &[],
                 name: header.name.remove_spaces(arena),
+                version: header.version.remove_spaces(arena),
                 exposes: header.exposes.remove_spaces(arena),
                 packages: header.packages.remove_spaces(arena),
             }),
@@ -358,6 +359,12 @@ impl<'a> RemoveSpaces<'a> for ModuleName<'a> {
     }
 }
 
+impl<'a> RemoveSpaces<'a> for PackageVersion<'a> {
+    fn remove_spaces(&self, _arena: &'a Bump) -> Self {
+        *self
+    }
+}
+
 impl<'a> RemoveSpaces<'a> for PackageName<'a> {
     fn remove_spaces(&self, _arena: &'a Bump) -> Self {
         *self
@@ -0,0 +1,682 @@
+//! DWARF debug info for the object files of the dev backend, so that debuggers and profilers
+//! can map generated code back to the Roc source.
+//!
+//! Every proc whose definition we know the location of gets a subprogram in `.debug_info`, which
+//! points at the line the proc is defined on, and a sequence in the line table in `.debug_line`,
+//! with a row for each statement we know the location of. There is no information about
+//! variables yet.
+//!
+//! ELF and Mach-O object files get debug info. On Mach-O it goes in the `__DWARF` segment, which
+//! the linker leaves in the object files for `dsymutil` to find.
+
+use object::write::{self, Object, SectionId, SymbolId};
+use object::{BinaryFormat, RelocationEncoding, RelocationKind, SectionKind};
+use roc_collections::all::MutMap;
+use roc_error_macros::internal_error;
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::source_locations::SourceLocations;
+use roc_region::all::LineColumn;
+
+const DW_TAG_COMPILE_UNIT: u64 = 0x11;
+const DW_TAG_SUBPROGRAM: u64 = 0x2e;
+
+const DW_CHILDREN_NO: u8 = 0;
+const DW_CHILDREN_YES: u8 = 1;
+
+const DW_AT_NAME: u64 = 0x03;
+const DW_AT_STMT_LIST: u64 = 0x10;
+const DW_AT_LOW_PC: u64 = 0x11;
+const DW_AT_HIGH_PC: u64 = 0x12;
+const DW_AT_LANGUAGE: u64 = 0x13;
+const DW_AT_COMP_DIR: u64 = 0x1b;
+const DW_AT_PRODUCER: u64 = 0x25;
+const DW_AT_DECL_FILE: u64 = 0x3a;
+const DW_AT_DECL_LINE: u64 = 0x3b;
+const DW_AT_LINKAGE_NAME: u64 = 0x6e;
+
+const DW_FORM_ADDR: u64 = 0x01;
+const DW_FORM_DATA2: u64 = 0x05;
+const DW_FORM_DATA8: u64 = 0x07;
+const DW_FORM_STRING: u64 = 0x08;
+const DW_FORM_DATA1: u64 = 0x0b;
+const DW_FORM_UDATA: u64 = 0x0f;
+const DW_FORM_SEC_OFFSET: u64 = 0x17;
+
+/// There is no language code for Roc. Like the LLVM backend, we say Haskell, its closest relative
+/// that has one, rather than have debuggers treat the code as C.
+const DW_LANG_HASKELL: u16 = 0x18;
+
+const DW_LNS_COPY: u8 = 0x01;
+const DW_LNS_ADVANCE_PC: u8 = 0x02;
+const DW_LNS_ADVANCE_LINE: u8 = 0x03;
+const DW_LNS_SET_COLUMN: u8 = 0x05;
+const DW_LNE_END_SEQUENCE: u8 = 0x01;
+const DW_LNE_SET_ADDRESS: u8 = 0x02;
+
+const ABBREV_COMPILE_UNIT: u64 = 1;
+const ABBREV_SUBPROGRAM: u64 = 2;
+
+/// The version of DWARF we emit. Version 4 is understood by every debugger and profiler we
+/// care about, and needs no string offset tables.
+const DWARF_VERSION: u16 = 4;
+
+const ADDRESS_SIZE: u8 = 8;
+
+/// The code of a proc in the object file.
+pub(crate) struct ProcCode {
+    pub symbol: Symbol,
+    pub symbol_id: SymbolId,
+    pub linkage_name: String,
+    pub size: u64,
+    /// Where the code of each statement starts, relative to the start of the proc, and the
+    /// symbol that the statement binds or uses
+    pub statements: Vec<(u64, Symbol)>,
+}
+
+/// Adds `.debug_abbrev`, `.debug_info` and `.debug_line` sections describing `procs`, with a
+/// compilation unit for each Roc module they are from.
+pub(crate) fn add_debug_sections(
+    output: &mut Object,
+    source_locations: &SourceLocations,
+    interns: &Interns,
+    procs: &[ProcCode],
+) {
+    if !matches!(output.format(), BinaryFormat::Elf | BinaryFormat::MachO) {
+        return;
+    }
+
+    let mut by_module: MutMap<ModuleId, Vec<&ProcCode>> = MutMap::default();
+    for proc in procs {
+        if source_locations.position(proc.symbol).is_some() {
+            by_module
+                .entry(proc.symbol.module_id())
+                .or_default()
+                .push(proc);
+        }
+    }
+
+    if by_module.is_empty() {
+        return;
+    }
+
+    // Sorted, so that the same program always gets the same debug info
+    let mut modules: Vec<_> = by_module.into_iter().collect();
+    modules.sort_by_key(|(module_id, _)| interns.module_name(*module_id).as_str().to_string());
+
+    let abbrev_section = add_debug_section(output, ".debug_abbrev");
+    let info_section = add_debug_section(output, ".debug_info");
+    let line_section = add_debug_section(output, ".debug_line");
+
+    output.append_section_data(abbrev_section, &abbreviations(), 1);
+
+    let mut info = DebugSection::new(info_section);
+    let mut line = DebugSection::new(line_section);
+
+    for (module_id, procs) in modules {
+        let path = match source_locations.path(module_id) {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let file_name = path
+            .file_name()
+            .map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy());
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy(),
+            _ => ".".into(),
+        };
+
+        let line_program_offset = line.bytes.len() as u64;
+        line_program(&mut line, &file_name, &directory, source_locations, &procs);
+
+        compile_unit(
+            &mut info,
+            output,
+            abbrev_section,
+            line_section,
+            line_program_offset,
+            &file_name,
+            &directory,
+            source_locations,
+            interns,
+            &procs,
+        );
+    }
+
+    info.finish(output);
+    line.finish(output);
+}
+
+fn add_debug_section(output: &mut Object, name: &str) -> SectionId {
+    match output.format() {
+        // e.g. `__DWARF,__debug_line` for `.debug_line`
+        BinaryFormat::MachO => output.add_section(
+            b"__DWARF".to_vec(),
+            name.replacen('.', "__", 1).into_bytes(),
+            SectionKind::Debug,
+        ),
+        _ => output.add_section(vec![], name.as_bytes().to_vec(), SectionKind::Debug),
+    }
+}
+
+fn abbreviations() -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    let mut abbreviation = |code, tag, children, attributes: &[(u64, u64)]| {
+        write_uleb128(&mut bytes, code);
+        write_uleb128(&mut bytes, tag);
+        bytes.push(children);
+
+        for (name, form) in attributes {
+            write_uleb128(&mut bytes, *name);
+            write_uleb128(&mut bytes, *form);
+        }
+
+        bytes.extend([0, 0]);
+    };
+
+    abbreviation(
+        ABBREV_COMPILE_UNIT,
+        DW_TAG_COMPILE_UNIT,
+        DW_CHILDREN_YES,
+        &[
+            (DW_AT_PRODUCER, DW_FORM_STRING),
+            (DW_AT_LANGUAGE, DW_FORM_DATA2),
+            (DW_AT_NAME, DW_FORM_STRING),
+            (DW_AT_COMP_DIR, DW_FORM_STRING),
+            (DW_AT_STMT_LIST, DW_FORM_SEC_OFFSET),
+        ],
+    );
+
+    abbreviation(
+        ABBREV_SUBPROGRAM,
+        DW_TAG_SUBPROGRAM,
+        DW_CHILDREN_NO,
+        &[
+            (DW_AT_NAME, DW_FORM_STRING),
+            (DW_AT_LINKAGE_NAME, DW_FORM_STRING),
+            (DW_AT_DECL_FILE, DW_FORM_DATA1),
+            (DW_AT_DECL_LINE, DW_FORM_UDATA),
+            (DW_AT_LOW_PC, DW_FORM_ADDR),
+            (DW_AT_HIGH_PC, DW_FORM_DATA8),
+        ],
+    );
+
+    // the end of the table
+    bytes.push(0);
+
+    bytes
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compile_unit(
+    info: &mut DebugSection,
+    output: &mut Object,
+    abbrev_section: SectionId,
+    line_section: SectionId,
+    line_program_offset: u64,
+    file_name: &str,
+    directory: &str,
+    source_locations: &SourceLocations,
+    interns: &Interns,
+    procs: &[&ProcCode],
+) {
+    let unit_length = info.reserve_u32();
+    let start = info.bytes.len();
+
+    info.bytes.extend(DWARF_VERSION.to_le_bytes());
+    info.section_offset(output, abbrev_section, 0);
+    info.bytes.push(ADDRESS_SIZE);
+
+    write_uleb128(&mut info.bytes, ABBREV_COMPILE_UNIT);
+    write_string(&mut info.bytes, "roc");
+    info.bytes.extend(DW_LANG_HASKELL.to_le_bytes());
+    write_string(&mut info.bytes, file_name);
+    write_string(&mut info.bytes, directory);
+    info.section_offset(output, line_section, line_program_offset);
+
+    for proc in procs {
+        let position = source_locations.position(proc.symbol).unwrap();
+        let name = format!(
+            "{}.{}",
+            proc.symbol.module_string(interns).as_str(),
+            proc.symbol.as_str(interns)
+        );
+
+        write_uleb128(&mut info.bytes, ABBREV_SUBPROGRAM);
+        write_string(&mut info.bytes, &name);
+        write_string(&mut info.bytes, &proc.linkage_name);
+        // the one file of this compilation unit
+        info.bytes.push(1);
+        write_uleb128(&mut info.bytes, position.line as u64 + 1);
+        info.address(proc.symbol_id);
+        info.bytes.extend(proc.size.to_le_bytes());
+    }
+
+    // the end of the children of the compilation unit
+    info.bytes.push(0);
+
+    info.patch_u32(unit_length, (info.bytes.len() - start) as u32);
+}
+
+/// A line program with a sequence for each proc, which maps its code to the lines of its
+/// statements.
+fn line_program(
+    line: &mut DebugSection,
+    file_name: &str,
+    directory: &str,
+    source_locations: &SourceLocations,
+    procs: &[&ProcCode],
+) {
+    // Opcodes from 13 on are special opcodes, which we don't use, so these are the defaults
+    const LINE_BASE: i8 = -5;
+    const LINE_RANGE: u8 = 14;
+    const OPCODE_BASE: u8 = 13;
+    const STANDARD_OPCODE_LENGTHS: [u8; 12] = [0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1];
+
+    let unit_length = line.reserve_u32();
+    let start = line.bytes.len();
+
+    line.bytes.extend(DWARF_VERSION.to_le_bytes());
+
+    let header_length = line.reserve_u32();
+    let header_start = line.bytes.len();
+
+    line.bytes.extend([
+        /* minimum_instruction_length */ 1,
+        /* maximum_operations_per_instruction */ 1,
+        /* default_is_stmt */ 1,
+        LINE_BASE as u8,
+        LINE_RANGE,
+        OPCODE_BASE,
+    ]);
+    line.bytes.extend(STANDARD_OPCODE_LENGTHS);
+
+    // include_directories
+    write_string(&mut line.bytes, directory);
+    line.bytes.push(0);
+
+    // file_names: the name, the index of its directory, its modification time and its length
+    write_string(&mut line.bytes, file_name);
+    line.bytes.extend([1, 0, 0]);
+    line.bytes.push(0);
+
+    line.patch_u32(header_length, (line.bytes.len() - header_start) as u32);
+
+    for proc in procs {
+        line.bytes.extend([0, 1 + ADDRESS_SIZE, DW_LNE_SET_ADDRESS]);
+        line.address(proc.symbol_id);
+
+        // every sequence starts at line 1, which is line 0 for a `LineColumn`
+        let mut previous_offset = 0;
+        let mut previous_line = 0;
+
+        for (offset, position) in line_rows(source_locations, proc) {
+            line.bytes.push(DW_LNS_ADVANCE_PC);
+            write_uleb128(&mut line.bytes, offset - previous_offset);
+
+            line.bytes.push(DW_LNS_ADVANCE_LINE);
+            write_sleb128(&mut line.bytes, position.line as i64 - previous_line);
+
+            line.bytes.push(DW_LNS_SET_COLUMN);
+            write_uleb128(&mut line.bytes, position.column as u64 + 1);
+
+            line.bytes.push(DW_LNS_COPY);
+
+            previous_offset = offset;
+            previous_line = position.line as i64;
+        }
+
+        line.bytes.push(DW_LNS_ADVANCE_PC);
+        write_uleb128(&mut line.bytes, proc.size - previous_offset);
+
+        line.bytes.extend([0, 1, DW_LNE_END_SEQUENCE]);
+    }
+
+    line.patch_u32(unit_length, (line.bytes.len() - start) as u32);
+}
+
+/// The rows of the line table for `proc`: where the proc starts, followed by where each of its
+/// statements that we know the location of starts. When statements start at the same offset
+/// (e.g. because some of them need no code), only the last of them has code there.
+fn line_rows(source_locations: &SourceLocations, proc: &ProcCode) -> Vec<(u64, LineColumn)> {
+    let start = source_locations.position(proc.symbol).unwrap();
+    let mut rows = vec![(0, start)];
+
+    for (offset, symbol) in proc.statements.iter().copied() {
+        // The line table of a proc only has the file the proc is defined in
+        if symbol.module_id() != proc.symbol.module_id() || offset >= proc.size {
+            continue;
+        }
+
+        let Some(position) = source_locations.position(symbol) else {
+            continue;
+        };
+
+        match rows.last_mut() {
+            Some((last_offset, last_position)) if *last_offset == offset => {
+                *last_position = position;
+            }
+            _ => rows.push((offset, position)),
+        }
+    }
+
+    rows
+}
+
+/// The contents of a debug section, and the relocations they need once they are in the object
+/// file.
+struct DebugSection {
+    id: SectionId,
+    bytes: Vec<u8>,
+    relocations: Vec<write::Relocation>,
+}
+
+impl DebugSection {
+    fn new(id: SectionId) -> Self {
+        Self {
+            id,
+            bytes: Vec::new(),
+            relocations: Vec::new(),
+        }
+    }
+
+    fn reserve_u32(&mut self) -> usize {
+        let offset = self.bytes.len();
+        self.bytes.extend([0; 4]);
+
+        offset
+    }
+
+    fn patch_u32(&mut self, offset: usize, value: u32) {
+        self.bytes[offset..][..4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// The address of `symbol`, which the linker fills in.
+    fn address(&mut self, symbol: SymbolId) {
+        self.relocation(symbol, 64, 0);
+        self.bytes.extend([0; ADDRESS_SIZE as usize]);
+    }
+
+    /// An offset into `section`. The ELF linker puts the sections of all object files together,
+    /// so it has to fill these in too. The Mach-O linker leaves the debug info in the object
+    /// files, so there the offsets stay as they are.
+    fn section_offset(&mut self, output: &mut Object, section: SectionId, offset: u64) {
+        if output.format() == BinaryFormat::MachO {
+            self.bytes.extend((offset as u32).to_le_bytes());
+        } else {
+            let symbol = output.section_symbol(section);
+
+            self.relocation(symbol, 32, offset as i64);
+            self.bytes.extend([0; 4]);
+        }
+    }
+
+    fn relocation(&mut self, symbol: SymbolId, size: u8, addend: i64) {
+        self.relocations.push(write::Relocation {
+            offset: self.bytes.len() as u64,
+            size,
+            kind: RelocationKind::Absolute,
+            encoding: RelocationEncoding::Generic,
+            symbol,
+            addend,
+        });
+    }
+
+    fn finish(self, output: &mut Object) {
+        output.append_section_data(self.id, &self.bytes, 1);
+
+        for relocation in self.relocations {
+            if let Err(e) = output.add_relocation(self.id, relocation) {
+                internal_error!("{:?}", e);
+            }
+        }
+    }
+}
+
+fn write_string(bytes: &mut Vec<u8>, string: &str) {
+    bytes.extend(string.as_bytes());
+    bytes.push(0);
+}
+
+fn write_uleb128(bytes: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+
+        bytes.push(byte | 0x80);
+    }
+}
+
+fn write_sleb128(bytes: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+
+        if done {
+            bytes.push(byte);
+            return;
+        }
+
+        bytes.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object::read::{Object as _, ObjectSection as _};
+    use object::write::{StandardSection, SymbolSection};
+    use object::{Architecture, Endianness, SymbolFlags, SymbolKind, SymbolScope};
+    use roc_module::symbol::{IdentIds, ModuleIds};
+    use roc_region::all::{Position, Region};
+    use std::path::PathBuf;
+
+    const SOURCE: &str = "main =\n    x = 1\n    x + 2\n";
+
+    /// An object file with a 16 byte `main` proc in it, whose statements start at offsets 4 and 10
+    fn object_with_debug_info(format: BinaryFormat) -> Vec<u8> {
+        let mut module_ids = ModuleIds::default();
+        let home = module_ids.get_or_insert(&"Test".into());
+
+        let mut all_ident_ids = IdentIds::exposed_builtins(1);
+        let ident_ids = all_ident_ids.get_or_insert(home);
+        let main = Symbol::new(home, ident_ids.add_str("main"));
+        let x = Symbol::new(home, ident_ids.add_str("x"));
+        let sum = Symbol::new(home, ident_ids.gen_unique());
+
+        let interns = Interns {
+            module_ids,
+            all_ident_ids,
+        };
+
+        let region_of = |text: &str| {
+            let start = SOURCE.find(text).unwrap() as u32;
+            Region::new(
+                Position::new(start),
+                Position::new(start + text.len() as u32),
+            )
+        };
+
+        let mut sources = MutMap::default();
+        sources.insert(home, (PathBuf::from("src/Test.roc"), SOURCE.into()));
+
+        let mut symbol_regions = MutMap::default();
+        symbol_regions.insert(main, region_of("main"));
+        symbol_regions.insert(x, region_of("x = 1"));
+        symbol_regions.insert(sum, region_of("x + 2"));
+
+        let source_locations = SourceLocations::new(&sources, symbol_regions);
+
+        let mut output = Object::new(format, Architecture::X86_64, Endianness::Little);
+        let text = output.section_id(StandardSection::Text);
+        let offset = output.append_section_data(text, &[0x90; 16], 16);
+        let symbol_id = output.add_symbol(write::Symbol {
+            name: b"roc_main".to_vec(),
+            value: offset,
+            size: 16,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+
+        let proc = ProcCode {
+            symbol: main,
+            symbol_id,
+            linkage_name: "roc_main".to_string(),
+            size: 16,
+            statements: vec![(4, x), (10, sum)],
+        };
+
+        add_debug_sections(&mut output, &source_locations, &interns, &[proc]);
+
+        output.write().unwrap()
+    }
+
+    type Dwarf<'a> = gimli::Dwarf<gimli::EndianSlice<'a, gimli::LittleEndian>>;
+
+    fn load_dwarf<'a>(object: &object::File<'a>) -> Dwarf<'a> {
+        gimli::Dwarf::load(|id| -> Result<_, gimli::Error> {
+            let data = object
+                .section_by_name(id.name())
+                .and_then(|section| section.data().ok())
+                .unwrap_or_default();
+
+            Ok(gimli::EndianSlice::new(data, gimli::LittleEndian))
+        })
+        .unwrap()
+    }
+
+    /// The address, file, line and column of every row of the line table
+    fn line_rows(dwarf: &Dwarf) -> Vec<(u64, String, u64, u64)> {
+        let mut units = dwarf.units();
+        let unit = dwarf.unit(units.next().unwrap().unwrap()).unwrap();
+        let mut rows = unit.line_program.clone().unwrap().rows();
+
+        let mut line_rows = Vec::new();
+        while let Some((header, row)) = rows.next_row().unwrap() {
+            if row.end_sequence() {
+                continue;
+            }
+
+            let file = row.file(header).unwrap();
+            let file_name = dwarf.attr_string(&unit, file.path_name()).unwrap();
+            let column = match row.column() {
+                gimli::ColumnType::LeftEdge => 0,
+                gimli::ColumnType::Column(column) => column.get(),
+            };
+
+            line_rows.push((
+                row.address(),
+                file_name.to_string_lossy().into_owned(),
+                row.line().unwrap().get(),
+                column,
+            ));
+        }
+
+        line_rows
+    }
+
+    /// The name and line of every subprogram
+    fn subprograms(dwarf: &Dwarf) -> Vec<(String, u64)> {
+        let mut units = dwarf.units();
+        let unit = dwarf.unit(units.next().unwrap().unwrap()).unwrap();
+        let mut entries = unit.entries();
+
+        let mut subprograms = Vec::new();
+        while let Some((_, entry)) = entries.next_dfs().unwrap() {
+            if entry.tag() != gimli::DW_TAG_subprogram {
+                continue;
+            }
+
+            let name = entry.attr_value(gimli::DW_AT_name).unwrap().unwrap();
+            let name = dwarf.attr_string(&unit, name).unwrap();
+            let line = entry.attr_value(gimli::DW_AT_decl_line).unwrap().unwrap();
+
+            subprograms.push((
+                name.to_string_lossy().into_owned(),
+                line.udata_value().unwrap(),
+            ));
+        }
+
+        subprograms
+    }
+
+    fn assert_maps_to_source(object_bytes: &[u8]) {
+        let object = object::File::parse(object_bytes).unwrap();
+        let dwarf = load_dwarf(&object);
+
+        // the addresses are relocations, which are 0 until the linker fills them in
+        assert_eq!(
+            line_rows(&dwarf),
+            [
+                (0, "Test.roc".to_string(), 1, 1),
+                (4, "Test.roc".to_string(), 2, 5),
+                (10, "Test.roc".to_string(), 3, 5),
+            ]
+        );
+        assert_eq!(subprograms(&dwarf), [("Test.main".to_string(), 1)]);
+    }
+
+    #[test]
+    fn elf_debug_info() {
+        assert_maps_to_source(&object_with_debug_info(BinaryFormat::Elf));
+    }
+
+    #[test]
+    fn macho_debug_info() {
+        let bytes = object_with_debug_info(BinaryFormat::MachO);
+        let object = object::File::parse(&*bytes).unwrap();
+
+        for name in ["__debug_abbrev", "__debug_info", "__debug_line"] {
+            let section = object.section_by_name(name).unwrap();
+            assert_eq!(section.segment_name().unwrap(), Some("__DWARF"));
+        }
+
+        assert_maps_to_source(&bytes);
+    }
+
+    fn uleb128(value: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_uleb128(&mut bytes, value);
+        bytes
+    }
+
+    fn sleb128(value: i64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_sleb128(&mut bytes, value);
+        bytes
+    }
+
+    // The examples from section 7.6 of the DWARF 4 standard
+    #[test]
+    fn unsigned_leb128() {
+        assert_eq!(uleb128(2), [2]);
+        assert_eq!(uleb128(127), [127]);
+        assert_eq!(uleb128(128), [0x80, 1]);
+        assert_eq!(uleb128(129), [0x81, 1]);
+        assert_eq!(uleb128(130), [0x82, 1]);
+        assert_eq!(uleb128(12857), [0xb9, 100]);
+    }
+
+    #[test]
+    fn signed_leb128() {
+        assert_eq!(sleb128(2), [2]);
+        assert_eq!(sleb128(-2), [0x7e]);
+        assert_eq!(sleb128(127), [0xff, 0]);
+        assert_eq!(sleb128(-127), [0x81, 0x7f]);
+        assert_eq!(sleb128(128), [0x80, 1]);
+        assert_eq!(sleb128(-128), [0x80, 0x7f]);
+        assert_eq!(sleb128(129), [0x81, 1]);
+        assert_eq!(sleb128(-129), [0xff, 0x7e]);
+    }
+}
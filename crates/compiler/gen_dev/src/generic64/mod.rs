@@ -746,6 +746,8 @@ pub struct Backend64Bit<
     caller_procs: Vec<'a, CallerProc<'a>>,
     buf: Vec<'a, u8>,
     relocs: Vec<'a, Relocation>,
+    /// Where the statements of the current proc start in `buf`, for debug info
+    statement_offsets: Vec<'a, (u64, Symbol)>,
     proc_name: Option<String>,
     is_self_recursive: Option<SelfRecursive>,
 
@@ -786,6 +788,7 @@ pub fn new_backend_64bit<
         is_self_recursive: None,
         buf: bumpalo::vec![in env.arena],
         relocs: bumpalo::vec![in env.arena],
+        statement_offsets: bumpalo::vec![in env.arena],
         last_seen_map: MutMap::default(),
         layout_map: MutMap::default(),
         free_map: MutMap::default(),
@@ -873,9 +876,20 @@ impl<
         self.join_map.clear();
         self.free_map.clear();
         self.buf.clear();
+        self.statement_offsets.clear();
         self.storage_manager.reset();
     }
 
+    fn mark_statement(&mut self, symbol: Symbol) {
+        if self.env.source_locations.is_some() {
+            self.statement_offsets.push((self.buf.len() as u64, symbol));
+        }
+    }
+
+    fn statement_offsets(&self) -> &[(u64, Symbol)] {
+        &self.statement_offsets
+    }
+
     fn literal_map(&mut self) -> &mut MutMap<Symbol, (*const Literal<'a>, *const InLayout<'a>)> {
         &mut self.literal_map
     }
@@ -960,7 +974,13 @@ impl<
         }
 
         // Add function body.
-        out.extend(&self.buf[..self.buf.len() - end_jmp_size]);
+        let body_len = self.buf.len() - end_jmp_size;
+        out.extend(&self.buf[..body_len]);
+
+        // The statements come after the stack setup now, and none are in the jump that was left out
+        for (offset, _) in self.statement_offsets.iter_mut() {
+            *offset = setup_offset as u64 + (*offset).min(body_len as u64);
+        }
 
         // Cleanup stack.
         CC::cleanup_stack(
@@ -1302,6 +1322,24 @@ impl<
                 let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
                 ASM::abs_freg64_freg64(&mut self.buf, &mut self.relocs, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_ABS_WRAP_INT[width].to_string(),
+                    &[*src],
+                    &[*layout],
+                    layout,
+                );
+            }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_ABS.to_string(),
+                    &[*src],
+                    &[Layout::DEC],
+                    &Layout::DEC,
+                );
+            }
             x => todo!("NumAbs: layout, {:?}", x),
         }
     }
@@ -1318,6 +1356,15 @@ impl<
                     .load_to_general_reg(&mut self.buf, src2);
                 ASM::add_reg64_reg64_reg64(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_ADD_WRAP_INT[width].to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
             LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => {
                 let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
                 let src1_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src1);
@@ -1351,7 +1398,7 @@ impl<
         layout: InLayout<'a>,
     ) {
         match self.layout_interner.get_repr(layout) {
-            LayoutRepr::Builtin(Builtin::Int(width @ quadword_and_smaller!())) => {
+            LayoutRepr::Builtin(Builtin::Int(width)) => {
                 let intrinsic = bitcode::NUM_ADD_SATURATED_INT[width].to_string();
                 self.build_fn_call(&dst, intrinsic, &[src1, src2], &[layout, layout], &layout);
             }
@@ -1424,9 +1471,22 @@ impl<
     }
 
     fn build_num_mul(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, layout: &InLayout<'a>) {
-        // for the time being, `num_mul` is implemented as wrapping multiplication. In roc, the normal
-        // `mul` should panic on overflow, but we just don't do that yet
-        self.build_num_mul_wrap(dst, src1, src2, layout)
+        match self.layout_interner.get_repr(*layout) {
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_MUL_OR_PANIC.to_string(),
+                    &[*src1, *src2],
+                    &[Layout::DEC, Layout::DEC],
+                    &Layout::DEC,
+                );
+            }
+            _ => {
+                // for the time being, `num_mul` is implemented as wrapping multiplication. In roc, the normal
+                // `mul` should panic on overflow, but we just don't do that yet
+                self.build_num_mul_wrap(dst, src1, src2, layout)
+            }
+        }
     }
 
     fn build_num_mul_wrap(
@@ -1509,7 +1569,7 @@ impl<
         layout: InLayout<'a>,
     ) {
         match self.layout_interner.get_repr(layout) {
-            LayoutRepr::Builtin(Builtin::Int(width @ quadword_and_smaller!())) => {
+            LayoutRepr::Builtin(Builtin::Int(width)) => {
                 let intrinsic = bitcode::NUM_MUL_SATURATED_INT[width].to_string();
                 self.build_fn_call(&dst, intrinsic, &[src1, src2], &[layout, layout], &layout);
             }
@@ -1609,6 +1669,24 @@ impl<
                 let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
                 ASM::div_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_DIV_TRUNC[width].to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_DIV.to_string(),
+                    &[*src1, *src2],
+                    &[Layout::DEC, Layout::DEC],
+                    &Layout::DEC,
+                );
+            }
             x => todo!("NumDiv: layout, {:?}", x),
         }
     }
@@ -1672,7 +1750,16 @@ impl<
                     src2_reg,
                 );
             }
-            x => todo!("NumDiv: layout, {:?}", x),
+            LayoutRepr::Builtin(Builtin::Int(width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_REM[width].to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
+            x => todo!("NumRem: layout, {:?}", x),
         }
     }
 
@@ -1683,14 +1770,45 @@ impl<
                 let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
                 ASM::neg_reg64_reg64(&mut self.buf, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_NEGATE_WRAP_INT[width].to_string(),
+                    &[*src],
+                    &[*layout],
+                    layout,
+                );
+            }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_NEGATE.to_string(),
+                    &[*src],
+                    &[Layout::DEC],
+                    &Layout::DEC,
+                );
+            }
             x => todo!("NumNeg: layout, {:?}", x),
         }
     }
 
     fn build_num_sub(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, layout: &InLayout<'a>) {
-        // for the time being, `num_sub` is implemented as wrapping subtraction. In roc, the normal
-        // `sub` should panic on overflow, but we just don't do that yet
-        self.build_num_sub_wrap(dst, src1, src2, layout)
+        match self.layout_interner.get_repr(*layout) {
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_SUB_OR_PANIC.to_string(),
+                    &[*src1, *src2],
+                    &[Layout::DEC, Layout::DEC],
+                    &Layout::DEC,
+                );
+            }
+            _ => {
+                // for the time being, `num_sub` is implemented as wrapping subtraction. In roc, the normal
+                // `sub` should panic on overflow, but we just don't do that yet
+                self.build_num_sub_wrap(dst, src1, src2, layout)
+            }
+        }
     }
 
     fn build_num_sub_wrap(
@@ -1711,6 +1829,15 @@ impl<
                     .load_to_general_reg(&mut self.buf, src2);
                 ASM::sub_reg64_reg64_reg64(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_SUB_WRAP_INT[width].to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
             x => todo!("NumSubWrap: layout, {:?}", x),
         }
     }
@@ -4431,7 +4558,13 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => self.build_int_bitwise_128bit(
+                dst,
+                src1,
+                src2,
+                int_width,
+                ASM::and_reg64_reg64_reg64,
+            ),
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -4451,7 +4584,9 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                self.build_int_bitwise_128bit(dst, src1, src2, int_width, ASM::or_reg64_reg64_reg64)
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -4471,7 +4606,13 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => self.build_int_bitwise_128bit(
+                dst,
+                src1,
+                src2,
+                int_width,
+                ASM::xor_reg64_reg64_reg64,
+            ),
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -4491,7 +4632,21 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                let layout = match int_width {
+                    IntWidth::I128 => Layout::I128,
+                    IntWidth::U128 => Layout::U128,
+                    _ => unreachable!(),
+                };
+
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_SHIFT_LEFT[int_width].to_string(),
+                    &[*src1, *src2],
+                    &[layout, Layout::U8],
+                    &layout,
+                );
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -4518,7 +4673,21 @@ impl<
         let buf = &mut self.buf;
 
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                let layout = match int_width {
+                    IntWidth::I128 => Layout::I128,
+                    IntWidth::U128 => Layout::U128,
+                    _ => unreachable!(),
+                };
+
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_SHIFT_RIGHT[int_width].to_string(),
+                    &[*src1, *src2],
+                    &[layout, Layout::U8],
+                    &layout,
+                );
+            }
             _ => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
@@ -4596,7 +4765,7 @@ impl<
                     dst,
                     bitcode::NUM_SHIFT_RIGHT_ZERO_FILL[int_width].to_string(),
                     &[*src1, *src2],
-                    &[layout, layout],
+                    &[layout, Layout::U8],
                     &layout,
                 );
             }
@@ -4640,38 +4809,103 @@ impl<
         let buf = &mut self.buf;
 
         match (source, target) {
-            (U128, U64) => {
+            (U128 | I128, U128 | I128) => {
+                let (src_offset, _) = self.storage_manager.stack_offset_and_size(src);
+                let base_offset = self.storage_manager.claim_stack_area_layout(
+                    self.layout_interner,
+                    *dst,
+                    Layout::int_width(target),
+                );
+
+                let tmp = self.debug_symbol("int_cast_tmp");
+                let tmp_reg = self.storage_manager.claim_general_reg(&mut self.buf, &tmp);
+
+                // the bits stay the same, only the signedness changes
+                for offset in [0, 8] {
+                    ASM::mov_reg64_base32(&mut self.buf, tmp_reg, src_offset + offset);
+                    ASM::mov_base32_reg64(&mut self.buf, base_offset + offset, tmp_reg);
+                }
+
+                self.free_symbol(&tmp);
+
+                return;
+            }
+            (U128 | I128, _) => {
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
 
                 let (offset, _size) = self.storage_manager.stack_offset_and_size(src);
 
-                ASM::mov_reg64_base32(buf, dst_reg, offset + 8);
+                // the lower 8 bytes are stored first
+                ASM::mov_reg64_base32(buf, dst_reg, offset);
+
+                // then leave behind any bits that don't fit the target
+                match target.stack_size() {
+                    8 => {}
+                    4 => ASM::mov_reg_reg(buf, RegisterWidth::W32, dst_reg, dst_reg),
+                    2 => ASM::mov_reg_reg(buf, RegisterWidth::W16, dst_reg, dst_reg),
+                    1 => ASM::mov_reg_reg(buf, RegisterWidth::W8, dst_reg, dst_reg),
+                    _ => unreachable!(),
+                }
 
                 return;
             }
-            (U64, U128) => {
+            (_, U128 | I128) => {
+                let register_width = match source.stack_size() {
+                    8 => RegisterWidth::W64,
+                    4 => RegisterWidth::W32,
+                    2 => RegisterWidth::W16,
+                    1 => RegisterWidth::W8,
+                    _ => unreachable!(),
+                };
+
+                let tmp = self.debug_symbol("int_cast_tmp");
+
+                let buf = &mut self.buf;
+
                 let src_reg = self.storage_manager.load_to_general_reg(buf, src);
 
                 let base_offset = self.storage_manager.claim_stack_area_layout(
                     self.layout_interner,
                     *dst,
-                    Layout::U128,
+                    Layout::int_width(target),
                 );
 
-                let tmp = Symbol::DEV_TMP;
                 let tmp_reg = self.storage_manager.claim_general_reg(buf, &tmp);
 
-                // move a zero into the lower 8 bytes
-                ASM::mov_reg64_imm64(buf, tmp_reg, 0x0);
+                // the lower 8 bytes hold the source, extended to 64 bits
+                if source.is_signed() {
+                    ASM::movsx_reg_reg(buf, register_width, tmp_reg, src_reg);
+                } else {
+                    ASM::movzx_reg_reg(buf, register_width, tmp_reg, src_reg);
+                }
+
                 ASM::mov_base32_reg64(buf, base_offset, tmp_reg);
 
-                ASM::mov_base32_reg64(buf, base_offset + 8, src_reg);
+                // the upper 8 bytes are all sign bits, or all zeros
+                if source.is_signed() {
+                    self.storage_manager.with_tmp_general_reg(
+                        buf,
+                        |storage_manager, buf, shift_reg| {
+                            ASM::mov_reg64_imm64(buf, shift_reg, 63);
+                            ASM::sar_reg64_reg64_reg64(
+                                buf,
+                                storage_manager,
+                                tmp_reg,
+                                tmp_reg,
+                                shift_reg,
+                            );
+                        },
+                    );
+                } else {
+                    ASM::mov_reg64_imm64(buf, tmp_reg, 0);
+                }
+
+                ASM::mov_base32_reg64(buf, base_offset + 8, tmp_reg);
 
                 self.free_symbol(&tmp);
 
                 return;
             }
-
             _ => {}
         }
 
@@ -4794,39 +5028,88 @@ impl<
     }
 
     fn num_to_f32(&mut self, dst: &Symbol, src: &Symbol, arg_layout: &InLayout<'a>) {
-        let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
         match self.layout_interner.get_repr(*arg_layout) {
             LayoutRepr::Builtin(Builtin::Int(IntWidth::I32 | IntWidth::I64)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
                 let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
                 ASM::to_float_freg32_reg64(&mut self.buf, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_TO_F32[width].to_string(),
+                    &[*src],
+                    &[*arg_layout],
+                    &Layout::F32,
+                );
+            }
             LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
                 let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
                 ASM::to_float_freg32_freg64(&mut self.buf, dst_reg, src_reg);
             }
             LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
                 let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
                 ASM::mov_freg64_freg64(&mut self.buf, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                // there is no direct conversion, so go through an F64
+                let tmp = self.debug_symbol("dec_to_f64");
+
+                self.build_fn_call(
+                    &tmp,
+                    bitcode::DEC_TO_F64.to_string(),
+                    &[*src],
+                    &[Layout::DEC],
+                    &Layout::F64,
+                );
+
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let tmp_reg = self.storage_manager.load_to_float_reg(&mut self.buf, &tmp);
+                ASM::to_float_freg32_freg64(&mut self.buf, dst_reg, tmp_reg);
+
+                self.free_symbol(&tmp);
+            }
             arg => todo!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::F32),
         }
     }
 
     fn num_to_f64(&mut self, dst: &Symbol, src: &Symbol, arg_layout: &InLayout<'a>) {
-        let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
         match self.layout_interner.get_repr(*arg_layout) {
             LayoutRepr::Builtin(Builtin::Int(IntWidth::I32 | IntWidth::I64)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
                 let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
                 ASM::to_float_freg64_reg64(&mut self.buf, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_TO_F64[width].to_string(),
+                    &[*src],
+                    &[*arg_layout],
+                    &Layout::F64,
+                );
+            }
             LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
                 let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
                 ASM::to_float_freg64_freg32(&mut self.buf, dst_reg, src_reg);
             }
             LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
                 let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
                 ASM::mov_freg64_freg64(&mut self.buf, dst_reg, src_reg);
             }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_TO_F64.to_string(),
+                    &[*src],
+                    &[Layout::DEC],
+                    &Layout::F64,
+                );
+            }
             arg => todo!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::F64),
         }
     }
@@ -4847,6 +5130,43 @@ impl<
         }
     }
 
+    /// Applies a bitwise `op` to both 8-byte halves of two 128-bit integers.
+    fn build_int_bitwise_128bit(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        int_width: IntWidth,
+        op: fn(&mut Vec<'a, u8>, GeneralReg, GeneralReg, GeneralReg),
+    ) {
+        let (src1_offset, _) = self.storage_manager.stack_offset_and_size(src1);
+        let (src2_offset, _) = self.storage_manager.stack_offset_and_size(src2);
+
+        let base_offset = self.storage_manager.claim_stack_area_layout(
+            self.layout_interner,
+            *dst,
+            Layout::int_width(int_width),
+        );
+
+        let tmp1_symbol = self.debug_symbol("bitwise_tmp1");
+        let tmp2_symbol = self.debug_symbol("bitwise_tmp2");
+
+        let buf = &mut self.buf;
+
+        let tmp1 = self.storage_manager.claim_general_reg(buf, &tmp1_symbol);
+        let tmp2 = self.storage_manager.claim_general_reg(buf, &tmp2_symbol);
+
+        for offset in [0, 8] {
+            ASM::mov_reg64_base32(buf, tmp1, src1_offset + offset);
+            ASM::mov_reg64_base32(buf, tmp2, src2_offset + offset);
+            op(buf, tmp1, tmp1, tmp2);
+            ASM::mov_base32_reg64(buf, base_offset + offset, tmp1);
+        }
+
+        self.free_symbol(&tmp1_symbol);
+        self.free_symbol(&tmp2_symbol);
+    }
+
     fn compare_128bit(
         &mut self,
         op: CompareOperation,
@@ -24,7 +24,9 @@ use roc_mono::layout::{
     TagIdIntType, UnionLayout,
 };
 use roc_mono::list_element_layout;
+use roc_mono::source_locations::SourceLocations;
 
+mod dwarf;
 mod generic64;
 mod object_builder;
 pub use object_builder::build_module;
@@ -73,6 +75,9 @@ pub struct Env<'a> {
     pub exposed_to_host: MutSet<Symbol>,
     pub lazy_literals: bool,
     pub mode: AssemblyBackendMode,
+    /// Where the procs and their statements are in the source code. When this is given, the
+    /// object file gets debug info that points at it.
+    pub source_locations: Option<SourceLocations>,
 }
 
 // These relocations likely will need a length.
@@ -478,6 +483,14 @@ trait Backend<'a> {
     /// finalize is run at the end of build_proc when all internal code is finalized.
     fn finalize(&mut self) -> (Vec<u8>, Vec<Relocation>);
 
+    /// Remember that the code of the statement which binds or uses `symbol` starts here, so that
+    /// the debug info can point at the statement.
+    fn mark_statement(&mut self, symbol: Symbol);
+
+    /// Where the code of each statement of the last proc that was built starts, relative to the
+    /// start of the proc, and the symbol that the statement binds or uses.
+    fn statement_offsets(&self) -> &[(u64, Symbol)];
+
     // load_args is used to let the backend know what the args are.
     // The backend should track these args so it can use them as needed.
     fn load_args(&mut self, args: &'a [(InLayout<'a>, Symbol)], ret_layout: &InLayout<'a>);
@@ -548,12 +561,14 @@ trait Backend<'a> {
     ) {
         match stmt {
             Stmt::Let(sym, expr, layout, following) => {
+                self.mark_statement(*sym);
                 self.build_expr(sym, expr, layout);
                 self.set_layout_map(*sym, layout);
                 self.free_symbols(stmt);
                 self.build_stmt(layout_ids, following, ret_layout);
             }
             Stmt::Ret(sym) => {
+                self.mark_statement(*sym);
                 self.load_literal_symbols(&[*sym]);
                 self.return_symbol(sym, ret_layout);
                 self.free_symbols(stmt);
@@ -615,6 +630,7 @@ trait Backend<'a> {
                 default_branch,
                 ret_layout,
             } => {
+                self.mark_statement(*cond_symbol);
                 self.load_literal_symbols(&[*cond_symbol]);
                 self.build_switch(
                     layout_ids,
@@ -1072,13 +1088,16 @@ trait Backend<'a> {
                 );
                 self.build_num_neg(sym, &args[0], ret_layout)
             }
-            LowLevel::NumPowInt => self.build_fn_call(
-                sym,
-                bitcode::NUM_POW_INT[IntWidth::I64].to_string(),
-                args,
-                arg_layouts,
-                ret_layout,
-            ),
+            LowLevel::NumPowInt => match self.interner().get_repr(*ret_layout) {
+                LayoutRepr::Builtin(Builtin::Int(int_width)) => self.build_fn_call(
+                    sym,
+                    bitcode::NUM_POW_INT[int_width].to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                _ => internal_error!("invalid return type"),
+            },
             LowLevel::NumSub => {
                 debug_assert_eq!(
                     2,
@@ -1126,10 +1145,13 @@ trait Backend<'a> {
                     // saturated sub is just normal sub
                     self.build_num_sub(sym, &args[0], &args[1], ret_layout)
                 }
-                LayoutRepr::Builtin(Builtin::Decimal) => {
-                    // self.load_args_and_call_zig(backend, bitcode::DEC_SUB_SATURATED)
-                    todo!()
-                }
+                LayoutRepr::Builtin(Builtin::Decimal) => self.build_fn_call(
+                    sym,
+                    bitcode::DEC_SUB_SATURATED.to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
                 _ => internal_error!("invalid return type"),
             },
             LowLevel::NumBitwiseAnd => {
@@ -1385,13 +1407,24 @@ trait Backend<'a> {
 
                 self.build_num_sqrt(*sym, args[0], float_width);
             }
-            LowLevel::NumRound => self.build_fn_call(
-                sym,
-                bitcode::NUM_ROUND_F64[IntWidth::I64].to_string(),
-                args,
-                arg_layouts,
-                ret_layout,
-            ),
+            LowLevel::NumRound => {
+                let int_width = match self.interner().get_repr(*ret_layout) {
+                    LayoutRepr::Builtin(Builtin::Int(int_width)) => int_width,
+                    _ => internal_error!("invalid return type"),
+                };
+
+                let intrinsic = match self.interner().get_repr(arg_layouts[0]) {
+                    LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => {
+                        &bitcode::NUM_ROUND_F32[int_width]
+                    }
+                    LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => {
+                        &bitcode::NUM_ROUND_F64[int_width]
+                    }
+                    x => todo!("NumRound: layout, {:?}", x),
+                };
+
+                self.build_fn_call(sym, intrinsic.to_string(), args, arg_layouts, ret_layout)
+            }
             LowLevel::ListLen => {
                 debug_assert_eq!(
                     1,
@@ -1780,6 +1813,38 @@ trait Backend<'a> {
                 let intrinsic = bitcode::NUM_COUNT_ONE_BITS[int_width].to_string();
                 self.build_fn_call(sym, intrinsic, args, arg_layouts, ret_layout);
             }
+            LowLevel::NumToIntChecked => {
+                let arg_width = arg_layouts[0].try_int_width().unwrap();
+
+                let ret_width = match self.interner().get_repr(*ret_layout) {
+                    LayoutRepr::Struct(&[value_layout, ..]) => value_layout.try_int_width(),
+                    _ => None,
+                };
+
+                let ret_width = match ret_width {
+                    Some(ret_width) => ret_width,
+                    None => internal_error!(
+                        "NumToIntChecked is not defined for signature {:?} -> {:?}",
+                        arg_layouts[0],
+                        ret_layout
+                    ),
+                };
+
+                let intrinsic = if arg_width.is_signed() {
+                    &bitcode::NUM_INT_TO_INT_CHECKING_MAX_AND_MIN[ret_width][arg_width]
+                } else {
+                    &bitcode::NUM_INT_TO_INT_CHECKING_MAX[ret_width][arg_width]
+                };
+
+                self.build_fn_call(sym, intrinsic.to_string(), args, arg_layouts, ret_layout);
+            }
+            LowLevel::I128OfDec => self.build_fn_call(
+                sym,
+                bitcode::DEC_TO_I128.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
             LowLevel::ListSublist => {
                 //    list: RocList,
                 //    alignment: u32,
@@ -1,3 +1,4 @@
+use crate::dwarf::{add_debug_sections, ProcCode};
 use crate::generic64::{aarch64, new_backend_64bit, x86_64};
 use crate::{AssemblyBackendMode, Backend, Env, Relocation};
 use bumpalo::collections::Vec;
@@ -599,8 +600,12 @@ fn build_object<'a, B: Backend<'a>>(
 
     // Build procedures from user code
     let mut relocations = bumpalo::vec![in arena];
+    let mut proc_code = std::vec::Vec::with_capacity(procs.len());
     for (fn_name, section_id, proc_id, proc) in procs {
-        build_proc(
+        let symbol = proc.name.name();
+        let linkage_name = fn_name.clone();
+
+        let size = build_proc(
             &mut output,
             &mut backend,
             &mut relocations,
@@ -610,7 +615,15 @@ fn build_object<'a, B: Backend<'a>>(
             section_id,
             proc_id,
             proc,
-        )
+        );
+
+        proc_code.push(ProcCode {
+            symbol,
+            symbol_id: proc_id,
+            linkage_name,
+            size,
+            statements: backend.statement_offsets().to_vec(),
+        });
     }
 
     // Generate IR for specialized helper procs (refcounting & equality)
@@ -701,7 +714,7 @@ fn build_object<'a, B: Backend<'a>>(
             section_id,
             proc_id,
             proc,
-        )
+        );
     }
 
     // Relocations for all procedures (user code & helpers)
@@ -711,6 +724,11 @@ fn build_object<'a, B: Backend<'a>>(
             Err(e) => internal_error!("{:?}", e),
         }
     }
+
+    if let Some(source_locations) = &backend.env().source_locations {
+        add_debug_sections(&mut output, source_locations, backend.interns(), &proc_code);
+    }
+
     output
 }
 
@@ -908,7 +926,7 @@ fn build_proc<'a, B: Backend<'a>>(
     section_id: SectionId,
     proc_id: SymbolId,
     proc: Proc<'a>,
-) {
+) -> u64 {
     let mut local_data_index = 0;
     let target_info = backend.target_info();
     let (proc_data, relocs, rc_proc_names) = backend.build_proc(proc, layout_ids);
@@ -1040,6 +1058,8 @@ fn build_proc<'a, B: Backend<'a>>(
         };
         relocations.push((section_id, elfreloc));
     }
+
+    proc_data.len() as u64
 }
 
 fn add_undefined_rc_proc(
@@ -17,7 +17,8 @@ use inkwell::basic_block::BasicBlock;
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::debug_info::{
-    AsDIScope, DICompileUnit, DIFlagsConstants, DISubprogram, DebugInfoBuilder,
+    AsDIScope, DICompileUnit, DIFile, DIFlags, DIFlagsConstants, DILocalVariable, DILocation,
+    DISubprogram, DIType, DebugInfoBuilder,
 };
 use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::{Linkage, Module};
@@ -42,7 +43,7 @@ use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::ROC_PRINT_LLVM_FN_VERIFICATION;
 use roc_error_macros::{internal_error, todo_lambda_erasure};
-use roc_module::symbol::{Interns, Symbol};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::ir::{
     BranchInfo, CallType, CrashTag, EntryPoint, GlueLayouts, HostExposedLambdaSet,
     HostExposedLambdaSets, ListLiteralElement, ModifyRc, OptLevel, ProcLayout, SingleEntryPoint,
@@ -51,6 +52,7 @@ use roc_mono::layout::{
     Builtin, InLayout, LambdaName, LambdaSet, Layout, LayoutIds, LayoutInterner, LayoutRepr, Niche,
     RawFunctionLayout, STLayoutInterner, TagIdIntType, UnionLayout,
 };
+use roc_mono::source_locations::SourceLocations;
 use roc_std::RocDec;
 use roc_target::{PtrWidth, TargetInfo};
 use std::convert::TryInto;
@@ -740,6 +742,9 @@ pub struct Env<'a, 'ctx, 'env> {
     pub target_info: TargetInfo,
     pub mode: LlvmBackendMode,
     pub exposed_to_host: MutSet<Symbol>,
+    /// Where the procs are in the source code. Without these, the debug info of Roc procs
+    /// only says which function an instruction is in.
+    pub source_locations: Option<&'env SourceLocations>,
 }
 
 impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
@@ -948,13 +953,15 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
         }
     }
 
+    /// DWARF has no language code for Roc, so the compile unit says Haskell, the closest relative
+    /// of Roc that has one. Saying C would make debuggers treat the code as C.
     pub fn new_debug_info(module: &Module<'ctx>) -> (DebugInfoBuilder<'ctx>, DICompileUnit<'ctx>) {
         module.create_debug_info_builder(
             true,
-            /* language */ inkwell::debug_info::DWARFSourceLanguage::C,
+            /* language */ inkwell::debug_info::DWARFSourceLanguage::Haskell,
             /* filename */ "roc_app",
             /* directory */ ".",
-            /* producer */ "my llvm compiler frontend",
+            /* producer */ "roc",
             /* is_optimized */ false,
             /* compiler command line flags */ "",
             /* runtime_ver */ 0,
@@ -969,8 +976,38 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
     }
 
     pub fn new_subprogram(&self, function_name: &str) -> DISubprogram<'ctx> {
+        self.subprogram_at(function_name, None, self.compile_unit.get_file(), 0)
+    }
+
+    /// The subprogram of a Roc proc. Debuggers show it under the Roc name of the proc, at the
+    /// place in the source code where the proc is defined.
+    pub fn new_proc_subprogram(&self, symbol: Symbol, function_name: &str) -> DISubprogram<'ctx> {
+        let name = format!(
+            "{}.{}",
+            symbol.module_string(&self.interns).as_str(),
+            symbol.as_str(&self.interns)
+        );
+
+        let file = self
+            .debug_file(symbol.module_id())
+            .unwrap_or_else(|| self.compile_unit.get_file());
+
+        let line = self
+            .source_locations
+            .and_then(|locations| locations.position(symbol))
+            .map_or(0, |position| position.line + 1);
+
+        self.subprogram_at(&name, Some(function_name), file, line)
+    }
+
+    fn subprogram_at(
+        &self,
+        function_name: &str,
+        linkage_name: Option<&str>,
+        file: DIFile<'ctx>,
+        line_no: u32,
+    ) -> DISubprogram<'ctx> {
         let dibuilder = self.dibuilder;
-        let compile_unit = self.compile_unit;
 
         let ditype = dibuilder
             .create_basic_type(
@@ -982,26 +1019,188 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
             .unwrap();
 
         let subroutine_type = dibuilder.create_subroutine_type(
-            compile_unit.get_file(),
+            file,
             /* return type */ Some(ditype.as_type()),
             /* parameter types */ &[],
             inkwell::debug_info::DIFlags::PUBLIC,
         );
 
         dibuilder.create_function(
-            /* scope */ compile_unit.get_file().as_debug_info_scope(),
+            /* scope */ file.as_debug_info_scope(),
             /* func name */ function_name,
-            /* linkage_name */ None,
-            /* file */ compile_unit.get_file(),
-            /* line_no */ 0,
+            /* linkage_name */ linkage_name,
+            /* file */ file,
+            /* line_no */ line_no,
             /* DIType */ subroutine_type,
             /* is_local_to_unit */ true,
             /* is_definition */ true,
-            /* scope_line */ 0,
+            /* scope_line */ line_no,
             /* flags */ inkwell::debug_info::DIFlags::PUBLIC,
             /* is_optimized */ false,
         )
     }
+
+    fn debug_file(&self, module_id: ModuleId) -> Option<DIFile<'ctx>> {
+        let path = self.source_locations?.path(module_id)?;
+
+        let file_name = path.file_name()?.to_string_lossy();
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy(),
+            _ => ".".into(),
+        };
+
+        Some(self.dibuilder.create_file(&file_name, &directory))
+    }
+
+    /// Where `symbol` is defined, as a location in the subprogram of `parent`. The symbols used
+    /// in a proc are always from the same module as the proc, so that subprogram is in the
+    /// right file.
+    fn debug_location_of(
+        &self,
+        parent: FunctionValue<'ctx>,
+        symbol: Symbol,
+    ) -> Option<DILocation<'ctx>> {
+        let position = self.source_locations?.position(symbol)?;
+        let subprogram = parent.get_subprogram()?;
+
+        Some(self.dibuilder.create_debug_location(
+            self.context,
+            position.line + 1,
+            position.column + 1,
+            subprogram.as_debug_info_scope(),
+            None,
+        ))
+    }
+
+    /// Attribute the instructions built from now on to where `symbol` is defined, if we know
+    /// where that is.
+    pub(crate) fn set_debug_location_of(&self, parent: FunctionValue<'ctx>, symbol: Symbol) {
+        if let Some(location) = self.debug_location_of(parent, symbol) {
+            self.builder.set_current_debug_location(location);
+        }
+    }
+
+    /// Describe the Roc value `symbol` to debuggers, as a local variable of `parent` defined
+    /// where `defined_at` is. Only numbers and booleans are described for now, and values the
+    /// compiler made up are left out.
+    pub(crate) fn debug_variable(
+        &self,
+        layout_interner: &STLayoutInterner<'a>,
+        parent: FunctionValue<'ctx>,
+        symbol: Symbol,
+        layout: InLayout<'a>,
+        defined_at: Symbol,
+        arg_no: Option<u32>,
+    ) -> Option<(DILocalVariable<'ctx>, DILocation<'ctx>)> {
+        let name = symbol.as_str(&self.interns);
+
+        // The names of generated symbols are numbers
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let ditype = self.debug_type(layout_interner, layout)?;
+        let location = self.debug_location_of(parent, defined_at)?;
+        let file = self.debug_file(defined_at.module_id())?;
+        let scope = location.get_scope();
+        let line = location.get_line();
+
+        let variable = match arg_no {
+            Some(arg_no) => self.dibuilder.create_parameter_variable(
+                scope,
+                name,
+                arg_no,
+                file,
+                line,
+                ditype,
+                /* always_preserve */ true,
+                DIFlags::ZERO,
+            ),
+            None => self.dibuilder.create_auto_variable(
+                scope,
+                name,
+                file,
+                line,
+                ditype,
+                /* always_preserve */ true,
+                DIFlags::ZERO,
+                /* align_in_bits */ 0,
+            ),
+        };
+
+        Some((variable, location))
+    }
+
+    fn debug_type(
+        &self,
+        layout_interner: &STLayoutInterner<'a>,
+        layout: InLayout<'a>,
+    ) -> Option<DIType<'ctx>> {
+        // Attribute encodings, from section 7.8 of the DWARF 5 standard
+        const DW_ATE_BOOLEAN: u32 = 0x02;
+        const DW_ATE_FLOAT: u32 = 0x04;
+        const DW_ATE_SIGNED: u32 = 0x05;
+        const DW_ATE_UNSIGNED: u32 = 0x07;
+
+        let (name, encoding) = match layout_interner.get_repr(layout) {
+            LayoutRepr::Builtin(Builtin::Bool) => ("Bool".to_string(), DW_ATE_BOOLEAN),
+            LayoutRepr::Builtin(Builtin::Int(int_width)) => {
+                let encoding = if int_width.is_signed() {
+                    DW_ATE_SIGNED
+                } else {
+                    DW_ATE_UNSIGNED
+                };
+
+                (int_width.type_name().to_uppercase(), encoding)
+            }
+            LayoutRepr::Builtin(Builtin::Float(float_width)) => {
+                (float_width.type_name().to_uppercase(), DW_ATE_FLOAT)
+            }
+            _ => return None,
+        };
+
+        let size_in_bits = layout_interner.stack_size(layout) as u64 * 8;
+
+        self.dibuilder
+            .create_basic_type(&name, size_in_bits, encoding, DIFlags::ZERO)
+            .ok()
+            .map(|basic_type| basic_type.as_type())
+    }
+
+    /// Tell debuggers that `variable` has `value`, from the point where `value` is defined in
+    /// `block` (or from the start of `block`, if `value` is defined before it). This has to
+    /// wait until `block` has instructions after that point.
+    pub(crate) fn declare_debug_value(
+        &self,
+        (variable, location): (DILocalVariable<'ctx>, DILocation<'ctx>),
+        value: BasicValueEnum<'ctx>,
+        block: BasicBlock<'ctx>,
+    ) {
+        // Aggregates and pointers are not described yet
+        if !(value.is_int_value() || value.is_float_value()) {
+            return;
+        }
+
+        let mut insert_before = match value.as_instruction_value() {
+            Some(instruction) if instruction.get_parent() == Some(block) => {
+                instruction.get_next_instruction()
+            }
+            _ => block.get_first_instruction(),
+        };
+
+        // Debug intrinsics can't go in between the phis at the start of a block
+        while let Some(instruction) = insert_before {
+            if instruction.get_opcode() != InstructionOpcode::Phi {
+                break;
+            }
+            insert_before = instruction.get_next_instruction();
+        }
+
+        if let Some(instruction) = insert_before {
+            self.dibuilder
+                .insert_dbg_value_before(value, variable, None, location, instruction);
+        }
+    }
 }
 
 pub fn module_from_builtins<'ctx>(
@@ -3194,6 +3393,7 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
             }
 
             let mut stack = Vec::with_capacity_in(queue.len(), env.arena);
+            let mut debug_values = Vec::new_in(env.arena);
 
             for (symbol, expr, layout) in queue {
                 debug_assert!(!matches!(
@@ -3201,6 +3401,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                     LayoutRepr::RecursivePointer(_)
                 ));
 
+                env.set_debug_location_of(parent, *symbol);
+
                 let val = build_exp_expr(
                     env,
                     layout_interner,
@@ -3219,6 +3421,13 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                 // access itself!
                 // scope = scope.clone();
 
+                if let (Some(variable), Some(block)) = (
+                    env.debug_variable(layout_interner, parent, *symbol, *layout, *symbol, None),
+                    env.builder.get_insert_block(),
+                ) {
+                    debug_values.push((variable, val, block));
+                }
+
                 scope.insert(*symbol, *layout, val);
                 stack.push(*symbol);
             }
@@ -3233,6 +3442,11 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                 cont,
             );
 
+            // Only now are there instructions to put the debug values in front of
+            for (debug_variable, val, block) in debug_values {
+                env.declare_debug_value(debug_variable, val, block);
+            }
+
             for symbol in stack {
                 scope.remove(&symbol);
             }
@@ -3240,6 +3454,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
             result
         }
         Ret(symbol) => {
+            env.set_debug_location_of(parent, *symbol);
+
             let (value, layout) = scope.load_symbol_and_layout(symbol);
 
             build_return(
@@ -3260,6 +3476,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
             cond_layout,
             cond_symbol,
         } => {
+            env.set_debug_location_of(parent, *cond_symbol);
+
             let ret_type =
                 basic_type_from_layout(env, layout_interner, layout_interner.get_repr(*ret_layout));
 
@@ -5830,7 +6048,7 @@ fn build_proc_header<'a, 'ctx>(
         Linkage::Internal,
     );
 
-    let subprogram = env.new_subprogram(&fn_name);
+    let subprogram = env.new_proc_subprogram(symbol, &fn_name);
     fn_val.set_subprogram(subprogram);
 
     debug_info_init!(env, fn_val);
@@ -6138,11 +6356,26 @@ fn build_proc<'a, 'ctx>(
     builder.position_at_end(entry);
 
     debug_info_init!(env, fn_val);
+    env.set_debug_location_of(fn_val, proc.name.name());
+
+    let mut debug_params = Vec::new_in(env.arena);
 
     // Add args to scope
-    for (arg_val, (layout, arg_symbol)) in fn_val.get_param_iter().zip(args) {
+    for (i, (arg_val, (layout, arg_symbol))) in fn_val.get_param_iter().zip(args).enumerate() {
         arg_val.set_name(arg_symbol.as_str(&env.interns));
         scope.insert(*arg_symbol, *layout, arg_val);
+
+        // Arguments don't have regions of their own, so they are placed at the proc
+        if let Some(debug_param) = env.debug_variable(
+            layout_interner,
+            fn_val,
+            *arg_symbol,
+            *layout,
+            proc.name.name(),
+            Some(i as u32 + 1),
+        ) {
+            debug_params.push((debug_param, arg_val));
+        }
     }
 
     let body = build_exp_stmt(
@@ -6161,6 +6394,10 @@ fn build_proc<'a, 'ctx>(
             builder.new_build_return(Some(&body));
         }
     }
+
+    for (debug_param, arg_val) in debug_params {
+        env.declare_debug_value(debug_param, arg_val, entry);
+    }
 }
 
 pub fn verify_fn(fn_val: FunctionValue<'_>) {
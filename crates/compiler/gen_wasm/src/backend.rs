@@ -29,7 +29,7 @@ use roc_wasm_module::{
 use crate::code_builder::CodeBuilder;
 use crate::layout::{ReturnMethod, WasmLayout};
 use crate::low_level::{call_higher_order_lowlevel, LowLevelCall};
-use crate::storage::{AddressValue, Storage, StoredValue, StoredVarKind};
+use crate::storage::{AddressValue, StackMemoryLocation, Storage, StoredValue, StoredVarKind};
 use crate::{
     copy_memory, CopyMemoryConfig, Env, DEBUG_SETTINGS, MEMORY_NAME, PTR_SIZE, PTR_TYPE,
     TARGET_INFO,
@@ -66,6 +66,9 @@ pub struct WasmBackend<'a, 'r> {
     host_lookup: Vec<'a, (&'a str, u32)>,
     helper_proc_gen: CodeGenHelp<'a>,
     can_relocate_heap: bool,
+    /// For the source map: the Wasm function index, the offset in the function body,
+    /// and the symbol bound by each statement we've generated
+    pub statement_offsets: Vec<'a, (u32, u32, Symbol)>,
 
     // Function-level data
     pub code_builder: CodeBuilder<'a>,
@@ -74,6 +77,8 @@ pub struct WasmBackend<'a, 'r> {
     /// how many blocks deep are we (used for jumps)
     block_depth: u32,
     joinpoint_label_map: MutMap<JoinPointId, (u32, Vec<'a, StoredValue>)>,
+    /// Where each statement of the current proc starts in the code builder
+    statement_positions: Vec<'a, (usize, Symbol)>,
 }
 
 impl<'a, 'r> WasmBackend<'a, 'r> {
@@ -136,10 +141,12 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             host_lookup,
             helper_proc_gen,
             can_relocate_heap: has_heap_base && has_heap_end,
+            statement_offsets: Vec::new_in(env.arena),
 
             // Function-level data
             block_depth: 0,
             joinpoint_label_map: MutMap::default(),
+            statement_positions: Vec::new_in(env.arena),
             code_builder: CodeBuilder::new(env.arena),
             storage: Storage::new(env.arena),
         }
@@ -394,6 +401,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         self.code_builder.clear();
         self.storage.clear();
         self.joinpoint_label_map.clear();
+        self.statement_positions.clear();
         assert_eq!(self.block_depth, 0);
     }
 
@@ -408,13 +416,17 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             println!("\ngenerating procedure {:?}\n", proc.name);
         }
 
-        self.append_proc_debug_name(proc.name.name());
+        let wasm_fn_index = self.append_proc_debug_name(proc.name.name());
 
         self.start_proc(proc);
 
         self.stmt(&proc.body);
 
         self.finalize_proc();
+        if self.env.source_locations.is_some() {
+            self.append_local_debug_names(wasm_fn_index);
+            self.append_statement_offsets(wasm_fn_index);
+        }
         self.reset();
 
         if DEBUG_SETTINGS.proc_start_end {
@@ -480,7 +492,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         }
     }
 
-    fn append_proc_debug_name(&mut self, sym: Symbol) {
+    fn append_proc_debug_name(&mut self, sym: Symbol) -> u32 {
         let proc_index = self
             .proc_lookup
             .iter()
@@ -490,6 +502,48 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
         let name = String::from_str_in(sym.as_str(self.interns), self.env.arena).into_bump_str();
         self.module.names.append_function(wasm_fn_index, name);
+
+        wasm_fn_index
+    }
+
+    /// Name the locals of the current proc after the symbols stored in them, for debuggers
+    fn append_local_debug_names(&mut self, wasm_fn_index: u32) {
+        let mut locals =
+            Vec::with_capacity_in(self.storage.symbol_storage_map.len() + 1, self.env.arena);
+
+        for (sym, storage) in self.storage.symbol_storage_map.iter() {
+            let local_id = match storage {
+                StoredValue::Local { local_id, .. } => *local_id,
+                StoredValue::StackMemory {
+                    location: StackMemoryLocation::PointerArg(local_id),
+                    ..
+                } => *local_id,
+                StoredValue::StackMemory { .. } => continue,
+            };
+
+            let ident_ids = self.interns.all_ident_ids.get(&sym.module_id());
+            if let Some(name) = ident_ids.and_then(|ids| ids.get_name(sym.ident_id())) {
+                let name = String::from_str_in(name, self.env.arena).into_bump_str();
+                locals.push((local_id.0, name));
+            }
+        }
+
+        if let Some(frame_pointer) = self.storage.stack_frame_pointer {
+            locals.push((frame_pointer.0, "stack_frame_pointer"));
+        }
+
+        locals.sort_by_key(|(local_index, _)| *local_index);
+        locals.dedup_by_key(|(local_index, _)| *local_index);
+
+        self.module.names.append_locals(wasm_fn_index, locals);
+    }
+
+    /// Now that the proc's header is built, work out where its statements are in the function body
+    fn append_statement_offsets(&mut self, wasm_fn_index: u32) {
+        for (code_position, sym) in self.statement_positions.iter() {
+            let offset = self.code_builder.serialized_offset(*code_position);
+            self.statement_offsets.push((wasm_fn_index, offset, *sym));
+        }
     }
 
     /// Build a wrapper around a Roc procedure so that it can be called from Zig builtins List.map*
@@ -703,7 +757,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         match stmt {
             Stmt::Let(_, _, _, _) => self.stmt_let(stmt),
 
-            Stmt::Ret(sym) => self.stmt_ret(*sym),
+            Stmt::Ret(sym) => {
+                self.mark_statement(*sym);
+                self.stmt_ret(*sym)
+            }
 
             Stmt::Switch {
                 cond_symbol,
@@ -711,7 +768,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 branches,
                 default_branch,
                 ret_layout: _,
-            } => self.stmt_switch(*cond_symbol, *cond_layout, branches, default_branch),
+            } => {
+                self.mark_statement(*cond_symbol);
+                self.stmt_switch(*cond_symbol, *cond_layout, branches, default_branch)
+            }
 
             Stmt::Join {
                 id,
@@ -735,6 +795,14 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         }
     }
 
+    /// Note where the code for a statement starts, so the source map can point at its source line
+    fn mark_statement(&mut self, sym: Symbol) {
+        if self.env.source_locations.is_some() {
+            let code_position = self.code_builder.code_position();
+            self.statement_positions.push((code_position, sym));
+        }
+    }
+
     fn start_block(&mut self) {
         // Wasm blocks can have result types, but we don't use them.
         // You need the right type on the stack when you jump from an inner block to an outer one.
@@ -766,6 +834,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 _ => StoredVarKind::Variable,
             };
 
+            self.mark_statement(*sym);
             self.stmt_let_store_expr(*sym, *layout, expr, kind);
 
             current_stmt = *following;
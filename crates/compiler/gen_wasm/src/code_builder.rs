@@ -210,6 +210,25 @@ impl<'a> CodeBuilder<'a> {
         self.inner_length.len() + self.preamble.len() + self.code.len() + self.insert_bytes.len()
     }
 
+    /// Where the next instruction will go, in the code we've generated so far
+    pub fn code_position(&self) -> usize {
+        self.code.len()
+    }
+
+    /// Find where the instruction at `code_position` will be, relative to the start of the
+    /// serialized function. Only valid once the header has been built.
+    pub fn serialized_offset(&self, code_position: usize) -> u32 {
+        // Bytes inserted at this position belong to the instruction before it
+        let inserted_bytes: usize = self
+            .insertions
+            .iter()
+            .take_while(|ins| ins.at <= code_position)
+            .map(|ins| ins.end - ins.start)
+            .sum();
+
+        (self.inner_length.len() + self.preamble.len() + inserted_bytes + code_position) as u32
+    }
+
     /// Serialize all byte vectors in the right order
     /// Insert relocations for imported functions
     pub fn insert_into_module(&self, module: &mut WasmModule<'a>) {
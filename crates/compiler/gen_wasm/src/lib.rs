@@ -14,13 +14,17 @@ use bumpalo::collections::Vec;
 use bumpalo::{self, Bump};
 
 use roc_collections::all::{MutMap, MutSet};
+use roc_error_macros::internal_error;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::CodeGenHelp;
 use roc_mono::ir::{Proc, ProcLayout};
 use roc_mono::layout::{LayoutIds, STLayoutInterner};
+use roc_mono::source_locations::SourceLocations;
 use roc_target::TargetInfo;
 use roc_wasm_module::parse::ParseError;
-use roc_wasm_module::{Align, LocalId, ValueType, WasmModule};
+use roc_wasm_module::sections::SourceMappingUrlSection;
+use roc_wasm_module::source_map::{SourceMap, SourceMapping};
+use roc_wasm_module::{Align, LocalId, Serialize, ValueType, WasmModule};
 
 use crate::backend::{ProcLookupData, ProcSource, WasmBackend};
 use crate::code_builder::CodeBuilder;
@@ -46,6 +50,9 @@ pub struct Env<'a> {
     pub module_id: ModuleId,
     pub exposed_to_host: MutSet<Symbol>,
     pub stack_bytes: u32,
+    /// Where the procs are in the source code. When this is given, the module gets local names
+    /// and a source map that points at it.
+    pub source_locations: Option<SourceLocations>,
 }
 
 impl Env<'_> {
@@ -71,16 +78,68 @@ pub fn build_app_binary<'a, 'r>(
     host_module: WasmModule<'a>,
     procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
 ) -> std::vec::Vec<u8> {
-    let (mut wasm_module, called_fns, _) =
-        build_app_module(env, layout_interner, interns, host_module, procedures);
+    let (mut wasm_module, called_fns, _, code_locations) =
+        build_app_module_help(env, layout_interner, interns, host_module, procedures);
 
     wasm_module.eliminate_dead_code(env.arena, called_fns);
 
     let mut buffer = std::vec::Vec::with_capacity(wasm_module.size());
     wasm_module.serialize(&mut buffer);
+
+    if let Some(source_locations) = env.source_locations.as_ref() {
+        // Code offsets are only known once the module is serialized. The source mapping URL
+        // is the last section, so appending it doesn't move any code.
+        let source_map = build_source_map(env.arena, &buffer, source_locations, &code_locations);
+        if !source_map.is_empty() {
+            let url = source_map.to_data_url();
+            SourceMappingUrlSection { url: &url }.serialize(&mut buffer);
+        }
+    }
+
     buffer
 }
 
+/// Map the start of each Roc proc and statement in the final binary to where it is defined.
+/// `code_locations` has the Wasm function index, the offset in the function body, and the symbol.
+fn build_source_map(
+    arena: &Bump,
+    module_bytes: &[u8],
+    source_locations: &SourceLocations,
+    code_locations: &[(u32, u32, Symbol)],
+) -> SourceMap {
+    let mut source_map = SourceMap::new();
+
+    let module = match WasmModule::preload(arena, module_bytes, false) {
+        Ok(module) => module,
+        Err(e) => internal_error!("Failed to parse the generated Wasm module: {}", e.message),
+    };
+    let import_fn_count = module.import.function_count() as u32;
+
+    for (fn_index, offset_in_fn, symbol) in code_locations.iter() {
+        let path = source_locations.path(symbol.module_id());
+        let position = source_locations.position(*symbol);
+        let (path, position) = match (path, position) {
+            (Some(path), Some(position)) => (path, position),
+            _ => continue,
+        };
+
+        let internal_index = (fn_index - import_fn_count) as usize;
+        let code_offset = module.code.section_offset
+            + module.code.function_offsets[internal_index]
+            + offset_in_fn;
+
+        let source = source_map.add_source(&path.to_string_lossy());
+        source_map.add_mapping(SourceMapping {
+            code_offset,
+            source,
+            line: position.line,
+            column: position.column,
+        });
+    }
+
+    source_map
+}
+
 /// Generate an unserialized Wasm module
 /// Shared by all consumers of gen_wasm: roc_build, roc_repl_wasm, and test_gen
 /// (roc_repl_wasm and test_gen will add more generated code for a wrapper function
@@ -92,6 +151,26 @@ pub fn build_app_module<'a, 'r>(
     host_module: WasmModule<'a>,
     procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
 ) -> (WasmModule<'a>, BitVec<usize>, u32) {
+    let (module, called_fns, main_function_index, _) =
+        build_app_module_help(env, layout_interner, interns, host_module, procedures);
+
+    (module, called_fns, main_function_index)
+}
+
+/// Like `build_app_module`, but also returns where the Roc procs and their statements are in the
+/// code, for the source map
+fn build_app_module_help<'a, 'r>(
+    env: &'r Env<'a>,
+    layout_interner: &'r mut STLayoutInterner<'a>,
+    interns: &'r mut Interns,
+    host_module: WasmModule<'a>,
+    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) -> (
+    WasmModule<'a>,
+    BitVec<usize>,
+    u32,
+    Vec<'a, (u32, u32, Symbol)>,
+) {
     let mut layout_ids = LayoutIds::default();
     let mut procs = Vec::with_capacity_in(procedures.len(), env.arena);
     let mut proc_lookup = Vec::with_capacity_in(procedures.len() * 2, env.arena);
@@ -183,11 +262,22 @@ pub fn build_app_module<'a, 'r>(
         }
     }
 
+    let mut code_locations = Vec::from_iter_in(
+        backend
+            .proc_lookup
+            .iter()
+            .enumerate()
+            .filter(|(_, ProcLookupData { source, .. })| matches!(source, ProcSource::Roc))
+            .map(|(i, ProcLookupData { name, .. })| (fn_index_offset + i as u32, 0, *name)),
+        env.arena,
+    );
+    code_locations.extend_from_slice(&backend.statement_offsets);
+
     let (module, called_fns) = backend.finalize();
     let main_function_index =
         maybe_main_fn_index.expect("The app must expose at least one value to the host");
 
-    (module, called_fns, main_function_index)
+    (module, called_fns, main_function_index, code_locations)
 }
 
 pub struct CopyMemoryConfig {
@@ -10,6 +10,7 @@ use roc_mono::layout::{Builtin, InLayout, Layout, LayoutInterner, LayoutRepr, Un
 use roc_mono::low_level::HigherOrder;
 
 use crate::backend::{ProcLookupData, ProcSource, WasmBackend};
+use crate::code_builder::CodeBuilder;
 use crate::layout::{StackMemoryFormat, WasmLayout};
 use crate::storage::{AddressValue, StackMemoryLocation, StoredValue};
 use crate::PTR_TYPE;
@@ -790,8 +791,7 @@ impl<'a> LowLevelCall<'a> {
             NumAddWrap => match self.ret_layout_raw {
                 LayoutRepr::Builtin(Builtin::Int(width)) => match width {
                     IntWidth::I128 | IntWidth::U128 => {
-                        // TODO: don't panic
-                        self.load_args_and_call_zig(backend, &bitcode::NUM_ADD_OR_PANIC_INT[width])
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_ADD_WRAP_INT[width])
                     }
                     IntWidth::I64 | IntWidth::U64 => {
                         self.load_args(backend);
@@ -881,8 +881,7 @@ impl<'a> LowLevelCall<'a> {
             NumSubWrap => match self.ret_layout_raw {
                 LayoutRepr::Builtin(Builtin::Int(width)) => match width {
                     IntWidth::I128 | IntWidth::U128 => {
-                        // TODO: don't panic
-                        self.load_args_and_call_zig(backend, &bitcode::NUM_SUB_OR_PANIC_INT[width])
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_SUB_WRAP_INT[width])
                     }
                     IntWidth::I64 | IntWidth::U64 => {
                         self.load_args(backend);
@@ -1230,26 +1229,36 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.f64_lt();
                         backend.code_builder.i32_add();
                     }
-                    I128 | Decimal => {
-                        self.load_args(backend);
+                    I128 => {
+                        let width = if is_signed {
+                            IntWidth::I128
+                        } else {
+                            IntWidth::U128
+                        };
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_COMPARE[width]);
+                    }
+                    Decimal => {
                         self.load_args_and_call_zig(backend, &bitcode::NUM_COMPARE[IntWidth::I128]);
                     }
                 }
             }
-            NumDivFrac => {
-                self.load_args(backend);
-                match CodeGenNumType::for_symbol(backend, self.arguments[0]) {
-                    F32 => backend.code_builder.f32_div(),
-                    F64 => backend.code_builder.f64_div(),
-                    Decimal => self.load_args_and_call_zig(backend, bitcode::DEC_DIV),
-                    x => todo!("{:?} for {:?}", self.lowlevel, x),
+            NumDivFrac => match CodeGenNumType::for_symbol(backend, self.arguments[0]) {
+                F32 => {
+                    self.load_args(backend);
+                    backend.code_builder.f32_div();
                 }
-            }
+                F64 => {
+                    self.load_args(backend);
+                    backend.code_builder.f64_div();
+                }
+                Decimal => self.load_args_and_call_zig(backend, bitcode::DEC_DIV),
+                x => internal_error!("{:?} is not defined for {:?}", self.lowlevel, x),
+            },
             NumDivTruncUnchecked => {
-                self.load_args(backend);
                 let is_signed = symbol_is_signed_int(backend, self.arguments[0]);
                 match CodeGenNumType::for_symbol(backend, self.arguments[0]) {
                     I32 => {
+                        self.load_args(backend);
                         if is_signed {
                             backend.code_builder.i32_div_s()
                         } else {
@@ -1257,13 +1266,20 @@ impl<'a> LowLevelCall<'a> {
                         }
                     }
                     I64 => {
+                        self.load_args(backend);
                         if is_signed {
                             backend.code_builder.i64_div_s()
                         } else {
                             backend.code_builder.i64_div_u()
                         }
                     }
-                    x => todo!("{:?} for {:?}", self.lowlevel, x),
+                    I128 => match self.ret_layout_raw {
+                        LayoutRepr::Builtin(Builtin::Int(width)) => {
+                            self.load_args_and_call_zig(backend, &bitcode::NUM_DIV_TRUNC[width])
+                        }
+                        _ => panic_ret_type(),
+                    },
+                    x => internal_error!("{:?} is not defined for {:?}", self.lowlevel, x),
                 }
             }
             NumDivCeilUnchecked => match self.ret_layout_raw {
@@ -1274,11 +1290,31 @@ impl<'a> LowLevelCall<'a> {
             },
 
             NumRemUnchecked => {
-                self.load_args(backend);
+                let is_signed = symbol_is_signed_int(backend, self.arguments[0]);
                 match CodeGenNumType::for_symbol(backend, self.arguments[0]) {
-                    I32 => backend.code_builder.i32_rem_s(),
-                    I64 => backend.code_builder.i64_rem_s(),
-                    _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
+                    I32 => {
+                        self.load_args(backend);
+                        if is_signed {
+                            backend.code_builder.i32_rem_s()
+                        } else {
+                            backend.code_builder.i32_rem_u()
+                        }
+                    }
+                    I64 => {
+                        self.load_args(backend);
+                        if is_signed {
+                            backend.code_builder.i64_rem_s()
+                        } else {
+                            backend.code_builder.i64_rem_u()
+                        }
+                    }
+                    I128 => match self.ret_layout_raw {
+                        LayoutRepr::Builtin(Builtin::Int(width)) => {
+                            self.load_args_and_call_zig(backend, &bitcode::NUM_REM[width])
+                        }
+                        _ => panic_ret_type(),
+                    },
+                    x => internal_error!("{:?} is not defined for {:?}", self.lowlevel, x),
                 }
             }
             NumIsMultipleOf => {
@@ -1384,6 +1420,23 @@ impl<'a> LowLevelCall<'a> {
                 const PANIC_MSG: &str =
                     "Integer absolute overflowed because its argument is the minimum value";
 
+                match self.ret_layout_raw {
+                    LayoutRepr::Builtin(Builtin::Int(
+                        width @ (IntWidth::I128 | IntWidth::U128),
+                    )) => {
+                        if width.is_signed() {
+                            self.num128_panic_if_min(backend, PANIC_MSG);
+                        }
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_ABS_WRAP_INT[width]);
+                        return;
+                    }
+                    LayoutRepr::Builtin(Builtin::Decimal) => {
+                        self.load_args_and_call_zig(backend, bitcode::DEC_ABS);
+                        return;
+                    }
+                    _ => {}
+                }
+
                 self.load_args(backend);
 
                 match CodeGenNumType::from(self.ret_layout) {
@@ -1441,13 +1494,30 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_abs(),
                     F64 => backend.code_builder.f64_abs(),
-                    _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
+                    _ => panic_ret_type(),
                 }
             }
             NumNeg => {
                 const PANIC_MSG: &str =
                     "Integer negation overflowed because its argument is the minimum value";
 
+                match self.ret_layout_raw {
+                    LayoutRepr::Builtin(Builtin::Int(
+                        width @ (IntWidth::I128 | IntWidth::U128),
+                    )) => {
+                        if width.is_signed() {
+                            self.num128_panic_if_min(backend, PANIC_MSG);
+                        }
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_NEGATE_WRAP_INT[width]);
+                        return;
+                    }
+                    LayoutRepr::Builtin(Builtin::Decimal) => {
+                        self.load_args_and_call_zig(backend, bitcode::DEC_NEGATE);
+                        return;
+                    }
+                    _ => {}
+                }
+
                 self.load_args(backend);
                 match CodeGenNumType::from(self.ret_layout) {
                     I32 => {
@@ -1474,7 +1544,7 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_neg(),
                     F64 => backend.code_builder.f64_neg(),
-                    _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
+                    _ => panic_ret_type(),
                 }
             }
             NumSin => match self.ret_layout_raw {
@@ -1523,20 +1593,70 @@ impl<'a> LowLevelCall<'a> {
                 _ => panic_ret_type(),
             },
             NumToFrac => {
-                self.load_args(backend);
+                let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
                 let ret_type = CodeGenNumType::from(self.ret_layout);
-                let arg_type = CodeGenNumType::for_symbol(backend, self.arguments[0]);
-                let arg_is_signed = symbol_is_signed_int(backend, self.arguments[0]);
+                let arg_type = CodeGenNumType::from(arg_layout);
+                let arg_is_signed = layout_is_signed_int(arg_layout);
+                let int128_width = match arg_is_signed {
+                    true => IntWidth::I128,
+                    false => IntWidth::U128,
+                };
+
                 match (ret_type, arg_type) {
-                    (F32, I32) => backend.code_builder.f32_convert_s_i32(),
-                    (F32, I64) => backend.code_builder.f32_convert_s_i64(),
-                    (F32, F32) => {}
-                    (F32, F64) => backend.code_builder.f32_demote_f64(),
+                    (F32, I32) => {
+                        self.load_args(backend);
+                        if arg_is_signed {
+                            backend.code_builder.f32_convert_s_i32()
+                        } else {
+                            backend.code_builder.f32_convert_u_i32()
+                        }
+                    }
+                    (F32, I64) => {
+                        self.load_args(backend);
+                        if arg_is_signed {
+                            backend.code_builder.f32_convert_s_i64()
+                        } else {
+                            backend.code_builder.f32_convert_u_i64()
+                        }
+                    }
+                    (F32, I128) => {
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_TO_F32[int128_width]);
+                    }
+                    (F32, F32) => self.load_args(backend),
+                    (F32, F64) => {
+                        self.load_args(backend);
+                        backend.code_builder.f32_demote_f64();
+                    }
+                    (F32, Decimal) => {
+                        self.load_args_and_call_zig(backend, bitcode::DEC_TO_F64);
+                        backend.code_builder.f32_demote_f64();
+                    }
 
-                    (F64, I32) => backend.code_builder.f64_convert_s_i32(),
-                    (F64, I64) => backend.code_builder.f64_convert_s_i64(),
-                    (F64, F32) => backend.code_builder.f64_promote_f32(),
-                    (F64, F64) => {}
+                    (F64, I32) => {
+                        self.load_args(backend);
+                        if arg_is_signed {
+                            backend.code_builder.f64_convert_s_i32()
+                        } else {
+                            backend.code_builder.f64_convert_u_i32()
+                        }
+                    }
+                    (F64, I64) => {
+                        self.load_args(backend);
+                        if arg_is_signed {
+                            backend.code_builder.f64_convert_s_i64()
+                        } else {
+                            backend.code_builder.f64_convert_u_i64()
+                        }
+                    }
+                    (F64, I128) => {
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_TO_F64[int128_width]);
+                    }
+                    (F64, F32) => {
+                        self.load_args(backend);
+                        backend.code_builder.f64_promote_f32();
+                    }
+                    (F64, F64) => self.load_args(backend),
+                    (F64, Decimal) => self.load_args_and_call_zig(backend, bitcode::DEC_TO_F64),
 
                     (Decimal, I32) => {
                         let int_width = match arg_is_signed {
@@ -1554,6 +1674,9 @@ impl<'a> LowLevelCall<'a> {
 
                         self.load_args_and_call_zig(backend, &bitcode::DEC_FROM_INT[int_width]);
                     }
+                    (Decimal, I128) => {
+                        self.load_args_and_call_zig(backend, &bitcode::DEC_FROM_INT[int128_width]);
+                    }
                     (Decimal, F32) => {
                         self.load_args_and_call_zig(
                             backend,
@@ -1566,9 +1689,18 @@ impl<'a> LowLevelCall<'a> {
                             &bitcode::DEC_FROM_FLOAT[FloatWidth::F64],
                         );
                     }
-                    (Decimal, Decimal) => {}
+                    (Decimal, Decimal) => {
+                        let (ret_local, ret_offset) =
+                            Self::num128_local_and_offset(backend, &self.ret_storage);
+                        backend.storage.copy_value_to_memory(
+                            &mut backend.code_builder,
+                            ret_local,
+                            ret_offset,
+                            self.arguments[0],
+                        );
+                    }
 
-                    _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
+                    _ => internal_error!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
                 }
             }
             NumPow => match self.ret_layout_raw {
@@ -1612,15 +1744,11 @@ impl<'a> LowLevelCall<'a> {
                 _ => panic_ret_type(),
             },
             NumRound => {
-                self.load_args(backend);
                 let arg_type = CodeGenNumType::for_symbol(backend, self.arguments[0]);
-                let ret_type = CodeGenNumType::from(self.ret_layout);
 
-                let width = match ret_type {
-                    CodeGenNumType::I32 => IntWidth::I32,
-                    CodeGenNumType::I64 => IntWidth::I64,
-                    CodeGenNumType::I128 => todo!("{:?} for I128", self.lowlevel),
-                    _ => internal_error!("Invalid return type for round: {:?}", ret_type),
+                let width = match self.ret_layout_raw {
+                    LayoutRepr::Builtin(Builtin::Int(width)) => width,
+                    _ => internal_error!("Invalid return type for round: {:?}", self.ret_layout),
                 };
 
                 match arg_type {
@@ -1630,9 +1758,26 @@ impl<'a> LowLevelCall<'a> {
                 }
             }
             NumCeiling | NumFloor => {
-                self.load_args(backend);
                 let arg_type = CodeGenNumType::for_symbol(backend, self.arguments[0]);
                 let ret_type = CodeGenNumType::from(self.ret_layout);
+
+                if let LayoutRepr::Builtin(Builtin::Int(
+                    width @ (IntWidth::I128 | IntWidth::U128),
+                )) = self.ret_layout_raw
+                {
+                    // Wasm has no instructions for 128-bit integers, so let Zig do all of it
+                    let intrinsic = match (arg_type, self.lowlevel) {
+                        (F32, NumCeiling) => &bitcode::NUM_CEILING_F32[width],
+                        (F64, NumCeiling) => &bitcode::NUM_CEILING_F64[width],
+                        (F32, NumFloor) => &bitcode::NUM_FLOOR_F32[width],
+                        (F64, NumFloor) => &bitcode::NUM_FLOOR_F64[width],
+                        _ => internal_error!("Invalid argument type for ceiling: {:?}", arg_type),
+                    };
+                    self.load_args_and_call_zig(backend, intrinsic);
+                    return;
+                }
+
+                self.load_args(backend);
                 match (arg_type, self.lowlevel) {
                     (F32, NumCeiling) => {
                         backend.code_builder.f32_ceil();
@@ -1654,12 +1799,10 @@ impl<'a> LowLevelCall<'a> {
                     (I32, F64) => backend.code_builder.i32_trunc_s_f64(),
                     (I64, F32) => backend.code_builder.i64_trunc_s_f32(),
                     (I64, F64) => backend.code_builder.i64_trunc_s_f64(),
-                    (I128, _) => todo!("{:?} for I128", self.lowlevel),
                     _ => panic_ret_type(),
                 }
             }
             NumPowInt => {
-                self.load_args(backend);
                 let base_type = CodeGenNumType::for_symbol(backend, self.arguments[0]);
                 let exponent_type = CodeGenNumType::for_symbol(backend, self.arguments[1]);
                 let ret_type = CodeGenNumType::from(self.ret_layout);
@@ -1667,10 +1810,8 @@ impl<'a> LowLevelCall<'a> {
                 debug_assert!(base_type == exponent_type);
                 debug_assert!(exponent_type == ret_type);
 
-                let width = match ret_type {
-                    CodeGenNumType::I32 => IntWidth::I32,
-                    CodeGenNumType::I64 => IntWidth::I64,
-                    CodeGenNumType::I128 => todo!("{:?} for I128", self.lowlevel),
+                let width = match self.ret_layout_raw {
+                    LayoutRepr::Builtin(Builtin::Int(width)) => width,
                     _ => internal_error!("Invalid return type for pow: {:?}", ret_type),
                 };
 
@@ -1712,46 +1853,66 @@ impl<'a> LowLevelCall<'a> {
             NumBytesToU32 => self.load_args_and_call_zig(backend, bitcode::NUM_BYTES_TO_U32),
             NumBytesToU64 => self.load_args_and_call_zig(backend, bitcode::NUM_BYTES_TO_U64),
             NumBytesToU128 => self.load_args_and_call_zig(backend, bitcode::NUM_BYTES_TO_U128),
-            NumBitwiseAnd => {
-                self.load_args(backend);
-                match CodeGenNumType::from(self.ret_layout) {
-                    I32 => backend.code_builder.i32_and(),
-                    I64 => backend.code_builder.i64_and(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
-                    _ => panic_ret_type(),
+            NumBitwiseAnd => match CodeGenNumType::from(self.ret_layout) {
+                I32 => {
+                    self.load_args(backend);
+                    backend.code_builder.i32_and();
                 }
-            }
-            NumBitwiseXor => {
-                self.load_args(backend);
-                match CodeGenNumType::from(self.ret_layout) {
-                    I32 => backend.code_builder.i32_xor(),
-                    I64 => backend.code_builder.i64_xor(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
-                    _ => panic_ret_type(),
+                I64 => {
+                    self.load_args(backend);
+                    backend.code_builder.i64_and();
                 }
-            }
-            NumBitwiseOr => {
-                self.load_args(backend);
-                match CodeGenNumType::from(self.ret_layout) {
-                    I32 => backend.code_builder.i32_or(),
-                    I64 => backend.code_builder.i64_or(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
-                    _ => panic_ret_type(),
+                I128 => self.num128_bitwise(backend, CodeBuilder::i64_and),
+                _ => panic_ret_type(),
+            },
+            NumBitwiseXor => match CodeGenNumType::from(self.ret_layout) {
+                I32 => {
+                    self.load_args(backend);
+                    backend.code_builder.i32_xor();
                 }
-            }
+                I64 => {
+                    self.load_args(backend);
+                    backend.code_builder.i64_xor();
+                }
+                I128 => self.num128_bitwise(backend, CodeBuilder::i64_xor),
+                _ => panic_ret_type(),
+            },
+            NumBitwiseOr => match CodeGenNumType::from(self.ret_layout) {
+                I32 => {
+                    self.load_args(backend);
+                    backend.code_builder.i32_or();
+                }
+                I64 => {
+                    self.load_args(backend);
+                    backend.code_builder.i64_or();
+                }
+                I128 => self.num128_bitwise(backend, CodeBuilder::i64_or),
+                _ => panic_ret_type(),
+            },
             NumShiftLeftBy => {
                 let num = self.arguments[0];
                 let bits = self.arguments[1];
-                backend
-                    .storage
-                    .load_symbols(&mut backend.code_builder, &[num, bits]);
                 match CodeGenNumType::from(self.ret_layout) {
-                    I32 => backend.code_builder.i32_shl(),
+                    I32 => {
+                        backend
+                            .storage
+                            .load_symbols(&mut backend.code_builder, &[num, bits]);
+                        backend.code_builder.i32_shl();
+                    }
                     I64 => {
+                        backend
+                            .storage
+                            .load_symbols(&mut backend.code_builder, &[num, bits]);
                         backend.code_builder.i64_extend_u_i32();
                         backend.code_builder.i64_shl();
                     }
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => {
+                        let width = match symbol_is_signed_int(backend, num) {
+                            true => IntWidth::I128,
+                            false => IntWidth::U128,
+                        };
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_SHIFT_LEFT[width]);
+                    }
                     _ => panic_ret_type(),
                 }
             }
@@ -1798,7 +1959,13 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.i64_extend_u_i32();
                         backend.code_builder.i64_shr_s();
                     }
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => {
+                        let width = match symbol_is_signed_int(backend, num) {
+                            true => IntWidth::I128,
+                            false => IntWidth::U128,
+                        };
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_SHIFT_RIGHT[width]);
+                    }
                     _ => panic_ret_type(),
                 }
             }
@@ -1869,8 +2036,13 @@ impl<'a> LowLevelCall<'a> {
                         self.wrap_small_int(backend, ret_width);
                     }
                     (I32, I128) => {
-                        self.load_args(backend);
-                        backend.code_builder.i32_load(Align::Bytes4, 0);
+                        let (frame_ptr, offset) = Self::num128_local_and_offset(
+                            backend,
+                            backend.storage.get(&self.arguments[0]),
+                        );
+                        backend.code_builder.get_local(frame_ptr);
+                        backend.code_builder.i32_load(Align::Bytes4, offset);
+                        self.wrap_small_int(backend, ret_width);
                     }
                     (I64, I32) => {
                         self.load_args(backend);
@@ -1884,37 +2056,73 @@ impl<'a> LowLevelCall<'a> {
                         self.load_args(backend);
                     }
                     (I64, I128) => {
-                        let (frame_ptr, offset) = match backend.storage.get(&self.arguments[0]) {
-                            StoredValue::StackMemory { location, .. } => {
-                                location.local_and_offset(backend.storage.stack_frame_pointer)
-                            }
-                            _ => internal_error!("I128 should be in stack memory"),
-                        };
+                        let (frame_ptr, offset) = Self::num128_local_and_offset(
+                            backend,
+                            backend.storage.get(&self.arguments[0]),
+                        );
                         backend.code_builder.get_local(frame_ptr);
                         backend.code_builder.i64_load(Align::Bytes8, offset);
                     }
-                    (I128, I64) => {
-                        // Symbols are loaded as if for a call, so the i128 "return address" and i64 value are on the value stack
-                        self.load_args(backend);
-                        backend.code_builder.i64_store(Align::Bytes8, 0);
+                    (I128, I32 | I64) => {
+                        let (ret_ptr, ret_offset) =
+                            Self::num128_local_and_offset(backend, &self.ret_storage);
 
-                        // Zero the most significant 64 bits
-                        let (frame_ptr, offset) = match &self.ret_storage {
-                            StoredValue::StackMemory { location, .. } => {
-                                location.local_and_offset(backend.storage.stack_frame_pointer)
+                        // Least significant 64 bits
+                        backend.code_builder.get_local(ret_ptr);
+                        backend
+                            .storage
+                            .load_symbols(&mut backend.code_builder, &[self.arguments[0]]);
+                        if arg_type == I32 {
+                            if arg_width.is_signed() {
+                                backend.code_builder.i64_extend_s_i32();
+                            } else {
+                                backend.code_builder.i64_extend_u_i32();
                             }
-                            _ => internal_error!("I128 should be in stack memory"),
-                        };
-                        backend.code_builder.get_local(frame_ptr);
-                        backend.code_builder.i64_const(0);
-                        backend.code_builder.i64_store(Align::Bytes8, offset + 8);
+                        }
+                        backend.code_builder.i64_store(Align::Bytes8, ret_offset);
+
+                        // Most significant 64 bits are all copies of the sign bit, or all zeros
+                        backend.code_builder.get_local(ret_ptr);
+                        if arg_width.is_signed() {
+                            backend
+                                .storage
+                                .load_symbols(&mut backend.code_builder, &[self.arguments[0]]);
+                            if arg_type == I32 {
+                                backend.code_builder.i64_extend_s_i32();
+                            }
+                            backend.code_builder.i64_const(63);
+                            backend.code_builder.i64_shr_s();
+                        } else {
+                            backend.code_builder.i64_const(0);
+                        }
+                        backend
+                            .code_builder
+                            .i64_store(Align::Bytes8, ret_offset + 8);
+                    }
+                    (I128, I128) => {
+                        let (ret_ptr, ret_offset) =
+                            Self::num128_local_and_offset(backend, &self.ret_storage);
+                        let (arg_ptr, arg_offset) = Self::num128_local_and_offset(
+                            backend,
+                            backend.storage.get(&self.arguments[0]),
+                        );
+
+                        for half in [0, 8] {
+                            backend.code_builder.get_local(ret_ptr);
+                            backend.code_builder.get_local(arg_ptr);
+                            backend
+                                .code_builder
+                                .i64_load(Align::Bytes8, arg_offset + half);
+                            backend
+                                .code_builder
+                                .i64_store(Align::Bytes8, ret_offset + half);
+                        }
                     }
 
-                    _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
+                    _ => internal_error!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
                 }
             }
             NumToFloatCast => {
-                self.load_args(backend);
                 let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
                 let arg_signed = match backend.layout_interner.get_repr(arg_layout) {
                     LayoutRepr::Builtin(Builtin::Int(w)) => w.is_signed(),
@@ -1925,6 +2133,31 @@ impl<'a> LowLevelCall<'a> {
                 let ret_type = CodeGenNumType::from(self.ret_layout);
                 let arg_type = CodeGenNumType::from(arg_layout);
 
+                // 128-bit arguments are in stack memory and need Zig builtins
+                match (ret_type, arg_type) {
+                    (F32 | F64, I128) => {
+                        let int_width = match arg_signed {
+                            true => IntWidth::I128,
+                            false => IntWidth::U128,
+                        };
+                        let intrinsic = match ret_type {
+                            F32 => &bitcode::NUM_TO_F32[int_width],
+                            _ => &bitcode::NUM_TO_F64[int_width],
+                        };
+                        self.load_args_and_call_zig(backend, intrinsic);
+                        return;
+                    }
+                    (F32 | F64, Decimal) => {
+                        self.load_args_and_call_zig(backend, bitcode::DEC_TO_F64);
+                        if ret_type == F32 {
+                            backend.code_builder.f32_demote_f64();
+                        }
+                        return;
+                    }
+                    _ => {}
+                }
+
+                self.load_args(backend);
                 match (ret_type, arg_type) {
                     (F32, F32) => {}
                     (F32, F64) => backend.code_builder.f32_demote_f64(),
@@ -1943,6 +2176,7 @@ impl<'a> LowLevelCall<'a> {
                         }
                     }
                     (F64, F64) => {}
+                    (F64, F32) => backend.code_builder.f64_promote_f32(),
                     (F64, I32) => {
                         if arg_signed {
                             backend.code_builder.f64_convert_s_i32()
@@ -1957,7 +2191,7 @@ impl<'a> LowLevelCall<'a> {
                             backend.code_builder.f64_convert_u_i64()
                         }
                     }
-                    _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
+                    _ => internal_error!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
                 }
             }
             NumToIntChecked => {
@@ -2002,7 +2236,47 @@ impl<'a> LowLevelCall<'a> {
                 }
             }
             NumToFloatChecked => {
-                todo!("implement toF32Checked and toF64Checked");
+                let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
+
+                let ret_width = match self.ret_layout_raw {
+                    LayoutRepr::Struct(&[ret, ..]) => match backend.layout_interner.get_repr(ret) {
+                        LayoutRepr::Builtin(Builtin::Float(ret_width)) => ret_width,
+                        _ => internal_error!(
+                            "NumToFloatChecked is not defined for signature {:?} -> {:?}",
+                            arg_layout,
+                            self.ret_layout
+                        ),
+                    },
+                    _ => internal_error!(
+                        "NumToFloatChecked is not defined for signature {:?} -> {:?}",
+                        arg_layout,
+                        self.ret_layout
+                    ),
+                };
+
+                let intrinsic = match (backend.layout_interner.get_repr(arg_layout), ret_width) {
+                    (LayoutRepr::Builtin(Builtin::Int(w)), FloatWidth::F32) => {
+                        &bitcode::NUM_TO_F32_CHECKED_INT[w]
+                    }
+                    (LayoutRepr::Builtin(Builtin::Int(w)), FloatWidth::F64) => {
+                        &bitcode::NUM_TO_F64_CHECKED_INT[w]
+                    }
+                    (LayoutRepr::Builtin(Builtin::Float(w)), FloatWidth::F32) => {
+                        &bitcode::NUM_TO_F32_CHECKED_FLOAT[w]
+                    }
+                    (LayoutRepr::Builtin(Builtin::Float(w)), FloatWidth::F64) => {
+                        &bitcode::NUM_TO_F64_CHECKED_FLOAT[w]
+                    }
+                    (LayoutRepr::Builtin(Builtin::Decimal), FloatWidth::F32) => {
+                        bitcode::DEC_TO_F32_CHECKED
+                    }
+                    (LayoutRepr::Builtin(Builtin::Decimal), FloatWidth::F64) => {
+                        bitcode::DEC_TO_F64_CHECKED
+                    }
+                    (x, _) => internal_error!("NumToFloatChecked is not defined for {:?}", x),
+                };
+
+                self.load_args_and_call_zig(backend, intrinsic);
             }
             I128OfDec => self.load_args_and_call_zig(backend, bitcode::DEC_TO_I128),
             And => {
@@ -2065,7 +2339,7 @@ impl<'a> LowLevelCall<'a> {
                 backend.code_builder.i32_and();
             }
 
-            Hash => todo!("{:?}", self.lowlevel),
+            Hash => internal_error!("{:?} is not generated by the compiler", self.lowlevel),
 
             Eq | NotEq => self.eq_or_neq(backend),
 
@@ -2250,22 +2524,72 @@ impl<'a> LowLevelCall<'a> {
         backend.code_builder.i32_and();
     }
 
+    fn num128_local_and_offset(
+        backend: &WasmBackend<'a, '_>,
+        storage: &StoredValue,
+    ) -> (LocalId, u32) {
+        match storage {
+            StoredValue::StackMemory { location, .. } => {
+                location.local_and_offset(backend.storage.stack_frame_pointer)
+            }
+            _ => internal_error!("I128 should be in stack memory"),
+        }
+    }
+
+    /// Panic if the first argument is the minimum signed 128-bit value,
+    /// which has no positive counterpart.
+    fn num128_panic_if_min(&self, backend: &mut WasmBackend<'a, '_>, msg: &'a str) {
+        let (local, offset) =
+            Self::num128_local_and_offset(backend, backend.storage.get(&self.arguments[0]));
+
+        // Least significant half is zero
+        backend.code_builder.get_local(local);
+        backend.code_builder.i64_load(Align::Bytes8, offset);
+        backend.code_builder.i64_eqz();
+
+        // Most significant half is only the sign bit
+        backend.code_builder.get_local(local);
+        backend.code_builder.i64_load(Align::Bytes8, offset + 8);
+        backend.code_builder.i64_const(i64::MIN);
+        backend.code_builder.i64_eq();
+
+        backend.code_builder.i32_and();
+        backend.code_builder.if_();
+        backend.stmt_internal_error(msg);
+        backend.code_builder.end();
+    }
+
+    /// Apply a bitwise operation to each half of two 128-bit numbers
+    /// (Don't call "load arguments" or "load symbols" helpers before this, it'll just waste instructions)
+    fn num128_bitwise(&self, backend: &mut WasmBackend<'a, '_>, op: fn(&mut CodeBuilder<'a>)) {
+        let (ret_local, ret_offset) = Self::num128_local_and_offset(backend, &self.ret_storage);
+        let (local0, offset0) =
+            Self::num128_local_and_offset(backend, backend.storage.get(&self.arguments[0]));
+        let (local1, offset1) =
+            Self::num128_local_and_offset(backend, backend.storage.get(&self.arguments[1]));
+
+        for half in [0, 8] {
+            backend.code_builder.get_local(ret_local);
+            backend.code_builder.get_local(local0);
+            backend.code_builder.i64_load(Align::Bytes8, offset0 + half);
+            backend.code_builder.get_local(local1);
+            backend.code_builder.i64_load(Align::Bytes8, offset1 + half);
+            op(&mut backend.code_builder);
+            backend
+                .code_builder
+                .i64_store(Align::Bytes8, ret_offset + half);
+        }
+    }
+
     fn num_to_str(&self, backend: &mut WasmBackend<'a, '_>) {
         let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
         match backend.layout_interner.runtime_representation(arg_layout) {
             LayoutRepr::Builtin(Builtin::Int(width)) => {
                 self.load_args_and_call_zig(backend, &bitcode::STR_FROM_INT[width])
             }
-            LayoutRepr::Builtin(Builtin::Float(width)) => match width {
-                FloatWidth::F32 => {
-                    self.load_args(backend);
-                    backend.code_builder.f64_promote_f32();
-                    self.load_args_and_call_zig(backend, &bitcode::STR_FROM_FLOAT[width]);
-                }
-                FloatWidth::F64 => {
-                    self.load_args_and_call_zig(backend, &bitcode::STR_FROM_FLOAT[width]);
-                }
-            },
+            LayoutRepr::Builtin(Builtin::Float(width)) => {
+                self.load_args_and_call_zig(backend, &bitcode::STR_FROM_FLOAT[width])
+            }
             LayoutRepr::Builtin(Builtin::Decimal) => {
                 self.load_args_and_call_zig(backend, bitcode::DEC_TO_STR)
             }
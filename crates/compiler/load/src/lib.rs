@@ -18,8 +18,8 @@ const SKIP_SUBS_CACHE: bool = {
 
 pub use roc_load_internal::docs;
 pub use roc_load_internal::file::{
-    ExecutionMode, ExpectMetadata, LoadConfig, LoadResult, LoadStart, LoadingProblem, Phase,
-    Threading,
+    CancelToken, ExecutionMode, ExpectMetadata, LoadConfig, LoadResult, LoadStart, LoadingProblem,
+    Phase, Threading,
 };
 pub use roc_load_internal::module::{
     CheckedModule, EntryPoint, Expectations, ExposedToHost, LoadedModule, MonomorphizedModule,
@@ -269,3 +269,9 @@ fn read_cached_types() -> MutMap<ModuleId, TypeState> {
 
     output
 }
+
+/// The solved types of the builtin modules, as they were cached when the compiler was built.
+/// This is empty on targets where that cache is not used.
+pub fn cached_builtin_types() -> MutMap<ModuleId, TypeState> {
+    read_cached_types()
+}
@@ -47,6 +47,7 @@ use roc_mono::layout::{
 use roc_mono::reset_reuse;
 use roc_mono::{drop_specialization, inc_dec};
 use roc_packaging::cache::RocCacheDir;
+use roc_packaging::vendor::Lockfile;
 use roc_parse::ast::{
     self, CommentOrNewline, Expr, ExtractSpaces, Pattern, Spaced, StrLiteral, ValueDef,
 };
@@ -58,9 +59,9 @@ use roc_parse::module::module_defs;
 use roc_parse::parser::{FileError, Parser, SourceError, SyntaxError};
 use roc_problem::Severity;
 use roc_region::all::{LineInfo, Loc, Region};
-#[cfg(not(target_family = "wasm"))]
-use roc_reporting::report::to_https_problem_report_string;
 use roc_reporting::report::{to_file_problem_report_string, Palette, RenderTarget};
+#[cfg(not(target_family = "wasm"))]
+use roc_reporting::report::{to_https_problem_report_string, to_version_problem_report_string};
 use roc_solve::module::{extract_module_owned_implementations, SolveConfig, Solved, SolvedModule};
 use roc_solve::FunctionKind;
 use roc_solve_problem::TypeError;
@@ -74,14 +75,17 @@ use std::iter;
 use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
 use std::str::from_utf8_unchecked;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::{env, fs};
 #[cfg(not(target_family = "wasm"))]
 use {
     roc_packaging::cache::{self},
+    roc_packaging::dependencies::{resolve_versions, DependencyProblem},
     roc_packaging::https::{PackageMetadata, Problem},
 };
 
+use crate::type_cache::{self, CacheMiss, TypeCache};
 pub use crate::work::Phase;
 use crate::work::{DepCycle, Dependencies};
 
@@ -102,6 +106,22 @@ macro_rules! log {
     ($($arg:tt)*) => (dbg_do!(ROC_PRINT_LOAD_LOG, println!($($arg)*)))
 }
 
+/// Lets another thread stop a load early, e.g. because the file being loaded has changed in the
+/// meantime and the result would be thrown away. The load stops with
+/// [LoadingProblem::Cancelled] once the task each worker is busy with has finished.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
 #[derive(Debug)]
 pub struct LoadConfig {
     pub target_info: TargetInfo,
@@ -290,8 +310,31 @@ fn start_phase<'a>(
 
                 let skip_constraint_gen = {
                     // Give this its own scope to make sure that the Guard from the lock() is dropped
-                    // immediately after contains_key returns
-                    state.cached_types.lock().contains_key(&module_id)
+                    // immediately after we're done with it
+                    let mut cached_types = state.cached_types.lock();
+
+                    if let Some(type_cache) = state.type_cache.as_mut() {
+                        let key = type_cache.insert_key(
+                            module_id,
+                            &module_ids,
+                            parsed.src.as_bytes(),
+                            parsed.imported_modules.keys().copied(),
+                            state.function_kind,
+                        );
+
+                        // A platform's types depend on the app's, through its `requires`,
+                        // so its key doesn't capture everything its types depend on.
+                        if parsed.symbols_from_requires.is_empty()
+                            && !cached_types.contains_key(&module_id)
+                        {
+                            if let Some(type_state) = type_cache.read(module_id, &module_ids, &key)
+                            {
+                                cached_types.insert(module_id, type_state);
+                            }
+                        }
+                    }
+
+                    cached_types.contains_key(&module_id)
                 };
 
                 BuildTask::CanonicalizeAndConstrain {
@@ -326,6 +369,11 @@ fn start_phase<'a>(
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
 
+                let type_cache_entry = state
+                    .type_cache
+                    .as_mut()
+                    .and_then(|type_cache| type_cache.take_miss(module_id));
+
                 #[cfg(debug_assertions)]
                 let checkmate = if roc_checkmate::is_checkmate_enabled() {
                     Some(roc_checkmate::Collector::new())
@@ -348,6 +396,7 @@ fn start_phase<'a>(
                     dep_idents,
                     declarations,
                     state.cached_types.clone(),
+                    type_cache_entry,
                     derived_module,
                     //
                     #[cfg(debug_assertions)]
@@ -701,6 +750,7 @@ struct State<'a> {
     pub dependencies: Dependencies<'a>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
     pub host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
+    pub symbol_regions: MutMap<Symbol, Region>,
     pub toplevel_expects: ToplevelExpects,
     pub exposed_to_host: ExposedToHost,
 
@@ -740,6 +790,11 @@ struct State<'a> {
     // cached types (used for builtin modules, could include packages in the future too)
     cached_types: CachedTypeState,
 
+    /// The on-disk cache of user modules' types, if it's enabled
+    type_cache: Option<TypeCache>,
+
+    cancel_token: CancelToken,
+
     layout_interner: GlobalLayoutInterner<'a>,
 }
 
@@ -759,6 +814,7 @@ impl<'a> State<'a> {
         arc_modules: Arc<Mutex<PackageModuleIds<'a>>>,
         ident_ids_by_module: SharedIdentIdsByModule,
         cached_types: MutMap<ModuleId, TypeState>,
+        type_cache: Option<TypeCache>,
         render: RenderTarget,
         palette: Palette,
         number_of_workers: usize,
@@ -781,6 +837,7 @@ impl<'a> State<'a> {
             dependencies,
             procedures: MutMap::default(),
             host_exposed_lambda_sets: std::vec::Vec::new(),
+            symbol_regions: MutMap::default(),
             toplevel_expects: ToplevelExpects::default(),
             exposed_to_host: ExposedToHost::default(),
             exposed_modules: &[],
@@ -795,6 +852,8 @@ impl<'a> State<'a> {
             timings: MutMap::default(),
             layout_caches: std::vec::Vec::with_capacity(number_of_workers),
             cached_types: Arc::new(Mutex::new(cached_types)),
+            type_cache,
+            cancel_token: CancelToken::default(),
             render,
             palette,
             exec_mode,
@@ -887,6 +946,8 @@ enum BuildTask<'a> {
         declarations: Declarations,
         dep_idents: IdentIdsByModule,
         cached_subs: CachedTypeState,
+        /// Where to write this module's types to the on-disk type cache, once they're solved
+        type_cache_entry: Option<CacheMiss>,
         derived_module: SharedDerivedModule,
 
         #[cfg(debug_assertions)]
@@ -954,6 +1015,8 @@ pub enum LoadingProblem<'a> {
     IncorrectModuleName(FileError<'a, IncorrectModuleName<'a>>),
     CouldNotFindCacheDir,
     ChannelProblem(ChannelProblem),
+    /// The load's [CancelToken] was cancelled.
+    Cancelled,
 }
 
 #[derive(Debug)]
@@ -1080,6 +1143,9 @@ pub struct LoadStart<'a> {
     opt_platform_shorthand: Option<&'a str>,
     root_msg: Msg<'a>,
     src_dir: PathBuf,
+    /// The lock file (written by `roc vendor`) in the root module's directory, if any
+    lockfile: Option<Lockfile>,
+    cancel_token: CancelToken,
 }
 
 impl<'a> LoadStart<'a> {
@@ -1094,6 +1160,8 @@ impl<'a> LoadStart<'a> {
         let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
         let ident_ids_by_module = Arc::new(Mutex::new(root_exposed_ident_ids));
         let mut src_dir = filename.parent().unwrap().to_path_buf();
+        let lockfile = read_lockfile(&src_dir)?;
+        let roc_cache_dir = vendored_cache_dir(roc_cache_dir, lockfile.as_ref());
 
         // Load the root module synchronously; we can't proceed until we have its id.
         let header_output = {
@@ -1135,9 +1203,11 @@ impl<'a> LoadStart<'a> {
             arc_modules,
             ident_ids_by_module,
             src_dir,
+            lockfile,
             root_id: header_output.module_id,
             root_msg: header_output.msg,
             opt_platform_shorthand: header_output.opt_platform_shorthand,
+            cancel_token: CancelToken::default(),
         })
     }
 
@@ -1151,6 +1221,8 @@ impl<'a> LoadStart<'a> {
         let arc_modules = Arc::new(Mutex::new(PackageModuleIds::default()));
         let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
         let ident_ids_by_module = Arc::new(Mutex::new(root_exposed_ident_ids));
+        let lockfile = read_lockfile(&src_dir)?;
+        let roc_cache_dir = vendored_cache_dir(roc_cache_dir, lockfile.as_ref());
 
         // Load the root module synchronously; we can't proceed until we have its id.
         let HeaderOutput {
@@ -1176,12 +1248,40 @@ impl<'a> LoadStart<'a> {
         Ok(LoadStart {
             arc_modules,
             src_dir,
+            lockfile,
             ident_ids_by_module,
             root_id,
             root_msg,
             opt_platform_shorthand: opt_platform_id,
+            cancel_token: CancelToken::default(),
         })
     }
+
+    /// Stop the load once `cancel_token` is cancelled.
+    pub fn with_cancel_token(self, cancel_token: CancelToken) -> Self {
+        LoadStart {
+            cancel_token,
+            ..self
+        }
+    }
+}
+
+fn read_lockfile<'a>(src_dir: &Path) -> Result<Option<Lockfile>, LoadingProblem<'a>> {
+    Lockfile::read(src_dir).map_err(|err| LoadingProblem::FileProblem {
+        filename: src_dir.join(roc_packaging::vendor::LOCKFILE_NAME),
+        error: err.kind(),
+    })
+}
+
+/// If the app has a lock file, load its packages from the vendor dir instead of the cache dir.
+fn vendored_cache_dir<'c>(
+    roc_cache_dir: RocCacheDir<'c>,
+    opt_lockfile: Option<&'c Lockfile>,
+) -> RocCacheDir<'c> {
+    match opt_lockfile {
+        Some(lockfile) => RocCacheDir::Vendored(lockfile),
+        None => roc_cache_dir,
+    }
 }
 
 fn adjust_header_paths<'a>(
@@ -1355,8 +1455,13 @@ pub fn load_single_threaded<'a>(
         root_msg,
         src_dir,
         opt_platform_shorthand,
+        lockfile,
+        cancel_token,
         ..
     } = load_start;
+    // The type cache lives next to the user's packages dir, even if packages are vendored
+    let type_cache = TypeCache::new(roc_cache_dir);
+    let roc_cache_dir = vendored_cache_dir(roc_cache_dir, lockfile.as_ref());
 
     let (msg_tx, msg_rx) = bounded(1024);
 
@@ -1374,12 +1479,20 @@ pub fn load_single_threaded<'a>(
         arc_modules,
         ident_ids_by_module,
         cached_types,
+        type_cache,
         render,
         palette,
         number_of_workers,
         exec_mode,
     );
 
+    state.cancel_token = cancel_token;
+
+    // Packages were vendored into the same layout as the cache dir, so we can use it in its place.
+    if let Some(lockfile) = &lockfile {
+        state.cache_dir = lockfile.vendor_dir().to_path_buf();
+    }
+
     // We'll add tasks to this, and then worker threads will take tasks from it.
     let injector = Injector::new();
 
@@ -1441,6 +1554,10 @@ fn state_thread_step<'a>(
     msg_tx: &crossbeam::channel::Sender<Msg<'a>>,
     msg_rx: &crossbeam::channel::Receiver<Msg<'a>>,
 ) -> Result<ControlFlow<LoadResult<'a>, State<'a>>, LoadingProblem<'a>> {
+    if state.cancel_token.is_cancelled() {
+        return Err(LoadingProblem::Cancelled);
+    }
+
     match msg_rx.try_recv() {
         Ok(msg) => {
             match msg {
@@ -1680,8 +1797,13 @@ fn load_multi_threaded<'a>(
         root_msg,
         src_dir,
         opt_platform_shorthand,
+        lockfile,
+        cancel_token,
         ..
     } = load_start;
+    // The type cache lives next to the user's packages dir, even if packages are vendored
+    let type_cache = TypeCache::new(roc_cache_dir);
+    let roc_cache_dir = vendored_cache_dir(roc_cache_dir, lockfile.as_ref());
 
     let (msg_tx, msg_rx) = bounded(1024);
     msg_tx
@@ -1714,12 +1836,20 @@ fn load_multi_threaded<'a>(
         arc_modules,
         ident_ids_by_module,
         cached_types,
+        type_cache,
         render,
         palette,
         num_workers,
         exec_mode,
     );
 
+    state.cancel_token = cancel_token;
+
+    // Packages were vendored into the same layout as the cache dir, so we can use it in its place.
+    if let Some(lockfile) = &lockfile {
+        state.cache_dir = lockfile.vendor_dir().to_path_buf();
+    }
+
     // an arena for every worker, stored in an arena-allocated bumpalo vec to make the lifetimes work
     let arenas = std::iter::repeat_with(Bump::new).take(num_workers);
     let worker_arenas = arena.alloc(bumpalo::collections::Vec::from_iter_in(arenas, arena));
@@ -2214,7 +2344,7 @@ fn update<'a>(
 
                 for (shorthand, package_name) in header.packages.iter() {
                     let package_str = package_name.as_str();
-                    let shorthand_path = if package_str.starts_with("https://") {
+                    let shorthand_path = if roc_packaging::cache::is_package_url(package_str) {
                         #[cfg(not(target_family = "wasm"))]
                         {
                             let url = package_str;
@@ -2249,8 +2379,10 @@ fn update<'a>(
                             panic!("Specifying packages via URLs is curently unsupported in wasm.");
                         }
                     } else {
-                        // This wasn't a URL, so it must be a filesystem path.
-                        let root_module: PathBuf = src_dir.join(package_str);
+                        // This wasn't a URL, so it must be a filesystem path
+                        // (either to the root module, or to the directory containing it).
+                        let root_module: PathBuf =
+                            roc_packaging::cache::local_package_root_module(src_dir, package_str);
                         let root_module_dir = root_module.parent().unwrap_or_else(|| {
                             if root_module.is_file() {
                                 // Files must have parents!
@@ -2735,6 +2867,10 @@ fn update<'a>(
 
             state.toplevel_expects.pure.extend(toplevel_expects.pure);
             state.toplevel_expects.fx.extend(toplevel_expects.fx);
+            state
+                .toplevel_expects
+                .preceding_defs
+                .extend(toplevel_expects.preceding_defs);
 
             state
                 .module_cache
@@ -2793,6 +2929,7 @@ fn update<'a>(
             state
                 .host_exposed_lambda_sets
                 .extend(host_exposed_lambda_sets);
+            state.symbol_regions.extend(&procs_base.symbol_regions);
             state.module_cache.late_specializations.insert(
                 module_id,
                 LateSpecializationsModule {
@@ -3213,6 +3350,7 @@ fn finish_specialization<'a>(
         toplevel_expects,
         procedures,
         host_exposed_lambda_sets,
+        symbol_regions,
         module_cache,
         platform_data,
         ..
@@ -3249,6 +3387,7 @@ fn finish_specialization<'a>(
         layout_interner,
         procedures,
         host_exposed_lambda_sets,
+        symbol_regions,
         entry_point,
         sources,
         timings: state.timings,
@@ -3919,8 +4058,7 @@ fn parse_header<'a>(
             },
             parse_state,
         )) => {
-            let mut app_file_dir = filename.clone();
-            app_file_dir.pop();
+            let app_path = filename.clone();
 
             let packages = if let Some(packages) = header.packages {
                 unspace(arena, packages.item.items)
@@ -3977,7 +4115,7 @@ fn parse_header<'a>(
                 packages,
                 &mut messages,
                 roc_cache_dir,
-                app_file_dir,
+                &app_path,
                 arena,
                 module_id,
                 module_ids,
@@ -4079,12 +4217,42 @@ fn load_packages<'a>(
     packages: &[Loc<PackageEntry<'a>>],
     load_messages: &mut Vec<Msg<'a>>,
     roc_cache_dir: RocCacheDir,
-    cwd: PathBuf,
+    app_path: &Path,
     arena: &'a Bump,
     module_id: ModuleId,
     module_ids: Arc<Mutex<PackageModuleIds<'a>>>,
     ident_ids_by_module: SharedIdentIdsByModule,
 ) {
+    let cwd = app_path.parent().unwrap_or_else(|| Path::new("."));
+
+    // Install every package the app depends on, directly or through other packages, and check
+    // that no two of them need incompatible versions of the same package.
+    #[cfg(not(target_family = "wasm"))]
+    let mut root_modules = {
+        let package_strs = packages
+            .iter()
+            .map(|entry| entry.value.package_name.value.as_str().to_string())
+            .collect();
+
+        match resolve_versions(roc_cache_dir, app_path, package_strs) {
+            Ok(resolved) => resolved.root_modules,
+            Err(DependencyProblem::Install(src, problem)) => {
+                let buf = to_https_problem_report_string(&src, problem);
+
+                load_messages.push(Msg::FailedToLoad(LoadingProblem::FormattedReport(buf)));
+                return;
+            }
+            Err(DependencyProblem::Version(problem)) => {
+                let buf = to_version_problem_report_string(problem);
+
+                load_messages.push(Msg::FailedToLoad(LoadingProblem::FormattedReport(buf)));
+                return;
+            }
+            // Problems reading or parsing a package's header get reported when it's loaded below
+            Err(_) => HashMap::new(),
+        }
+    };
+
     // Load all the packages
     for Loc { value: entry, .. } in packages.iter() {
         let PackageEntry {
@@ -4101,10 +4269,17 @@ fn load_packages<'a>(
 
         // find the `package` or `platform` module on disk,
         // downloading it into a cache dir first if necessary.
-        let root_module_path = if src.starts_with("https://") {
+        #[cfg(not(target_family = "wasm"))]
+        let opt_resolved = root_modules.remove(src);
+        #[cfg(target_family = "wasm")]
+        let opt_resolved: Option<PathBuf> = None;
+
+        let root_module_path = if let Some(root_module_path) = opt_resolved {
+            root_module_path
+        } else if roc_packaging::cache::is_package_url(src) {
             #[cfg(not(target_family = "wasm"))]
             {
-                // If this is a HTTPS package, synchronously download it
+                // If this is a HTTPS (or file:// tarball) package, synchronously install it
                 // to the cache before proceeding.
 
                 // TODO we should do this async; however, with the current
@@ -4134,7 +4309,7 @@ fn load_packages<'a>(
                 panic!("Specifying packages via URLs is curently unsupported in wasm.");
             }
         } else {
-            cwd.join(src)
+            roc_packaging::cache::local_package_root_module(cwd, src)
         };
 
         match load_package_from_disk(
@@ -4571,6 +4746,7 @@ impl<'a> BuildTask<'a> {
         dep_idents: IdentIdsByModule,
         declarations: Declarations,
         cached_subs: CachedTypeState,
+        type_cache_entry: Option<CacheMiss>,
         derived_module: SharedDerivedModule,
 
         #[cfg(debug_assertions)] checkmate: Option<roc_checkmate::Collector>,
@@ -4595,6 +4771,7 @@ impl<'a> BuildTask<'a> {
             dep_idents,
             module_timing,
             cached_subs,
+            type_cache_entry,
             derived_module,
 
             #[cfg(debug_assertions)]
@@ -5000,6 +5177,7 @@ fn run_solve<'a>(
     decls: Declarations,
     dep_idents: IdentIdsByModule,
     cached_types: CachedTypeState,
+    type_cache_entry: Option<CacheMiss>,
     derived_module: SharedDerivedModule,
 
     #[cfg(debug_assertions)] checkmate: Option<roc_checkmate::Collector>,
@@ -5016,55 +5194,45 @@ fn run_solve<'a>(
     let loc_dbgs = std::mem::take(&mut module.loc_dbgs);
     let module = module;
 
-    let solve_result = {
-        if module_id.is_builtin() {
-            match cached_types.lock().remove(&module_id) {
-                None => run_solve_solve(
-                    exposed_for_module,
-                    types,
-                    constraints,
-                    constraint,
-                    function_kind,
-                    pending_derives,
-                    var_store,
-                    module,
-                    derived_module,
-                    //
-                    #[cfg(debug_assertions)]
-                    checkmate,
-                ),
-                Some(TypeState {
-                    subs,
-                    exposed_vars_by_symbol,
-                    abilities,
-                    solved_implementations,
-                }) => SolveResult {
-                    solved: Solved(subs),
-                    solved_implementations,
-                    exposed_vars_by_symbol,
-                    problems: vec![],
-                    abilities_store: abilities,
+    let cached_type_state = cached_types.lock().remove(&module_id);
 
-                    #[cfg(debug_assertions)]
-                    checkmate: None,
-                },
-            }
-        } else {
-            run_solve_solve(
-                exposed_for_module,
-                types,
-                constraints,
-                constraint,
-                function_kind,
-                pending_derives,
-                var_store,
-                module,
-                derived_module,
-                //
-                #[cfg(debug_assertions)]
-                checkmate,
-            )
+    let solve_result = match cached_type_state {
+        None => run_solve_solve(
+            exposed_for_module,
+            types,
+            constraints,
+            constraint,
+            function_kind,
+            pending_derives,
+            var_store,
+            module,
+            derived_module,
+            //
+            #[cfg(debug_assertions)]
+            checkmate,
+        ),
+        Some(TypeState {
+            subs,
+            exposed_vars_by_symbol,
+            abilities,
+            solved_implementations,
+        }) => SolveResult {
+            solved: Solved(subs),
+            solved_implementations,
+            exposed_vars_by_symbol,
+            problems: vec![],
+            abilities_store: abilities,
+
+            #[cfg(debug_assertions)]
+            checkmate: None,
+        },
+    };
+
+    let solve_result = match type_cache_entry {
+        Some(miss) if solve_result.problems.is_empty() => {
+            write_type_cache_entry(&miss, solve_result)
         }
+        _ => solve_result,
     };
 
     let SolveResult {
@@ -5116,6 +5284,42 @@ fn run_solve<'a>(
     }
 }
 
+/// Write a module's solved types to the on-disk type cache, so they can be reused the next time
+/// the module is loaded, as long as neither it nor its imports have changed.
+fn write_type_cache_entry(miss: &CacheMiss, solve_result: SolveResult) -> SolveResult {
+    let SolveResult {
+        solved,
+        solved_implementations,
+        exposed_vars_by_symbol,
+        problems,
+        abilities_store,
+
+        #[cfg(debug_assertions)]
+        checkmate,
+    } = solve_result;
+
+    let type_state = TypeState {
+        subs: solved.into_inner(),
+        exposed_vars_by_symbol,
+        abilities: abilities_store,
+        solved_implementations,
+    };
+
+    // The cache is only an optimization, so if writing to it fails, carry on without it.
+    let _ = type_cache::write_entry(miss, &type_state);
+
+    SolveResult {
+        solved: Solved(type_state.subs),
+        solved_implementations: type_state.solved_implementations,
+        exposed_vars_by_symbol: type_state.exposed_vars_by_symbol,
+        problems,
+        abilities_store: type_state.abilities,
+
+        #[cfg(debug_assertions)]
+        checkmate,
+    }
+}
+
 fn unspace<'a, T: Copy>(arena: &'a Bump, items: &[Loc<Spaced<'a, T>>]) -> &'a [Loc<T>] {
     bumpalo::collections::Vec::from_iter_in(
         items
@@ -5647,6 +5851,7 @@ fn make_specializations<'a>(
     procs.host_exposed_symbols = host_exposed_symbols.into_bump_slice();
     procs.module_thunks = procs_base.module_thunks;
     procs.runtime_errors = procs_base.runtime_errors;
+    procs.symbol_regions = procs_base.symbol_regions;
     procs.imported_module_thunks = procs_base.imported_module_thunks;
 
     // TODO: for now this final specialization pass is sequential,
@@ -5715,6 +5920,7 @@ fn build_pending_specializations<'a>(
         host_specializations: roc_mono::ir::HostSpecializations::new(),
         runtime_errors: BumpMap::default(),
         imported_module_thunks,
+        symbol_regions: BumpMap::default(),
     };
 
     let mut update_mode_ids = UpdateModeIds::new();
@@ -5755,6 +5961,10 @@ fn build_pending_specializations<'a>(
         let symbol = declarations.symbols[index].value;
         let expr_var = declarations.variables[index];
 
+        procs_base
+            .symbol_regions
+            .insert(symbol, declarations.symbols[index].region);
+
         let is_host_exposed = exposed_to_host.top_level_values.contains_key(&symbol);
 
         let body = declarations.expressions[index].clone();
@@ -6117,6 +6327,10 @@ fn build_pending_specializations<'a>(
         }
     }
 
+    if build_expects {
+        toplevel_expects.preceding_defs = preceding_defs(&declarations, &toplevel_expects);
+    }
+
     layout_cache.rollback_to(layout_cache_snapshot);
 
     procs_base.module_thunks = module_thunks.into_bump_slice();
@@ -6137,6 +6351,39 @@ fn build_pending_specializations<'a>(
     }
 }
 
+/// Finds the closest top-level def before each expect in the module's source. The declarations
+/// are in dependency order, so they are sorted by where they are defined first.
+fn preceding_defs(
+    declarations: &Declarations,
+    toplevel_expects: &ToplevelExpects,
+) -> VecMap<Symbol, Symbol> {
+    use roc_can::expr::DeclarationTag::*;
+
+    let mut defs: Vec<(u32, Symbol)> = (0..declarations.len())
+        .filter(|index| {
+            matches!(
+                declarations.declarations[*index],
+                Value | Function(_) | Recursive(_) | TailRecursive(_)
+            )
+        })
+        .map(|index| {
+            let loc_symbol = &declarations.symbols[index];
+            (loc_symbol.region.start().offset, loc_symbol.value)
+        })
+        .collect();
+    defs.sort_unstable_by_key(|(offset, _)| *offset);
+
+    (toplevel_expects.pure.iter())
+        .chain(toplevel_expects.fx.iter())
+        .filter_map(|(expect, region)| {
+            let defs_before = defs.partition_point(|(offset, _)| *offset < region.start().offset);
+            let (_, def) = defs.get(defs_before.checked_sub(1)?)?;
+
+            Some((*expect, *def))
+        })
+        .collect()
+}
+
 fn register_toplevel_function_into_procs_base<'a>(
     mono_env: &mut roc_mono::ir::Env<'a, '_>,
     procs_base: &mut ProcsBase<'a>,
@@ -6339,6 +6586,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            type_cache_entry,
             derived_module,
 
             #[cfg(debug_assertions)]
@@ -6357,6 +6605,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            type_cache_entry,
             derived_module,
             //
             #[cfg(debug_assertions)]
@@ -8,6 +8,7 @@ pub mod docs;
 pub mod file;
 pub mod module;
 mod module_cache;
+mod type_cache;
 mod work;
 
 #[cfg(target_family = "wasm")]
@@ -161,6 +161,8 @@ pub(crate) struct LateSpecializationsModule<'a> {
 pub struct ToplevelExpects {
     pub pure: VecMap<Symbol, Region>,
     pub fx: VecMap<Symbol, Region>,
+    /// The top-level def that comes before each expect in its module, if there is one
+    pub preceding_defs: VecMap<Symbol, Symbol>,
 }
 
 #[derive(Debug)]
@@ -173,6 +175,9 @@ pub struct MonomorphizedModule<'a> {
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
     pub host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
+    /// Where the procedures, their named values and their statements are in the source code,
+    /// for debug info. Statements are keyed by the symbol they bind.
+    pub symbol_regions: MutMap<Symbol, Region>,
     pub toplevel_expects: ToplevelExpects,
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
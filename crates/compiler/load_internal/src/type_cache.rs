@@ -0,0 +1,470 @@
+//! An on-disk cache of the solved types of user modules, so that a module which hasn't changed
+//! since the last `roc check` or `roc build` can skip constraint generation and solving,
+//! the same way builtin modules use the types serialized by roc_load's build script.
+//! Modules are still parsed and canonicalized on a cache hit, since later phases need their
+//! canonical declarations.
+//!
+//! Each entry is keyed by a hash of the compiler build, the module's name and source, and the
+//! names and keys of all the modules it imports, so changing a module invalidates the entries
+//! of every module which (transitively) depends on it. Module IDs are assigned in whatever
+//! order modules happen to be discovered, so they aren't part of the key. Instead, each entry
+//! records the names of the modules its types refer to, and the types are given those modules'
+//! current IDs when the entry is read.
+use roc_can::module::TypeState;
+use roc_collections::{MutMap, MutSet};
+use roc_module::ident::ModuleName;
+use roc_module::symbol::{ModuleId, ModuleIds};
+use roc_packaging::cache::RocCacheDir;
+use roc_solve::FunctionKind;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+
+/// Set this environment variable to 1 to neither read nor write the module type cache.
+pub const SKIP_MODULE_CACHE_ENV_VAR: &str = "ROC_SKIP_MODULE_CACHE";
+
+/// Bump this whenever the format of entries changes.
+const FORMAT_VERSION: u32 = 2;
+
+const MAGIC: &[u8; 8] = b"roctypes";
+
+const HEADER_LEN: usize = MAGIC.len() + std::mem::size_of::<u32>() + blake3::OUT_LEN;
+
+/// Once the entries add up to more than this many bytes, the oldest ones get deleted
+/// until they're down to half of it.
+const MAX_CACHE_BYTES: u64 = 512 * 1024 * 1024;
+
+const ENTRY_EXTENSION: &str = "dat";
+
+pub(crate) type CacheKey = blake3::Hash;
+
+#[derive(Debug)]
+pub(crate) struct TypeCache {
+    /// e.g. ~/.cache/roc/modules
+    dir: PathBuf,
+    build_id: blake3::Hash,
+    keys: MutMap<ModuleId, CacheKey>,
+    /// Each module, and every user module it imports directly or transitively;
+    /// these are the only modules its types can refer to.
+    reachable: MutMap<ModuleId, MutSet<ModuleId>>,
+    /// Modules which had no entry, so one should be written once they've been solved
+    misses: MutMap<ModuleId, CacheMiss>,
+}
+
+/// Where to write a module's entry once it's been solved, and which modules its types
+/// can refer to, by their index in [ModuleIds] and name.
+#[derive(Debug)]
+pub(crate) struct CacheMiss {
+    path: PathBuf,
+    modules: Vec<(u32, ModuleName)>,
+}
+
+impl TypeCache {
+    /// The cache lives next to the packages dir, e.g. in ~/.cache/roc/modules, and is only used
+    /// when there is one, so that tests and build scripts which use [RocCacheDir::Disallowed]
+    /// never touch it. Apps with a lock file still pass the user's packages dir here.
+    pub(crate) fn new(roc_cache_dir: RocCacheDir<'_>) -> Option<Self> {
+        if cfg!(target_family = "wasm") || skip_module_cache() {
+            return None;
+        }
+
+        match roc_cache_dir {
+            RocCacheDir::Persistent(packages_dir)
+            | RocCacheDir::Mirrored {
+                cache_dir: packages_dir,
+                ..
+            } => {
+                let build_id = *compiler_build_id()?;
+                let dir = roc_packaging::cache::roc_module_cache_dir(packages_dir);
+
+                // The cache is only an optimization, so if pruning it fails, carry on anyway.
+                let _ = prune(&dir, MAX_CACHE_BYTES);
+
+                Some(Self {
+                    dir,
+                    build_id,
+                    keys: MutMap::default(),
+                    reachable: MutMap::default(),
+                    misses: MutMap::default(),
+                })
+            }
+            RocCacheDir::Vendored(_) | RocCacheDir::Disallowed => None,
+        }
+    }
+
+    /// Compute and remember the key for the given module. This must be called for every module
+    /// before it's called for any module which imports it; builtins don't need to be, since
+    /// their key only depends on their source, which is part of the compiler.
+    pub(crate) fn insert_key(
+        &mut self,
+        module_id: ModuleId,
+        module_ids: &ModuleIds,
+        src: &[u8],
+        imported: impl Iterator<Item = ModuleId>,
+        function_kind: FunctionKind,
+    ) -> CacheKey {
+        let mut hasher = blake3::Hasher::new();
+
+        hasher.update(self.build_id.as_bytes());
+        hasher.update(&FORMAT_VERSION.to_le_bytes());
+        hasher.update(format!("{function_kind:?}").as_bytes());
+
+        hash_module_name(&mut hasher, module_id, module_ids);
+        hasher.update(&(src.len() as u64).to_le_bytes());
+        hasher.update(src);
+
+        // Sort the imports, so the key doesn't depend on hash map iteration order.
+        let mut imported: Vec<_> = imported
+            .map(|dep_id| (module_name(module_ids, dep_id).as_str(), dep_id))
+            .collect();
+
+        imported.sort_by_key(|(name, _)| *name);
+
+        let mut reachable = MutSet::default();
+
+        reachable.insert(module_id);
+
+        for (_, dep_id) in imported {
+            let dep_key = if dep_id.is_builtin() {
+                blake3::hash(roc_builtins::roc::module_source(dep_id).as_bytes())
+            } else {
+                // Imports are always canonicalized before the modules which import them.
+                match self.keys.get(&dep_id) {
+                    Some(key) => *key,
+                    None => roc_error_macros::internal_error!(
+                        "No type cache key for {:?}, which is imported by {:?}",
+                        dep_id,
+                        module_id
+                    ),
+                }
+            };
+
+            if let Some(dep_reachable) = self.reachable.get(&dep_id) {
+                reachable.extend(dep_reachable.iter().copied());
+            }
+
+            hash_module_name(&mut hasher, dep_id, module_ids);
+            hasher.update(dep_key.as_bytes());
+        }
+
+        let key = hasher.finalize();
+
+        self.keys.insert(module_id, key);
+        self.reachable.insert(module_id, reachable);
+
+        key
+    }
+
+    /// Read the given module's entry, giving the modules its types refer to the IDs they have
+    /// in `module_ids`. If there isn't an entry, remember where to write it once the module has
+    /// been solved (see [TypeCache::take_miss]).
+    pub(crate) fn read(
+        &mut self,
+        module_id: ModuleId,
+        module_ids: &ModuleIds,
+        key: &CacheKey,
+    ) -> Option<TypeState> {
+        let path = self.entry_path(key);
+
+        match read_entry(&path).and_then(|entry| remap_entry(entry, module_ids)) {
+            Some(type_state) => Some(type_state),
+            None => {
+                let mut modules: Vec<_> = self
+                    .reachable
+                    .get(&module_id)
+                    .into_iter()
+                    .flatten()
+                    .map(|id| (id.index() as u32, module_name(module_ids, *id).clone()))
+                    .collect();
+
+                modules.sort_by_key(|(index, _)| *index);
+
+                self.misses.insert(module_id, CacheMiss { path, modules });
+
+                None
+            }
+        }
+    }
+
+    /// If reading the given module's entry missed, where and how its entry should be written.
+    pub(crate) fn take_miss(&mut self, module_id: ModuleId) -> Option<CacheMiss> {
+        self.misses.remove(&module_id)
+    }
+
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        let hex = key.to_hex();
+
+        // Spread entries over subdirectories, like git does, so no one directory gets huge.
+        self.dir
+            .join(&hex.as_str()[..2])
+            .join(format!("{}.{ENTRY_EXTENSION}", &hex.as_str()[2..]))
+    }
+}
+
+/// An entry's types, and the modules they refer to by their index and name when it was written.
+struct Entry {
+    type_state: TypeState,
+    modules: Vec<(u32, ModuleName)>,
+}
+
+/// Read the entry at the given path. Returns None if there's no entry, or it's corrupted
+/// or was written in a different format.
+fn read_entry(path: &Path) -> Option<Entry> {
+    let bytes = std::fs::read(path).ok()?;
+
+    if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return None;
+    }
+
+    let (version_bytes, rest) = bytes[MAGIC.len()..].split_at(std::mem::size_of::<u32>());
+    let (hash_bytes, payload) = rest.split_at(blake3::OUT_LEN);
+
+    if version_bytes != FORMAT_VERSION.to_le_bytes()
+        || hash_bytes != blake3::hash(payload).as_bytes()
+    {
+        return None;
+    }
+
+    let (modules, payload) = deserialize_modules(payload)?;
+    let (type_state, _) = TypeState::deserialize(payload);
+
+    Some(Entry {
+        type_state,
+        modules,
+    })
+}
+
+/// Give the modules an entry's types refer to the IDs they have now. Returns None if one of
+/// them isn't loaded, though that shouldn't happen, since they're all part of the entry's key.
+fn remap_entry(entry: Entry, module_ids: &ModuleIds) -> Option<TypeState> {
+    let Entry {
+        mut type_state,
+        modules,
+    } = entry;
+
+    let mut current_ids = MutMap::default();
+
+    for (index, name) in modules {
+        current_ids.insert(index as usize, module_ids.get_id(&name)?);
+    }
+
+    if current_ids
+        .iter()
+        .any(|(index, module_id)| *index != module_id.index())
+    {
+        type_state.remap_module_ids(|module_id| {
+            current_ids
+                .get(&module_id.index())
+                .copied()
+                .unwrap_or(module_id)
+        });
+    }
+
+    Some(type_state)
+}
+
+/// Write an entry for the given miss. The entry is written to a temporary file first and then
+/// moved into place, so concurrent `roc` processes never see a partially-written entry.
+pub(crate) fn write_entry(miss: &CacheMiss, type_state: &TypeState) -> io::Result<()> {
+    let mut payload = Vec::new();
+
+    serialize_modules(&miss.modules, &mut payload)?;
+    type_state.serialize(&mut payload)?;
+
+    let dir = miss.path.parent().unwrap_or_else(|| Path::new("."));
+
+    std::fs::create_dir_all(dir)?;
+
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+
+    file.write_all(MAGIC)?;
+    file.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    file.write_all(blake3::hash(&payload).as_bytes())?;
+    file.write_all(&payload)?;
+    file.persist(&miss.path).map_err(|err| err.error)?;
+
+    Ok(())
+}
+
+fn serialize_modules(modules: &[(u32, ModuleName)], writer: &mut impl Write) -> io::Result<()> {
+    writer.write_all(&(modules.len() as u32).to_le_bytes())?;
+
+    for (index, name) in modules {
+        writer.write_all(&index.to_le_bytes())?;
+        writer.write_all(&(name.as_str().len() as u32).to_le_bytes())?;
+        writer.write_all(name.as_str().as_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn deserialize_modules(bytes: &[u8]) -> Option<(Vec<(u32, ModuleName)>, &[u8])> {
+    fn take_u32(bytes: &mut &[u8]) -> Option<u32> {
+        let (int_bytes, rest) = (bytes.get(..4)?, &bytes[4..]);
+
+        *bytes = rest;
+
+        Some(u32::from_le_bytes(int_bytes.try_into().ok()?))
+    }
+
+    let mut bytes = bytes;
+    let len = take_u32(&mut bytes)?;
+    let mut modules = Vec::with_capacity(len as usize);
+
+    for _ in 0..len {
+        let index = take_u32(&mut bytes)?;
+        let name_len = take_u32(&mut bytes)? as usize;
+        let name = std::str::from_utf8(bytes.get(..name_len)?).ok()?;
+
+        bytes = &bytes[name_len..];
+        modules.push((index, ModuleName::from(name)));
+    }
+
+    Some((modules, bytes))
+}
+
+/// Delete the oldest entries in the cache dir, if they add up to more than `max_bytes`,
+/// until they add up to half of that.
+fn prune(dir: &Path, max_bytes: u64) -> io::Result<()> {
+    let mut entries = Vec::new();
+    let mut total_bytes = 0;
+
+    for subdir in std::fs::read_dir(dir)? {
+        let subdir = subdir?.path();
+
+        if !subdir.is_dir() {
+            continue;
+        }
+
+        for entry in std::fs::read_dir(&subdir)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|ext| ext.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+
+            let metadata = std::fs::metadata(&path)?;
+
+            total_bytes += metadata.len();
+            entries.push((metadata.modified()?, metadata.len(), path));
+        }
+    }
+
+    if total_bytes <= max_bytes {
+        return Ok(());
+    }
+
+    entries.sort_by_key(|(modified, _, _)| *modified);
+
+    for (_, len, path) in entries {
+        if total_bytes <= max_bytes / 2 {
+            break;
+        }
+
+        std::fs::remove_file(path)?;
+        total_bytes -= len;
+    }
+
+    Ok(())
+}
+
+/// Identifies the running compiler, so entries written by one build of it are never read by
+/// another - even one with the same version number, like a nightly or a local build. Like ccache,
+/// this goes by the executable's path, size, and modification time instead of hashing all of it.
+fn compiler_build_id() -> Option<&'static blake3::Hash> {
+    static BUILD_ID: OnceLock<Option<blake3::Hash>> = OnceLock::new();
+
+    BUILD_ID
+        .get_or_init(|| {
+            let exe = std::env::current_exe().ok()?;
+            let metadata = std::fs::metadata(&exe).ok()?;
+            let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+            let mut hasher = blake3::Hasher::new();
+
+            hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+            hasher.update(exe.to_string_lossy().as_bytes());
+            hasher.update(&metadata.len().to_le_bytes());
+            hasher.update(&modified.as_nanos().to_le_bytes());
+
+            Some(hasher.finalize())
+        })
+        .as_ref()
+}
+
+fn skip_module_cache() -> bool {
+    matches!(std::env::var(SKIP_MODULE_CACHE_ENV_VAR).as_deref(), Ok("1"))
+}
+
+fn module_name(module_ids: &ModuleIds, module_id: ModuleId) -> &ModuleName {
+    module_ids.get_name(module_id).unwrap_or_else(|| {
+        roc_error_macros::internal_error!("Could not find ModuleIds for {:?}", module_id)
+    })
+}
+
+fn hash_module_name(hasher: &mut blake3::Hasher, module_id: ModuleId, module_ids: &ModuleIds) {
+    hasher.update(module_name(module_ids, module_id).as_str().as_bytes());
+    hasher.update(&[0]);
+}
+
+#[test]
+fn entry_round_trip() {
+    use roc_types::subs::Subs;
+
+    let dir = tempfile::tempdir().unwrap();
+    let miss = CacheMiss {
+        path: dir.path().join("ab").join("cdef.dat"),
+        modules: vec![(20, "Foo".into()), (21, "Bar.Baz".into())],
+    };
+    let type_state = TypeState {
+        subs: Subs::new(),
+        exposed_vars_by_symbol: Vec::new(),
+        abilities: Default::default(),
+        solved_implementations: Default::default(),
+    };
+
+    write_entry(&miss, &type_state).unwrap();
+
+    let read = read_entry(&miss.path).unwrap();
+
+    assert_eq!(read.type_state.subs.len(), type_state.subs.len());
+    assert_eq!(read.modules, miss.modules);
+
+    // Flip a byte in the payload; the entry should be rejected rather than misread.
+    let mut bytes = std::fs::read(&miss.path).unwrap();
+    let last = bytes.len() - 1;
+
+    bytes[last] ^= 0xff;
+    std::fs::write(&miss.path, bytes).unwrap();
+
+    assert!(read_entry(&miss.path).is_none());
+}
+
+#[test]
+fn prune_deletes_oldest_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = |name: &str| {
+        dir.path()
+            .join("ab")
+            .join(format!("{name}.{ENTRY_EXTENSION}"))
+    };
+
+    std::fs::create_dir_all(dir.path().join("ab")).unwrap();
+
+    for name in ["old", "middle", "new"] {
+        std::fs::write(entry(name), [0; 100]).unwrap();
+
+        // Make sure the modification times differ, even on file systems with coarse timestamps.
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    // Under the limit, nothing is deleted
+    prune(dir.path(), 300).unwrap();
+    assert!(entry("old").exists());
+
+    // Over it, the oldest entries are deleted until they add up to half of it
+    prune(dir.path(), 250).unwrap();
+    assert!(!entry("old").exists());
+    assert!(!entry("middle").exists());
+    assert!(entry("new").exists());
+}
@@ -33,8 +33,9 @@ use roc_solve::FunctionKind;
 use roc_target::TargetInfo;
 use roc_types::pretty_print::name_and_print_var;
 use roc_types::pretty_print::DebugPrint;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 fn load_and_typecheck(
     arena: &Bump,
@@ -988,6 +989,314 @@ fn import_builtin_in_platform_and_check_app() {
     assert!(result.is_ok(), "should check");
 }
 
+#[test]
+fn load_vendored_package_without_downloading() {
+    use roc_packaging::vendor::{hash_dir, LOCKFILE_NAME, VENDOR_DIR_NAME};
+
+    let dir = roc_test_utils::TmpDir::new("tmp/load_vendored_package_without_downloading");
+    let hash = "jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE";
+    let url = format!("https://example.invalid/pkgs/{hash}.tar.br");
+
+    // Vendored packages are laid out like the cache dir
+    let package_dir = dir
+        .path()
+        .join(VENDOR_DIR_NAME)
+        .join("example.invalid/pkgs")
+        .join(hash);
+    fs::create_dir_all(&package_dir).unwrap();
+    fs::write(
+        package_dir.join("main.roc"),
+        indoc!(
+            r#"
+                package "greeting"
+                    exposes [Greeting]
+                    packages {}
+                "#
+        ),
+    )
+    .unwrap();
+    fs::write(
+        package_dir.join("Greeting.roc"),
+        indoc!(
+            r#"
+                interface Greeting
+                    exposes [greet]
+                    imports []
+
+                greet : Str -> Str
+                greet = \name -> "Hello, \(name)!"
+                "#
+        ),
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join(LOCKFILE_NAME),
+        format!("{url} {}\n", hash_dir(&package_dir).unwrap()),
+    )
+    .unwrap();
+
+    fs::create_dir_all(dir.path().join("platform")).unwrap();
+    fs::write(
+        dir.path().join("platform/main.roc"),
+        indoc!(
+            r#"
+                platform "testplatform"
+                    requires {} { main : Str }
+                    exposes []
+                    packages {}
+                    imports []
+                    provides [mainForHost]
+
+                mainForHost : Str
+                mainForHost = main
+                "#
+        ),
+    )
+    .unwrap();
+
+    let app_path = dir.path().join("Main.roc");
+    fs::write(
+        &app_path,
+        formatdoc!(
+            r#"
+                app "test"
+                    packages {{ pf: "platform/main.roc", greeting: "{url}" }}
+                    imports [greeting.Greeting]
+                    provides [main] to pf
+
+                main = Greeting.greet "Roc"
+                "#
+        ),
+    )
+    .unwrap();
+
+    // load_and_typecheck uses RocCacheDir::Disallowed, which panics if anything gets downloaded
+    let arena = Bump::new();
+    let loaded_module = match load_and_typecheck(
+        &arena,
+        app_path,
+        Default::default(),
+        TARGET_INFO,
+        FunctionKind::LambdaSet,
+    ) {
+        Ok(loaded_module) => loaded_module,
+        Err(LoadingProblem::FormattedReport(report)) => panic!("{report}"),
+        Err(problem) => panic!("{problem:?}"),
+    };
+
+    expect_types(loaded_module, hashmap! { "main" => "Str" });
+}
+
+/// Like [load_and_typecheck], but with a packages dir, so the on-disk type cache is used.
+/// It lives next to the packages dir, like ~/.cache/roc/modules does.
+fn load_with_type_cache(arena: &Bump, filename: PathBuf, packages_dir: &Path) -> LoadedModule {
+    let roc_cache_dir = RocCacheDir::Persistent(packages_dir);
+    let load_start = LoadStart::from_path(
+        arena,
+        filename,
+        RenderTarget::Generic,
+        roc_cache_dir,
+        DEFAULT_PALETTE,
+    )
+    .unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        function_kind: FunctionKind::LambdaSet,
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Check,
+    };
+
+    match roc_load_internal::file::load(
+        arena,
+        load_start,
+        Default::default(),
+        Default::default(),
+        roc_cache_dir,
+        load_config,
+    ) {
+        Ok(LoadResult::TypeChecked(module)) => module,
+        Ok(LoadResult::Monomorphized(_)) => unreachable!(),
+        Err(LoadingProblem::FormattedReport(report)) => panic!("{report}"),
+        Err(problem) => panic!("{problem:?}"),
+    }
+}
+
+/// The entries in the type cache next to the given packages dir
+fn type_cache_entries(packages_dir: &Path) -> BTreeSet<PathBuf> {
+    let mut entries = BTreeSet::new();
+
+    for subdir in fs::read_dir(packages_dir.with_file_name("modules")).unwrap() {
+        for entry in fs::read_dir(subdir.unwrap().path()).unwrap() {
+            entries.insert(entry.unwrap().path());
+        }
+    }
+
+    entries
+}
+
+/// Main imports Middle, Dep, and Other, and Middle imports Dep
+fn write_type_cache_modules(dir: &Path, dep_answer: &str) {
+    fs::write(
+        dir.join("Dep.roc"),
+        formatdoc!(
+            r#"
+            interface Dep
+                exposes [Pair, pair, first, answer]
+                imports []
+
+            Pair a := {{ left : a, right : a }}
+
+            pair : a, a -> Pair a
+            pair = \left, right -> @Pair {{ left, right }}
+
+            first : Pair a -> a
+            first = \@Pair {{ left }} -> left
+
+            answer = {dep_answer}
+            "#
+        ),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Middle.roc"),
+        indoc!(
+            r#"
+            interface Middle
+                exposes [strPair, firstStr]
+                imports [Dep.{ Pair }]
+
+            strPair : Str -> Pair Str
+            strPair = \str -> Dep.pair str str
+
+            firstStr : Pair Str -> Str
+            firstStr = \pair -> Dep.first pair
+            "#
+        ),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Other.roc"),
+        indoc!(
+            r#"
+            interface Other
+                exposes [other]
+                imports []
+
+            other = "other"
+            "#
+        ),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Main.roc"),
+        indoc!(
+            r#"
+            interface Main
+                exposes [main, numPair, answer, other]
+                imports [Middle, Dep, Other]
+
+            main = Middle.firstStr (Middle.strPair "roc")
+
+            numPair = Dep.pair 1 2
+
+            answer = Dep.answer
+
+            other = Other.other
+            "#
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn type_cache_cold_and_warm_loads_agree() {
+    let dir = roc_test_utils::TmpDir::new("tmp/type_cache_cold_and_warm_loads_agree");
+    let packages_dir = dir.path().join("cache").join("packages");
+    let main_types = hashmap! {
+        "main" => "Str",
+        "numPair" => "Dep.Pair (Num *)",
+        "answer" => "Num *",
+        "other" => "Str",
+    };
+
+    write_type_cache_modules(dir.path(), "42");
+
+    let arena = Bump::new();
+    let cold = load_with_type_cache(&arena, dir.path().join("Main.roc"), &packages_dir);
+
+    expect_types(cold, main_types.clone());
+
+    let entries = type_cache_entries(&packages_dir);
+
+    assert_eq!(entries.len(), 4);
+
+    let arena = Bump::new();
+    let warm = load_with_type_cache(&arena, dir.path().join("Main.roc"), &packages_dir);
+
+    expect_types(warm, main_types);
+    assert_eq!(type_cache_entries(&packages_dir), entries);
+
+    // On its own, Middle and Dep get other module IDs than when Main imports them,
+    // so the types read from the cache need to be given the new ones.
+    let arena = Bump::new();
+    let middle = load_with_type_cache(&arena, dir.path().join("Middle.roc"), &packages_dir);
+
+    expect_types(
+        middle,
+        hashmap! {
+            "strPair" => "Str -> Dep.Pair Str",
+            "firstStr" => "Dep.Pair Str -> Str",
+        },
+    );
+    assert_eq!(type_cache_entries(&packages_dir), entries);
+}
+
+#[test]
+fn type_cache_editing_a_dependency_invalidates_its_dependents() {
+    let dir = roc_test_utils::TmpDir::new("tmp/type_cache_editing_a_dependency");
+    let packages_dir = dir.path().join("cache").join("packages");
+
+    write_type_cache_modules(dir.path(), "42");
+
+    let arena = Bump::new();
+    let before = load_with_type_cache(&arena, dir.path().join("Main.roc"), &packages_dir);
+
+    expect_types(
+        before,
+        hashmap! {
+            "main" => "Str",
+            "numPair" => "Dep.Pair (Num *)",
+            "answer" => "Num *",
+            "other" => "Str",
+        },
+    );
+
+    let entries_before = type_cache_entries(&packages_dir);
+
+    write_type_cache_modules(dir.path(), "\"forty-two\"");
+
+    let arena = Bump::new();
+    let after = load_with_type_cache(&arena, dir.path().join("Main.roc"), &packages_dir);
+
+    expect_types(
+        after,
+        hashmap! {
+            "main" => "Str",
+            "numPair" => "Dep.Pair (Num *)",
+            "answer" => "Str",
+            "other" => "Str",
+        },
+    );
+
+    // Dep changed, and Middle and Main import it, so they all get new entries. Other doesn't.
+    let entries_after = type_cache_entries(&packages_dir);
+
+    assert!(entries_after.is_superset(&entries_before));
+    assert_eq!(entries_after.len(), entries_before.len() + 3);
+}
+
 #[test]
 fn module_doesnt_match_file_path() {
     let modules = vec![(
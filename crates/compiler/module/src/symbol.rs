@@ -366,6 +366,11 @@ impl ModuleId {
         (self.0.get() - 1) as usize
     }
 
+    /// The position of this module in the [ModuleIds] which gave it its ID.
+    pub const fn index(self) -> usize {
+        self.to_zero_indexed()
+    }
+
     #[cfg(any(debug_assertions, feature = "debug-symbols"))]
     pub fn register_debug_idents(self, ident_ids: &IdentIds) {
         let mut all = DEBUG_IDENT_IDS_BY_MODULE_ID.lock().expect("Failed to acquire lock for Debug interning into DEBUG_MODULE_ID_NAMES, presumably because a thread panicked.");
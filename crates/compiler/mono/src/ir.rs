@@ -869,6 +869,10 @@ pub struct ProcsBase<'a> {
     pub host_specializations: HostSpecializations<'a>,
     pub runtime_errors: BumpMap<Symbol, &'a str>,
     pub imported_module_thunks: &'a [Symbol],
+    /// Where the top-level definitions, named values and statements of this module are in its
+    /// source code, used to give the generated code debug info. Statements are keyed by the
+    /// symbol they bind.
+    pub symbol_regions: BumpMap<Symbol, Region>,
 }
 
 impl<'a> ProcsBase<'a> {
@@ -906,6 +910,7 @@ pub struct Procs<'a> {
     specialized: Specialized<'a>,
     host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
     pub runtime_errors: BumpMap<Symbol, &'a str>,
+    pub symbol_regions: BumpMap<Symbol, Region>,
     pub externals_we_need: BumpMap<ModuleId, ExternalSpecializations<'a>>,
     symbol_specializations: SymbolSpecializations<'a>,
     specialization_stack: SpecializationStack<'a>,
@@ -923,6 +928,7 @@ impl<'a> Procs<'a> {
             pending_specializations: PendingSpecializations::Finding(Suspended::new_in(arena)),
             specialized: Specialized::default(),
             runtime_errors: BumpMap::new_in(arena),
+            symbol_regions: BumpMap::new_in(arena),
             externals_we_need: BumpMap::new_in(arena),
             host_exposed_lambda_sets: std::vec::Vec::new(),
             symbol_specializations: Default::default(),
@@ -934,6 +940,44 @@ impl<'a> Procs<'a> {
         }
     }
 
+    /// Remember where `symbol` is defined, for debug info
+    fn record_region(&mut self, symbol: Symbol, region: Region) {
+        self.symbol_regions.entry(symbol).or_insert(region);
+    }
+
+    /// Remember that the statements at the start of `stmt` which compute the value of an
+    /// expression are at `region`: the `Let`s up to the one that binds `assigned`, or without a
+    /// hole to fill, up to the first statement that isn't a `Let`. Statements that already have
+    /// a more precise location keep it.
+    fn record_statement_regions(
+        &mut self,
+        stmt: &Stmt<'a>,
+        region: Region,
+        assigned: Option<Symbol>,
+    ) {
+        // The expressions the compiler makes up are nowhere in the source
+        if region.is_empty() {
+            return;
+        }
+
+        // With a hole, the statements after the one that binds `assigned` are the hole's. If the
+        // expression doesn't bind `assigned` itself (e.g. because it is an alias), we can't tell
+        // where it ends, so we leave them all alone.
+        if let Some(assigned) = assigned {
+            if !leading_lets(stmt).any(|symbol| symbol == assigned) {
+                return;
+            }
+        }
+
+        for symbol in leading_lets(stmt) {
+            self.record_region(symbol, region);
+
+            if Some(symbol) == assigned {
+                return;
+            }
+        }
+    }
+
     fn push_active_specialization(&mut self, specialization: Symbol) {
         self.specialization_stack.0.push(specialization);
     }
@@ -1010,6 +1054,7 @@ impl<'a> Procs<'a> {
             host_specializations: HostSpecializations::default(),
             runtime_errors: self.runtime_errors,
             imported_module_thunks: self.imported_module_thunks,
+            symbol_regions: self.symbol_regions,
         };
 
         (
@@ -2438,16 +2483,24 @@ fn from_can_let<'a>(
 ) -> Stmt<'a> {
     use roc_can::expr::Expr::*;
 
+    let cont_region = cont.region;
+
     macro_rules! lower_rest {
         ($variable:expr, $expr:expr) => {
             lower_rest!(env, procs, layout_cache, $variable, $expr)
         };
         ($env:expr, $procs:expr, $layout_cache:expr, $variable:expr, $expr:expr) => {
             match opt_assigned_and_hole {
-                None => from_can($env, $variable, $expr, $procs, $layout_cache),
-                Some((assigned, hole)) => with_hole(
+                None => from_can_located(
                     $env,
-                    $expr,
+                    $variable,
+                    Loc::at(cont_region, $expr),
+                    $procs,
+                    $layout_cache,
+                ),
+                Some((assigned, hole)) => with_hole_located(
+                    $env,
+                    Loc::at(cont_region, $expr),
                     $variable,
                     $procs,
                     $layout_cache,
@@ -2459,6 +2512,8 @@ fn from_can_let<'a>(
     }
 
     if let roc_can::pattern::Pattern::Identifier(symbol) = &def.loc_pattern.value {
+        procs.record_region(*symbol, def.loc_pattern.region);
+
         return match def.loc_expr.value {
             Closure(closure_data) => {
                 register_capturing_closure(env, procs, layout_cache, *symbol, closure_data);
@@ -2724,9 +2779,9 @@ fn from_can_let<'a>(
             _ => {
                 let rest = lower_rest!(variable, cont.value);
 
-                with_hole(
+                with_hole_located(
                     env,
-                    def.loc_expr.value,
+                    def.loc_expr,
                     def.expr_var,
                     procs,
                     layout_cache,
@@ -2762,9 +2817,9 @@ fn from_can_let<'a>(
             stmt = store_pattern(env, procs, layout_cache, &mono_pattern, outer_symbol, stmt);
 
             // convert the def body, store in outer_symbol
-            with_hole(
+            with_hole_located(
                 env,
-                def.loc_expr.value,
+                def.loc_expr,
                 def.expr_var,
                 procs,
                 layout_cache,
@@ -4613,9 +4668,9 @@ pub fn with_hole<'a>(
             variable: cond_variable,
             symbol: dbg_symbol,
         } => {
-            let rest = with_hole(
+            let rest = with_hole_located(
                 env,
-                loc_continuation.value,
+                *loc_continuation,
                 variable,
                 procs,
                 layout_cache,
@@ -4654,9 +4709,9 @@ pub fn with_hole<'a>(
                     if is_terminated {
                         let terminator = hole;
 
-                        let mut stmt = with_hole(
+                        let mut stmt = with_hole_located(
                             env,
-                            final_else.value,
+                            *final_else,
                             branch_var,
                             procs,
                             layout_cache,
@@ -4667,9 +4722,9 @@ pub fn with_hole<'a>(
                         for (loc_cond, loc_then) in branches.into_iter().rev() {
                             let branching_symbol = env.unique_symbol();
 
-                            let then = with_hole(
+                            let then = with_hole_located(
                                 env,
-                                loc_then.value,
+                                loc_then,
                                 branch_var,
                                 procs,
                                 layout_cache,
@@ -4680,9 +4735,9 @@ pub fn with_hole<'a>(
                             stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
                             // add condition
-                            stmt = with_hole(
+                            stmt = with_hole_located(
                                 env,
-                                loc_cond.value,
+                                loc_cond,
                                 cond_var,
                                 procs,
                                 layout_cache,
@@ -4699,9 +4754,9 @@ pub fn with_hole<'a>(
                             .arena
                             .alloc(Stmt::Jump(id, env.arena.alloc([assigned_in_jump])));
 
-                        let mut stmt = with_hole(
+                        let mut stmt = with_hole_located(
                             env,
-                            final_else.value,
+                            *final_else,
                             branch_var,
                             procs,
                             layout_cache,
@@ -4718,9 +4773,9 @@ pub fn with_hole<'a>(
                                 cond_var,
                             );
 
-                            let then = with_hole(
+                            let then = with_hole_located(
                                 env,
-                                loc_then.value,
+                                loc_then,
                                 branch_var,
                                 procs,
                                 layout_cache,
@@ -6917,6 +6972,60 @@ fn register_capturing_closure<'a>(
     }
 }
 
+/// The symbols that the `Let`s at the start of `stmt` bind, up to the first statement that isn't
+/// a `Let`.
+fn leading_lets<'a, 'b>(mut stmt: &'b Stmt<'a>) -> impl Iterator<Item = Symbol> + 'b {
+    std::iter::from_fn(move || match stmt {
+        Stmt::Let(symbol, _, _, cont) => {
+            stmt = *cont;
+
+            Some(*symbol)
+        }
+        _ => None,
+    })
+}
+
+/// Like [from_can], but the statements are attributed to where `loc_expr` is in the source, for
+/// debug info.
+fn from_can_located<'a>(
+    env: &mut Env<'a, '_>,
+    variable: Variable,
+    loc_expr: Loc<roc_can::expr::Expr>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+) -> Stmt<'a> {
+    let stmt = from_can(env, variable, loc_expr.value, procs, layout_cache);
+    procs.record_statement_regions(&stmt, loc_expr.region, None);
+
+    stmt
+}
+
+/// Like [with_hole], but the statements that compute `assigned` are attributed to where
+/// `loc_expr` is in the source, for debug info.
+#[allow(clippy::too_many_arguments)]
+fn with_hole_located<'a>(
+    env: &mut Env<'a, '_>,
+    loc_expr: Loc<roc_can::expr::Expr>,
+    variable: Variable,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    assigned: Symbol,
+    hole: &'a Stmt<'a>,
+) -> Stmt<'a> {
+    let stmt = with_hole(
+        env,
+        loc_expr.value,
+        variable,
+        procs,
+        layout_cache,
+        assigned,
+        hole,
+    );
+    procs.record_statement_regions(&stmt, loc_expr.region, Some(assigned));
+
+    stmt
+}
+
 pub fn from_can<'a>(
     env: &mut Env<'a, '_>,
     variable: Variable,
@@ -6984,7 +7093,7 @@ pub fn from_can<'a>(
                 "invalid condition type in if expression"
             );
 
-            let mut stmt = from_can(env, branch_var, final_else.value, procs, layout_cache);
+            let mut stmt = from_can_located(env, branch_var, *final_else, procs, layout_cache);
 
             for (loc_cond, loc_then) in branches.into_iter().rev() {
                 let branching_symbol = possible_reuse_symbol_or_specialize(
@@ -6994,7 +7103,7 @@ pub fn from_can<'a>(
                     &loc_cond.value,
                     cond_var,
                 );
-                let then = from_can(env, branch_var, loc_then.value, procs, layout_cache);
+                let then = from_can_located(env, branch_var, loc_then, procs, layout_cache);
 
                 stmt = cond(env, branching_symbol, cond_layout, then, stmt, ret_layout);
 
@@ -7017,7 +7126,7 @@ pub fn from_can<'a>(
             loc_continuation,
             lookups_in_cond,
         } => {
-            let rest = from_can(env, variable, loc_continuation.value, procs, layout_cache);
+            let rest = from_can_located(env, variable, *loc_continuation, procs, layout_cache);
             let cond_symbol = env.unique_symbol();
 
             let mut lookups = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
@@ -7064,9 +7173,9 @@ pub fn from_can<'a>(
                 remainder: env.arena.alloc(rest),
             };
 
-            stmt = with_hole(
+            stmt = with_hole_located(
                 env,
-                loc_condition.value,
+                *loc_condition,
                 Variable::BOOL,
                 procs,
                 layout_cache,
@@ -7086,7 +7195,7 @@ pub fn from_can<'a>(
             loc_continuation,
             lookups_in_cond,
         } => {
-            let rest = from_can(env, variable, loc_continuation.value, procs, layout_cache);
+            let rest = from_can_located(env, variable, *loc_continuation, procs, layout_cache);
             let cond_symbol = env.unique_symbol();
 
             let mut lookups = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
@@ -7133,9 +7242,9 @@ pub fn from_can<'a>(
                 remainder: env.arena.alloc(rest),
             };
 
-            stmt = with_hole(
+            stmt = with_hole_located(
                 env,
-                loc_condition.value,
+                *loc_condition,
                 Variable::BOOL,
                 procs,
                 layout_cache,
@@ -7156,7 +7265,7 @@ pub fn from_can<'a>(
             variable: cond_variable,
             symbol: dbg_symbol,
         } => {
-            let rest = from_can(env, variable, loc_continuation.value, procs, layout_cache);
+            let rest = from_can_located(env, variable, *loc_continuation, procs, layout_cache);
 
             compile_dbg(
                 env,
@@ -7195,7 +7304,7 @@ pub fn from_can<'a>(
                 unreachable!("recursive value does not have Identifier pattern")
             }
 
-            from_can(env, variable, cont.value, procs, layout_cache)
+            from_can_located(env, variable, *cont, procs, layout_cache)
         }
         LetNonRec(def, cont) => from_can_let(env, procs, layout_cache, def, cont, variable, None),
         _ => {
@@ -7230,7 +7339,7 @@ fn to_opt_branches<'a>(
 ) -> std::vec::Vec<(
     Pattern<'a>,
     Option<Loc<roc_can::expr::Expr>>,
-    roc_can::expr::Expr,
+    Loc<roc_can::expr::Expr>,
 )> {
     debug_assert!(!branches.is_empty());
 
@@ -7242,6 +7351,8 @@ fn to_opt_branches<'a>(
             continue;
         }
 
+        let body_region = when_branch.value.region;
+
         for loc_pattern in when_branch.patterns {
             match from_can_pattern(env, procs, layout_cache, &loc_pattern.pattern.value) {
                 Ok((mono_pattern, assignments)) => {
@@ -7275,14 +7386,21 @@ fn to_opt_branches<'a>(
                     };
 
                     // TODO remove clone?
-                    opt_branches.push((mono_pattern, when_branch.guard.clone(), loc_expr.value));
+                    opt_branches.push((
+                        mono_pattern,
+                        when_branch.guard.clone(),
+                        Loc::at(body_region, loc_expr.value),
+                    ));
                 }
                 Err(runtime_error) => {
                     // TODO remove clone?
                     opt_branches.push((
                         Pattern::Underscore,
                         when_branch.guard.clone(),
-                        roc_can::expr::Expr::RuntimeError(runtime_error),
+                        Loc::at(
+                            body_region,
+                            roc_can::expr::Expr::RuntimeError(runtime_error),
+                        ),
                     ));
                 }
             }
@@ -7295,7 +7413,9 @@ fn to_opt_branches<'a>(
         opt_branches.push((
             Pattern::Underscore,
             None,
-            roc_can::expr::Expr::RuntimeError(roc_problem::can::RuntimeError::NonExhaustivePattern),
+            Loc::at_zero(roc_can::expr::Expr::RuntimeError(
+                roc_problem::can::RuntimeError::NonExhaustivePattern,
+            )),
         ));
     }
 
@@ -7336,7 +7456,7 @@ fn from_can_when<'a>(
     let arena = env.arena;
     let it = opt_branches
         .into_iter()
-        .filter_map(|(pattern, opt_guard, can_expr)| {
+        .filter_map(|(pattern, opt_guard, loc_body)| {
             // If the pattern has a void layout we can drop it; however, we must still perform the
             // work of building the body, because that may contain specializations we must
             // discover for use elsewhere. See
@@ -7354,13 +7474,13 @@ fn from_can_when<'a>(
             };
 
             let branch_stmt = match join_point {
-                None => from_can(env, expr_var, can_expr, procs, layout_cache),
+                None => from_can_located(env, expr_var, loc_body, procs, layout_cache),
                 Some(id) => {
                     let symbol = env.unique_symbol();
                     let arguments = bumpalo::vec![in env.arena; symbol].into_bump_slice();
                     let jump = env.arena.alloc(Stmt::Jump(id, arguments));
 
-                    with_hole(env, can_expr, expr_var, procs, layout_cache, symbol, jump)
+                    with_hole_located(env, loc_body, expr_var, procs, layout_cache, symbol, jump)
                 }
             };
 
@@ -8484,6 +8604,12 @@ fn assign_to_symbol<'a>(
     result: Stmt<'a>,
 ) -> Stmt<'a> {
     use ReuseSymbol::*;
+
+    procs.record_region(symbol, loc_arg.region);
+    if let roc_can::expr::Expr::Closure(closure_data) = &loc_arg.value {
+        procs.record_region(closure_data.name, loc_arg.region);
+    }
+
     match can_reuse_symbol(env, layout_cache, procs, &loc_arg.value, arg_var) {
         Imported(original) | LocalFunction(original) | UnspecializedExpr(original) => {
             // for functions we must make sure they are specialized correctly
@@ -8498,9 +8624,9 @@ fn assign_to_symbol<'a>(
             )
         }
         Value(_symbol) => result,
-        NotASymbol => with_hole(
+        NotASymbol => with_hole_located(
             env,
-            loc_arg.value,
+            loc_arg,
             arg_var,
             procs,
             layout_cache,
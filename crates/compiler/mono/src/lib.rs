@@ -16,6 +16,7 @@ pub mod ir;
 pub mod layout;
 pub mod low_level;
 pub mod reset_reuse;
+pub mod source_locations;
 pub mod tail_recursion;
 
 pub mod debug;
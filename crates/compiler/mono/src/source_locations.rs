@@ -0,0 +1,46 @@
+use roc_collections::all::MutMap;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::{LineColumn, LineInfo, Region};
+use std::path::{Path, PathBuf};
+
+/// Where the procs, named values and statements of a program are in its source code. The
+/// backends use this to point the debug info of the code they generate at real files, lines and
+/// columns.
+///
+/// A statement is known by the symbol it binds (for a `Let`) or uses (for e.g. a `Ret` or a
+/// `Switch`), so the position of that symbol is where the statement is.
+#[derive(Debug, Default)]
+pub struct SourceLocations {
+    modules: MutMap<ModuleId, (PathBuf, LineInfo)>,
+    symbol_regions: MutMap<Symbol, Region>,
+}
+
+impl SourceLocations {
+    pub fn new(
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+        symbol_regions: MutMap<Symbol, Region>,
+    ) -> Self {
+        let modules = sources
+            .iter()
+            .map(|(module_id, (path, src))| (*module_id, (path.clone(), LineInfo::new(src))))
+            .collect();
+
+        Self {
+            modules,
+            symbol_regions,
+        }
+    }
+
+    pub fn path(&self, module_id: ModuleId) -> Option<&Path> {
+        self.modules.get(&module_id).map(|(path, _)| path.as_path())
+    }
+
+    /// Where `symbol` is defined in the source of its module, or for the symbols the compiler
+    /// made up, where the expression it holds the value of is
+    pub fn position(&self, symbol: Symbol) -> Option<LineColumn> {
+        let region = self.symbol_regions.get(&symbol)?;
+        let (_, line_info) = self.modules.get(&symbol.module_id())?;
+
+        Some(line_info.convert_pos(region.start()))
+    }
+}
@@ -111,6 +111,23 @@ impl<'a> From<&'a str> for PackageName<'a> {
     }
 }
 
+/// The version of a package, e.g. the "1.2.3" in `version "1.2.3"`.
+/// This is checked to be a valid semantic version when the package is loaded, not when it's parsed.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PackageVersion<'a>(&'a str);
+
+impl<'a> PackageVersion<'a> {
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+}
+
+impl<'a> From<&'a str> for PackageVersion<'a> {
+    fn from(string: &'a str) -> Self {
+        Self(string)
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
 pub struct ModuleName<'a>(&'a str);
 
@@ -176,6 +193,7 @@ keywords! {
     RequiresKeyword => "requires",
     ProvidesKeyword => "provides",
     ToKeyword => "to",
+    VersionKeyword => "version",
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -239,6 +257,8 @@ pub struct PackageHeader<'a> {
     pub before_name: &'a [CommentOrNewline<'a>],
     pub name: Loc<PackageName<'a>>,
 
+    pub version: Option<KeywordItem<'a, VersionKeyword, Loc<PackageVersion<'a>>>>,
+
     pub exposes: KeywordItem<'a, ExposesKeyword, Collection<'a, Loc<Spaced<'a, ModuleName<'a>>>>>,
     pub packages:
         KeywordItem<'a, PackagesKeyword, Collection<'a, Loc<Spaced<'a, PackageEntry<'a>>>>>,
@@ -356,6 +376,20 @@ pub fn package_name<'a>() -> impl Parser<'a, PackageName<'a>, EPackageName<'a>>
     )
 }
 
+pub fn package_version<'a>() -> impl Parser<'a, PackageVersion<'a>, EPackageName<'a>> {
+    then(
+        loc!(specialize(
+            EPackageName::BadPath,
+            string_literal::parse_str_literal()
+        )),
+        move |_arena, state, progress, text| match text.value {
+            StrLiteral::PlainLine(text) => Ok((progress, PackageVersion(text), state)),
+            StrLiteral::Line(_) => Err((progress, EPackageName::Escapes(text.region.start()))),
+            StrLiteral::Block(_) => Err((progress, EPackageName::Multiline(text.region.start()))),
+        },
+    )
+}
+
 impl<'a, K, V> Malformed for KeywordItem<'a, K, V>
 where
     K: Malformed,
@@ -1,17 +1,18 @@
 use crate::ast::{Collection, Defs, Header, Module, Spaced, Spaces};
 use crate::blankspace::{space0_around_ee, space0_before_e, space0_e};
 use crate::header::{
-    package_entry, package_name, AppHeader, ExposedName, ExposesKeyword, GeneratesKeyword,
-    HostedHeader, ImportsEntry, ImportsKeyword, InterfaceHeader, Keyword, KeywordItem, ModuleName,
-    PackageEntry, PackageHeader, PackagesKeyword, PlatformHeader, PlatformRequires,
-    ProvidesKeyword, ProvidesTo, RequiresKeyword, To, ToKeyword, TypedIdent, WithKeyword,
+    package_entry, package_name, package_version, AppHeader, ExposedName, ExposesKeyword,
+    GeneratesKeyword, HostedHeader, ImportsEntry, ImportsKeyword, InterfaceHeader, Keyword,
+    KeywordItem, ModuleName, PackageEntry, PackageHeader, PackageVersion, PackagesKeyword,
+    PlatformHeader, PlatformRequires, ProvidesKeyword, ProvidesTo, RequiresKeyword, To, ToKeyword,
+    TypedIdent, VersionKeyword, WithKeyword,
 };
 use crate::ident::{self, lowercase_ident, unqualified_ident, uppercase, UppercaseIdent};
 use crate::parser::Progress::{self, *};
 use crate::parser::{
     backtrackable, increment_min_indent, optional, reset_min_indent, specialize, word1, word2,
     EExposes, EGenerates, EGeneratesWith, EHeader, EImports, EPackages, EProvides, ERequires,
-    ETypedIdent, Parser, SourceError, SpaceProblem, SyntaxError,
+    ETypedIdent, EVersion, Parser, SourceError, SpaceProblem, SyntaxError,
 };
 use crate::state::State;
 use crate::string_literal::{self, parse_str_literal};
@@ -195,6 +196,7 @@ fn package_header<'a>() -> impl Parser<'a, PackageHeader<'a>, EHeader<'a>> {
     record!(PackageHeader {
         before_name: space0_e(EHeader::IndentStart),
         name: loc!(specialize(EHeader::PackageName, package_name())),
+        version: optional(specialize(EHeader::Version, version())),
         exposes: specialize(EHeader::Exposes, exposes_modules()),
         packages: specialize(EHeader::Packages, packages()),
     })
@@ -490,6 +492,20 @@ fn packages<'a>() -> impl Parser<
     })
 }
 
+#[inline(always)]
+fn version<'a>(
+) -> impl Parser<'a, KeywordItem<'a, VersionKeyword, Loc<PackageVersion<'a>>>, EVersion<'a>> {
+    record!(KeywordItem {
+        keyword: spaces_around_keyword(
+            VersionKeyword,
+            EVersion::Version,
+            EVersion::IndentVersion,
+            EVersion::IndentVersionStart
+        ),
+        item: loc!(specialize(EVersion::BadVersion, package_version()))
+    })
+}
+
 #[inline(always)]
 fn generates<'a>(
 ) -> impl Parser<'a, KeywordItem<'a, GeneratesKeyword, UppercaseIdent<'a>>, EGenerates> {
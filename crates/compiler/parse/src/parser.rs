@@ -99,6 +99,7 @@ impl_space_problem! {
     ERequires<'a>,
     EString<'a>,
     EType<'a>,
+    EVersion<'a>,
     ETypeInParens<'a>,
     ETypeRecord<'a>,
     ETypeTagUnion<'a>,
@@ -120,6 +121,7 @@ pub enum EHeader<'a> {
     Packages(EPackages<'a>, Position),
     Generates(EGenerates, Position),
     GeneratesWith(EGeneratesWith, Position),
+    Version(EVersion<'a>, Position),
 
     Space(BadInputError, Position),
     Start(Position),
@@ -204,6 +206,15 @@ pub enum EPackageName<'a> {
     Multiline(Position),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EVersion<'a> {
+    Version(Position),
+    IndentVersion(Position),
+    IndentVersionStart(Position),
+    BadVersion(EPackageName<'a>, Position),
+    Space(BadInputError, Position),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EPackageEntry<'a> {
     BadPackage(EPackageName<'a>, Position),
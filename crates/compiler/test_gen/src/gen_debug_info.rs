@@ -0,0 +1,268 @@
+//! Tests of the debug info the backends emit. Object files are read back with gimli, and Wasm
+//! binaries with their source map, to check that their code maps to the right lines of the Roc source.
+
+// The object file helpers are unused when only the wasm backend is tested
+#![cfg_attr(
+    not(any(feature = "gen-llvm", feature = "gen-dev")),
+    allow(dead_code, unused_imports)
+)]
+
+use bumpalo::Bump;
+use indoc::indoc;
+use roc_build::program::{gen_from_mono_module, CodeGenBackend, CodeGenOptions};
+use roc_load::{ExecutionMode, FunctionKind, LoadConfig, MonomorphizedModule, Threading};
+use roc_mono::ir::OptLevel;
+use roc_packaging::cache::RocCacheDir;
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use target_lexicon::Triple;
+
+const SOURCE: &str = indoc!(
+    r#"
+    app "test" provides [main] to "./platform"
+
+    double = \x ->
+        y = x * 2
+        y + 1
+
+    main =
+        a = double 3
+        b = double a
+        a + b
+    "#
+);
+
+/// The lines of the statements of `double` and `main` in [SOURCE]
+const STATEMENT_LINES: [u64; 5] = [4, 5, 8, 9, 10];
+
+fn load_module<'a>(arena: &'a Bump, src: &str, target_info: TargetInfo) -> MonomorphizedModule<'a> {
+    let load_config = LoadConfig {
+        target_info,
+        function_kind: FunctionKind::LambdaSet,
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Executable,
+    };
+
+    match roc_load::load_and_monomorphize_from_str(
+        arena,
+        PathBuf::from("Test.roc"),
+        src,
+        PathBuf::from("fake/test/path"),
+        RocCacheDir::Disallowed,
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(e) => panic!("{e:?}"),
+    }
+}
+
+fn build_object(src: &str, backend: CodeGenBackend) -> Vec<u8> {
+    let arena = Bump::new();
+    let loaded = load_module(&arena, src, TargetInfo::from(&Triple::host()));
+
+    let code_gen_options = CodeGenOptions {
+        backend,
+        opt_level: OptLevel::Normal,
+        emit_debug_info: true,
+        emit_llvm_ir: false,
+    };
+
+    let (object, _, _) = gen_from_mono_module(
+        &arena,
+        loaded,
+        Path::new("Test.roc"),
+        &Triple::host(),
+        code_gen_options,
+        // only used by the wasm backend
+        Path::new("host.rh"),
+        None,
+    );
+
+    object.to_vec()
+}
+
+/// The debug sections of an object file, with the offsets between them filled in like the linker
+/// would. Only the addresses of the code are left at 0.
+fn debug_sections(object_bytes: &[u8]) -> HashMap<String, Vec<u8>> {
+    use object::{Object, ObjectSection, ObjectSymbol, RelocationTarget, SymbolKind};
+
+    let object = object::File::parse(object_bytes).unwrap();
+    let mut sections = HashMap::new();
+
+    for section in object.sections() {
+        let name = section.name().unwrap();
+        if !name.starts_with(".debug") && !name.starts_with("__debug") {
+            continue;
+        }
+
+        let mut data = section.data().unwrap().to_vec();
+
+        for (offset, relocation) in section.relocations() {
+            let RelocationTarget::Symbol(index) = relocation.target() else {
+                continue;
+            };
+            let symbol = object.symbol_by_index(index).unwrap();
+
+            // With implicit addends, the offset is in the data already
+            if symbol.kind() != SymbolKind::Section || relocation.has_implicit_addend() {
+                continue;
+            }
+
+            let value = symbol.address() as i64 + relocation.addend();
+            let offset = offset as usize;
+
+            match relocation.size() {
+                32 => data[offset..][..4].copy_from_slice(&(value as u32).to_le_bytes()),
+                64 => data[offset..][..8].copy_from_slice(&(value as u64).to_le_bytes()),
+                size => panic!("unexpected relocation size {size}"),
+            }
+        }
+
+        sections.insert(name.replacen("__", ".", 1), data);
+    }
+
+    sections
+}
+
+/// The lines of `file_name` that the line tables of `object_bytes` have rows for
+fn lines_of(object_bytes: &[u8], file_name: &str) -> BTreeSet<u64> {
+    let sections = debug_sections(object_bytes);
+
+    let dwarf = gimli::Dwarf::load(|id| -> Result<_, gimli::Error> {
+        let data = sections.get(id.name()).map_or(&[][..], Vec::as_slice);
+
+        Ok(gimli::EndianSlice::new(data, gimli::LittleEndian))
+    })
+    .unwrap();
+
+    let mut lines = BTreeSet::new();
+    let mut units = dwarf.units();
+
+    while let Some(header) = units.next().unwrap() {
+        let unit = dwarf.unit(header).unwrap();
+        let Some(program) = unit.line_program.clone() else {
+            continue;
+        };
+
+        let mut rows = program.rows();
+        while let Some((header, row)) = rows.next_row().unwrap() {
+            let (Some(file), Some(line)) = (row.file(header), row.line()) else {
+                continue;
+            };
+
+            let path = dwarf.attr_string(&unit, file.path_name()).unwrap();
+            if !row.end_sequence() && path.to_string_lossy() == file_name {
+                lines.insert(line.get());
+            }
+        }
+    }
+
+    lines
+}
+
+fn assert_has_statement_lines(object_bytes: &[u8]) {
+    let lines = lines_of(object_bytes, "Test.roc");
+
+    for line in STATEMENT_LINES {
+        assert!(
+            lines.contains(&line),
+            "line {line} is not in the line table, which has {lines:?}"
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "gen-llvm")]
+fn llvm_statements_have_lines() {
+    use roc_gen_llvm::llvm::build::LlvmBackendMode;
+
+    let object = build_object(SOURCE, CodeGenBackend::Llvm(LlvmBackendMode::Binary));
+    assert_has_statement_lines(&object);
+}
+
+#[test]
+#[cfg(feature = "gen-dev")]
+fn dev_statements_have_lines() {
+    use roc_gen_dev::AssemblyBackendMode;
+
+    let object = build_object(
+        SOURCE,
+        CodeGenBackend::Assembly(AssemblyBackendMode::Binary),
+    );
+    assert_has_statement_lines(&object);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn wasm_statements_are_in_source_map() {
+    use roc_collections::MutSet;
+    use roc_mono::source_locations::SourceLocations;
+    use roc_wasm_module::source_map::SourceMap;
+    use roc_wasm_module::WasmModule;
+
+    let arena = Bump::new();
+    let loaded = load_module(&arena, SOURCE, TargetInfo::default_wasm32());
+
+    let MonomorphizedModule {
+        module_id,
+        procedures,
+        mut interns,
+        exposed_to_host,
+        mut layout_interner,
+        sources,
+        symbol_regions,
+        ..
+    } = loaded;
+
+    let env = roc_gen_wasm::Env {
+        arena: &arena,
+        module_id,
+        exposed_to_host: exposed_to_host
+            .top_level_values
+            .keys()
+            .copied()
+            .collect::<MutSet<_>>(),
+        stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+        source_locations: Some(SourceLocations::new(&sources, symbol_regions)),
+    };
+
+    // Should manually match build.rs
+    let host_bytes = include_bytes!(concat!(env!("OUT_DIR"), "/wasm_test_platform.wasm"));
+    let host_module = roc_gen_wasm::parse_host(&arena, host_bytes).unwrap();
+
+    let wasm_bytes = roc_gen_wasm::build_app_binary(
+        &env,
+        &mut layout_interner,
+        &mut interns,
+        host_module,
+        procedures,
+    );
+
+    let module = WasmModule::preload(&arena, &wasm_bytes, false).unwrap();
+    let source_map = SourceMap::from_data_url(module.source_mapping_url.url).unwrap();
+
+    let code_start = module.code.section_offset;
+    let code_end = code_start + module.code.bytes.len() as u32;
+
+    for line in STATEMENT_LINES {
+        // Source maps count lines from 0
+        let mapping = source_map
+            .mappings
+            .iter()
+            .find(|mapping| mapping.line + 1 == line as u32)
+            .unwrap_or_else(|| panic!("line {line} is not in the source map"));
+
+        assert!((code_start..code_end).contains(&mapping.code_offset));
+
+        for code_offset in [mapping.code_offset, mapping.code_offset + 1] {
+            let (path, found_line, _) = source_map.lookup(code_offset).unwrap();
+
+            assert_eq!(path, "Test.roc");
+            assert_eq!(found_line + 1, line as u32);
+        }
+    }
+}
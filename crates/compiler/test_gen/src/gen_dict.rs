@@ -550,3 +550,85 @@ fn walk_sum_keys() {
         i64
     );
 }
+
+#[cfg(feature = "gen-llvm")]
+fn dict_from_list_src<K: std::fmt::Display>(ty: &str, pairs: &[(K, u8)]) -> String {
+    let elems: Vec<String> = pairs.iter().map(|(k, v)| format!("({k}, {v})")).collect();
+
+    format!(
+        "dict : Dict.Dict {ty} U8\ndict = Dict.fromList [{}]\n\ndict",
+        elems.join(", ")
+    )
+}
+
+#[cfg(feature = "gen-llvm")]
+fn quickcheck_dict_roundtrip<K>(ty: &str, pairs: Vec<(K, u8)>, to_roc: fn(&K) -> String) -> bool
+where
+    K: Clone + Eq + std::hash::Hash + roc_std::RocHash + std::fmt::Debug,
+{
+    use crate::helpers::llvm::assert_llvm_evals_to;
+    use roc_std::RocDict;
+    use std::collections::HashMap;
+
+    let roc_pairs: Vec<(String, u8)> = pairs.iter().map(|(k, v)| (to_roc(k), *v)).collect();
+    let src = dict_from_list_src(ty, &roc_pairs);
+
+    let expected: HashMap<K, u8> = pairs.iter().cloned().collect();
+    let rust_dict: RocDict<K, u8> = pairs.iter().cloned().collect();
+
+    let lookups = |dict: &RocDict<K, u8>| {
+        pairs
+            .iter()
+            .map(|(k, _)| dict.get(k).copied())
+            .collect::<Vec<_>>()
+    };
+    let expected_lookups: Vec<_> = pairs
+        .iter()
+        .map(|(k, _)| expected.get(k).copied())
+        .collect();
+
+    // Lookups from Rust find everything Roc inserted, and Rust inserts the
+    // same entries in the same order as Roc does.
+    assert_eq!(lookups(&rust_dict), expected_lookups);
+    assert_llvm_evals_to!(
+        &src,
+        (expected_lookups, rust_dict),
+        RocDict<K, u8>,
+        |dict: RocDict<K, u8>| (lookups(&dict), dict)
+    );
+
+    true
+}
+
+#[test]
+#[cfg(feature = "gen-llvm")]
+fn dict_roundtrip_i64_keys() {
+    fn prop(pairs: Vec<(i64, u8)>) -> bool {
+        quickcheck_dict_roundtrip("I64", pairs, |k| format!("{k}i64"))
+    }
+
+    quickcheck::QuickCheck::new()
+        .tests(20)
+        .quickcheck(prop as fn(Vec<(i64, u8)>) -> bool);
+}
+
+#[test]
+#[cfg(feature = "gen-llvm")]
+fn dict_roundtrip_str_keys() {
+    fn prop(pairs: Vec<(String, u8)>) -> bool {
+        let pairs = pairs
+            .into_iter()
+            .map(|(k, v)| {
+                let k: String = k.chars().filter(char::is_ascii_alphanumeric).collect();
+
+                (RocStr::from(k.as_str()), v)
+            })
+            .collect();
+
+        quickcheck_dict_roundtrip("Str", pairs, |k| format!("\"{k}\""))
+    }
+
+    quickcheck::QuickCheck::new()
+        .tests(20)
+        .quickcheck(prop as fn(Vec<(String, u8)>) -> bool);
+}
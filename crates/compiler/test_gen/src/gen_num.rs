@@ -628,7 +628,7 @@ fn gen_float_eq() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn gen_add_dec() {
     assert_evals_to!(
         indoc!(
@@ -737,7 +737,7 @@ fn gen_div_checked_by_zero_f64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn gen_div_dec() {
     assert_evals_to!(
         indoc!(
@@ -866,7 +866,7 @@ fn float_greater_than() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn gen_dec_eq() {
     assert_evals_to!(
         indoc!(
@@ -886,7 +886,7 @@ fn gen_dec_eq() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn gen_dec_neq() {
     assert_evals_to!(
         indoc!(
@@ -1022,7 +1022,7 @@ fn gen_add_i64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn gen_sub_dec() {
     assert_evals_to!(
         indoc!(
@@ -1045,7 +1045,7 @@ fn gen_sub_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn gen_mul_dec() {
     assert_evals_to!(
         indoc!(
@@ -1661,7 +1661,7 @@ fn num_to_frac() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn num_to_frac_f64_to_f32() {
     assert_evals_to!(
         indoc!(
@@ -1680,7 +1680,7 @@ fn num_to_frac_f64_to_f32() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn num_to_frac_f32_to_f32() {
     assert_evals_to!(
         indoc!(
@@ -2599,6 +2599,44 @@ to_int_checked_tests! {
     )
 }
 
+macro_rules! to_float_checked_tests {
+    ($($fn:expr, $typ:ty, ($($test_name:ident, $input:expr, $output:expr)*))*) => {$($(
+        #[test]
+        #[cfg(feature = "gen-wasm")]
+        fn $test_name() {
+            let sentinel = 23.0;
+            // Some n = Ok n, None = OutOfBounds
+            let expected = match $output.into() {
+                None => sentinel,
+                Some(n) => {
+                    assert_ne!(n, sentinel);
+                    n
+                }
+            };
+            let input = format!("Result.withDefault ({} {}) {}", $fn, $input, sentinel);
+            assert_evals_to!(&input, expected, $typ)
+        }
+    )*)*}
+}
+
+to_float_checked_tests! {
+    "Num.toF32Checked", f32, (
+        to_f32_checked_from_i8,           "-15i8",        -15.0
+        to_f32_checked_from_u128_fits,    "15u128",       15.0
+        to_f32_checked_from_u128_oob,     "Num.maxU128",  None
+        to_f32_checked_from_f64_fits,     "1.5f64",       1.5
+        to_f32_checked_from_f64_oob,      "Num.maxF64",   None
+        to_f32_checked_from_dec,          "1.5dec",       1.5
+    )
+    "Num.toF64Checked", f64, (
+        to_f64_checked_from_i128,         "-15i128",      -15.0
+        to_f64_checked_from_u128,         "15u128",       15.0
+        to_f64_checked_from_f32,          "1.5f32",       1.5
+        to_f64_checked_from_f64,          "Num.maxF64",   f64::MAX
+        to_f64_checked_from_dec,          "1.5dec",       1.5
+    )
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn is_multiple_of_signed() {
@@ -3666,7 +3704,7 @@ fn div_of_unsigned() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn dec_float_suffix() {
     assert_evals_to!(
         indoc!(
@@ -3680,7 +3718,7 @@ fn dec_float_suffix() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn dec_no_decimal() {
     assert_evals_to!(
         indoc!(
@@ -3756,7 +3794,7 @@ fn promote_u64_number_layout() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn promote_i128_number_layout() {
     assert_evals_to!(
         indoc!(
@@ -3773,7 +3811,7 @@ fn promote_i128_number_layout() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn promote_u128_number_layout() {
     assert_evals_to!(
         indoc!(
@@ -3907,7 +3945,7 @@ fn num_abs_diff_int() {
 }
 
 #[test]
-#[cfg(feature = "gen-llvm")]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn num_abs_diff_large_bits() {
     assert_evals_to!(r#"Num.absDiff 0u128 0u128"#, 0, u128);
     assert_evals_to!(r#"Num.absDiff 1u128 2u128"#, 1, u128);
@@ -3940,7 +3978,7 @@ fn num_abs_int_min_overflow() {
 }
 
 #[test]
-#[cfg(feature = "gen-llvm")]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 #[should_panic(expected = r#"Roc failed with message: "Integer subtraction overflowed!"#)]
 fn num_abs_large_bits_min_overflow() {
     assert_evals_to!(r#"Num.absDiff Num.minI128 0"#, 0, i128);
@@ -4092,3 +4130,73 @@ fn num_max() {
     assert_evals_to!(r#"Num.max Num.minI64 Num.maxI64"#, i64::MAX, i64);
     assert_evals_to!(r#"Num.max Num.maxI64 Num.minI64"#, i64::MAX, i64);
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn i128_arithmetic() {
+    assert_evals_to!(r#"Num.addWrap Num.maxI128 1"#, i128::MIN, i128);
+    assert_evals_to!(r#"Num.subWrap Num.minI128 1"#, i128::MAX, i128);
+    assert_evals_to!(r#"Num.divTrunc -7i128 2"#, -3, i128);
+    assert_evals_to!(r#"Num.rem -7i128 2"#, -1, i128);
+    assert_evals_to!(
+        r#"Num.neg 18446744073709551616i128"#,
+        -18446744073709551616,
+        i128
+    );
+    assert_evals_to!(
+        r#"Num.abs -18446744073709551616i128"#,
+        18446744073709551616,
+        i128
+    );
+    assert_evals_to!(
+        r#"Num.divTrunc 340282366920938463463374607431768211455u128 2"#,
+        u128::MAX / 2,
+        u128
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn i128_bitwise() {
+    assert_evals_to!(
+        r#"Num.bitwiseAnd 1208907372870555465220095i128 71112198404150321483535i128"#,
+        0x0F0F_0000_0000_0000_0F0F,
+        i128
+    );
+    assert_evals_to!(
+        r#"Num.bitwiseOr 1204203453131759529492480u128 255u128"#,
+        0xFF00_0000_0000_0000_00FF,
+        u128
+    );
+    assert_evals_to!(r#"Num.bitwiseXor -1i128 0"#, -1, i128);
+    assert_evals_to!(r#"Num.shiftLeftBy 1u128 100"#, 1 << 100, u128);
+    assert_evals_to!(
+        r#"Num.shiftRightBy -(Num.shiftLeftBy 1i128 100) 99"#,
+        -2,
+        i128
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[should_panic(
+    expected = r#"Roc failed with message: "Integer negation overflowed because its argument is the minimum value"#
+)]
+fn i128_neg_min_overflow() {
+    assert_evals_to!(r#"Num.neg Num.minI128"#, 0, i128);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn int_cast_128() {
+    assert_evals_to!(r#"Num.toI128 -5i8"#, -5, i128);
+    assert_evals_to!(r#"Num.toI128 255u8"#, 255, i128);
+    assert_evals_to!(r#"Num.toU8 18446744073709551874u128"#, 2, u8);
+    assert_evals_to!(r#"Num.toI64 -3i128"#, -3, i64);
+    assert_evals_to!(
+        r#"Num.toF64 -18446744073709551616i128"#,
+        -18446744073709551616.0,
+        f64
+    );
+    assert_evals_to!(r#"Num.toF64 1.5dec"#, 1.5, f64);
+}
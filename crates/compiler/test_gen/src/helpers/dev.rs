@@ -196,6 +196,7 @@ pub fn helper(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: roc_gen_dev::AssemblyBackendMode::Test,
+        source_locations: None,
     };
 
     let target = target_lexicon::Triple::host();
@@ -231,6 +232,7 @@ pub fn helper(
             builtins_host_tempfile.path().to_str().unwrap(),
         ],
         LinkType::Dylib,
+        false,
     )
     .expect("failed to link dynamic library");
 
@@ -231,6 +231,7 @@ fn create_llvm_module<'a>(
         mode: config.mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        source_locations: None,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -6,6 +6,8 @@
 
 pub mod gen_abilities;
 pub mod gen_compare;
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+pub mod gen_debug_info;
 pub mod gen_definitions;
 pub mod gen_dict;
 pub mod gen_erased;
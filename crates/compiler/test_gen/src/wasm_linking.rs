@@ -159,6 +159,7 @@ impl<'a> BackendInputs<'a> {
             module_id,
             exposed_to_host,
             stack_bytes: Env::DEFAULT_STACK_BYTES,
+            source_locations: None,
         };
 
         // Identifier stuff for the backend
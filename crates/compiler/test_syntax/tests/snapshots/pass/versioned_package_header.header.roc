@@ -0,0 +1,4 @@
+package "foo/barbaz"
+    version "1.2.3"
+    exposes [Foo, Bar]
+    packages { foo: "./foo" }
\ No newline at end of file
@@ -4860,6 +4860,17 @@ mod test_fmt {
         );
     }
 
+    #[test]
+    fn package_with_version() {
+        module_formats_same(indoc!(
+            r#"
+                package "foo/bar"
+                    version "1.2.3"
+                    exposes [Foo]
+                    packages {}"#
+        ));
+    }
+
     #[test]
     fn module_defs_with_comments() {
         module_formats_to(
@@ -470,6 +470,7 @@ mod test_snapshots {
         pass/var_minus_two.expr,
         pass/var_then.expr,
         pass/var_when.expr,
+        pass/versioned_package_header.header,
         pass/when_if_guard.expr,
         pass/when_in_assignment.expr,
         pass/when_in_function.expr,
@@ -298,6 +298,36 @@ impl Subs {
 
         (tag_names, offset)
     }
+
+    /// Replace every symbol in these types with `remap(symbol)`. Subs read back from disk refer
+    /// to other modules by the IDs they had when they were written, which needn't be the IDs
+    /// those modules have now.
+    pub fn remap_symbols(&mut self, remap: &impl Fn(Symbol) -> Symbol) {
+        for symbol in self.symbol_names.iter_mut() {
+            *symbol = remap(*symbol);
+        }
+
+        for uls in self.unspecialized_lambda_sets.iter_mut() {
+            uls.1 = remap(uls.1);
+        }
+
+        // Everything else refers to symbols through symbol_names, besides these two.
+        for index in 0..self.len() {
+            let var = unsafe { Variable::from_index(index as u32) };
+
+            let content = match *self.get_content_without_compacting(var) {
+                Content::Alias(symbol, args, real_var, kind) => {
+                    Content::Alias(remap(symbol), args, real_var, kind)
+                }
+                Content::Structure(FlatType::Apply(symbol, args)) => {
+                    Content::Structure(FlatType::Apply(remap(symbol), args))
+                }
+                _ => continue,
+            };
+
+            self.set_content_unchecked(var, content);
+        }
+    }
 }
 
 /// Mapping of variables to [Content::LambdaSet]s containing unspecialized lambda sets depending on
@@ -0,0 +1,1339 @@
+app "c-glue"
+    packages { pf: "../platform/main.roc" }
+    imports [
+        pf.Types.{ Types },
+        pf.Shape.{ Shape, RocFn },
+        pf.File.{ File },
+        pf.TypeId.{ TypeId },
+        "../static/roc_std.h" as rocStd : Str,
+    ]
+    provides [makeGlue] to pf
+
+makeGlue : List Types -> Result (List File) Str
+makeGlue = \typesByArch ->
+    # Everything goes in a single header, so each architecture gets its own section,
+    # and the preprocessor picks the one for whichever architecture is being compiled.
+    archSections =
+        List.walkWithIndex typesByArch "" \content, types, index ->
+            directive = if index == 0 then "#if" else "#elif"
+            condition = archCondition (Types.target types).architecture
+
+            "\(content)\(directive) \(condition)\n\(generateArchSection types)\n"
+
+    content =
+        """
+        \(fileHeader)
+        #ifndef ROC_APP_H
+        #define ROC_APP_H
+
+        \(rocStd)
+        #ifdef __cplusplus
+        extern "C" {
+        #endif
+
+        \(archSections)#else
+        #error "`roc glue` did not generate code for this architecture"
+        #endif
+
+        #ifdef __cplusplus
+        }
+        #endif
+
+        #endif // ROC_APP_H
+
+        """
+
+    Ok [{ name: "roc_app.h", content }]
+
+## C requires each type to be declared before it's referred to, and to be defined
+## before anything stores it by value, so the types are generated in several passes:
+##
+## 1. A typedef for every struct and union, so that they can all refer to each other.
+## 2. The types which only point to other types, and the ones which are plain integers.
+## 3. The types which store other types by value, each one after the types it stores.
+## 4. Layout checks and helper functions, once every type has been completely defined.
+## 5. The `_decref` functions of recursive tag unions, which can call each other.
+generateArchSection : Types -> Str
+generateArchSection = \types ->
+    forwardDeclarations =
+        Types.walkShapes types "" \buf, shape, id -> declareType buf types shape id
+
+    initialBuf = if Str.isEmpty forwardDeclarations then "" else "\n\(forwardDeclarations)"
+
+    initialBuf
+    |> defineIndirectTypes types
+    |> defineDirectTypes types
+    |> generateAllHelpers types
+    |> generateDecrefs types
+    |> generateEntryPoints types
+
+declareType : Str, Types, Shape, TypeId -> Str
+declareType = \buf, types, shape, id ->
+    if isZeroSized types id then
+        buf
+    else
+        when shape is
+            Struct { name } | TagUnionPayload { name } ->
+                Str.concat buf (typedefStruct (escapeKW name))
+
+            TagUnion (SingleTagStruct { name }) | TagUnion (NullableUnwrapped { name }) | TagUnion (NonNullableUnwrapped { name }) ->
+                Str.concat buf (typedefStruct (escapeKW name))
+
+            TagUnion (NonRecursive { name }) | TagUnion (Recursive { name }) | TagUnion (NullableWrapped { name }) ->
+                escapedName = escapeKW name
+
+                buf
+                |> Str.concat (typedefStruct escapedName)
+                |> Str.concat "typedef union union_\(escapedName) union_\(escapedName);\n"
+
+            RocResult _ _ ->
+                Str.concat buf (typedefStruct (typeName types id))
+
+            Function rocFn ->
+                if rocFn.isToplevel then
+                    buf
+                else
+                    Str.concat buf (typedefStruct (escapeKW rocFn.functionName))
+
+            _ ->
+                # These are either C primitives or defined in roc_std.h, except for
+                # enumerations, which aren't structs.
+                buf
+
+typedefStruct = \name -> "typedef struct \(name) \(name);\n"
+
+## Defines the types which don't store any other type by value, so they
+## can be defined as soon as every type has been declared.
+defineIndirectTypes : Str, Types -> Str
+defineIndirectTypes = \buf, types ->
+    Types.walkShapes types buf \accum, shape, id -> defineIndirectType accum types shape id
+
+defineIndirectType : Str, Types, Shape, TypeId -> Str
+defineIndirectType = \buf, types, shape, id ->
+    if isZeroSized types id then
+        buf
+    else
+        when shape is
+            TagUnion (Enumeration { name, tags, size }) ->
+                generateEnumeration buf (escapeKW name) tags size
+
+            TagUnion (NonRecursive { name, tags, discriminantSize }) ->
+                discriminantName = "discriminant_\(escapeKW name)"
+
+                generateEnumeration buf discriminantName (List.map tags .name) discriminantSize
+
+            TagUnion (Recursive { name, tags, discriminantSize }) | TagUnion (NullableWrapped { name, tags, discriminantSize }) ->
+                escapedName = escapeKW name
+                discriminantName = "discriminant_\(escapedName)"
+
+                """
+                \(generateEnumeration buf discriminantName (List.map tags .name) discriminantSize)
+                // A pointer to a union_\(escapedName), with the tag stored in its lowest bits.
+                struct \(escapedName) {
+                    union_\(escapedName) *pointer;
+                };
+
+                """
+
+            TagUnion (NullableUnwrapped { name, nullTag, nonNullTag, nonNullPayload, whichTagIsNull }) ->
+                escapedName = escapeKW name
+                discriminantName = "discriminant_\(escapedName)"
+
+                discriminantTags =
+                    when whichTagIsNull is
+                        FirstTagIsNull -> [nullTag, nonNullTag]
+                        SecondTagIsNull -> [nonNullTag, nullTag]
+
+                """
+                \(generateEnumeration buf discriminantName discriminantTags 1)
+                // A null pointer for the `\(nullTag)` tag, or a pointer to the payload of the `\(nonNullTag)` tag.
+                struct \(escapedName) {
+                    \(typeName types nonNullPayload) *pointer;
+                };
+
+                """
+
+            TagUnion (NonNullableUnwrapped { name, payload }) ->
+                escapedName = escapeKW name
+
+                """
+                \(buf)
+                struct \(escapedName) {
+                    \(typeName types payload) *pointer;
+                };
+
+                """
+
+            Function rocFn ->
+                if rocFn.isToplevel then
+                    buf
+                else
+                    name = escapeKW rocFn.functionName
+
+                    """
+                    \(buf)
+                    // A closure, along with the values it captured. Call it with \(name)_force_thunk.
+                    struct \(name) {
+                        void *closure_data;
+                    };
+
+                    """
+
+            _ ->
+                buf
+
+## Defines the types which store other types by value. C requires those other
+## types to be defined first, so each type's dependencies are visited before it.
+defineDirectTypes : Str, Types -> Str
+defineDirectTypes = \buf, types ->
+    initialState = { buf, visited: Set.empty {}, definedResults: Set.empty {} }
+
+    finalState = Types.walkShapes types initialState \state, _, id -> defineWithDependencies state types id
+
+    finalState.buf
+
+defineWithDependencies = \state, types, id ->
+    if Set.contains state.visited id then
+        state
+    else
+        shape = Types.shape types id
+        visitedState = { state & visited: Set.insert state.visited id }
+
+        directDependencies types shape
+        |> List.walk visitedState \accum, dependency -> defineWithDependencies accum types dependency
+        |> defineDirectType types shape id
+
+## The types which have to be completely defined before this one can be.
+directDependencies : Types, Shape -> List TypeId
+directDependencies = \types, shape ->
+    tagPayloads = \tags ->
+        List.keepOks tags \{ payload } ->
+            when payload is
+                Some payloadId -> Ok payloadId
+                None -> Err NoPayload
+
+    candidates =
+        when shape is
+            Struct { fields } | TagUnionPayload { fields } ->
+                List.map (fieldsToList fields) .id
+
+            TagUnion (SingleTagStruct { payload }) ->
+                singleTagFieldIds payload
+
+            # A recursive tag union's payloads are stored by value in the union it points to.
+            TagUnion (NonRecursive { tags }) | TagUnion (Recursive { tags }) | TagUnion (NullableWrapped { tags }) ->
+                tagPayloads tags
+
+            RocResult okId errId ->
+                [okId, errId]
+
+            _ ->
+                []
+
+    List.keepIf candidates \candidateId -> isStoredDirectly (Types.shape types candidateId)
+
+isStoredDirectly : Shape -> Bool
+isStoredDirectly = \shape ->
+    when shape is
+        Struct _ | TagUnionPayload _ | RocResult _ _ | TagUnion (NonRecursive _) | TagUnion (SingleTagStruct _) -> Bool.true
+        _ -> Bool.false
+
+defineDirectType = \state, types, shape, id ->
+    if isZeroSized types id then
+        state
+    else
+        when shape is
+            Struct { name, fields } ->
+                { state & buf: generateStruct state.buf types (escapeKW name) (fieldsToList fields) }
+
+            TagUnionPayload { name, fields } ->
+                { state & buf: generateStruct state.buf types (escapeKW name) (payloadFields fields) }
+
+            TagUnion (SingleTagStruct { name, payload }) ->
+                fields = List.mapWithIndex (singleTagFieldIds payload) \fieldId, index -> { name: "f\(Num.toStr index)", id: fieldId }
+
+                { state & buf: generateStruct state.buf types (escapeKW name) fields }
+
+            TagUnion (NonRecursive { name, tags, discriminantOffset }) ->
+                { state & buf: generateNonRecursiveTagUnion state.buf types id name tags discriminantOffset }
+
+            TagUnion (Recursive { name, tags }) | TagUnion (NullableWrapped { name, tags }) ->
+                unionName = "union_\(escapeKW name)"
+
+                newBuf =
+                    """
+                    \(state.buf)
+                    union \(unionName) {
+                    \(generateUnionFields types tags)};
+
+                    """
+
+                { state & buf: newBuf }
+
+            RocResult okId errId ->
+                # Different Roc types can have the same C type, e.g. `List U8` and `List Str`
+                # are both a RocList, so the same RocResult may come up more than once.
+                name = typeName types id
+
+                if Set.contains state.definedResults name then
+                    state
+                else
+                    { state &
+                        buf: generateRocResult state.buf types name okId errId,
+                        definedResults: Set.insert state.definedResults name,
+                    }
+
+            _ ->
+                state
+
+generateStruct : Str, Types, Str, List { name : Str, id : TypeId } -> Str
+generateStruct = \buf, types, name, fields ->
+    fieldDecls =
+        List.walk fields "" \accum, { name: fieldName, id: fieldId } ->
+            if isZeroSized types fieldId then
+                accum
+            else
+                Str.concat accum "\(indent)\(typeName types fieldId) \(escapeKW fieldName);\n"
+
+    """
+    \(buf)
+    struct \(name) {
+    \(fieldDecls)};
+
+    """
+
+generateNonRecursiveTagUnion : Str, Types, TypeId, Str, List { name : Str, payload : [Some TypeId, None] }, U32 -> Str
+generateNonRecursiveTagUnion = \buf, types, id, name, tags, discriminantOffset ->
+    escapedName = escapeKW name
+    unionName = "union_\(escapedName)"
+    discriminantOffsetStr = Num.toStr discriminantOffset
+    sizeStr = Num.toStr (Types.size types id)
+
+    # Roc stores the discriminant after the largest payload, which can be inside of the
+    # padding at the end of the union, so it's read and written at its offset instead of
+    # being a field. The extra member makes room for it when there's no padding to use.
+    """
+    \(buf)
+    union \(unionName) {
+    \(generateUnionFields types tags)    // Makes room for the discriminant, which Roc stores at byte \(discriminantOffsetStr).
+        uint8_t _sizer[\(sizeStr)];
+    };
+
+    struct \(escapedName) {
+        \(unionName) payload;
+    };
+
+    """
+
+generateRocResult : Str, Types, Str, TypeId, TypeId -> Str
+generateRocResult = \buf, types, name, okId, errId ->
+    unionFields =
+        [{ name: "ok", id: okId }, { name: "err", id: errId }]
+        |> List.walk "" \accum, { name: fieldName, id: fieldId } ->
+            if isZeroSized types fieldId then
+                accum
+            else
+                Str.concat accum "\(indent)\(indent)\(typeName types fieldId) \(fieldName);\n"
+
+    payload =
+        if Str.isEmpty unionFields then
+            ""
+        else
+            "\(indent)union {\n\(unionFields)\(indent)} payload;\n"
+
+    """
+    \(buf)
+    // Check `tag` against RocResult_Ok and RocResult_Err before reading the payload.
+    struct \(name) {
+    \(payload)    uint8_t tag;
+    };
+
+    """
+
+generateEnumeration : Str, Str, List Str, U32 -> Str
+generateEnumeration = \buf, name, tags, size ->
+    tagDecls =
+        List.walkWithIndex tags "" \accum, tagName, index ->
+            indexStr = Num.toStr index
+
+            Str.concat accum "\(indent)\(name)_\(tagName) = \(indexStr),\n"
+
+    """
+    \(buf)
+    typedef \(unsignedType size) \(name);
+    enum {
+    \(tagDecls)};
+
+    """
+
+generateUnionFields = \types, tags ->
+    List.walk tags "" \accum, { name: tagName, payload } ->
+        when nonZeroSized types payload is
+            Some payloadId ->
+                Str.concat accum "\(indent)\(typeName types payloadId) \(escapeKW tagName);\n"
+
+            None ->
+                accum
+
+generateAllHelpers : Str, Types -> Str
+generateAllHelpers = \buf, types ->
+    Types.walkShapes types buf \accum, shape, id -> generateHelpers accum types shape id
+
+generateHelpers : Str, Types, Shape, TypeId -> Str
+generateHelpers = \buf, types, shape, id ->
+    if isZeroSized types id then
+        buf
+    else
+        when shape is
+            Struct { name } | TagUnionPayload { name } | TagUnion (Enumeration { name }) ->
+                "\(buf)\n\(layoutCheck types id (escapeKW name))\n"
+
+            RocResult _ _ ->
+                "\(buf)\n\(layoutCheck types id (typeName types id))\n"
+
+            TagUnion (NonRecursive { name, tags, discriminantOffset }) ->
+                generateNonRecursiveHelpers buf types id name tags discriminantOffset
+
+            TagUnion (Recursive { name, tags }) ->
+                generateRecursiveHelpers buf types id name tags None
+
+            TagUnion (NullableWrapped { name, tags, indexOfNullTag }) ->
+                generateRecursiveHelpers buf types id name tags (Some indexOfNullTag)
+
+            TagUnion (NullableUnwrapped { name, nullTag, nonNullTag, nonNullPayload }) ->
+                generateNullableUnwrappedHelpers buf types id name nullTag nonNullTag nonNullPayload
+
+            TagUnion (NonNullableUnwrapped { name, tagName, payload }) ->
+                generateNonNullableUnwrappedHelpers buf types id name tagName payload
+
+            TagUnion (SingleTagStruct { name, tagName, payload }) ->
+                generateSingleTagStructHelpers buf types id name tagName payload
+
+            Function rocFn ->
+                if rocFn.isToplevel then
+                    buf
+                else
+                    generateClosureHelpers buf types rocFn
+
+            _ ->
+                buf
+
+generateNonRecursiveHelpers = \buf, types, id, name, tags, discriminantOffset ->
+    escapedName = escapeKW name
+    discriminantName = "discriminant_\(escapedName)"
+    discriminantOffsetStr = Num.toStr discriminantOffset
+
+    functions =
+        List.walk tags "" \accum, { name: tagName, payload } ->
+            payloadExpr = "tag_union.payload.\(escapeKW tagName)"
+            { params, assignments } = tagConstructor types payload payloadExpr (memberOf payloadExpr)
+
+            getter =
+                when nonZeroSized types payload is
+                    Some payloadId ->
+                        """
+
+                        static inline \(typeName types payloadId) \(escapedName)_get_\(tagName)(\(escapedName) tag_union) {
+                            assert(\(escapedName)_is_\(tagName)(tag_union));
+
+                            return \(payloadExpr);
+                        }
+
+                        """
+
+                    None ->
+                        ""
+
+            """
+            \(accum)
+            static inline \(escapedName) \(escapedName)_make_\(tagName)(\(params)) {
+                \(escapedName) tag_union;
+
+                memset(&tag_union, 0, sizeof(tag_union));
+            \(assignments)    \(escapedName)_set_discriminant(&tag_union, \(discriminantName)_\(tagName));
+
+                return tag_union;
+            }
+
+            static inline bool \(escapedName)_is_\(tagName)(\(escapedName) tag_union) {
+                return \(escapedName)_discriminant(tag_union) == \(discriminantName)_\(tagName);
+            }
+            \(getter)
+            """
+
+    """
+    \(buf)
+    \(layoutCheck types id escapedName)
+
+    \(discriminantDocComment)
+    static inline \(discriminantName) \(escapedName)_discriminant(\(escapedName) tag_union) {
+        \(discriminantName) discriminant;
+
+        memcpy(&discriminant, (const char *)&tag_union + \(discriminantOffsetStr), sizeof(discriminant));
+
+        return discriminant;
+    }
+
+    static inline void \(escapedName)_set_discriminant(\(escapedName) *tag_union, \(discriminantName) discriminant) {
+        memcpy((char *)tag_union + \(discriminantOffsetStr), &discriminant, sizeof(discriminant));
+    }
+    \(functions)
+    """
+
+generateRecursiveHelpers = \buf, types, id, name, tags, nullTagIndex ->
+    escapedName = escapeKW name
+    discriminantName = "discriminant_\(escapedName)"
+    unionName = "union_\(escapedName)"
+
+    isNullTag = \index -> Some (Num.intCast index) == nullTagIndex
+
+    nullTagCheck =
+        when nullTagIndex is
+            Some index ->
+                nullTagName =
+                    List.get tags (Num.intCast index)
+                    |> Result.map .name
+                    |> Result.withDefault ""
+
+                """
+                    if (tag_union.pointer == NULL) {
+                        return \(discriminantName)_\(nullTagName);
+                    }
+
+
+                """
+
+            None ->
+                ""
+
+    functions =
+        List.walkWithIndex tags "" \accum, { name: tagName, payload }, index ->
+            payloadExpr = "node->\(escapeKW tagName)"
+            { params, assignments } = tagConstructor types payload payloadExpr (memberOf payloadExpr)
+
+            constructor =
+                if isNullTag index then
+                    """
+                    static inline \(escapedName) \(escapedName)_make_\(tagName)(void) {
+                        \(escapedName) tag_union;
+
+                        tag_union.pointer = NULL;
+
+                        return tag_union;
+                    }
+                    """
+                else
+                    """
+                    static inline \(escapedName) \(escapedName)_make_\(tagName)(\(params)) {
+                        \(unionName) *node = (\(unionName) *)roc_allocate_with_refcount(sizeof(\(unionName)), ROC_ALIGNOF(\(unionName)));
+                        \(escapedName) tag_union;
+
+                    \(assignments)    tag_union.pointer = (\(unionName) *)((uintptr_t)node | \(discriminantName)_\(tagName));
+
+                        return tag_union;
+                    }
+                    """
+
+            getter =
+                when nonZeroSized types payload is
+                    Some payloadId ->
+                        """
+
+                        static inline \(typeName types payloadId) \(escapedName)_get_\(tagName)(\(escapedName) tag_union) {
+                            assert(\(escapedName)_is_\(tagName)(tag_union));
+
+                            return \(escapedName)_unmasked_pointer(tag_union)->\(escapeKW tagName);
+                        }
+
+                        """
+
+                    None ->
+                        ""
+
+            """
+            \(accum)
+            \(constructor)
+
+            static inline bool \(escapedName)_is_\(tagName)(\(escapedName) tag_union) {
+                return \(escapedName)_discriminant(tag_union) == \(discriminantName)_\(tagName);
+            }
+            \(getter)
+            """
+
+    """
+    \(buf)
+    \(layoutCheck types id escapedName)
+
+    \(discriminantDocComment)
+    static inline \(discriminantName) \(escapedName)_discriminant(\(escapedName) tag_union) {
+    \(nullTagCheck)    return (\(discriminantName))((uintptr_t)tag_union.pointer & ROC_TAG_ID_MASK);
+    }
+
+    static inline \(unionName) *\(escapedName)_unmasked_pointer(\(escapedName) tag_union) {
+        return (\(unionName) *)((uintptr_t)tag_union.pointer & ~ROC_TAG_ID_MASK);
+    }
+
+    static inline void \(escapedName)_incref(\(escapedName) tag_union, intptr_t amount) {
+        roc_incref_pointer(\(escapedName)_unmasked_pointer(tag_union), amount);
+    }
+    \(functions)
+    """
+
+generateNullableUnwrappedHelpers = \buf, types, id, name, nullTag, nonNullTag, nonNullPayload ->
+    escapedName = escapeKW name
+    discriminantName = "discriminant_\(escapedName)"
+    payloadType = typeName types nonNullPayload
+    { params, assignments } = tagConstructor types (Some nonNullPayload) "*node" \field -> "node->\(field)"
+
+    """
+    \(buf)
+    \(layoutCheck types id escapedName)
+
+    \(discriminantDocComment)
+    static inline \(discriminantName) \(escapedName)_discriminant(\(escapedName) tag_union) {
+        return tag_union.pointer == NULL ? \(discriminantName)_\(nullTag) : \(discriminantName)_\(nonNullTag);
+    }
+
+    static inline void \(escapedName)_incref(\(escapedName) tag_union, intptr_t amount) {
+        roc_incref_pointer(tag_union.pointer, amount);
+    }
+
+    static inline \(escapedName) \(escapedName)_make_\(nullTag)(void) {
+        \(escapedName) tag_union;
+
+        tag_union.pointer = NULL;
+
+        return tag_union;
+    }
+
+    static inline bool \(escapedName)_is_\(nullTag)(\(escapedName) tag_union) {
+        return tag_union.pointer == NULL;
+    }
+
+    static inline \(escapedName) \(escapedName)_make_\(nonNullTag)(\(params)) {
+        \(payloadType) *node = (\(payloadType) *)roc_allocate_with_refcount(sizeof(\(payloadType)), ROC_ALIGNOF(\(payloadType)));
+        \(escapedName) tag_union;
+
+    \(assignments)    tag_union.pointer = node;
+
+        return tag_union;
+    }
+
+    static inline bool \(escapedName)_is_\(nonNullTag)(\(escapedName) tag_union) {
+        return tag_union.pointer != NULL;
+    }
+
+    static inline \(payloadType) \(escapedName)_get_\(nonNullTag)(\(escapedName) tag_union) {
+        assert(\(escapedName)_is_\(nonNullTag)(tag_union));
+
+        return *tag_union.pointer;
+    }
+
+    """
+
+generateNonNullableUnwrappedHelpers = \buf, types, id, name, tagName, payload ->
+    escapedName = escapeKW name
+    payloadType = typeName types payload
+    { params, assignments } = tagConstructor types (Some payload) "*node" \field -> "node->\(field)"
+
+    """
+    \(buf)
+    \(layoutCheck types id escapedName)
+
+    static inline void \(escapedName)_incref(\(escapedName) tag_union, intptr_t amount) {
+        roc_incref_pointer(tag_union.pointer, amount);
+    }
+
+    static inline \(escapedName) \(escapedName)_make_\(tagName)(\(params)) {
+        \(payloadType) *node = (\(payloadType) *)roc_allocate_with_refcount(sizeof(\(payloadType)), ROC_ALIGNOF(\(payloadType)));
+        \(escapedName) tag_union;
+
+    \(assignments)    tag_union.pointer = node;
+
+        return tag_union;
+    }
+
+    static inline \(payloadType) \(escapedName)_get_\(tagName)(\(escapedName) tag_union) {
+        return *tag_union.pointer;
+    }
+
+    """
+
+generateSingleTagStructHelpers = \buf, types, id, name, tagName, payload ->
+    # Single-tag unions are stored as structs rather than tag unions, because
+    # they have only one alternative. However, they still get a constructor.
+    escapedName = escapeKW name
+
+    fields =
+        singleTagFieldIds payload
+        |> List.mapWithIndex \fieldId, index -> { name: "f\(Num.toStr index)", id: fieldId }
+        |> List.dropIf \{ id: fieldId } -> isZeroSized types fieldId
+
+    params =
+        if List.isEmpty fields then
+            "void"
+        else
+            fields
+            |> List.map \{ name: fieldName, id: fieldId } -> "\(typeName types fieldId) \(fieldName)"
+            |> Str.joinWith ", "
+
+    assignments =
+        List.walk fields "" \accum, { name: fieldName } ->
+            Str.concat accum "\(indent)tag_union.\(fieldName) = \(fieldName);\n"
+
+    """
+    \(buf)
+    \(layoutCheck types id escapedName)
+
+    // A tag named `\(tagName)`, with the given payload.
+    static inline \(escapedName) \(escapedName)_make_\(tagName)(\(params)) {
+        \(escapedName) tag_union;
+
+    \(assignments)
+        return tag_union;
+    }
+
+    """
+
+generateClosureHelpers : Str, Types, RocFn -> Str
+generateClosureHelpers = \buf, types, rocFn ->
+    name = escapeKW rocFn.functionName
+    externName = rocFn.externName
+    args = nonZeroSizedArgs types rocFn.args
+    ret = typeName types rocFn.ret
+    returnsVoid = isZeroSized types rocFn.ret
+
+    # The closure's caller always takes its arguments by pointer, and always has a
+    # first argument, even when there's nothing for it to point to.
+    externArgs =
+        if List.isEmpty args then
+            "const void *arg0"
+        else
+            args
+            |> List.map \{ type, index } -> "const \(type) *arg\(index)"
+            |> Str.joinWith ", "
+
+    callArgs =
+        if List.isEmpty args then
+            "NULL"
+        else
+            args
+            |> List.map \{ index } -> "&arg\(index)"
+            |> Str.joinWith ", "
+
+    publicArgs =
+        args
+        |> List.map \{ type, index } -> ", \(type) arg\(index)"
+        |> Str.joinWith ""
+
+    body =
+        if returnsVoid then
+            "\(indent)\(externName)(\(callArgs), closure.closure_data, NULL);\n"
+        else
+            """
+                \(ret) output;
+
+                \(externName)(\(callArgs), closure.closure_data, &output);
+
+                return output;
+
+            """
+
+    outputParam = if returnsVoid then "void *output" else "\(ret) *output"
+
+    """
+    \(buf)
+    void \(externName)(\(externArgs), void *closure_data, \(outputParam));
+
+    static inline \(ret) \(name)_force_thunk(\(name) closure\(publicArgs)) {
+    \(body)}
+
+    // Frees the closure's captured values. Only do this once it won't be called anymore!
+    static inline void \(name)_deinit(\(name) closure) {
+        roc_dealloc(closure.closure_data, ROC_CLOSURE_DATA_ALIGNMENT);
+    }
+
+    """
+
+## Every recursive tag union gets a `_decref`, which frees what its payload refers to once
+## nothing else refers to the node. Recursive tag unions can refer to each other, so each
+## `_decref` is declared before any of them are defined.
+generateDecrefs : Str, Types -> Str
+generateDecrefs = \buf, types ->
+    recursiveUnions =
+        Types.walkShapes types [] \accum, shape, id ->
+            if isRecursiveUnion shape && !(isZeroSized types id) then
+                List.append accum id
+            else
+                accum
+
+    declarations =
+        List.walk recursiveUnions "" \accum, id ->
+            name = typeName types id
+
+            Str.concat accum "static inline void \(name)_decref(\(name) tag_union);\n"
+
+    if List.isEmpty recursiveUnions then
+        buf
+    else
+        List.walk recursiveUnions "\(buf)\n\(declarations)" \accum, id -> generateDecref accum types id
+
+generateDecref : Str, Types, TypeId -> Str
+generateDecref = \buf, types, id ->
+    name = typeName types id
+
+    when Types.shape types id is
+        TagUnion (Recursive { tags }) | TagUnion (NullableWrapped { tags }) ->
+            cases = decrefCases types name tags (\tagName -> "node->\(tagName)") 2
+            payloadDecrefs = decrefSwitch "\(indent)\(indent)" "\(name)_discriminant(tag_union)" cases
+
+            generateNodeDecref buf name "union_\(name)" "\(name)_unmasked_pointer(tag_union)" payloadDecrefs
+
+        TagUnion (NullableUnwrapped { nonNullPayload }) ->
+            payloadDecrefs = decrefValue types nonNullPayload "(*node)" 2
+
+            generateNodeDecref buf name (typeName types nonNullPayload) "tag_union.pointer" payloadDecrefs
+
+        TagUnion (NonNullableUnwrapped { payload }) ->
+            payloadDecrefs = decrefValue types payload "(*node)" 2
+
+            generateNodeDecref buf name (typeName types payload) "tag_union.pointer" payloadDecrefs
+
+        _ ->
+            buf
+
+generateNodeDecref : Str, Str, Str, Str, Str -> Str
+generateNodeDecref = \buf, name, nodeType, pointerExpr, payloadDecrefs ->
+    ifUnique =
+        if Str.isEmpty payloadDecrefs then
+            ""
+        else
+            "\(indent)if (roc_pointer_is_unique(node)) {\n\(payloadDecrefs)\(indent)}\n\n"
+
+    """
+    \(buf)
+    // Decrements this node, and once nothing else refers to it, everything its payload refers to.
+    static inline void \(name)_decref(\(name) tag_union) {
+        \(nodeType) *node = \(pointerExpr);
+
+    \(ifUnique)    roc_decref_pointer(node, ROC_ALIGNOF(\(nodeType)));
+    }
+
+    """
+
+isRecursiveUnion : Shape -> Bool
+isRecursiveUnion = \shape ->
+    when shape is
+        TagUnion (Recursive _) | TagUnion (NullableWrapped _) | TagUnion (NullableUnwrapped _) | TagUnion (NonNullableUnwrapped _) -> Bool.true
+        _ -> Bool.false
+
+## The statements which decrement what `expr`, a value of type `id`, refers to on the heap,
+## along with what that refers to once nothing else does. Recursive tag unions are handed
+## off to their own `_decref`, which is what keeps this from going on forever.
+decrefValue : Types, TypeId, Str, Nat -> Str
+decrefValue = \types, id, expr, depth ->
+    pad = Str.repeat indent depth
+
+    if isZeroSized types id then
+        ""
+    else
+        when Types.shape types id is
+            RocStr ->
+                "\(pad)roc_str_decref(\(expr));\n"
+
+            RocList elem ->
+                index = loopIndex depth
+                elemDecref = decrefValue types elem "((\(typeName types elem) *)\(expr).elements)[\(index)]" (depth + 2)
+                elements = decrefLoop pad expr index elemDecref
+
+                "\(elements)\(pad)roc_list_decref(\(expr), \(alignmentOf types elem));\n"
+
+            RocBox elem ->
+                contents = decrefValue types elem "(*(\(typeName types elem) *)\(expr).contents)" (depth + 1)
+
+                ifUnique =
+                    if Str.isEmpty contents then
+                        ""
+                    else
+                        "\(pad)if (roc_pointer_is_unique(\(expr).contents)) {\n\(contents)\(pad)}\n"
+
+                "\(ifUnique)\(pad)roc_box_decref(\(expr), \(alignmentOf types elem));\n"
+
+            RocDict key value ->
+                decrefDict types key (Some value) expr depth
+
+            RocSet elem ->
+                decrefDict types elem None "\(expr).dict" depth
+
+            Struct { fields } ->
+                decrefFields types (fieldsToList fields) expr depth
+
+            TagUnionPayload { fields } ->
+                decrefFields types (payloadFields fields) expr depth
+
+            TagUnion (SingleTagStruct { payload }) ->
+                fields = List.mapWithIndex (singleTagFieldIds payload) \fieldId, index -> { name: "f\(Num.toStr index)", id: fieldId }
+
+                decrefFields types fields expr depth
+
+            TagUnion (NonRecursive { name, tags }) ->
+                escapedName = escapeKW name
+                cases = decrefCases types escapedName tags (\tagName -> "\(expr).payload.\(tagName)") depth
+
+                decrefSwitch pad "\(escapedName)_discriminant(\(expr))" cases
+
+            RocResult okId errId ->
+                ok = decrefValue types okId "\(expr).payload.ok" (depth + 1)
+                err = decrefValue types errId "\(expr).payload.err" (depth + 1)
+
+                if Str.isEmpty ok && Str.isEmpty err then
+                    ""
+                else
+                    "\(pad)if (\(expr).tag == RocResult_Ok) {\n\(ok)\(pad)} else {\n\(err)\(pad)}\n"
+
+            TagUnion (Recursive _) | TagUnion (NullableWrapped _) | TagUnion (NullableUnwrapped _) | TagUnion (NonNullableUnwrapped _) | RecursivePointer _ ->
+                "\(pad)\(typeName types id)_decref(\(expr));\n"
+
+            _ ->
+                # Closures are freed with their `_deinit`, and nothing else refers to the heap.
+                ""
+
+decrefFields = \types, fields, expr, depth ->
+    List.walk fields "" \accum, { name, id } ->
+        Str.concat accum (decrefValue types id "\(expr).\(escapeKW name)" depth)
+
+## A `case` for each tag whose payload refers to the heap, at the given depth.
+decrefCases = \types, name, tags, payloadExpr, depth ->
+    pad = Str.repeat indent depth
+
+    List.walk tags "" \accum, { name: tagName, payload } ->
+        when nonZeroSized types payload is
+            Some payloadId ->
+                payloadDecref = decrefValue types payloadId (payloadExpr (escapeKW tagName)) (depth + 1)
+
+                if Str.isEmpty payloadDecref then
+                    accum
+                else
+                    "\(accum)\(pad)case discriminant_\(name)_\(tagName):\n\(payloadDecref)\(pad)\(indent)break;\n"
+
+            None ->
+                accum
+
+decrefSwitch = \pad, discriminantExpr, cases ->
+    if Str.isEmpty cases then
+        ""
+    else
+        "\(pad)switch (\(discriminantExpr)) {\n\(cases)\(pad)default:\n\(pad)\(indent)break;\n\(pad)}\n"
+
+## A `Dict`'s key-value pairs are stored like tuples: whichever has the larger alignment
+## comes first, with the key winning ties. C doesn't know that type, so they're found by offset.
+decrefDict = \types, key, optValue, expr, depth ->
+    pad = Str.repeat indent depth
+    index = loopIndex depth
+
+    keySize = Types.size types key
+    keyAlignment = Num.max 1 (Types.alignment types key)
+
+    (valueSize, valueAlignment) =
+        when nonZeroSized types optValue is
+            Some value -> (Types.size types value, Num.max 1 (Types.alignment types value))
+            None -> (0, 1)
+
+    (keyOffset, valueOffset, end) =
+        if keyAlignment >= valueAlignment then
+            valueStart = roundUp keySize valueAlignment
+
+            (0, valueStart, valueStart + valueSize)
+        else
+            keyStart = roundUp valueSize keyAlignment
+
+            (keyStart, 0, keyStart + keySize)
+
+    itemAlignment = Num.max keyAlignment valueAlignment
+    item = "(char *)\(expr).data.elements + \(index) * \(Num.toStr (roundUp end itemAlignment))"
+
+    itemDecref = \id, offset ->
+        decrefValue types id "(*(\(typeName types id) *)(\(item) + \(Num.toStr offset)))" (depth + 2)
+
+    valueDecref =
+        when nonZeroSized types optValue is
+            Some value -> itemDecref value valueOffset
+            None -> ""
+
+    items = decrefLoop pad "\(expr).data" index (Str.concat (itemDecref key keyOffset) valueDecref)
+
+    [
+        "roc_list_decref(\(expr).data, \(Num.toStr itemAlignment));",
+        "roc_list_decref(\(expr).data_indices, ROC_ALIGNOF(size_t));",
+        "roc_list_decref(\(expr).metadata, 1);",
+    ]
+    |> List.walk items \accum, statement -> "\(accum)\(pad)\(statement)\n"
+
+## Runs `body` for each element of `list`, but only if the list is about to be freed.
+decrefLoop = \pad, list, index, body ->
+    if Str.isEmpty body then
+        ""
+    else
+        loop = "for (size_t \(index) = 0; \(index) < \(list).length; \(index)++)"
+
+        "\(pad)if (roc_list_is_unique(\(list))) {\n\(pad)\(indent)\(loop) {\n\(body)\(pad)\(indent)}\n\(pad)}\n"
+
+## Nested loops each need their own index.
+loopIndex = \depth -> "i\(Num.toStr depth)"
+
+alignmentOf = \types, id -> Num.toStr (Num.max 1 (Types.alignment types id))
+
+roundUp : U32, U32 -> U32
+roundUp = \bytes, alignment -> (bytes + alignment - 1) // alignment * alignment
+
+generateEntryPoints : Str, Types -> Str
+generateEntryPoints = \buf, types ->
+    List.walk (Types.entryPoints types) buf \accum, T name id -> generateEntryPoint accum types name id
+
+generateEntryPoint : Str, Types, Str, TypeId -> Str
+generateEntryPoint = \buf, types, name, id ->
+    (argIds, retId) =
+        when Types.shape types id is
+            Function rocFn -> (rocFn.args, rocFn.ret)
+            _ -> ([], id)
+
+    args = nonZeroSizedArgs types argIds
+    ret = typeName types retId
+
+    publicArgs =
+        if List.isEmpty args then
+            "void"
+        else
+            args
+            |> List.map \{ type, index } -> "\(type) arg\(index)"
+            |> Str.joinWith ", "
+
+    # Values which refer to heap memory are passed by pointer, which hands them over to Roc.
+    externArgs =
+        args
+        |> List.map \{ type, index, shape } ->
+            if canPassByValue types shape then
+                ", \(type) arg\(index)"
+            else
+                ", const \(type) *arg\(index)"
+        |> Str.joinWith ""
+
+    callArgs =
+        args
+        |> List.map \{ index, shape } ->
+            if canPassByValue types shape then
+                ", arg\(index)"
+            else
+                ", &arg\(index)"
+        |> Str.joinWith ""
+
+    when Types.shape types retId is
+        Function _ ->
+            """
+            \(buf)
+            void roc__\(name)_1_exposed_generic(void *closure_data\(externArgs));
+            int64_t roc__\(name)_1_exposed_size(void);
+
+            static inline \(ret) \(name)(\(publicArgs)) {
+                int64_t size = roc__\(name)_1_exposed_size();
+                \(ret) closure;
+
+                // roc_alloc may not be asked for 0 bytes, which is all some closures capture.
+                closure.closure_data = roc_alloc(size > 0 ? (size_t)size : 1, ROC_CLOSURE_DATA_ALIGNMENT);
+                roc__\(name)_1_exposed_generic(closure.closure_data\(callArgs));
+
+                return closure;
+            }
+
+            // The closure's own name changes from one build to the next, so these don't.
+            typedef \(ret) \(name)_closure;
+            #define \(name)_closure_force_thunk \(ret)_force_thunk
+            #define \(name)_closure_deinit \(ret)_deinit
+
+            """
+
+        _ ->
+            if isZeroSized types retId then
+                """
+                \(buf)
+                void roc__\(name)_1_exposed_generic(void *ret\(externArgs));
+
+                static inline void \(name)(\(publicArgs)) {
+                    roc__\(name)_1_exposed_generic(NULL\(callArgs));
+                }
+
+                """
+            else
+                """
+                \(buf)
+                void roc__\(name)_1_exposed_generic(\(ret) *ret\(externArgs));
+
+                static inline \(ret) \(name)(\(publicArgs)) {
+                    \(ret) ret;
+
+                    roc__\(name)_1_exposed_generic(&ret\(callArgs));
+
+                    return ret;
+                }
+
+                """
+
+## The parameters a tag's constructor takes, and the statements which store them in its payload.
+tagConstructor : Types, [Some TypeId, None], Str, (Str -> Str) -> { params : Str, assignments : Str }
+tagConstructor = \types, optPayload, payloadExpr, fieldExpr ->
+    when nonZeroSized types optPayload is
+        None ->
+            { params: "void", assignments: "" }
+
+        Some payloadId ->
+            when Types.shape types payloadId is
+                TagUnionPayload { fields } ->
+                    nonZeroSizedFields = payloadFields fields |> List.dropIf \{ id } -> isZeroSized types id
+
+                    params =
+                        nonZeroSizedFields
+                        |> List.map \{ name, id } -> "\(typeName types id) \(name)"
+                        |> Str.joinWith ", "
+
+                    assignments =
+                        List.walk nonZeroSizedFields "" \accum, { name } ->
+                            Str.concat accum "\(indent)\(fieldExpr name) = \(name);\n"
+
+                    { params: if Str.isEmpty params then "void" else params, assignments }
+
+                _ ->
+                    { params: "\(typeName types payloadId) payload", assignments: "\(indent)\(payloadExpr) = payload;\n" }
+
+memberOf = \expr -> \field -> "\(expr).\(field)"
+
+layoutCheck = \types, id, name ->
+    size = Num.toStr (Types.size types id)
+    alignment = Num.toStr (Types.alignment types id)
+
+    "ROC_ASSERT_LAYOUT(\(name), \(size), \(alignment));"
+
+fieldsToList = \structFields ->
+    when structFields is
+        HasNoClosure fields -> List.map fields \{ name, id } -> { name, id }
+        HasClosure fields -> List.map fields \{ name, id } -> { name, id }
+
+## Tag union payloads have numbered fields, so we prefix them
+## with an "f" because C doesn't allow struct fields to be numbers.
+payloadFields = \structFields ->
+    List.map (fieldsToList structFields) \{ name, id } -> { name: "f\(name)", id }
+
+singleTagFieldIds = \payload ->
+    when payload is
+        HasNoClosure fields -> List.map fields .id
+        HasClosure fields -> List.map fields .id
+
+nonZeroSizedArgs = \types, argIds ->
+    List.walkWithIndex argIds [] \accum, argId, index ->
+        if isZeroSized types argId then
+            accum
+        else
+            List.append accum { type: typeName types argId, index: Num.toStr index, shape: Types.shape types argId }
+
+nonZeroSized = \types, optId ->
+    when optId is
+        Some id if !(isZeroSized types id) -> Some id
+        _ -> None
+
+## C has no zero-sized types, so values of them are left out wherever they come up:
+## they don't get a field in a struct, an argument to a function, or a return value.
+isZeroSized : Types, TypeId -> Bool
+isZeroSized = \types, id ->
+    when Types.shape types id is
+        # Closures are a pointer to their captured values, even when they capture nothing.
+        Function _ -> Bool.false
+        _ -> Types.size types id == 0
+
+## Whether a value can be passed to Roc directly. Anything which refers
+## to heap memory gets passed by pointer instead.
+canPassByValue : Types, Shape -> Bool
+canPassByValue = \types, type ->
+    when type is
+        Function rocFn ->
+            runtimeRepresentation = Types.shape types rocFn.lambdaSet
+            canPassByValue types runtimeRepresentation
+
+        # unsized values are heap-allocated
+        Unsized -> Bool.false
+        Unit | EmptyTagUnion | Bool | Num _ | TagUnion (Enumeration _) -> Bool.true
+        RocStr | RocList _ | RocDict _ _ | RocSet _ | RocBox _ | TagUnion (NullableUnwrapped _) | TagUnion (NullableWrapped _) | TagUnion (Recursive _) | TagUnion (NonNullableUnwrapped _) | RecursivePointer _ -> Bool.false
+        TagUnion (SingleTagStruct { payload: HasNoClosure fields }) ->
+            List.all fields \{ id } -> canPassByValue types (Types.shape types id)
+
+        TagUnion (SingleTagStruct { payload: HasClosure fields }) ->
+            List.all fields \{ id } -> canPassByValue types (Types.shape types id)
+
+        TagUnion (NonRecursive { tags }) ->
+            List.all tags \{ payload } ->
+                when payload is
+                    Some id -> canPassByValue types (Types.shape types id)
+                    None -> Bool.true
+
+        RocResult okId errId ->
+            canPassByValue types (Types.shape types okId)
+            && canPassByValue types (Types.shape types errId)
+
+        Struct { fields: HasNoClosure fields } | TagUnionPayload { fields: HasNoClosure fields } ->
+            List.all fields \{ id } -> canPassByValue types (Types.shape types id)
+
+        Struct { fields: HasClosure fields } | TagUnionPayload { fields: HasClosure fields } ->
+            List.all fields \{ id } -> canPassByValue types (Types.shape types id)
+
+typeName = \types, id ->
+    if isZeroSized types id then
+        "void"
+    else
+        when Types.shape types id is
+            Unit -> "void"
+            Unsized -> "RocList"
+            EmptyTagUnion -> "void"
+            RocStr -> "RocStr"
+            Bool -> "bool"
+            Num U8 -> "uint8_t"
+            Num U16 -> "uint16_t"
+            Num U32 -> "uint32_t"
+            Num U64 -> "uint64_t"
+            Num U128 -> "RocU128"
+            Num I8 -> "int8_t"
+            Num I16 -> "int16_t"
+            Num I32 -> "int32_t"
+            Num I64 -> "int64_t"
+            Num I128 -> "RocI128"
+            Num F32 -> "float"
+            Num F64 -> "double"
+            Num Dec -> "RocDec"
+            # C has no generics, so the builtin collections don't know their elements' types.
+            RocDict _ _ -> "RocDict"
+            RocSet _ -> "RocSet"
+            RocList _ -> "RocList"
+            RocBox _ -> "RocBox"
+            RocResult ok err ->
+                okName = typeName types ok
+                errName = typeName types err
+
+                "RocResult_\(okName)_\(errName)"
+
+            RecursivePointer content ->
+                typeName types content
+
+            Struct { name } -> escapeKW name
+            TagUnionPayload { name } -> escapeKW name
+            TagUnion (NonRecursive { name }) -> escapeKW name
+            TagUnion (Recursive { name }) -> escapeKW name
+            TagUnion (Enumeration { name }) -> escapeKW name
+            TagUnion (NullableWrapped { name }) -> escapeKW name
+            TagUnion (NullableUnwrapped { name }) -> escapeKW name
+            TagUnion (NonNullableUnwrapped { name }) -> escapeKW name
+            TagUnion (SingleTagStruct { name }) -> escapeKW name
+            Function { functionName } -> escapeKW functionName
+
+unsignedType = \bytes ->
+    when bytes is
+        2 -> "uint16_t"
+        4 -> "uint32_t"
+        8 -> "uint64_t"
+        _ -> "uint8_t"
+
+archCondition = \arch ->
+    # The macros which GCC, Clang and MSVC predefine for each architecture.
+    when arch is
+        Aarch32 ->
+            "defined(__arm__) || defined(_M_ARM)"
+
+        Aarch64 ->
+            "defined(__aarch64__) || defined(_M_ARM64)"
+
+        Wasm32 ->
+            "defined(__wasm32__)"
+
+        X86x32 ->
+            "defined(__i386__) || defined(_M_IX86)"
+
+        X86x64 ->
+            "defined(__x86_64__) || defined(_M_X64)"
+
+fileHeader =
+    """
+    // ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+
+    """
+
+indent = "    "
+discriminantDocComment = "// Returns which variant this tag union holds. Note that this never includes a payload!"
+
+reservedKeywords = Set.fromList [
+    "alignas",
+    "alignof",
+    "asm",
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "constexpr",
+    "continue",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "explicit",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "friend",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "mutable",
+    "namespace",
+    "new",
+    "noexcept",
+    "nullptr",
+    "operator",
+    "private",
+    "protected",
+    "public",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "static_assert",
+    "struct",
+    "switch",
+    "template",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "typename",
+    "union",
+    "unsigned",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+]
+
+## C has no way to use a keyword as an identifier, so add an underscore to the end of it.
+escapeKW = \input ->
+    if Set.contains reservedKeywords input then
+        "\(input)_"
+    else
+        input
@@ -11,6 +11,7 @@ app "rust-glue"
         "../../roc_std/src/roc_box.rs" as rocStdBox : Str,
         "../../roc_std/src/roc_list.rs" as rocStdList : Str,
         "../../roc_std/src/roc_dict.rs" as rocStdDict : Str,
+        "../../roc_std/src/roc_hash.rs" as rocStdHash : Str,
         "../../roc_std/src/roc_set.rs" as rocStdSet : Str,
         "../../roc_std/src/roc_str.rs" as rocStdStr : Str,
         "../../roc_std/src/storage.rs" as rocStdStorage : Str,
@@ -49,6 +50,7 @@ staticFiles = [
     { name: "roc_std/src/roc_box.rs", content: rocStdBox },
     { name: "roc_std/src/roc_list.rs", content: rocStdList },
     { name: "roc_std/src/roc_dict.rs", content: rocStdDict },
+    { name: "roc_std/src/roc_hash.rs", content: rocStdHash },
     { name: "roc_std/src/roc_set.rs", content: rocStdSet },
     { name: "roc_std/src/roc_str.rs", content: rocStdStr },
     { name: "roc_std/src/storage.rs", content: rocStdStorage },
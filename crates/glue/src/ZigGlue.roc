@@ -2,26 +2,1070 @@ app "zig-glue"
     packages { pf: "../platform/main.roc" }
     imports [
         pf.Types.{ Types },
+        pf.Shape.{ Shape, RocFn },
         pf.File.{ File },
-        "../../compiler/builtins/bitcode/src/list.zig" as rocStdList : Str,
+        pf.TypeId.{ TypeId },
+        "../static/roc_std.zig" as rocStd : Str,
     ]
     provides [makeGlue] to pf
 
 makeGlue : List Types -> Result (List File) Str
 makeGlue = \typesByArch ->
+    archImports =
+        List.walk typesByArch "" \content, types ->
+            arch = (Types.target types).architecture
+            archStr = archName arch
+
+            Str.concat content "\(indent).\(archStr) => @import(\"\(archStr).zig\"),\n"
+
+    rootFileContent =
+        """
+        \(fileHeader)
+        const builtin = @import("builtin");
+
+        pub const roc_std = @import("roc_std.zig");
+
+        pub usingnamespace switch (builtin.cpu.arch) {
+        \(archImports)    else => @compileError("`roc glue` did not generate code for this architecture"),
+        };
+
+        """
+
     typesByArch
     |> List.map convertTypesToFile
+    |> List.append { name: "roc_app.zig", content: rootFileContent }
     |> List.concat staticFiles
     |> Ok
 
 ## These are always included, and don't depend on the specifics of the app.
 staticFiles : List File
 staticFiles = [
-    { name: "list.zig", content: rocStdList },
+    { name: "roc_std.zig", content: rocStd },
 ]
 
 convertTypesToFile : Types -> File
-convertTypesToFile = \_ -> {
-    name: "glue.zig",
-    content: "// Nothing to see yet",
-}
+convertTypesToFile = \types ->
+    content =
+        Types.walkShapes types archFileHeader \buf, type, id ->
+            when type is
+                Struct { name, fields } ->
+                    generateStruct buf types id name fields
+
+                TagUnionPayload { name, fields } ->
+                    generateStruct buf types id name (nameTagUnionPayloadFields fields)
+
+                TagUnion (Enumeration { name, tags, size }) ->
+                    generateEnumeration buf name tags size
+
+                TagUnion (NonRecursive { name, tags, discriminantSize, discriminantOffset }) ->
+                    if !(List.isEmpty tags) then
+                        generateNonRecursiveTagUnion buf types id name tags discriminantSize discriminantOffset
+                    else
+                        buf
+
+                TagUnion (Recursive { name, tags, discriminantSize }) ->
+                    if !(List.isEmpty tags) then
+                        generateRecursiveTagUnion buf types id name tags discriminantSize None
+                    else
+                        buf
+
+                TagUnion (NullableWrapped { name, indexOfNullTag, tags, discriminantSize }) ->
+                    generateRecursiveTagUnion buf types id name tags discriminantSize (Some indexOfNullTag)
+
+                TagUnion (NullableUnwrapped { name, nullTag, nonNullTag, nonNullPayload, whichTagIsNull }) ->
+                    generateNullableUnwrapped buf types id name nullTag nonNullTag nonNullPayload whichTagIsNull
+
+                TagUnion (SingleTagStruct { name, tagName, payload }) ->
+                    generateSingleTagStruct buf types id name tagName payload
+
+                TagUnion (NonNullableUnwrapped { name, tagName, payload }) ->
+                    generateNonNullableUnwrapped buf types id name tagName payload
+
+                Function rocFn ->
+                    if rocFn.isToplevel then
+                        buf
+                    else
+                        generateFunction buf types rocFn
+
+                RecursivePointer _ ->
+                    # This is recursively pointing to a type that should already have been added,
+                    # so no extra work needs to happen.
+                    buf
+
+                Unit
+                | Unsized
+                | EmptyTagUnion
+                | Num _
+                | Bool
+                | RocResult _ _
+                | RocStr
+                | RocDict _ _
+                | RocSet _
+                | RocList _
+                | RocBox _ ->
+                    # These types are either Zig primitives or defined in roc_std.zig.
+                    buf
+
+    arch = (Types.target types).architecture
+    archStr = archName arch
+
+    {
+        name: "\(archStr).zig",
+        content: content |> generateEntryPoints types,
+    }
+
+generateEntryPoints : Str, Types -> Str
+generateEntryPoints = \buf, types ->
+    List.walk (Types.entryPoints types) buf \accum, T name id -> generateEntryPoint accum types name id
+
+generateEntryPoint : Str, Types, Str, TypeId -> Str
+generateEntryPoint = \buf, types, name, id ->
+    (args, retId) =
+        when Types.shape types id is
+            Function rocFn -> (rocFn.args, rocFn.ret)
+            _ -> ([], id)
+
+    publicArguments =
+        toArgStr args types \argId, _shape, index ->
+            type = typeName types argId
+            indexStr = Num.toStr index
+
+            "arg\(indexStr): \(type)"
+
+    # Values which refer to heap memory are passed by pointer, which hands them over to Roc.
+    externArguments =
+        toArgStr args types \argId, shape, index ->
+            type = typeName types argId
+            indexStr = Num.toStr index
+
+            if canPassByValue types shape then
+                "arg\(indexStr): \(type)"
+            else
+                "arg\(indexStr): *const \(type)"
+
+    externCallArguments =
+        toArgStr args types \_argId, shape, index ->
+            indexStr = Num.toStr index
+
+            if canPassByValue types shape then
+                "arg\(indexStr)"
+            else
+                "&arg\(indexStr)"
+
+    withLeadingComma = \str ->
+        if Str.isEmpty str then
+            str
+        else
+            ", \(str)"
+
+    ret = typeName types retId
+
+    when Types.shape types retId is
+        Function _ ->
+            """
+            \(buf)
+
+            extern fn roc__\(name)_1_exposed_generic(closure_data: [*]u8\(withLeadingComma externArguments)) void;
+            extern fn roc__\(name)_1_exposed_size() i64;
+
+            pub fn \(name)(\(publicArguments)) \(ret) {
+                const closure_data = roc_std.allocateClosureData(@intCast(roc__\(name)_1_exposed_size()));
+
+                roc__\(name)_1_exposed_generic(closure_data\(withLeadingComma externCallArguments));
+
+                return .{ .closure_data = closure_data };
+            }
+
+            """
+
+        _ ->
+            """
+            \(buf)
+
+            extern fn roc__\(name)_1_exposed_generic(ret: *\(ret)\(withLeadingComma externArguments)) void;
+
+            pub fn \(name)(\(publicArguments)) \(ret) {
+                var ret: \(ret) = undefined;
+
+                roc__\(name)_1_exposed_generic(&ret\(withLeadingComma externCallArguments));
+
+                return ret;
+            }
+
+            """
+
+generateFunction : Str, Types, RocFn -> Str
+generateFunction = \buf, types, rocFn ->
+    name = escapeKW rocFn.functionName
+    externName = rocFn.externName
+
+    publicArguments =
+        toArgStr rocFn.args types \argId, _shape, index ->
+            type = typeName types argId
+            indexStr = Num.toStr index
+
+            "arg\(indexStr): \(type)"
+
+    externDefArguments =
+        withoutUnit =
+            toArgStr rocFn.args types \argId, _shape, index ->
+                type = typeName types argId
+                indexStr = Num.toStr index
+
+                "arg\(indexStr): *const \(type)"
+
+        if Str.isEmpty withoutUnit then
+            # These always have a first argument that's a pointer, even if it's to nothing.
+            "arg0: *const anyopaque"
+        else
+            withoutUnit
+
+    externCallArguments =
+        withoutUnit =
+            toArgStr rocFn.args types \_argId, _shape, index ->
+                indexStr = Num.toStr index
+
+                "&arg\(indexStr)"
+
+        if Str.isEmpty withoutUnit then
+            # These always have a first argument that's a pointer, even if it's to nothing.
+            "&{}"
+        else
+            withoutUnit
+
+    publicComma = if Str.isEmpty publicArguments then "" else ", "
+
+    ret = typeName types rocFn.ret
+
+    """
+    \(buf)
+
+    extern fn \(externName)(\(externDefArguments), closure_data: [*]u8, output: *\(ret)) void;
+
+    pub const \(name) = extern struct {
+        closure_data: [*]u8,
+
+        pub fn force_thunk(self: \(name)\(publicComma)\(publicArguments)) \(ret) {
+            var output: \(ret) = undefined;
+
+            \(externName)(\(externCallArguments), self.closure_data, &output);
+
+            return output;
+        }
+
+        /// Frees the closure's captured values. Only do this once it won't be called anymore!
+        pub fn deinit(self: \(name)) void {
+            roc_std.deallocateClosureData(self.closure_data);
+        }
+    };
+
+    """
+
+generateStruct : Str, Types, TypeId, Str, _ -> Str
+generateStruct = \buf, types, id, name, structFields ->
+    escapedName = escapeKW name
+    fields = fieldsToList structFields
+
+    fieldDecls =
+        List.walk fields "" \accum, { name: fieldName, id: fieldId } ->
+            typeStr = typeName types fieldId
+
+            Str.concat accum "\(indent)\(escapeKW fieldName): \(typeStr),\n"
+
+    formatFields =
+        List.walkWithIndex fields "" \accum, { name: fieldName }, index ->
+            separator = if index == 0 then " " else ", "
+
+            """
+            \(accum)
+                    try writer.writeAll("\(separator)\(fieldName): ");
+                    try roc_std.formatValue(self.\(escapeKW fieldName), writer);
+            """
+
+    unusedSelf = if List.isEmpty fields then "\n\(indent)\(indent)_ = self;" else ""
+
+    """
+    \(buf)
+
+    pub const \(escapedName) = extern struct {
+    \(fieldDecls)
+        \(formatSignature escapedName)\(unusedSelf)
+            try writer.writeAll("\(name) {");\(formatFields)
+            try writer.writeAll(" }");
+        }
+    };
+
+    \(generateLayoutCheck types id escapedName)
+    """
+
+fieldsToList = \structFields ->
+    when structFields is
+        HasNoClosure fields -> List.map fields \{ name, id } -> { name, id }
+        HasClosure fields -> List.map fields \{ name, id } -> { name, id }
+
+nameTagUnionPayloadFields = \payloadFields ->
+    # Tag union payloads have numbered fields, so we prefix them
+    # with an "f" because Zig doesn't allow struct fields to be numbers.
+    when payloadFields is
+        HasNoClosure fields ->
+            renamedFields = List.map fields \{ name, id } -> { name: "f\(name)", id }
+            HasNoClosure renamedFields
+
+        HasClosure fields ->
+            renamedFields = List.map fields \{ name, id, accessors } -> { name: "f\(name)", id, accessors }
+            HasClosure renamedFields
+
+generateEnumeration = \buf, name, tags, tagBytes ->
+    escapedName = escapeKW name
+    reprBits = tagBytes * 8 |> Num.toStr
+
+    tagDecls =
+        List.walkWithIndex tags "" \accum, tagName, index ->
+            indexStr = Num.toStr index
+
+            Str.concat accum "\(indent)\(escapeKW tagName) = \(indexStr),\n"
+
+    """
+    \(buf)
+
+    pub const \(escapedName) = enum(u\(reprBits)) {
+    \(tagDecls)
+        \(formatSignature escapedName)
+            try writer.writeAll("\(name).");
+            try writer.writeAll(@tagName(self));
+        }
+    };
+
+    """
+
+generateDiscriminant = \buf, name, tags, size ->
+    if size > 0 then
+        generateEnumeration buf name tags size
+    else
+        buf
+
+generateNonRecursiveTagUnion : Str, Types, TypeId, Str, List { name : Str, payload : [Some TypeId, None] }, U32, U32 -> Str
+generateNonRecursiveTagUnion = \buf, types, id, name, tags, discriminantSize, discriminantOffset ->
+    escapedName = escapeKW name
+    discriminantName = "discriminant_\(name)"
+    unionName = "union_\(name)"
+    tagNames = List.map tags \{ name: n } -> n
+    discriminantOffsetStr = Num.toStr discriminantOffset
+
+    functions =
+        List.walk tags "" \accum, { name: tagName, payload } ->
+            escapedTagName = escapeKW tagName
+            { params, init } = tagConstructor types payload
+
+            constructor =
+                """
+                    pub fn \(escapedTagName)(\(params)) \(escapedName) {
+                        return .{ .payload = .{ .\(escapedTagName) = \(init) }, .tag = .\(escapedTagName) };
+                    }
+
+                    pub fn is_\(tagName)(self: \(escapedName)) bool {
+                        return self.tag == .\(escapedTagName);
+                    }
+                """
+
+            getter =
+                when payload is
+                    Some payloadId ->
+                        payloadType = typeName types payloadId
+
+                        """
+
+
+                            pub fn get_\(tagName)(self: \(escapedName)) \(payloadType) {
+                                std.debug.assert(self.is_\(tagName)());
+
+                                return self.payload.\(escapedTagName);
+                            }
+                        """
+
+                    None ->
+                        ""
+
+            "\(accum)\(constructor)\(getter)\n\n"
+
+    formatBranches =
+        List.walk tags "" \accum, { name: tagName, payload } ->
+            Str.concat accum (formatTagBranch types name tagName payload "self.payload.\(escapeKW tagName)")
+
+    layoutCheck = generateLayoutCheck types id escapedName
+
+    buf
+    |> generateDiscriminant discriminantName tagNames discriminantSize
+    |> Str.concat
+        """
+
+        pub const \(unionName) = extern union {
+        \(generateUnionFields types tags)};
+
+        pub const \(escapedName) = extern struct {
+            payload: \(unionName),
+            tag: \(discriminantName),
+
+            \(discriminantDocComment)
+            pub fn discriminant(self: \(escapedName)) \(discriminantName) {
+                return self.tag;
+            }
+
+        \(functions)    \(formatSignature escapedName)
+                switch (self.tag) {
+        \(formatBranches)        }
+            }
+        };
+
+        \(layoutCheck)
+        comptime {
+            if (@offsetOf(\(escapedName), "tag") != \(discriminantOffsetStr)) {
+                @compileError("The discriminant of \(name) is not where Roc stores it");
+            }
+        }
+
+        """
+
+generateRecursiveTagUnion = \buf, types, id, tagUnionName, tags, discriminantSize, nullTagIndex ->
+    escapedName = escapeKW tagUnionName
+    discriminantName = "discriminant_\(tagUnionName)"
+    unionName = "union_\(tagUnionName)"
+    tagNames = List.map tags \{ name: n } -> n
+
+    isNullTag = \index -> Some (Num.intCast index) == nullTagIndex
+
+    nullTagCheck =
+        when nullTagIndex is
+            Some index ->
+                nullTagName =
+                    List.get tagNames (Num.intCast index)
+                    |> Result.withDefault ""
+                    |> escapeKW
+
+                """
+                        if (self.pointer == null) {
+                            return .\(nullTagName);
+                        }
+
+                """
+
+            None ->
+                """
+                        std.debug.assert(self.pointer != null);
+
+                """
+
+    functions =
+        List.walkWithIndex tags "" \accum, { name: tagName, payload }, index ->
+            escapedTagName = escapeKW tagName
+            { params, init } = tagConstructor types payload
+
+            isFn =
+                """
+                    pub fn is_\(tagName)(self: \(escapedName)) bool {
+                        return self.discriminant() == .\(escapedTagName);
+                    }
+                """
+
+            constructor =
+                if isNullTag index then
+                    """
+                        pub fn \(escapedTagName)() \(escapedName) {
+                            return .{ .pointer = null };
+                        }
+                    """
+                else
+                    """
+                        pub fn \(escapedTagName)(\(params)) \(escapedName) {
+                            const node = roc_std.allocate(\(unionName));
+                            node.* = .{ .\(escapedTagName) = \(init) };
+
+                            return .{ .pointer = @ptrFromInt(@intFromPtr(node) | @intFromEnum(\(discriminantName).\(escapedTagName))) };
+                        }
+                    """
+
+            getter =
+                when payload is
+                    Some payloadId ->
+                        payloadType = typeName types payloadId
+
+                        """
+
+
+                            pub fn get_\(tagName)(self: \(escapedName)) \(payloadType) {
+                                std.debug.assert(self.is_\(tagName)());
+
+                                return self.unmaskedPointer().\(escapedTagName);
+                            }
+                        """
+
+                    None ->
+                        ""
+
+            "\(accum)\(constructor)\n\n\(isFn)\(getter)\n\n"
+
+    formatBranches =
+        List.walk tags "" \accum, { name: tagName, payload } ->
+            Str.concat accum (formatTagBranch types tagUnionName tagName payload "self.unmaskedPointer().\(escapeKW tagName)")
+
+    buf
+    |> generateDiscriminant discriminantName tagNames discriminantSize
+    |> Str.concat
+        """
+
+        pub const \(unionName) = extern union {
+        \(generateUnionFields types tags)};
+
+        /// A pointer to a \(unionName) with the tag stored in its lowest bits.
+        pub const \(escapedName) = extern struct {
+            pointer: ?*anyopaque,
+
+            \(discriminantDocComment)
+            pub fn discriminant(self: \(escapedName)) \(discriminantName) {
+        \(nullTagCheck)        return @enumFromInt(@intFromPtr(self.pointer) & roc_std.TAG_ID_MASK);
+            }
+
+            fn unmaskedPointer(self: \(escapedName)) *\(unionName) {
+                return @ptrFromInt(@intFromPtr(self.pointer) & ~roc_std.TAG_ID_MASK);
+            }
+
+            pub fn incref(self: \(escapedName), amount: isize) void {
+                roc_std.increfPointer(self.pointer, amount);
+            }
+
+            /// Only this node is decremented, not the ones its payload refers to.
+            pub fn decref(self: \(escapedName)) void {
+                roc_std.decrefPointer(self.pointer, @alignOf(\(unionName)));
+            }
+
+        \(functions)    \(formatSignature escapedName)
+                switch (self.discriminant()) {
+        \(formatBranches)        }
+            }
+        };
+
+        \(generateLayoutCheck types id escapedName)
+        """
+
+generateNullableUnwrapped : Str, Types, TypeId, Str, Str, Str, TypeId, [FirstTagIsNull, SecondTagIsNull] -> Str
+generateNullableUnwrapped = \buf, types, id, name, nullTag, nonNullTag, nonNullPayload, whichTagIsNull ->
+    escapedName = escapeKW name
+    discriminantName = "discriminant_\(name)"
+    payloadType = typeName types nonNullPayload
+    escapedNullTag = escapeKW nullTag
+    escapedNonNullTag = escapeKW nonNullTag
+    { params, init } = tagConstructor types (Some nonNullPayload)
+
+    discriminantTags =
+        when whichTagIsNull is
+            FirstTagIsNull -> [nullTag, nonNullTag]
+            SecondTagIsNull -> [nonNullTag, nullTag]
+
+    formatBranches =
+        formatTagBranch types name nullTag None ""
+        |> Str.concat (formatTagBranch types name nonNullTag (Some nonNullPayload) "self.pointer.?.*")
+
+    buf
+    |> generateEnumeration discriminantName discriminantTags 1
+    |> Str.concat
+        """
+
+        pub const \(escapedName) = extern struct {
+            pointer: ?*\(payloadType),
+
+            \(discriminantDocComment)
+            pub fn discriminant(self: \(escapedName)) \(discriminantName) {
+                return if (self.pointer == null) .\(escapedNullTag) else .\(escapedNonNullTag);
+            }
+
+            pub fn incref(self: \(escapedName), amount: isize) void {
+                roc_std.increfPointer(self.pointer, amount);
+            }
+
+            /// Only this node is decremented, not the ones its payload refers to.
+            pub fn decref(self: \(escapedName)) void {
+                roc_std.decrefPointer(self.pointer, @alignOf(\(payloadType)));
+            }
+
+            pub fn \(escapedNullTag)() \(escapedName) {
+                return .{ .pointer = null };
+            }
+
+            pub fn is_\(nullTag)(self: \(escapedName)) bool {
+                return self.pointer == null;
+            }
+
+            pub fn \(escapedNonNullTag)(\(params)) \(escapedName) {
+                const node = roc_std.allocate(\(payloadType));
+                node.* = \(init);
+
+                return .{ .pointer = node };
+            }
+
+            pub fn is_\(nonNullTag)(self: \(escapedName)) bool {
+                return self.pointer != null;
+            }
+
+            pub fn get_\(nonNullTag)(self: \(escapedName)) \(payloadType) {
+                return self.pointer.?.*;
+            }
+
+            \(formatSignature escapedName)
+                switch (self.discriminant()) {
+        \(formatBranches)        }
+            }
+        };
+
+        \(generateLayoutCheck types id escapedName)
+        """
+
+generateNonNullableUnwrapped = \buf, types, id, name, tagName, payload ->
+    escapedName = escapeKW name
+    escapedTagName = escapeKW tagName
+    payloadType = typeName types payload
+    { params, init } = tagConstructor types (Some payload)
+    formatFields = formatPayload types payload "self.pointer.*" 2
+
+    """
+    \(buf)
+
+    pub const \(escapedName) = extern struct {
+        pointer: *\(payloadType),
+
+        pub fn incref(self: \(escapedName), amount: isize) void {
+            roc_std.increfPointer(self.pointer, amount);
+        }
+
+        /// Only this node is decremented, not the ones its payload refers to.
+        pub fn decref(self: \(escapedName)) void {
+            roc_std.decrefPointer(self.pointer, @alignOf(\(payloadType)));
+        }
+
+        pub fn \(escapedTagName)(\(params)) \(escapedName) {
+            const node = roc_std.allocate(\(payloadType));
+            node.* = \(init);
+
+            return .{ .pointer = node };
+        }
+
+        pub fn get_\(tagName)(self: \(escapedName)) \(payloadType) {
+            return self.pointer.*;
+        }
+
+        \(formatSignature escapedName)
+            try writer.writeAll("\(name).\(tagName)(");\(formatFields)
+            try writer.writeAll(")");
+        }
+    };
+
+    \(generateLayoutCheck types id escapedName)
+    """
+
+generateSingleTagStruct = \buf, types, id, name, tagName, payload ->
+    # Store single-tag unions as structs rather than tag unions,
+    # because they have only one alternative. However, still
+    # offer the usual tag union APIs.
+    escapedName = escapeKW name
+    escapedTagName = escapeKW tagName
+
+    fieldIds =
+        when payload is
+            HasNoClosure fields -> List.map fields .id
+            HasClosure fields -> List.map fields .id
+
+    fieldDecls =
+        List.walkWithIndex fieldIds "" \accum, fieldId, index ->
+            indexStr = Num.toStr index
+            typeStr = typeName types fieldId
+
+            Str.concat accum "\(indent)f\(indexStr): \(typeStr),\n"
+
+    params =
+        commaSeparated "" fieldIds \fieldId, index ->
+            indexStr = Num.toStr index
+            typeStr = typeName types fieldId
+
+            "f\(indexStr): \(typeStr)"
+
+    init =
+        commaSeparated "" fieldIds \_, index ->
+            indexStr = Num.toStr index
+
+            ".f\(indexStr) = f\(indexStr)"
+
+    formatBody =
+        if List.isEmpty fieldIds then
+            """
+                    _ = self;
+                    try writer.writeAll("\(name).\(tagName)");
+            """
+        else
+            formatFields =
+                List.walkWithIndex fieldIds "" \accum, _, index ->
+                    indexStr = Num.toStr index
+
+                    formatField accum index "self.f\(indexStr)" 2
+
+            """
+                    try writer.writeAll("\(name).\(tagName)(");\(formatFields)
+                    try writer.writeAll(")");
+            """
+
+    """
+    \(buf)
+
+    pub const \(escapedName) = extern struct {
+    \(fieldDecls)
+        /// A tag named `\(tagName)`, with the given payload.
+        pub fn \(escapedTagName)(\(params)) \(escapedName) {
+            return .{ \(init) };
+        }
+
+        \(formatSignature escapedName)
+    \(formatBody)
+        }
+    };
+
+    \(generateLayoutCheck types id escapedName)
+    """
+
+## The parameters a tag's constructor takes, and the payload it builds out of them.
+tagConstructor : Types, [Some TypeId, None] -> { params : Str, init : Str }
+tagConstructor = \types, optPayload ->
+    when optPayload is
+        None ->
+            { params: "", init: "{}" }
+
+        Some payloadId ->
+            when Types.shape types payloadId is
+                TagUnionPayload { fields } ->
+                    fieldIds = List.map (fieldsToList fields) .id
+
+                    params =
+                        commaSeparated "" fieldIds \fieldId, index ->
+                            indexStr = Num.toStr index
+                            type = typeName types fieldId
+
+                            "f\(indexStr): \(type)"
+
+                    fieldInits =
+                        commaSeparated "" fieldIds \_, index ->
+                            indexStr = Num.toStr index
+
+                            ".f\(indexStr) = f\(indexStr)"
+
+                    { params, init: ".{ \(fieldInits) }" }
+
+                _ ->
+                    type = typeName types payloadId
+
+                    { params: "payload: \(type)", init: "payload" }
+
+formatTagBranch : Types, Str, Str, [Some TypeId, None], Str -> Str
+formatTagBranch = \types, unionName, tagName, optPayload, payloadExpr ->
+    escapedTagName = escapeKW tagName
+
+    when optPayload is
+        None ->
+            "\(indent)\(indent)\(indent).\(escapedTagName) => try writer.writeAll(\"\(unionName).\(tagName)\"),\n"
+
+        Some payloadId ->
+            formatFields = formatPayload types payloadId payloadExpr 4
+
+            """
+                        .\(escapedTagName) => {
+                            try writer.writeAll("\(unionName).\(tagName)(");\(formatFields)
+                            try writer.writeAll(")");
+                        },
+
+            """
+
+## Formats each of a tag's payload values, separated by commas.
+formatPayload : Types, TypeId, Str, Nat -> Str
+formatPayload = \types, payloadId, payloadExpr, indents ->
+    when Types.shape types payloadId is
+        TagUnionPayload { fields } ->
+            List.walkWithIndex (fieldsToList fields) "" \accum, _, index ->
+                indexStr = Num.toStr index
+
+                formatField accum index "\(payloadExpr).f\(indexStr)" indents
+
+        _ ->
+            formatField "" 0 payloadExpr indents
+
+formatField = \buf, index, valueExpr, indents ->
+    separator =
+        if index == 0 then
+            ""
+        else
+            "\n"
+            |> writeIndents indents
+            |> Str.concat "try writer.writeAll(\", \");"
+
+    buf
+    |> Str.concat separator
+    |> Str.concat "\n"
+    |> writeIndents indents
+    |> Str.concat "try roc_std.formatValue(\(valueExpr), writer);"
+
+writeIndents = \buf, indents ->
+    if indents <= 0 then
+        buf
+    else
+        buf
+        |> Str.concat indent
+        |> writeIndents (indents - 1)
+
+formatSignature = \typeStr ->
+    "pub fn format(self: \(typeStr), comptime _: []const u8, _: std.fmt.FormatOptions, writer: anytype) @TypeOf(writer).Error!void {"
+
+generateUnionFields = \types, tags ->
+    List.walk tags "" \accum, { name: fieldName, payload } ->
+        typeStr =
+            when payload is
+                Some id -> typeName types id
+                None -> "void"
+
+        Str.concat accum "\(indent)\(escapeKW fieldName): \(typeStr),\n"
+
+generateLayoutCheck = \types, id, escapedName ->
+    size = Num.toStr (Types.size types id)
+    alignment = Num.toStr (Types.alignment types id)
+
+    """
+    comptime {
+        roc_std.assertLayout(\(escapedName), \(size), \(alignment));
+    }
+
+    """
+
+commaSeparated : Str, List a, (a, Nat -> Str) -> Str
+commaSeparated = \buf, items, step ->
+    length = List.len items
+    List.walk items { buf, count: 0 } \accum, item ->
+        if accum.count + 1 == length then
+            { buf: Str.concat accum.buf (step item accum.count), count: length }
+        else
+            { buf: Str.concat accum.buf (step item accum.count) |> Str.concat ", ", count: accum.count + 1 }
+    |> .buf
+
+## Whether a value can be passed to Roc directly. Anything which refers
+## to heap memory gets passed by pointer instead.
+canPassByValue : Types, Shape -> Bool
+canPassByValue = \types, type ->
+    when type is
+        Function rocFn ->
+            runtimeRepresentation = Types.shape types rocFn.lambdaSet
+            canPassByValue types runtimeRepresentation
+
+        # unsized values are heap-allocated
+        Unsized -> Bool.false
+        Unit | EmptyTagUnion | Bool | Num _ | TagUnion (Enumeration _) -> Bool.true
+        RocStr | RocList _ | RocDict _ _ | RocSet _ | RocBox _ | TagUnion (NullableUnwrapped _) | TagUnion (NullableWrapped _) | TagUnion (Recursive _) | TagUnion (NonNullableUnwrapped _) | RecursivePointer _ -> Bool.false
+        TagUnion (SingleTagStruct { payload: HasNoClosure fields }) ->
+            List.all fields \{ id } -> canPassByValue types (Types.shape types id)
+
+        TagUnion (SingleTagStruct { payload: HasClosure fields }) ->
+            List.all fields \{ id } -> canPassByValue types (Types.shape types id)
+
+        TagUnion (NonRecursive { tags }) ->
+            List.all tags \{ payload } ->
+                when payload is
+                    Some id -> canPassByValue types (Types.shape types id)
+                    None -> Bool.true
+
+        RocResult okId errId ->
+            canPassByValue types (Types.shape types okId)
+            && canPassByValue types (Types.shape types errId)
+
+        Struct { fields: HasNoClosure fields } | TagUnionPayload { fields: HasNoClosure fields } ->
+            List.all fields \{ id } -> canPassByValue types (Types.shape types id)
+
+        Struct { fields: HasClosure fields } | TagUnionPayload { fields: HasClosure fields } ->
+            List.all fields \{ id } -> canPassByValue types (Types.shape types id)
+
+typeName = \types, id ->
+    when Types.shape types id is
+        # Zig allows void in extern structs, unions and return types, and toArgStr drops it from arguments.
+        Unit -> "void"
+        Unsized -> "roc_std.RocList(u8)"
+        EmptyTagUnion -> "void"
+        RocStr -> "roc_std.RocStr"
+        Bool -> "bool"
+        Num U8 -> "u8"
+        Num U16 -> "u16"
+        Num U32 -> "u32"
+        Num U64 -> "u64"
+        Num U128 -> "u128"
+        Num I8 -> "i8"
+        Num I16 -> "i16"
+        Num I32 -> "i32"
+        Num I64 -> "i64"
+        Num I128 -> "i128"
+        Num F32 -> "f32"
+        Num F64 -> "f64"
+        Num Dec -> "roc_std.RocDec"
+        RocDict key value ->
+            keyName = typeName types key
+            valueName = typeName types value
+
+            "roc_std.RocDict(\(keyName), \(valueName))"
+
+        RocSet elem ->
+            elemName = typeName types elem
+
+            "roc_std.RocSet(\(elemName))"
+
+        RocList elem ->
+            elemName = typeName types elem
+
+            "roc_std.RocList(\(elemName))"
+
+        RocBox elem ->
+            elemName = typeName types elem
+
+            "roc_std.RocBox(\(elemName))"
+
+        RocResult ok err ->
+            okName = typeName types ok
+            errName = typeName types err
+
+            "roc_std.RocResult(\(okName), \(errName))"
+
+        RecursivePointer content ->
+            typeName types content
+
+        Struct { name } -> escapeKW name
+        TagUnionPayload { name } -> escapeKW name
+        TagUnion (NonRecursive { name }) -> escapeKW name
+        TagUnion (Recursive { name }) -> escapeKW name
+        TagUnion (Enumeration { name }) -> escapeKW name
+        TagUnion (NullableWrapped { name }) -> escapeKW name
+        TagUnion (NullableUnwrapped { name }) -> escapeKW name
+        TagUnion (NonNullableUnwrapped { name }) -> escapeKW name
+        TagUnion (SingleTagStruct { name }) -> escapeKW name
+        Function { functionName } -> escapeKW functionName
+
+archName = \arch ->
+    # These match the names of Zig's `std.Target.Cpu.Arch` tags.
+    when arch is
+        Aarch32 ->
+            "arm"
+
+        Aarch64 ->
+            "aarch64"
+
+        Wasm32 ->
+            "wasm32"
+
+        X86x32 ->
+            "x86"
+
+        X86x64 ->
+            "x86_64"
+
+fileHeader =
+    """
+    // ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+
+    """
+
+archFileHeader =
+    """
+    \(fileHeader)
+    const std = @import("std");
+    const roc_std = @import("roc_std.zig");
+
+    """
+
+indent = "    "
+discriminantDocComment = "/// Returns which variant this tag union holds. Note that this never includes a payload!"
+
+reservedKeywords = Set.fromList [
+    "addrspace",
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anytype",
+    "asm",
+    "async",
+    "await",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "false",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "linksection",
+    "noalias",
+    "noinline",
+    "nosuspend",
+    "null",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "true",
+    "try",
+    "type",
+    "undefined",
+    "union",
+    "unreachable",
+    "usingnamespace",
+    "var",
+    "volatile",
+    "while",
+]
+
+escapeKW = \input ->
+    # Zig lets any string be an identifier if it's written as @"...",
+    # which is how reserved keywords can still be used as names.
+    if Set.contains reservedKeywords input then
+        "@\"\(input)\""
+    else
+        input
+
+isUnit : Shape -> Bool
+isUnit = \shape ->
+    when shape is
+        Unit -> Bool.true
+        _ -> Bool.false
+
+toArgStr : List TypeId, Types, (TypeId, Shape, Nat -> Str) -> Str
+toArgStr = \args, types, fmt ->
+    List.walkWithIndex args "" \state, argId, index ->
+        shape = Types.shape types argId
+
+        # Drop `{}` args; Zig doesn't allow void parameters in extern functions, and nothing will get passed anyway.
+        if isUnit shape then
+            state
+        else
+            argStr = fmt argId shape index
+
+            if Str.isEmpty state then
+                argStr # Don't prepend a comma if this is the first one
+            else
+                state
+                |> Str.concat ", "
+                |> Str.concat argStr
@@ -0,0 +1,311 @@
+// C versions of Roc's builtin types, which the rest of the generated glue refers to.
+//
+// C has no generics, so the builtin collections aren't told what their elements are.
+// Cast `RocList.elements` (or `RocBox.contents`) to a pointer of the right type to read them.
+//
+// None of these types free what they point to automatically. A value that is passed to
+// Roc gets consumed by Roc, so call the matching `incref` first if the host still needs it
+// afterwards, and call `decref` on a value Roc returned once the host is done with it.
+//
+// The host must define `roc_alloc` and `roc_dealloc`, which these helpers allocate with.
+
+#ifndef ROC_STD_H
+#define ROC_STD_H
+
+#include <assert.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <string.h>
+
+#ifdef __cplusplus
+extern "C" {
+#define ROC_ALIGNAS(alignment) alignas(alignment)
+#define ROC_ALIGNOF(type) alignof(type)
+#define ROC_STATIC_ASSERT(condition, message) static_assert(condition, message)
+#else
+#define ROC_ALIGNAS(alignment) _Alignas(alignment)
+#define ROC_ALIGNOF(type) _Alignof(type)
+#define ROC_STATIC_ASSERT(condition, message) _Static_assert(condition, message)
+#endif
+
+// Fails compilation when the C definition of a type disagrees with the layout Roc
+// gave it, so that a mismatch can't silently corrupt memory at runtime.
+#define ROC_ASSERT_LAYOUT(type, size, alignment) \
+    ROC_STATIC_ASSERT(sizeof(type) == (size), #type " has a different size in C than in Roc"); \
+    ROC_STATIC_ASSERT(ROC_ALIGNOF(type) == (alignment), #type " has a different alignment in C than in Roc")
+
+// The bits of a recursive tag union's pointer which store which tag it is.
+#define ROC_TAG_ID_MASK ((uintptr_t)(sizeof(void *) == 8 ? 0x7 : 0x3))
+
+// The alignment the host uses when allocating room for a closure's captured values.
+#define ROC_CLOSURE_DATA_ALIGNMENT 16
+
+// Roc stores the refcount of an allocation just before its contents. A refcount of
+// this value means there's exactly one reference, and 0 means it is never freed.
+#define ROC_REFCOUNT_ONE INTPTR_MIN
+#define ROC_REFCOUNT_READONLY 0
+
+#if defined(__i386__) || defined(_M_IX86)
+#define ROC_INT128_ALIGNMENT 8
+#else
+#define ROC_INT128_ALIGNMENT 16
+#endif
+
+void *roc_alloc(size_t size, uint32_t alignment);
+void roc_dealloc(void *ptr, uint32_t alignment);
+
+typedef struct RocStr {
+    char *bytes;
+    size_t length;
+    size_t capacity_or_ref_ptr;
+} RocStr;
+
+typedef struct RocList {
+    void *elements;
+    size_t length;
+    size_t capacity_or_ref_ptr;
+} RocList;
+
+// The same layout as the builtin `Dict`: its key-value pairs in insertion order, plus
+// the hash table which indexes into them and the seed its keys are hashed with.
+// The table is only ever read or written by Roc.
+typedef struct RocDict {
+#if UINTPTR_MAX == UINT32_MAX && !defined(__i386__) && !defined(_M_IX86)
+    // Where a uint64_t is more aligned than a pointer (e.g. wasm32), Roc puts the seed first.
+    uint64_t seed;
+#endif
+    RocList data;
+    RocList data_indices;
+    RocList metadata;
+#if !(UINTPTR_MAX == UINT32_MAX && !defined(__i386__) && !defined(_M_IX86))
+    uint64_t seed;
+#endif
+} RocDict;
+
+// The builtin `Set` is a `Dict` whose values are all `{}`.
+typedef struct RocSet {
+    RocDict dict;
+} RocSet;
+
+typedef struct RocBox {
+    void *contents;
+} RocBox;
+
+typedef struct RocU128 {
+    ROC_ALIGNAS(ROC_INT128_ALIGNMENT) uint64_t lower;
+    uint64_t upper;
+} RocU128;
+
+typedef struct RocI128 {
+    ROC_ALIGNAS(ROC_INT128_ALIGNMENT) uint64_t lower;
+    int64_t upper;
+} RocI128;
+
+// A fixed-point decimal, which is stored as the number of quintillionths (10^-18).
+typedef struct RocDec {
+    RocI128 quintillionths;
+} RocDec;
+
+// The tags of every `RocResult_*` type's `tag` field.
+enum {
+    RocResult_Err = 0,
+    RocResult_Ok = 1,
+};
+
+// Allocates `data_bytes` behind a refcount of 1, the same way Roc allocates the contents
+// of a `Box`, a `List`, or a node of a recursive tag union.
+static inline void *roc_allocate_with_refcount(size_t data_bytes, uint32_t alignment) {
+    uint32_t extra_bytes = alignment > sizeof(intptr_t) ? alignment : sizeof(intptr_t);
+    char *allocation = (char *)roc_alloc(extra_bytes + data_bytes, extra_bytes);
+    char *data = allocation + extra_bytes;
+
+    ((intptr_t *)data)[-1] = ROC_REFCOUNT_ONE;
+
+    return data;
+}
+
+// Increments the refcount of memory allocated by `roc_allocate_with_refcount`.
+static inline void roc_incref_pointer(void *data, intptr_t amount) {
+    if (data == NULL) {
+        return;
+    }
+
+    intptr_t *refcount = (intptr_t *)data - 1;
+
+    if (*refcount != ROC_REFCOUNT_READONLY) {
+        *refcount += amount;
+    }
+}
+
+// Whether this is the only reference to memory allocated by `roc_allocate_with_refcount`,
+// in which case decrementing its refcount frees it.
+static inline bool roc_pointer_is_unique(void *data) {
+    return data != NULL && ((intptr_t *)data)[-1] == ROC_REFCOUNT_ONE;
+}
+
+// Decrements the refcount of memory allocated by `roc_allocate_with_refcount`, and frees
+// it once nothing refers to it anymore. What it points to is not decremented.
+static inline void roc_decref_pointer(void *data, uint32_t alignment) {
+    if (data == NULL) {
+        return;
+    }
+
+    intptr_t *refcount = (intptr_t *)data - 1;
+
+    if (*refcount == ROC_REFCOUNT_READONLY) {
+        return;
+    }
+
+    if (*refcount == ROC_REFCOUNT_ONE) {
+        uint32_t extra_bytes = alignment > sizeof(intptr_t) ? alignment : sizeof(intptr_t);
+
+        roc_dealloc((char *)data - extra_bytes, alignment);
+    } else {
+        *refcount -= 1;
+    }
+}
+
+// Strings shorter than a RocStr are stored inside of it, with their length in the last
+// byte, rather than on the heap.
+static inline bool roc_str_is_small(const RocStr *str) {
+    return (intptr_t)str->capacity_or_ref_ptr < 0;
+}
+
+// A seamless slice refers to part of another string's allocation, and stores a pointer
+// to that allocation (shifted right by 1) instead of its capacity.
+static inline bool roc_str_is_seamless_slice(const RocStr *str) {
+    return !roc_str_is_small(str) && (intptr_t)str->length < 0;
+}
+
+static inline size_t roc_str_len(const RocStr *str) {
+    if (roc_str_is_small(str)) {
+        return ((const uint8_t *)str)[sizeof(RocStr) - 1] & 0x7F;
+    }
+
+    return str->length & (SIZE_MAX >> 1);
+}
+
+// The string's UTF-8 bytes, which are not nul-terminated.
+static inline const char *roc_str_bytes(const RocStr *str) {
+    return roc_str_is_small(str) ? (const char *)str : str->bytes;
+}
+
+static inline RocStr roc_str_from_bytes(const char *bytes, size_t length) {
+    RocStr str;
+
+    if (length < sizeof(RocStr)) {
+        memset(&str, 0, sizeof(RocStr));
+        memcpy(&str, bytes, length);
+        ((uint8_t *)&str)[sizeof(RocStr) - 1] = (uint8_t)length | 0x80;
+    } else {
+        str.bytes = (char *)roc_allocate_with_refcount(length, ROC_ALIGNOF(size_t));
+        str.length = length;
+        str.capacity_or_ref_ptr = length;
+        memcpy(str.bytes, bytes, length);
+    }
+
+    return str;
+}
+
+static inline RocStr roc_str_from_cstr(const char *cstr) {
+    return roc_str_from_bytes(cstr, strlen(cstr));
+}
+
+static inline void *roc_str_refcounted_ptr(const RocStr *str) {
+    if (roc_str_is_seamless_slice(str)) {
+        return (void *)(str->capacity_or_ref_ptr << 1);
+    }
+
+    return str->bytes;
+}
+
+static inline void roc_str_incref(RocStr str, intptr_t amount) {
+    if (!roc_str_is_small(&str)) {
+        roc_incref_pointer(roc_str_refcounted_ptr(&str), amount);
+    }
+}
+
+static inline void roc_str_decref(RocStr str) {
+    if (!roc_str_is_small(&str)) {
+        roc_decref_pointer(roc_str_refcounted_ptr(&str), ROC_ALIGNOF(size_t));
+    }
+}
+
+static inline RocList roc_list_empty(void) {
+    RocList list;
+
+    list.elements = NULL;
+    list.length = 0;
+    list.capacity_or_ref_ptr = 0;
+
+    return list;
+}
+
+// Copies `length` elements of `element_size` bytes each into a new list.
+static inline RocList roc_list_from_array(const void *elements, size_t length, size_t element_size, uint32_t element_alignment) {
+    if (length == 0) {
+        return roc_list_empty();
+    }
+
+    RocList list;
+
+    list.elements = roc_allocate_with_refcount(length * element_size, element_alignment);
+    list.length = length;
+    list.capacity_or_ref_ptr = length;
+    memcpy(list.elements, elements, length * element_size);
+
+    return list;
+}
+
+static inline size_t roc_list_len(RocList list) {
+    return list.length;
+}
+
+// Like strings, lists can be seamless slices of another list's allocation.
+static inline bool roc_list_is_seamless_slice(RocList list) {
+    return (intptr_t)list.capacity_or_ref_ptr < 0;
+}
+
+static inline void *roc_list_refcounted_ptr(RocList list) {
+    if (roc_list_is_seamless_slice(list)) {
+        return (void *)(list.capacity_or_ref_ptr << 1);
+    }
+
+    return list.elements;
+}
+
+// Whether decrementing the list frees its elements. A seamless slice never does, because
+// its elements are only some of the ones in the allocation it refers to.
+static inline bool roc_list_is_unique(RocList list) {
+    return list.capacity_or_ref_ptr != 0 && !roc_list_is_seamless_slice(list) && roc_pointer_is_unique(list.elements);
+}
+
+static inline void roc_list_incref(RocList list, intptr_t amount) {
+    // An empty list which isn't a slice of another one has no allocation to count.
+    if (list.capacity_or_ref_ptr != 0) {
+        roc_incref_pointer(roc_list_refcounted_ptr(list), amount);
+    }
+}
+
+// Only the list's own allocation is decremented, not its elements.
+static inline void roc_list_decref(RocList list, uint32_t element_alignment) {
+    if (list.capacity_or_ref_ptr != 0) {
+        roc_decref_pointer(roc_list_refcounted_ptr(list), element_alignment);
+    }
+}
+
+static inline void roc_box_incref(RocBox box, intptr_t amount) {
+    roc_incref_pointer(box.contents, amount);
+}
+
+// Only the box's own allocation is decremented, not its contents.
+static inline void roc_box_decref(RocBox box, uint32_t alignment) {
+    roc_decref_pointer(box.contents, alignment);
+}
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif // ROC_STD_H
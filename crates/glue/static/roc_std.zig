@@ -0,0 +1,352 @@
+// Zig versions of Roc's builtin types, which the rest of the generated glue refers to.
+//
+// Str and Dec come straight from the `glue` package that roc makes available to every
+// Zig host. The builtin collections are defined here as generic extern structs instead,
+// so that they know what their elements are.
+//
+// None of these types free what they point to automatically. A value that is passed to
+// Roc gets consumed by Roc, so call `incref` first if the host still needs it afterwards,
+// and call `decref` on a value Roc returned once the host is done with it.
+
+const std = @import("std");
+const glue = @import("glue");
+const utils = glue.utils;
+
+pub const RocStr = glue.str.RocStr;
+pub const RocDec = glue.dec.RocDec;
+
+/// The bits of a recursive tag union's pointer which store which tag it is.
+pub const TAG_ID_MASK: usize = if (@sizeOf(usize) == 8) 0b111 else 0b011;
+
+/// The alignment the host uses when allocating room for a closure's captured values.
+pub const CLOSURE_DATA_ALIGNMENT: u32 = 16;
+
+/// Allocates a `T` behind a refcount of 1, the same way Roc allocates the contents of a
+/// `Box` or a node of a recursive tag union.
+pub fn allocate(comptime T: type) *T {
+    const bytes = utils.allocateWithRefcount(@sizeOf(T), @alignOf(T));
+
+    return @ptrCast(@alignCast(bytes));
+}
+
+/// Increments the refcount of memory allocated by `allocate`, ignoring any tag id bits.
+pub fn increfPointer(pointer: ?*anyopaque, amount: isize) void {
+    utils.increfDataPtrC(@ptrCast(pointer), amount);
+}
+
+/// Decrements the refcount of memory allocated by `allocate`, ignoring any tag id bits,
+/// and frees it once nothing refers to it anymore. What it points to is not decremented.
+pub fn decrefPointer(pointer: ?*anyopaque, comptime alignment: u32) void {
+    utils.decrefDataPtrC(@ptrCast(pointer), alignment);
+}
+
+pub fn allocateClosureData(size: usize) [*]u8 {
+    // Some closures capture nothing, but roc_alloc may not be asked for 0 bytes.
+    return utils.alloc(@max(size, 1), CLOSURE_DATA_ALIGNMENT) orelse @panic("roc_alloc returned null while allocating closure data");
+}
+
+pub fn deallocateClosureData(closure_data: [*]u8) void {
+    utils.dealloc(closure_data, CLOSURE_DATA_ALIGNMENT);
+}
+
+/// Fails compilation when the Zig definition of a type disagrees with the layout Roc
+/// gave it, so that a mismatch can't silently corrupt memory at runtime.
+pub fn assertLayout(comptime T: type, comptime size: usize, comptime alignment: usize) void {
+    if (@sizeOf(T) != size) {
+        @compileError(std.fmt.comptimePrint("{s} has a size of {} in Zig, but {} in Roc", .{ @typeName(T), @sizeOf(T), size }));
+    }
+
+    if (@alignOf(T) != alignment) {
+        @compileError(std.fmt.comptimePrint("{s} has an alignment of {} in Zig, but {} in Roc", .{ @typeName(T), @alignOf(T), alignment }));
+    }
+}
+
+/// Writes out a value the way Roc would display it, e.g. strings get quoted and `{}`
+/// is written as `{}`. The generated types' `format` functions are built on this.
+pub fn formatValue(value: anytype, writer: anytype) @TypeOf(writer).Error!void {
+    const T = @TypeOf(value);
+
+    if (T == RocStr) {
+        return writer.print("\"{s}\"", .{value.asSlice()});
+    }
+
+    if (T == RocDec) {
+        return writer.print("{d}", .{value.toF64()});
+    }
+
+    switch (@typeInfo(T)) {
+        .Void => return writer.writeAll("{}"),
+        .Float => return writer.print("{d}", .{value}),
+        .Struct, .Union, .Enum => if (@hasDecl(T, "format")) {
+            return writer.print("{}", .{value});
+        },
+        else => {},
+    }
+
+    return writer.print("{any}", .{value});
+}
+
+pub fn RocList(comptime T: type) type {
+    return extern struct {
+        elements: ?[*]T,
+        length: usize,
+        capacity_or_ref_ptr: usize,
+
+        const Self = @This();
+
+        pub fn empty() Self {
+            return fromUntyped(glue.list.RocList.empty());
+        }
+
+        pub fn fromSlice(slice: []const T) Self {
+            return fromUntyped(glue.list.RocList.fromSlice(T, slice));
+        }
+
+        pub fn len(self: Self) usize {
+            return self.length;
+        }
+
+        pub fn isEmpty(self: Self) bool {
+            return self.length == 0;
+        }
+
+        pub fn items(self: Self) []const T {
+            const elements = self.elements orelse return &[_]T{};
+
+            return elements[0..self.length];
+        }
+
+        pub fn incref(self: Self, amount: isize) void {
+            const list = self.asUntyped();
+
+            // An empty list which isn't a slice of another one has no allocation to count.
+            if (list.getCapacity() != 0 or list.isSeamlessSlice()) {
+                utils.increfDataPtrC(list.getRefcountPtr(), amount);
+            }
+        }
+
+        /// Only the list's own allocation is decremented, not its elements.
+        pub fn decref(self: Self) void {
+            self.asUntyped().decref(@alignOf(T));
+        }
+
+        pub fn format(self: Self, comptime _: []const u8, _: std.fmt.FormatOptions, writer: anytype) @TypeOf(writer).Error!void {
+            try writer.writeAll("[");
+
+            for (self.items(), 0..) |item, index| {
+                if (index > 0) {
+                    try writer.writeAll(", ");
+                }
+
+                try formatValue(item, writer);
+            }
+
+            try writer.writeAll("]");
+        }
+
+        fn asUntyped(self: Self) glue.list.RocList {
+            return .{
+                .bytes = @ptrCast(self.elements),
+                .length = self.length,
+                .capacity_or_ref_ptr = self.capacity_or_ref_ptr,
+            };
+        }
+
+        fn fromUntyped(list: glue.list.RocList) Self {
+            return .{
+                .elements = @ptrCast(@alignCast(list.bytes)),
+                .length = list.length,
+                .capacity_or_ref_ptr = list.capacity_or_ref_ptr,
+            };
+        }
+    };
+}
+
+pub fn RocBox(comptime T: type) type {
+    return extern struct {
+        contents: *T,
+
+        const Self = @This();
+
+        pub fn init(value: T) Self {
+            const contents = allocate(T);
+            contents.* = value;
+
+            return .{ .contents = contents };
+        }
+
+        pub fn get(self: Self) T {
+            return self.contents.*;
+        }
+
+        pub fn incref(self: Self, amount: isize) void {
+            increfPointer(self.contents, amount);
+        }
+
+        /// Only the box's own allocation is decremented, not its contents.
+        pub fn decref(self: Self) void {
+            decrefPointer(self.contents, @alignOf(T));
+        }
+
+        pub fn format(self: Self, comptime _: []const u8, _: std.fmt.FormatOptions, writer: anytype) @TypeOf(writer).Error!void {
+            try writer.writeAll("Box(");
+            try formatValue(self.contents.*, writer);
+            try writer.writeAll(")");
+        }
+    };
+}
+
+/// One of the key-value pairs a `RocDict` stores. Like any Roc tuple, whichever of the
+/// two has the larger alignment comes first in memory, with the key winning ties.
+pub fn RocDictItem(comptime K: type, comptime V: type) type {
+    if (@alignOf(K) >= @alignOf(V)) {
+        return extern struct {
+            key: K,
+            value: V,
+        };
+    } else {
+        return extern struct {
+            value: V,
+            key: K,
+        };
+    }
+}
+
+/// The same layout as the builtin `Dict`: its key-value pairs in insertion order, plus
+/// the hash table which indexes into them and the seed its keys are hashed with. The table
+/// is only ever read or written by Roc; a host can iterate over `items`.
+pub fn RocDict(comptime K: type, comptime V: type) type {
+    // Where a u64 is more aligned than a pointer (e.g. wasm32), Roc puts the seed first.
+    if (@alignOf(u64) > @alignOf(usize)) {
+        return extern struct {
+            seed: u64,
+            data: RocList(RocDictItem(K, V)),
+            dataIndices: RocList(usize),
+            metadata: RocList(i8),
+
+            pub usingnamespace RocDictMethods(@This(), K, V);
+        };
+    } else {
+        return extern struct {
+            data: RocList(RocDictItem(K, V)),
+            dataIndices: RocList(usize),
+            metadata: RocList(i8),
+            seed: u64,
+
+            pub usingnamespace RocDictMethods(@This(), K, V);
+        };
+    }
+}
+
+fn RocDictMethods(comptime Self: type, comptime K: type, comptime V: type) type {
+    return struct {
+        pub fn len(self: Self) usize {
+            return self.data.len();
+        }
+
+        pub fn items(self: Self) []const RocDictItem(K, V) {
+            return self.data.items();
+        }
+
+        pub fn decref(self: Self) void {
+            self.data.decref();
+            self.dataIndices.decref();
+            self.metadata.decref();
+        }
+
+        pub fn format(self: Self, comptime _: []const u8, _: std.fmt.FormatOptions, writer: anytype) @TypeOf(writer).Error!void {
+            try writer.writeAll("{");
+
+            for (self.items(), 0..) |item, index| {
+                if (index > 0) {
+                    try writer.writeAll(", ");
+                }
+
+                try formatValue(item.key, writer);
+                try writer.writeAll(": ");
+                try formatValue(item.value, writer);
+            }
+
+            try writer.writeAll("}");
+        }
+    };
+}
+
+/// The builtin `Set` is a `Dict` whose values are all `{}`.
+pub fn RocSet(comptime T: type) type {
+    return extern struct {
+        dict: RocDict(T, void),
+
+        const Self = @This();
+
+        pub fn len(self: Self) usize {
+            return self.dict.len();
+        }
+
+        pub fn decref(self: Self) void {
+            self.dict.decref();
+        }
+
+        pub fn format(self: Self, comptime _: []const u8, _: std.fmt.FormatOptions, writer: anytype) @TypeOf(writer).Error!void {
+            try writer.writeAll("{");
+
+            for (self.dict.items(), 0..) |item, index| {
+                if (index > 0) {
+                    try writer.writeAll(", ");
+                }
+
+                try formatValue(item.key, writer);
+            }
+
+            try writer.writeAll("}");
+        }
+    };
+}
+
+pub const RocResultTag = enum(u8) {
+    RocErr = 0,
+    RocOk = 1,
+};
+
+pub fn RocResult(comptime T: type, comptime E: type) type {
+    return extern struct {
+        payload: extern union {
+            ok: T,
+            err: E,
+        },
+        tag: RocResultTag,
+
+        const Self = @This();
+
+        pub fn ok(payload: T) Self {
+            return .{ .payload = .{ .ok = payload }, .tag = .RocOk };
+        }
+
+        pub fn err(payload: E) Self {
+            return .{ .payload = .{ .err = payload }, .tag = .RocErr };
+        }
+
+        pub fn isOk(self: Self) bool {
+            return self.tag == .RocOk;
+        }
+
+        pub fn isErr(self: Self) bool {
+            return self.tag == .RocErr;
+        }
+
+        pub fn format(self: Self, comptime _: []const u8, _: std.fmt.FormatOptions, writer: anytype) @TypeOf(writer).Error!void {
+            switch (self.tag) {
+                .RocOk => {
+                    try writer.writeAll("Ok(");
+                    try formatValue(self.payload.ok, writer);
+                },
+                .RocErr => {
+                    try writer.writeAll("Err(");
+                    try formatValue(self.payload.err, writer);
+                },
+            }
+
+            try writer.writeAll(")");
+        }
+    };
+}
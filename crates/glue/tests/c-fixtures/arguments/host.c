@@ -0,0 +1,12 @@
+#include <inttypes.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+int main(void) {
+    int64_t answer = mainForHost(42);
+
+    printf("Answer was: %" PRId64 "\n", answer);
+
+    return 0;
+}
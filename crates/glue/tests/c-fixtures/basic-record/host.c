@@ -0,0 +1,16 @@
+#include <assert.h>
+#include <inttypes.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+int main(void) {
+    MyRcd record = mainForHost();
+
+    assert(record.a == 1995);
+    assert(record.b.lower == 42 && record.b.upper == 0);
+
+    printf("Record was: MyRcd { b: %" PRIu64 ", a: %" PRIu64 " }\n", record.b.lower, record.a);
+
+    return 0;
+}
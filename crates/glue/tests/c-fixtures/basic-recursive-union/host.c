@@ -0,0 +1,49 @@
+#include <assert.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+static void print_str(RocStr str) {
+    printf("\"%.*s\"", (int)roc_str_len(&str), roc_str_bytes(&str));
+}
+
+static void print_expr(Expr expr) {
+    if (Expr_is_String(expr)) {
+        printf("Expr.String(");
+        print_str(Expr_get_String(expr).f0);
+        printf(")");
+    } else {
+        Expr_Concat concat = Expr_get_Concat(expr);
+
+        printf("Expr.Concat(");
+        print_expr(concat.f0);
+        printf(", ");
+        print_expr(concat.f1);
+        printf(")");
+    }
+}
+
+int main(void) {
+    Expr tag_union = mainForHost();
+    Expr concat = Expr_make_Concat(Expr_make_String(roc_str_from_cstr("Hello, ")), Expr_make_String(roc_str_from_cstr("World!")));
+    Expr string = Expr_make_String(roc_str_from_cstr("this is a test"));
+
+    assert(Expr_is_Concat(tag_union));
+    assert(Expr_is_String(Expr_get_Concat(tag_union).f0));
+
+    printf("tag_union was: ");
+    print_expr(tag_union);
+    printf("\n`Concat (String \"Hello, \") (String \"World!\")` is: ");
+    print_expr(concat);
+    printf("\n`String \"this is a test\"` is: ");
+    print_expr(string);
+    printf("\n");
+
+    // Decrementing the roots frees every node they refer to.
+    Expr_decref(tag_union);
+    Expr_decref(concat);
+    Expr_decref(string);
+    assert(roc_live_allocations == 0);
+
+    return 0;
+}
@@ -0,0 +1,14 @@
+#include <inttypes.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+int main(void) {
+    mainForHost_closure closure = mainForHost(42);
+
+    printf("Answer was: %" PRId64 "\n", mainForHost_closure_force_thunk(closure));
+
+    mainForHost_closure_deinit(closure);
+
+    return 0;
+}
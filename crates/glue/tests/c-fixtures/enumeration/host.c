@@ -0,0 +1,30 @@
+#include <assert.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+static const char *my_enum_name(MyEnum tag) {
+    switch (tag) {
+    case MyEnum_Bar:
+        return "MyEnum.Bar";
+    case MyEnum_Baz:
+        return "MyEnum.Baz";
+    case MyEnum_Foo:
+        return "MyEnum.Foo";
+    default:
+        return "unknown";
+    }
+}
+
+int main(void) {
+    MyEnum tag_union = mainForHost();
+
+    assert(tag_union == MyEnum_Foo);
+
+    printf("tag_union was: %s, Bar is: %s, Baz is: %s\n",
+           my_enum_name(tag_union),
+           my_enum_name(MyEnum_Bar),
+           my_enum_name(MyEnum_Baz));
+
+    return 0;
+}
@@ -0,0 +1,22 @@
+#include <assert.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+int main(void) {
+    Outer outer = mainForHost();
+    const uint8_t *z = (const uint8_t *)outer.z.elements;
+
+    assert(outer.x.a == 5);
+    assert(roc_list_len(outer.z) == 2 && z[0] == 1 && z[1] == 2);
+
+    printf("Record was: Outer { y: \"%.*s\", z: [%u, %u], x: Inner { b: %g, a: %u } }\n",
+           (int)roc_str_len(&outer.y), roc_str_bytes(&outer.y),
+           z[0], z[1],
+           outer.x.b, outer.x.a);
+
+    roc_str_decref(outer.y);
+    roc_list_decref(outer.z, ROC_ALIGNOF(uint8_t));
+
+    return 0;
+}
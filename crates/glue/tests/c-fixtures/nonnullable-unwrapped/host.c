@@ -0,0 +1,41 @@
+#include <assert.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+static void print_rose_tree(StrRoseTree tree) {
+    StrRoseTree_Tree node = StrRoseTree_get_Tree(tree);
+    const StrRoseTree *children = (const StrRoseTree *)node.f1.elements;
+
+    printf("StrRoseTree.Tree(\"%.*s\", [", (int)roc_str_len(&node.f0), roc_str_bytes(&node.f0));
+
+    for (size_t index = 0; index < roc_list_len(node.f1); index++) {
+        if (index > 0) {
+            printf(", ");
+        }
+
+        print_rose_tree(children[index]);
+    }
+
+    printf("])");
+}
+
+int main(void) {
+    StrRoseTree tag_union = mainForHost();
+    StrRoseTree leaf = StrRoseTree_make_Tree(roc_str_from_cstr("foo"), roc_list_empty());
+
+    assert(roc_list_len(StrRoseTree_get_Tree(tag_union).f1) == 2);
+
+    printf("tag_union was: ");
+    print_rose_tree(tag_union);
+    printf("\nTree \"foo\" [] is: ");
+    print_rose_tree(leaf);
+    printf("\n");
+
+    // Decrementing the root frees its children, and the list they're in.
+    StrRoseTree_decref(tag_union);
+    StrRoseTree_decref(leaf);
+    assert(roc_live_allocations == 0);
+
+    return 0;
+}
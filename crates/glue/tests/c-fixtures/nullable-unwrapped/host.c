@@ -0,0 +1,34 @@
+#include <assert.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+static void print_cons_list(StrConsList list) {
+    if (StrConsList_is_Nil(list)) {
+        printf("StrConsList.Nil");
+    } else {
+        StrConsList_Cons cons = StrConsList_get_Cons(list);
+
+        printf("StrConsList.Cons(\"%.*s\", ", (int)roc_str_len(&cons.f0), roc_str_bytes(&cons.f0));
+        print_cons_list(cons.f1);
+        printf(")");
+    }
+}
+
+int main(void) {
+    StrConsList tag_union = mainForHost();
+
+    assert(StrConsList_is_Cons(tag_union));
+    assert(StrConsList_is_Cons(StrConsList_get_Cons(tag_union).f1));
+    assert(StrConsList_discriminant(StrConsList_make_Nil()) == discriminant_StrConsList_Nil);
+
+    printf("tag_union was: ");
+    print_cons_list(tag_union);
+    printf("\n`Cons \"small str\" Nil` is: ");
+    print_cons_list(StrConsList_make_Cons(roc_str_from_cstr("small str"), StrConsList_make_Nil()));
+    printf("\n`Nil` is: ");
+    print_cons_list(StrConsList_make_Nil());
+    printf("\n");
+
+    return 0;
+}
@@ -0,0 +1,53 @@
+#include <assert.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+static void print_str(RocStr str) {
+    printf("\"%.*s\"", (int)roc_str_len(&str), roc_str_bytes(&str));
+}
+
+static void print_finger_tree(StrFingerTree tree) {
+    switch (StrFingerTree_discriminant(tree)) {
+    case discriminant_StrFingerTree_Empty:
+        printf("StrFingerTree.Empty");
+        break;
+    case discriminant_StrFingerTree_More: {
+        StrFingerTree_More more = StrFingerTree_get_More(tree);
+
+        printf("StrFingerTree.More(");
+        print_str(more.f0);
+        printf(", ");
+        print_finger_tree(more.f1);
+        printf(")");
+        break;
+    }
+    case discriminant_StrFingerTree_Single:
+        printf("StrFingerTree.Single(");
+        print_str(StrFingerTree_get_Single(tree).f0);
+        printf(")");
+        break;
+    }
+}
+
+int main(void) {
+    StrFingerTree tag_union = mainForHost();
+
+    assert(StrFingerTree_is_More(tag_union));
+    assert(StrFingerTree_is_Empty(StrFingerTree_make_Empty()));
+    assert(StrFingerTree_discriminant(StrFingerTree_make_Single(roc_str_from_cstr("foo"))) == discriminant_StrFingerTree_Single);
+
+    printf("tag_union was: ");
+    print_finger_tree(tag_union);
+    printf("\n`More \"small str\" (Single \"other str\")` is: ");
+    print_finger_tree(StrFingerTree_make_More(roc_str_from_cstr("small str"), StrFingerTree_make_Single(roc_str_from_cstr("other str"))));
+    printf("\n`More \"small str\" Empty` is: ");
+    print_finger_tree(StrFingerTree_make_More(roc_str_from_cstr("small str"), StrFingerTree_make_Empty()));
+    printf("\n`Single \"small str\"` is: ");
+    print_finger_tree(StrFingerTree_make_Single(roc_str_from_cstr("small str")));
+    printf("\n`Empty` is: ");
+    print_finger_tree(StrFingerTree_make_Empty());
+    printf("\n");
+
+    return 0;
+}
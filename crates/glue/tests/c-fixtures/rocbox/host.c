@@ -0,0 +1,21 @@
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "roc_externs.h"
+
+int main(void) {
+    RocBox box = mainForHost(roc_str_from_cstr("World"));
+    RocStr *str = (RocStr *)box.contents;
+
+    assert(roc_str_len(str) == 12 && memcmp(roc_str_bytes(str), "Hello World!", 12) == 0);
+
+    printf("Answer was: Box(\"%.*s\")\n", (int)roc_str_len(str), roc_str_bytes(str));
+
+    if (roc_pointer_is_unique(box.contents)) {
+        roc_str_decref(*str);
+    }
+    roc_box_decref(box, ROC_ALIGNOF(RocStr));
+
+    return 0;
+}
@@ -0,0 +1,10 @@
+app "app"
+    packages { pf: "platform.roc" }
+    imports []
+    provides [main] to pf
+
+main : Dict Str I64
+main =
+    Dict.empty {}
+    |> Dict.insert "foo" 1
+    |> Dict.insert "bar" 2
@@ -0,0 +1,40 @@
+#include <assert.h>
+#include <inttypes.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "roc_externs.h"
+
+// A key-value pair of a `Dict Str I64`. Roc puts the more aligned element of a tuple first,
+// which is the key as long as an int64_t isn't more aligned than a pointer.
+typedef struct Pair {
+    RocStr key;
+    int64_t value;
+} Pair;
+
+ROC_STATIC_ASSERT(ROC_ALIGNOF(int64_t) <= ROC_ALIGNOF(RocStr), "the value of a Pair would come first");
+
+int main(void) {
+    RocDict dict = mainForHost();
+    Pair *pairs = (Pair *)dict.data.elements;
+    size_t len = roc_list_len(dict.data);
+
+    assert(len == 2);
+    assert(roc_str_len(&pairs[0].key) == 3 && memcmp(roc_str_bytes(&pairs[0].key), "foo", 3) == 0);
+    assert(pairs[1].value == 2);
+
+    printf("Answer was: {");
+    for (size_t i = 0; i < len; i++) {
+        printf("%s\"%.*s\": %" PRId64, i == 0 ? "" : ", ", (int)roc_str_len(&pairs[i].key), roc_str_bytes(&pairs[i].key), pairs[i].value);
+    }
+    printf("}\n");
+
+    for (size_t i = 0; i < len; i++) {
+        roc_str_decref(pairs[i].key);
+    }
+    roc_list_decref(dict.data, ROC_ALIGNOF(Pair));
+    roc_list_decref(dict.data_indices, ROC_ALIGNOF(size_t));
+    roc_list_decref(dict.metadata, 1);
+
+    return 0;
+}
@@ -0,0 +1,9 @@
+platform "test-platform"
+    requires {} { main : Dict Str I64 }
+    exposes []
+    packages {}
+    imports []
+    provides [mainForHost]
+
+mainForHost : Dict Str I64
+mainForHost = main
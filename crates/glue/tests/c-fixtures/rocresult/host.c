@@ -0,0 +1,23 @@
+#include <assert.h>
+#include <inttypes.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+int main(void) {
+    RocResult_RocStr_int32_t string = mainForHost(true);
+
+    assert(string.tag == RocResult_Ok);
+
+    printf("Answer was: Ok(\"%.*s\")\n", (int)roc_str_len(&string.payload.ok), roc_str_bytes(&string.payload.ok));
+
+    roc_str_decref(string.payload.ok);
+
+    RocResult_RocStr_int32_t integer = mainForHost(false);
+
+    assert(integer.tag == RocResult_Err);
+
+    printf("Answer was: Err(%" PRId32 ")\n", integer.payload.err);
+
+    return 0;
+}
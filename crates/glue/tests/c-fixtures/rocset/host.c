@@ -0,0 +1,26 @@
+#include <assert.h>
+#include <inttypes.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+int main(void) {
+    RocSet set = mainForHost();
+    // The values of a `Set`'s dict are all `{}`, so each of its elements is just the key.
+    int64_t *elements = (int64_t *)set.dict.data.elements;
+    size_t len = roc_list_len(set.dict.data);
+
+    assert(len == 3);
+
+    printf("Answer was: {");
+    for (size_t i = 0; i < len; i++) {
+        printf("%s%" PRId64, i == 0 ? "" : ", ", elements[i]);
+    }
+    printf("}\n");
+
+    roc_list_decref(set.dict.data, ROC_ALIGNOF(int64_t));
+    roc_list_decref(set.dict.data_indices, ROC_ALIGNOF(size_t));
+    roc_list_decref(set.dict.metadata, 1);
+
+    return 0;
+}
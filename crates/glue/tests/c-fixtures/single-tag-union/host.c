@@ -0,0 +1,12 @@
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+int main(void) {
+    // A union with a single tag and no payload is zero-sized, so there's nothing to return.
+    mainForHost();
+
+    printf("tag_union was: SingleTagUnion.OneTag\n");
+
+    return 0;
+}
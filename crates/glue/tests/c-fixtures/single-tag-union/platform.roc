@@ -0,0 +1,11 @@
+platform "test-platform"
+    requires {} { main : _ }
+    exposes []
+    packages {}
+    imports []
+    provides [mainForHost]
+
+SingleTagUnion : [OneTag]
+
+mainForHost : SingleTagUnion
+mainForHost = main
@@ -0,0 +1,53 @@
+#include <assert.h>
+#include <inttypes.h>
+#include <stdio.h>
+
+#include "roc_externs.h"
+
+static void print_non_recursive(NonRecursive tag_union) {
+    switch (NonRecursive_discriminant(tag_union)) {
+    case discriminant_NonRecursive_Bar:
+        printf("NonRecursive.Bar(%" PRIu64 ")", NonRecursive_get_Bar(tag_union).lower);
+        break;
+    case discriminant_NonRecursive_Baz:
+        printf("NonRecursive.Baz");
+        break;
+    case discriminant_NonRecursive_Blah:
+        printf("NonRecursive.Blah(%" PRId32 ")", NonRecursive_get_Blah(tag_union));
+        break;
+    case discriminant_NonRecursive_Foo: {
+        RocStr str = NonRecursive_get_Foo(tag_union);
+
+        printf("NonRecursive.Foo(\"%.*s\")", (int)roc_str_len(&str), roc_str_bytes(&str));
+        break;
+    }
+    }
+}
+
+int main(void) {
+    NonRecursive tag_union = mainForHost();
+
+    assert(NonRecursive_discriminant(tag_union) == discriminant_NonRecursive_Foo);
+
+    NonRecursive long_str = NonRecursive_make_Foo(roc_str_from_cstr("A long enough string to not be small"));
+    RocU128 bar = { 123, 0 };
+
+    printf("tag_union was: ");
+    print_non_recursive(tag_union);
+    printf("\n`Foo \"small str\"` is: ");
+    print_non_recursive(NonRecursive_make_Foo(roc_str_from_cstr("small str")));
+    printf("\n`Foo \"A long enough string to not be small\"` is: ");
+    print_non_recursive(long_str);
+    printf("\n`Bar 123` is: ");
+    print_non_recursive(NonRecursive_make_Bar(bar));
+    printf("\n`Baz` is: ");
+    print_non_recursive(NonRecursive_make_Baz());
+    printf("\n`Blah 456` is: ");
+    print_non_recursive(NonRecursive_make_Blah(456));
+    printf("\n");
+
+    roc_str_decref(NonRecursive_get_Foo(tag_union));
+    roc_str_decref(NonRecursive_get_Foo(long_str));
+
+    return 0;
+}
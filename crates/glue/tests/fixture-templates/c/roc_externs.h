@@ -0,0 +1,73 @@
+// ⚠️ READ THIS BEFORE MODIFYING THIS FILE! ⚠️
+//
+// This file is a fixture template. If the file you're looking at is
+// in the fixture-templates/ directory, then you're all set - go ahead
+// and modify it, and it will modify all the fixture tests.
+//
+// If this file is in the c-fixtures/ directory, on the other hand, then
+// it is gitignored and will be overwritten the next time tests run.
+// So you probably don't want to modify it by hand! Instead, modify the
+// file with the same name in the fixture-templates/ directory.
+
+// Externs required by roc_app.h and by the Roc app
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_glue/roc_app.h"
+
+// How many allocations haven't been freed yet, so that fixtures can check for leaks.
+static long roc_live_allocations = 0;
+
+void *roc_alloc(size_t size, uint32_t alignment) {
+    (void)alignment;
+
+    roc_live_allocations++;
+
+    return malloc(size);
+}
+
+void *roc_realloc(void *ptr, size_t new_size, size_t old_size, uint32_t alignment) {
+    (void)old_size;
+    (void)alignment;
+
+    return realloc(ptr, new_size);
+}
+
+void roc_dealloc(void *ptr, uint32_t alignment) {
+    (void)alignment;
+
+    roc_live_allocations--;
+
+    free(ptr);
+}
+
+void roc_panic(RocStr *msg, uint32_t tag_id) {
+    int length = (int)roc_str_len(msg);
+    const char *bytes = roc_str_bytes(msg);
+
+    switch (tag_id) {
+    case 0:
+        fprintf(stderr, "Roc standard library hit a panic: %.*s\n", length, bytes);
+        break;
+    case 1:
+        fprintf(stderr, "Application hit a panic: %.*s\n", length, bytes);
+        break;
+    default:
+        abort();
+    }
+
+    exit(1);
+}
+
+void roc_dbg(RocStr *loc, RocStr *msg, RocStr *src) {
+    fprintf(stderr, "[%.*s] %.*s = %.*s\n",
+            (int)roc_str_len(loc), roc_str_bytes(loc),
+            (int)roc_str_len(src), roc_str_bytes(src),
+            (int)roc_str_len(msg), roc_str_bytes(msg));
+}
+
+void *roc_memset(void *dst, int value, size_t size) {
+    return memset(dst, value, size);
+}
@@ -15,6 +15,34 @@ pub fn fixtures_dir(dir_name: &str) -> PathBuf {
     path
 }
 
+#[allow(dead_code)]
+pub fn zig_fixtures_dir(dir_name: &str) -> PathBuf {
+    let mut path = root_dir();
+
+    // Descend into cli/tests/zig-fixtures/{dir_name}
+    path.push("crates");
+    path.push("glue");
+    path.push("tests");
+    path.push("zig-fixtures");
+    path.push(dir_name);
+
+    path
+}
+
+#[allow(dead_code)]
+pub fn c_fixtures_dir(dir_name: &str) -> PathBuf {
+    let mut path = root_dir();
+
+    // Descend into cli/tests/c-fixtures/{dir_name}
+    path.push("crates");
+    path.push("glue");
+    path.push("tests");
+    path.push("c-fixtures");
+    path.push(dir_name);
+
+    path
+}
+
 #[allow(dead_code)]
 pub fn root_dir() -> PathBuf {
     let mut path = env::current_exe().ok().unwrap();
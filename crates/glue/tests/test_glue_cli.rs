@@ -11,7 +11,6 @@ mod helpers;
 
 #[cfg(test)]
 mod glue_cli_run {
-    use crate::helpers::fixtures_dir;
     use cli_utils::helpers::{has_error, run_glue, run_roc, Out};
     use std::fs;
     use std::path::Path;
@@ -20,62 +19,87 @@ mod glue_cli_run {
     ///
     /// First, it generates and runs a separate test for each of the given
     /// expected stdout endings. Each of these should test a particular .roc file
-    /// in the fixtures/ directory. The fixtures themselves run assertions too, but
-    /// the stdout check verifies that we're actually running the code we think we are;
-    /// without it, it would be possible that the fixtures are just exiting without running
-    /// any assertions, and we would have no way to find out!
+    /// in the directory which `$fixtures_dir` finds, using a host written in the
+    /// language of `$template` and glue generated with `$glue_spec`. The fixtures
+    /// themselves run assertions too, but the stdout check verifies that we're actually
+    /// running the code we think we are; without it, it would be possible that the fixtures
+    /// are just exiting without running any assertions, and we would have no way to find out!
     ///
     /// Second, this generates an extra test which (non-recursively) traverses the
-    /// fixtures/ directory and verifies that each of the .roc files in there
+    /// fixtures directory and verifies that each of the .roc files in there
     /// has had a corresponding test generated in the previous step. This test
-    /// will fail if we ever add a new .roc file to fixtures/ and forget to
+    /// will fail if we ever add a new .roc file to the fixtures and forget to
     /// add a test for it here!
-    macro_rules! fixtures {
-        ($($test_name:ident:$fixture_dir:expr => $ends_with:expr,)+) => {
-            $(
-                #[test]
-                #[allow(non_snake_case)]
-                fn $test_name() {
-                    let dir = fixtures_dir($fixture_dir);
-
-                    generate_glue_for(&dir, std::iter::empty());
-
-                    let test_name_str = stringify!($test_name);
-
-                    // TODO after #5924 is fixed; remove this if
-                    if !(cfg!(target_os = "linux") && (test_name_str == "nullable_unwrapped" || test_name_str == "nullable_wrapped")) {
-                        let out = run_app(&dir.join("app.roc"), std::iter::empty());
-
-                        assert!(out.status.success());
-                        let ignorable = "🔨 Rebuilding platform...\n";
-                        let stderr = out.stderr.replacen(ignorable, "", 1);
-                        assert_eq!(stderr, "");
-                        assert!(
-                            out.stdout.ends_with($ends_with),
-                            "Unexpected stdout ending\n\n  expected:\n\n    {}\n\n  but stdout was:\n\n    {}",
-                            $ends_with,
-                            out.stdout
-                        );
+    ///
+    /// The tests go in a module of their own, so that each host language can have a test
+    /// of the same name.
+    macro_rules! host_fixtures {
+        ($module:ident, $fixtures_dir:ident, $template:expr, $glue_spec:expr, { $($test_name:ident:$fixture_dir:expr => $ends_with:expr,)+ }) => {
+            mod $module {
+                use super::{check_for_tests, generate_glue_for, run_app};
+                use crate::helpers::$fixtures_dir;
+
+                $(
+                    #[test]
+                    #[allow(non_snake_case)]
+                    fn $test_name() {
+                        let dir = $fixtures_dir($fixture_dir);
+
+                        generate_glue_for(&dir, $template, $glue_spec, std::iter::empty());
+
+                        let test_name_str = stringify!($test_name);
+
+                        // TODO after #5924 is fixed; remove this if
+                        if !($template == "rust" && cfg!(target_os = "linux") && (test_name_str == "nullable_unwrapped" || test_name_str == "nullable_wrapped")) {
+                            let out = run_app(&dir.join("app.roc"), std::iter::empty());
+
+                            assert!(out.status.success());
+                            let ignorable = "🔨 Rebuilding platform...\n";
+                            let stderr = out.stderr.replacen(ignorable, "", 1);
+                            assert_eq!(stderr, "");
+                            assert!(
+                                out.stdout.ends_with($ends_with),
+                                "Unexpected stdout ending\n\n  expected:\n\n    {}\n\n  but stdout was:\n\n    {}",
+                                $ends_with,
+                                out.stdout
+                            );
+                        }
                     }
-                }
-            )*
+                )*
 
-            #[test]
-            #[ignore]
-            fn all_fixtures_have_tests() {
-                use roc_collections::VecSet;
+                #[test]
+                #[ignore]
+                fn all_fixtures_have_tests() {
+                    use roc_collections::VecSet;
 
-                let mut all_fixtures: VecSet<String> = VecSet::default();
+                    let mut all_fixtures: VecSet<String> = VecSet::default();
 
-                $(
-                    all_fixtures.insert($fixture_dir.to_string());
-                )*
+                    $(
+                        all_fixtures.insert($fixture_dir.to_string());
+                    )*
 
-                check_for_tests(&mut all_fixtures);
+                    check_for_tests(&$fixtures_dir(""), &mut all_fixtures);
+                }
             }
         }
     }
 
+    /// `host_fixtures!` for the Rust hosts in the fixtures/ directory.
+    macro_rules! fixtures {
+        ($($tests:tt)+) => {
+            host_fixtures!(rust, fixtures_dir, "rust", "RustGlue.roc", { $($tests)+ });
+        }
+    }
+
+    /// `host_fixtures!` for the Zig hosts in zig-fixtures/ and the C hosts in c-fixtures/,
+    /// which print the same thing, so that they can't drift apart.
+    macro_rules! zig_and_c_fixtures {
+        ($($tests:tt)+) => {
+            host_fixtures!(zig, zig_fixtures_dir, "zig", "ZigGlue.roc", { $($tests)+ });
+            host_fixtures!(c, c_fixtures_dir, "c", "CGlue.roc", { $($tests)+ });
+        }
+    }
+
     fixtures! {
         basic_record:"basic-record" => "Record was: MyRcd { b: 42, a: 1995 }\n",
         nested_record:"nested-record" => "Record was: Outer { y: \"foo\", z: [1, 2], x: Inner { b: 24.0, a: 5 } }\n",
@@ -144,11 +168,74 @@ mod glue_cli_run {
         "#),
     }
 
-    fn check_for_tests(all_fixtures: &mut roc_collections::VecSet<String>) {
+    zig_and_c_fixtures! {
+        basic_record:"basic-record" => "Record was: MyRcd { b: 42, a: 1995 }\n",
+        nested_record:"nested-record" => "Record was: Outer { y: \"foo\", z: [1, 2], x: Inner { b: 24, a: 5 } }\n",
+        enumeration:"enumeration" => "tag_union was: MyEnum.Foo, Bar is: MyEnum.Bar, Baz is: MyEnum.Baz\n",
+        single_tag_union:"single-tag-union" => indoc!(r#"
+            tag_union was: SingleTagUnion.OneTag
+        "#),
+        union_with_padding:"union-with-padding" => indoc!(r#"
+            tag_union was: NonRecursive.Foo("This is a test")
+            `Foo "small str"` is: NonRecursive.Foo("small str")
+            `Foo "A long enough string to not be small"` is: NonRecursive.Foo("A long enough string to not be small")
+            `Bar 123` is: NonRecursive.Bar(123)
+            `Baz` is: NonRecursive.Baz
+            `Blah 456` is: NonRecursive.Blah(456)
+        "#),
+        union_without_padding:"union-without-padding" => indoc!(r#"
+            tag_union was: NonRecursive.Foo("This is a test")
+            `Foo "small str"` is: NonRecursive.Foo("small str")
+            `Bar 123` is: NonRecursive.Bar(123)
+            `Baz` is: NonRecursive.Baz
+            `Blah 456` is: NonRecursive.Blah(456)
+        "#),
+        nullable_wrapped:"nullable-wrapped" => indoc!(r#"
+            tag_union was: StrFingerTree.More("foo", StrFingerTree.More("bar", StrFingerTree.Empty))
+            `More "small str" (Single "other str")` is: StrFingerTree.More("small str", StrFingerTree.Single("other str"))
+            `More "small str" Empty` is: StrFingerTree.More("small str", StrFingerTree.Empty)
+            `Single "small str"` is: StrFingerTree.Single("small str")
+            `Empty` is: StrFingerTree.Empty
+        "#),
+        nullable_unwrapped:"nullable-unwrapped" => indoc!(r#"
+            tag_union was: StrConsList.Cons("World!", StrConsList.Cons("Hello ", StrConsList.Nil))
+            `Cons "small str" Nil` is: StrConsList.Cons("small str", StrConsList.Nil)
+            `Nil` is: StrConsList.Nil
+        "#),
+        nonnullable_unwrapped:"nonnullable-unwrapped" => indoc!(r#"
+            tag_union was: StrRoseTree.Tree("root", [StrRoseTree.Tree("leaf1", []), StrRoseTree.Tree("leaf2", [])])
+            Tree "foo" [] is: StrRoseTree.Tree("foo", [])
+        "#),
+        basic_recursive_union:"basic-recursive-union" => indoc!(r#"
+            tag_union was: Expr.Concat(Expr.String("Hello, "), Expr.String("World!"))
+            `Concat (String "Hello, ") (String "World!")` is: Expr.Concat(Expr.String("Hello, "), Expr.String("World!"))
+            `String "this is a test"` is: Expr.String("this is a test")
+        "#),
+        arguments:"arguments" => indoc!(r#"
+            Answer was: 84
+        "#),
+        closures:"closures" => indoc!(r#"
+            Answer was: 672
+        "#),
+        rocresult:"rocresult" => indoc!(r#"
+            Answer was: Ok("Hello World!")
+            Answer was: Err(42)
+        "#),
+        rocdict:"rocdict" => indoc!(r#"
+            Answer was: {"foo": 1, "bar": 2}
+        "#),
+        rocset:"rocset" => indoc!(r#"
+            Answer was: {1, 2, 3}
+        "#),
+        rocbox:"rocbox" => indoc!(r#"
+            Answer was: Box("Hello World!")
+        "#),
+    }
+
+    fn check_for_tests(fixtures: &Path, all_fixtures: &mut roc_collections::VecSet<String>) {
         use roc_collections::VecSet;
 
-        let fixtures = fixtures_dir("");
-        let entries = std::fs::read_dir(fixtures.as_path()).unwrap_or_else(|err| {
+        let entries = std::fs::read_dir(fixtures).unwrap_or_else(|err| {
             panic!(
                 "Error trying to read {} as a fixtures directory: {}",
                 fixtures.to_string_lossy(),
@@ -176,6 +263,8 @@ mod glue_cli_run {
 
     fn generate_glue_for<'a, I: IntoIterator<Item = &'a str>>(
         platform_dir: &'a Path,
+        template: &str,
+        glue_spec_file: &str,
         args: I,
     ) -> Out {
         let platform_module_path = platform_dir.join("platform.roc");
@@ -187,8 +276,8 @@ mod glue_cli_run {
             .unwrap()
             .join("fixture-templates");
 
-        // Copy the rust template from the templates directory into the fixture dir.
-        dircpy::CopyBuilder::new(fixture_templates_dir.join("rust"), platform_dir)
+        // Copy the template for the host's language from the templates directory into the fixture dir.
+        dircpy::CopyBuilder::new(fixture_templates_dir.join(template), platform_dir)
             .overwrite(true) // overwrite any files that were already present
             .run()
             .unwrap();
@@ -199,20 +288,20 @@ mod glue_cli_run {
                 .expect("Unable to remove test_glue dir in order to regenerate it in the test");
         }
 
-        let rust_glue_spec = fixture_templates_dir
+        let glue_spec = fixture_templates_dir
             .parent()
             .unwrap()
             .parent()
             .unwrap()
             .join("src")
-            .join("RustGlue.roc");
+            .join(glue_spec_file);
 
         // Generate a fresh test_glue for this platform
         let glue_out = run_glue(
             // converting these all to String avoids lifetime issues
             std::iter::once("glue".to_string()).chain(
                 args.into_iter().map(|arg| arg.to_string()).chain([
-                    rust_glue_spec.to_str().unwrap().to_string(),
+                    glue_spec.to_str().unwrap().to_string(),
                     glue_dir.to_str().unwrap().to_string(),
                     platform_module_path.to_str().unwrap().to_string(),
                 ]),
@@ -0,0 +1,17 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const answer = roc_app.mainForHost(42);
+
+    stdout.print("Answer was: {}\n", .{answer}) catch unreachable;
+
+    return 0;
+}
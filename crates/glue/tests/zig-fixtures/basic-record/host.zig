@@ -0,0 +1,20 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const record = roc_app.mainForHost();
+
+    std.debug.assert(record.a == 1995);
+    std.debug.assert(record.b == 42);
+
+    stdout.print("Record was: {}\n", .{record}) catch unreachable;
+
+    return 0;
+}
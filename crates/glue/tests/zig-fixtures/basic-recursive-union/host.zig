@@ -0,0 +1,32 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+const Expr = roc_app.Expr;
+const RocStr = roc_app.roc_std.RocStr;
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const tag_union = roc_app.mainForHost();
+
+    std.debug.assert(tag_union.is_Concat());
+    std.debug.assert(tag_union.get_Concat().f0.is_String());
+
+    stdout.print(
+        \\tag_union was: {}
+        \\`Concat (String "Hello, ") (String "World!")` is: {}
+        \\`String "this is a test"` is: {}
+        \\
+    , .{
+        tag_union,
+        Expr.Concat(Expr.String(RocStr.fromSlice("Hello, ")), Expr.String(RocStr.fromSlice("World!"))),
+        Expr.String(RocStr.fromSlice("this is a test")),
+    }) catch unreachable;
+
+    return 0;
+}
@@ -0,0 +1,18 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const closure = roc_app.mainForHost(42);
+    defer closure.deinit();
+
+    stdout.print("Answer was: {}\n", .{closure.force_thunk()}) catch unreachable;
+
+    return 0;
+}
@@ -0,0 +1,23 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const tag_union = roc_app.mainForHost();
+
+    std.debug.assert(tag_union == .Foo);
+
+    stdout.print("tag_union was: {}, Bar is: {}, Baz is: {}\n", .{
+        tag_union,
+        roc_app.MyEnum.Bar,
+        roc_app.MyEnum.Baz,
+    }) catch unreachable;
+
+    return 0;
+}
@@ -0,0 +1,6 @@
+app "app"
+    packages { pf: "platform.roc" }
+    imports []
+    provides [main] to pf
+
+main = { x: { a: 5, b: 24 }, y: "foo", z: [1, 2] }
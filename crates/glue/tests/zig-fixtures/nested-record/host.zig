@@ -0,0 +1,22 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const outer = roc_app.mainForHost();
+    defer outer.y.decref();
+    defer outer.z.decref();
+
+    std.debug.assert(outer.x.a == 5);
+    std.debug.assert(std.mem.eql(u8, outer.z.items(), &[_]u8{ 1, 2 }));
+
+    stdout.print("Record was: {}\n", .{outer}) catch unreachable;
+
+    return 0;
+}
@@ -0,0 +1,30 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+const StrRoseTree = roc_app.StrRoseTree;
+const RocList = roc_app.roc_std.RocList;
+const RocStr = roc_app.roc_std.RocStr;
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const tag_union = roc_app.mainForHost();
+
+    std.debug.assert(tag_union.get_Tree().f1.len() == 2);
+
+    stdout.print(
+        \\tag_union was: {}
+        \\Tree "foo" [] is: {}
+        \\
+    , .{
+        tag_union,
+        StrRoseTree.Tree(RocStr.fromSlice("foo"), RocList(StrRoseTree).empty()),
+    }) catch unreachable;
+
+    return 0;
+}
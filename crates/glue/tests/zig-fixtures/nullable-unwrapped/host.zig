@@ -0,0 +1,33 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+const StrConsList = roc_app.StrConsList;
+const RocStr = roc_app.roc_std.RocStr;
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const tag_union = roc_app.mainForHost();
+
+    std.debug.assert(tag_union.is_Cons());
+    std.debug.assert(tag_union.get_Cons().f1.is_Cons());
+    std.debug.assert(StrConsList.Nil().discriminant() == .Nil);
+
+    stdout.print(
+        \\tag_union was: {}
+        \\`Cons "small str" Nil` is: {}
+        \\`Nil` is: {}
+        \\
+    , .{
+        tag_union,
+        StrConsList.Cons(RocStr.fromSlice("small str"), StrConsList.Nil()),
+        StrConsList.Nil(),
+    }) catch unreachable;
+
+    return 0;
+}
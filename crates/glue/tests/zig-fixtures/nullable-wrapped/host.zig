@@ -0,0 +1,37 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+const StrFingerTree = roc_app.StrFingerTree;
+const RocStr = roc_app.roc_std.RocStr;
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const tag_union = roc_app.mainForHost();
+
+    std.debug.assert(tag_union.is_More());
+    std.debug.assert(StrFingerTree.Empty().is_Empty());
+    std.debug.assert(StrFingerTree.Single(RocStr.fromSlice("foo")).discriminant() == .Single);
+
+    stdout.print(
+        \\tag_union was: {}
+        \\`More "small str" (Single "other str")` is: {}
+        \\`More "small str" Empty` is: {}
+        \\`Single "small str"` is: {}
+        \\`Empty` is: {}
+        \\
+    , .{
+        tag_union,
+        StrFingerTree.More(RocStr.fromSlice("small str"), StrFingerTree.Single(RocStr.fromSlice("other str"))),
+        StrFingerTree.More(RocStr.fromSlice("small str"), StrFingerTree.Empty()),
+        StrFingerTree.Single(RocStr.fromSlice("small str")),
+        StrFingerTree.Empty(),
+    }) catch unreachable;
+
+    return 0;
+}
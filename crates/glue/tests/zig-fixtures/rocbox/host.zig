@@ -0,0 +1,22 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+const RocStr = roc_app.roc_std.RocStr;
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const box = roc_app.mainForHost(RocStr.fromSlice("World"));
+    defer box.decref();
+
+    std.debug.assert(std.mem.eql(u8, box.get().asSlice(), "Hello World!"));
+
+    stdout.print("Answer was: {}\n", .{box}) catch unreachable;
+
+    return 0;
+}
@@ -0,0 +1,9 @@
+platform "test-platform"
+    requires {} { main : Str -> Box Str }
+    exposes []
+    packages {}
+    imports []
+    provides [mainForHost]
+
+mainForHost : Str -> Box Str
+mainForHost = \name -> main name
@@ -0,0 +1,22 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const dict = roc_app.mainForHost();
+    defer dict.decref();
+
+    std.debug.assert(dict.len() == 2);
+    std.debug.assert(std.mem.eql(u8, dict.items()[0].key.asSlice(), "foo"));
+    std.debug.assert(dict.items()[1].value == 2);
+
+    stdout.print("Answer was: {}\n", .{dict}) catch unreachable;
+
+    return 0;
+}
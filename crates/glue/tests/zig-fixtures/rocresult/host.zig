@@ -0,0 +1,26 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const string = roc_app.mainForHost(true);
+    defer string.payload.ok.decref();
+
+    std.debug.assert(string.isOk());
+
+    stdout.print("Answer was: {}\n", .{string}) catch unreachable;
+
+    const integer = roc_app.mainForHost(false);
+
+    std.debug.assert(integer.isErr());
+
+    stdout.print("Answer was: {}\n", .{integer}) catch unreachable;
+
+    return 0;
+}
@@ -0,0 +1,20 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const set = roc_app.mainForHost();
+    defer set.decref();
+
+    std.debug.assert(set.len() == 3);
+
+    stdout.print("Answer was: {}\n", .{set}) catch unreachable;
+
+    return 0;
+}
@@ -0,0 +1,9 @@
+platform "test-platform"
+    requires {} { main : Set I64 }
+    exposes []
+    packages {}
+    imports []
+    provides [mainForHost]
+
+mainForHost : Set I64
+mainForHost = main
@@ -0,0 +1,41 @@
+const std = @import("std");
+const roc_app = @import("test_glue/roc_app.zig");
+
+comptime {
+    // Export roc_alloc and friends, which roc_std.zig and the Roc app call.
+    _ = @import("roc_externs.zig");
+}
+
+const NonRecursive = roc_app.NonRecursive;
+const RocStr = roc_app.roc_std.RocStr;
+
+pub fn main() u8 {
+    const stdout = std.io.getStdOut().writer();
+
+    const tag_union = roc_app.mainForHost();
+    defer tag_union.get_Foo().decref();
+
+    std.debug.assert(tag_union.discriminant() == .Foo);
+
+    const long_str = NonRecursive.Foo(RocStr.fromSlice("A long enough string to not be small"));
+    defer long_str.get_Foo().decref();
+
+    stdout.print(
+        \\tag_union was: {}
+        \\`Foo "small str"` is: {}
+        \\`Foo "A long enough string to not be small"` is: {}
+        \\`Bar 123` is: {}
+        \\`Baz` is: {}
+        \\`Blah 456` is: {}
+        \\
+    , .{
+        tag_union,
+        NonRecursive.Foo(RocStr.fromSlice("small str")),
+        long_str,
+        NonRecursive.Bar(123),
+        NonRecursive.Baz(),
+        NonRecursive.Blah(456),
+    }) catch unreachable;
+
+    return 0;
+}
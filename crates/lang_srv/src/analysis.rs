@@ -4,18 +4,31 @@ use std::{
 };
 
 use bumpalo::Bump;
-use roc_can::{abilities::AbilitiesStore, expr::Declarations};
-use roc_collections::MutMap;
-use roc_load::{CheckedModule, LoadedModule};
-use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_can::{
+    abilities::AbilitiesStore,
+    expr::{DeclarationTag, Declarations},
+    traverse::OccurrenceKind,
+};
+use roc_collections::{MutMap, MutSet};
+use roc_load::{
+    CancelToken, CheckedModule, ExecutionMode, LoadResult, LoadStart, LoadedModule, LoadingProblem,
+};
+use roc_module::{
+    ident::ModuleName,
+    symbol::{Interns, ModuleId, Symbol},
+};
 use roc_packaging::cache::{self, RocCacheDir};
-use roc_region::all::LineInfo;
+use roc_region::all::{LineInfo, Region};
 use roc_reporting::report::RocDocAllocator;
 use roc_solve_problem::TypeError;
-use roc_types::subs::Subs;
+use roc_types::subs::{Subs, Variable};
 use tower_lsp::lsp_types::{
-    Diagnostic, GotoDefinitionResponse, Hover, HoverContents, Location, MarkedString, Position,
-    Range, SemanticTokenType, SemanticTokens, SemanticTokensResult, TextEdit, Url,
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionResponse, CompletionItem,
+    CompletionItemKind, Diagnostic, DocumentSymbolResponse, FoldingRange, GotoDefinitionResponse,
+    Hover, HoverContents, InlayHint, InlayHintKind, InlayHintLabel, Location, MarkedString,
+    ParameterInformation, ParameterLabel, Position, Range, SemanticTokenType, SemanticTokens,
+    SemanticTokensResult, SignatureHelp, SignatureInformation, SymbolInformation, TextEdit, Url,
+    WorkspaceEdit,
 };
 
 use crate::convert::{
@@ -23,11 +36,36 @@ use crate::convert::{
     ToRange, ToRocPosition,
 };
 
+mod code_actions;
+mod completion;
+mod inlay_hints;
+mod outline;
 mod parse_ast;
+mod references;
 mod semantic_tokens;
+mod signature_help;
 mod tokens;
 
-use self::{parse_ast::Ast, semantic_tokens::arrange_semantic_tokens, tokens::Token};
+use self::{
+    code_actions::{
+        collection_append, collection_item_removal, indentation_of_line_at, line_start,
+        when_branches_insertion, whole_lines, QuickFix,
+    },
+    completion::{
+        field_var_at_path, make_completion_item, make_symbol_completion_item, offset_before_edit,
+        record_fields, symbols_in_scope_at,
+    },
+    inlay_hints::inlay_hint_positions,
+    outline::{document_symbols, folding_ranges, workspace_symbols},
+    parse_ast::Ast,
+    references::{identifier_region, replacement_text},
+    semantic_tokens::arrange_semantic_tokens,
+    signature_help::{argument_ranges, enclosing_call},
+    tokens::Token,
+};
+pub(crate) use completion::{builtin_completions, CompletionContext};
+pub(crate) use outline::fuzzy_match;
+pub(crate) use references::{validate_value_name, RenameError};
 pub const HIGHLIGHT_TOKENS_LEGEND: &[SemanticTokenType] = Token::LEGEND;
 
 pub(crate) struct GlobalAnalysis {
@@ -35,27 +73,44 @@ pub(crate) struct GlobalAnalysis {
 }
 
 impl GlobalAnalysis {
-    pub fn new(source_url: Url, source: String) -> GlobalAnalysis {
+    /// Loads and type checks the module graph of `source`. Modules that haven't changed since
+    /// they were last checked have their types read from roc_load's on-disk cache, so only the
+    /// changed module and the modules depending on it are solved again.
+    ///
+    /// Returns [None] if `cancel_token` was cancelled before the analysis finished.
+    pub fn new(
+        source_url: Url,
+        source: String,
+        cancel_token: CancelToken,
+    ) -> Option<GlobalAnalysis> {
         let arena = Bump::new();
 
         let fi = source_url.to_file_path().unwrap();
         let src_dir = find_src_dir(&fi).to_path_buf();
         let line_info = LineInfo::new(&source);
 
-        let loaded = roc_load::load_and_typecheck_str(
-            &arena,
-            fi,
-            &source,
-            src_dir,
-            roc_target::TargetInfo::default_x86_64(),
-            roc_load::FunctionKind::LambdaSet,
-            roc_reporting::report::RenderTarget::Generic,
-            RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
-            roc_reporting::report::DEFAULT_PALETTE,
+        let cache_dir = cache::roc_cache_dir();
+        let roc_cache_dir = RocCacheDir::Persistent(cache_dir.as_path());
+
+        let loaded = LoadStart::from_str(&arena, fi, &source, roc_cache_dir, src_dir).and_then(
+            |load_start| {
+                roc_load::load_single_threaded(
+                    &arena,
+                    load_start.with_cancel_token(cancel_token),
+                    roc_target::TargetInfo::default_x86_64(),
+                    roc_load::FunctionKind::LambdaSet,
+                    roc_reporting::report::RenderTarget::Generic,
+                    roc_reporting::report::DEFAULT_PALETTE,
+                    roc_cache_dir,
+                    ExecutionMode::Check,
+                )
+            },
         );
 
         let module = match loaded {
-            Ok(module) => module,
+            Ok(LoadResult::TypeChecked(module)) => module,
+            Ok(LoadResult::Monomorphized(_)) => unreachable!("analysis only type checks"),
+            Err(LoadingProblem::Cancelled) => return None,
             Err(problem) => {
                 let all_problems = problem
                     .into_lsp_diagnostic(&())
@@ -67,12 +122,14 @@ impl GlobalAnalysis {
                     line_info,
                     source,
                     module: None,
+                    stale_module: None,
                     diagnostics: all_problems,
+                    fixes: vec![],
                 };
 
-                return GlobalAnalysis {
+                return Some(GlobalAnalysis {
                     documents: vec![analyzed_document],
-                };
+                });
             }
         };
 
@@ -109,7 +166,7 @@ impl GlobalAnalysis {
             documents.push(builder.build_document(path, source, module_id));
         }
 
-        GlobalAnalysis { documents }
+        Some(GlobalAnalysis { documents })
     }
 }
 
@@ -117,6 +174,67 @@ fn find_src_dir(path: &Path) -> &Path {
     path.parent().unwrap_or(path)
 }
 
+/// The modules in the packages of `analyzed` that are not analyzed themselves, e.g. because
+/// nothing imports them.
+pub(crate) fn unanalyzed_package_modules<'a>(
+    analyzed: impl IntoIterator<Item = &'a AnalyzedDocument>,
+) -> Vec<PathBuf> {
+    let analyzed: Vec<&AnalyzedDocument> = analyzed.into_iter().collect();
+
+    let package_roots: MutSet<PathBuf> = analyzed
+        .iter()
+        .filter_map(|document| document.package_root())
+        .collect();
+
+    let mut modules = Vec::new();
+    for root in package_roots {
+        roc_files_in(&root, &mut modules);
+    }
+
+    modules.retain(|path| {
+        Url::from_file_path(path).map_or(false, |url| {
+            !analyzed.iter().any(|document| document.url() == &url)
+        })
+    });
+
+    modules
+}
+
+/// Adds the `.roc` files in `dir` and its subdirectories to `files`, skipping hidden ones.
+fn roc_files_in(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => roc_files_in(&path, files),
+            Ok(_) if path.extension().map_or(false, |ext| ext == "roc") => files.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// The symbols matching `query` in a module, from its parse AST alone.
+pub(crate) fn parsed_workspace_symbols(
+    url: &Url,
+    source: &str,
+    query: &str,
+) -> Vec<SymbolInformation> {
+    let arena = Bump::new();
+    let Ok(ast) = Ast::parse(&arena, source) else {
+        return vec![];
+    };
+
+    workspace_symbols(url, ast.defs(), source, &LineInfo::new(source), query)
+}
+
 fn _find_parent_git_repo(path: &Path) -> Option<&Path> {
     let mut path = path;
     loop {
@@ -148,6 +266,27 @@ fn path_to_url(path: &Path) -> Url {
     }
 }
 
+/// Prints the type of `var`, e.g. for a hover or a completion's detail.
+pub(crate) fn print_type(
+    subs: &mut Subs,
+    var: Variable,
+    interns: &Interns,
+    home: ModuleId,
+) -> String {
+    // Naming the type's variables changes subs, so undo that once it's printed.
+    let snapshot = subs.snapshot();
+    let type_str = roc_types::pretty_print::name_and_print_var(
+        var,
+        subs,
+        home,
+        interns,
+        roc_types::pretty_print::DebugPrint::NOTHING,
+    );
+    subs.rollback_to(snapshot);
+
+    type_str
+}
+
 struct RootModule {
     subs: Subs,
     abilities_store: AbilitiesStore,
@@ -195,14 +334,16 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
         };
 
         let line_info = LineInfo::new(&source);
-        let diagnostics = self.build_diagnostics(&path, &source, &line_info, module_id);
+        let (diagnostics, fixes) = self.build_diagnostics(&path, &source, &line_info, module_id);
 
         AnalyzedDocument {
             url: path_to_url(&path),
             line_info,
             source: source.into(),
             module: Some(analyzed_module),
+            stale_module: None,
             diagnostics,
+            fixes,
         }
     }
 
@@ -212,16 +353,19 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
         source: &str,
         line_info: &LineInfo,
         module_id: ModuleId,
-    ) -> Vec<Diagnostic> {
+    ) -> (Vec<Diagnostic>, Vec<(Diagnostic, QuickFix)>) {
         let lines: Vec<_> = source.lines().collect();
 
         let alloc = RocDocAllocator::new(&lines, module_id, self.interns);
+        let url = path_to_url(source_path);
 
         let mut all_problems = Vec::new();
+        let mut fixes = Vec::new();
         let fmt = ProblemFmt {
             alloc: &alloc,
             line_info,
             path: source_path,
+            url: &url,
         };
 
         let can_problems = self.can_problems.remove(&module_id).unwrap_or_default();
@@ -229,23 +373,49 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
         let type_problems = self.type_problems.remove(&module_id).unwrap_or_default();
 
         for can_problem in can_problems {
+            let fix = QuickFix::from_can_problem(&can_problem);
+
             if let Some(diag) = can_problem.into_lsp_diagnostic(&fmt) {
+                if let Some(fix) = fix {
+                    fixes.push((diag.clone(), fix));
+                }
                 all_problems.push(diag);
             }
         }
 
         for type_problem in type_problems {
+            let fix = QuickFix::from_type_problem(&type_problem, &alloc);
+
             if let Some(diag) = type_problem.into_lsp_diagnostic(&fmt) {
+                if let Some(fix) = fix {
+                    fixes.push((diag.clone(), fix));
+                }
                 all_problems.push(diag);
             }
         }
 
-        all_problems
+        (all_problems, fixes)
     }
 }
 
 type ModuleIdToUrl = HashMap<ModuleId, Url>;
 
+/// Identifies a symbol independently of any one analysis, whose [ModuleId]s may differ from
+/// those of other analyses.
+#[derive(Debug)]
+pub(crate) struct GlobalSymbol {
+    /// The symbol as resolved in the document the request was made in.
+    symbol: Symbol,
+    origin_url: Url,
+    /// The document defining the symbol.
+    module_url: Url,
+    module_name: String,
+    name: String,
+    /// Top-level symbols may be referenced from other modules; anything else is local to the
+    /// document it is defined in.
+    is_top_level: bool,
+}
+
 #[derive(Debug)]
 struct AnalyzedModule {
     module_id: ModuleId,
@@ -258,13 +428,26 @@ struct AnalyzedModule {
     module_id_to_url: ModuleIdToUrl,
 }
 
+/// An analysis of an earlier version of a document, along with the source it analyzed so that
+/// positions in the current source can be mapped to it.
+#[derive(Debug)]
+struct StaleModule {
+    module: AnalyzedModule,
+    source: String,
+}
+
 #[derive(Debug)]
 pub(crate) struct AnalyzedDocument {
     url: Url,
     line_info: LineInfo,
     source: String,
     module: Option<AnalyzedModule>,
+    /// The analysis of the most recent version of this document that loaded successfully, kept
+    /// around for completion while the current source (e.g. `foo.`) does not.
+    stale_module: Option<StaleModule>,
     diagnostics: Vec<Diagnostic>,
+    /// Quick fixes for some of the [Self::diagnostics].
+    fixes: Vec<(Diagnostic, QuickFix)>,
 }
 
 impl AnalyzedDocument {
@@ -272,6 +455,10 @@ impl AnalyzedDocument {
         &self.url
     }
 
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
     fn line_info(&self) -> &LineInfo {
         &self.line_info
     }
@@ -284,6 +471,73 @@ impl AnalyzedDocument {
         self.module.as_ref()
     }
 
+    /// The directory of the package this module belongs to, where a module like `Foo.Bar` is
+    /// the file `Foo/Bar.roc`. Builtin modules don't belong to any package.
+    pub fn package_root(&self) -> Option<PathBuf> {
+        let path = self.url.to_file_path().ok()?;
+
+        let depth = match self.latest_module() {
+            Some(AnalyzedModule {
+                module_id, interns, ..
+            }) => {
+                if module_id.is_builtin() {
+                    return None;
+                }
+
+                let module_name = interns.module_ids.get_name(*module_id)?;
+                module_name.as_str().matches('.').count()
+            }
+            None => 0,
+        };
+
+        path.ancestors().nth(depth + 1).map(Path::to_path_buf)
+    }
+
+    /// The current analysis if there is one, otherwise the last successful one.
+    fn latest_module(&self) -> Option<&AnalyzedModule> {
+        let stale_module = self.stale_module.as_ref().map(|stale| &stale.module);
+
+        self.module.as_ref().or(stale_module)
+    }
+
+    fn latest_module_mut(&mut self) -> Option<&mut AnalyzedModule> {
+        let stale_module = self.stale_module.as_mut().map(|stale| &mut stale.module);
+
+        self.module.as_mut().or(stale_module)
+    }
+
+    /// The current analysis if there is one, otherwise the last successful one, along with where
+    /// `position` in the current source is in the source that was analyzed.
+    fn latest_module_at(
+        &mut self,
+        position: Position,
+    ) -> Option<(&mut AnalyzedModule, roc_region::all::Position)> {
+        let offset = position.to_roc_position(&self.line_info).byte_offset();
+
+        match (&mut self.module, &mut self.stale_module) {
+            (Some(module), _) => Some((module, roc_region::all::Position::new(offset as u32))),
+            (None, Some(StaleModule { module, source })) => {
+                let stale_offset = offset_before_edit(&self.source, source, offset);
+
+                Some((module, roc_region::all::Position::new(stale_offset as u32)))
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Keeps the analysis of `previous` around if this version of the document failed to load.
+    pub fn inherit_stale_module(&mut self, previous: AnalyzedDocument) {
+        if self.module.is_none() {
+            self.stale_module = match previous.module {
+                Some(module) => Some(StaleModule {
+                    module,
+                    source: previous.source,
+                }),
+                None => previous.stale_module,
+            };
+        }
+    }
+
     fn location(&self, range: Range) -> Location {
         Location {
             uri: self.url.clone(),
@@ -334,15 +588,7 @@ impl AnalyzedDocument {
 
         let (region, var) = roc_can::traverse::find_closest_type_at(pos, declarations)?;
 
-        let snapshot = subs.snapshot();
-        let type_str = roc_types::pretty_print::name_and_print_var(
-            var,
-            subs,
-            *module_id,
-            interns,
-            roc_types::pretty_print::DebugPrint::NOTHING,
-        );
-        subs.rollback_to(snapshot);
+        let type_str = print_type(subs, var, interns, *module_id);
 
         let range = region.to_range(self.line_info());
 
@@ -396,4 +642,663 @@ impl AnalyzedDocument {
     pub(crate) fn module_url(&self, module_id: ModuleId) -> Option<Url> {
         self.module()?.module_id_to_url.get(&module_id).cloned()
     }
+
+    pub fn completion_context(&self, position: Position) -> Option<CompletionContext> {
+        let offset = position.to_roc_position(self.line_info()).byte_offset();
+
+        CompletionContext::from_source(&self.source, offset)
+    }
+
+    /// Resolves a module name as written in this document to its [ModuleId] and, if the module
+    /// is part of the loaded module graph, the document that defines it.
+    pub fn resolve_module_name(&self, module_name: &str) -> Option<(ModuleId, Option<Url>)> {
+        let AnalyzedModule {
+            interns,
+            module_id_to_url,
+            ..
+        } = self.latest_module()?;
+
+        let module_id = interns.module_ids.get_id(&ModuleName::from(module_name))?;
+
+        Some((module_id, module_id_to_url.get(&module_id).cloned()))
+    }
+
+    pub fn symbol_completions(&mut self, position: Position, prefix: &str) -> Vec<CompletionItem> {
+        let Some((
+            AnalyzedModule {
+                subs,
+                declarations,
+                module_id,
+                interns,
+                ..
+            },
+            pos,
+        )) = self.latest_module_at(position) else {
+            return vec![];
+        };
+
+        symbols_in_scope_at(pos, declarations)
+            .into_iter()
+            .filter(|(symbol, _)| symbol.as_str(interns).starts_with(prefix))
+            .map(|(symbol, var)| {
+                make_symbol_completion_item(symbol, var, subs, *module_id, interns)
+            })
+            .collect()
+    }
+
+    pub fn field_completions(
+        &mut self,
+        position: Position,
+        receiver: &str,
+        path: &[String],
+        prefix: &str,
+    ) -> Vec<CompletionItem> {
+        let Some((
+            AnalyzedModule {
+                subs,
+                declarations,
+                module_id,
+                interns,
+                ..
+            },
+            pos,
+        )) = self.latest_module_at(position) else {
+            return vec![];
+        };
+
+        let Some(receiver_var) = symbols_in_scope_at(pos, declarations)
+            .into_iter()
+            .find(|(symbol, _)| symbol.as_str(interns) == receiver)
+            .and_then(|(_, var)| field_var_at_path(subs, var, path)) else {
+            return vec![];
+        };
+
+        record_fields(subs, receiver_var)
+            .into_iter()
+            .filter(|(label, _)| label.starts_with(prefix))
+            .map(|(label, var)| {
+                make_completion_item(
+                    label,
+                    CompletionItemKind::FIELD,
+                    var,
+                    subs,
+                    *module_id,
+                    interns,
+                )
+            })
+            .collect()
+    }
+
+    /// Completions for the values this module exposes to modules importing it.
+    pub fn exposed_completions(&mut self, prefix: &str) -> Vec<CompletionItem> {
+        let arena = Bump::new();
+        let exposed_names: Option<Vec<String>> = match Ast::parse(&arena, &self.source) {
+            Ok(ast) => ast
+                .exposed_names()
+                .map(|names| names.into_iter().map(String::from).collect()),
+            Err(_) => None,
+        };
+
+        let Some(AnalyzedModule {
+            subs,
+            declarations,
+            module_id,
+            interns,
+            ..
+        }) = self.latest_module_mut() else {
+            return vec![];
+        };
+
+        declarations
+            .declarations
+            .iter()
+            .enumerate()
+            .filter(|(_, tag)| {
+                matches!(
+                    tag,
+                    DeclarationTag::Value
+                        | DeclarationTag::Function(_)
+                        | DeclarationTag::Recursive(_)
+                        | DeclarationTag::TailRecursive(_)
+                )
+            })
+            .map(|(index, _)| {
+                (
+                    declarations.symbols[index].value,
+                    declarations.variables[index],
+                )
+            })
+            .filter(|(symbol, _)| {
+                let name = symbol.as_str(interns);
+                let is_exposed = match &exposed_names {
+                    Some(names) => names.iter().any(|exposed| exposed == name),
+                    None => true,
+                };
+
+                is_exposed && name.starts_with(prefix)
+            })
+            .map(|(symbol, var)| {
+                make_symbol_completion_item(symbol, var, subs, *module_id, interns)
+            })
+            .collect()
+    }
+
+    /// Resolves the symbol at `position` to an identity that is meaningful to other documents.
+    pub fn global_symbol_at(&self, position: Position) -> Option<GlobalSymbol> {
+        let symbol = self.symbol_at(position)?;
+
+        let AnalyzedModule {
+            module_id,
+            interns,
+            declarations,
+            module_id_to_url,
+            ..
+        } = self.module()?;
+
+        let module_url = module_id_to_url.get(&symbol.module_id())?.clone();
+        let is_top_level = symbol.module_id() != *module_id
+            || declarations.symbols.iter().any(|s| s.value == symbol);
+
+        Some(GlobalSymbol {
+            symbol,
+            origin_url: self.url.clone(),
+            module_url,
+            module_name: interns.module_name(symbol.module_id()).as_str().to_string(),
+            name: symbol.as_str(interns).to_string(),
+            is_top_level,
+        })
+    }
+
+    /// The symbols this document's analysis uses to refer to `target`.
+    fn symbols_matching(&self, target: &GlobalSymbol) -> Vec<Symbol> {
+        let Some(AnalyzedModule {
+            interns,
+            module_id_to_url,
+            ..
+        }) = self.module() else {
+            return vec![];
+        };
+
+        if !target.is_top_level {
+            return if self.url == target.origin_url {
+                vec![target.symbol]
+            } else {
+                vec![]
+            };
+        }
+
+        let Some(module_id) = module_id_to_url
+            .iter()
+            .find(|(_, url)| **url == target.module_url)
+            .map(|(module_id, _)| *module_id) else {
+            return vec![];
+        };
+
+        let Some(ident_ids) = interns.all_ident_ids.get(&module_id) else {
+            return vec![];
+        };
+
+        ident_ids
+            .get_id_many(&target.name)
+            .map(|ident_id| Symbol::new(module_id, ident_id))
+            .collect()
+    }
+
+    /// Every occurrence of `target` in this document, narrowed down to the identifier itself.
+    fn occurrences(&self, target: &GlobalSymbol) -> Vec<(Symbol, Region, OccurrenceKind)> {
+        let Some(AnalyzedModule { declarations, .. }) = self.module() else {
+            return vec![];
+        };
+
+        self.symbols_matching(target)
+            .into_iter()
+            .flat_map(|symbol| {
+                roc_can::traverse::find_symbol_occurrences(symbol, declarations)
+                    .into_iter()
+                    .map(move |(region, kind)| (symbol, region, kind))
+            })
+            .filter_map(|(symbol, region, kind)| {
+                let region = identifier_region(&self.source, region, kind, &target.name)?;
+                Some((symbol, region, kind))
+            })
+            .collect()
+    }
+
+    pub fn references(&self, target: &GlobalSymbol, include_declaration: bool) -> Vec<Location> {
+        self.occurrences(target)
+            .into_iter()
+            .filter(|(_, _, kind)| {
+                include_declaration
+                    || matches!(
+                        kind,
+                        OccurrenceKind::Exact
+                            | OccurrenceKind::PunnedField
+                            | OccurrenceKind::RecordUpdate
+                    )
+            })
+            .map(|(_, region, _)| self.location(region.to_range(self.line_info())))
+            .collect()
+    }
+
+    /// Regions of the module header that refer to `target` by name.
+    fn header_regions(&self, target: &GlobalSymbol) -> Vec<Region> {
+        let arena = Bump::new();
+        let Ok(ast) = Ast::parse(&arena, &self.source) else {
+            return vec![];
+        };
+
+        let regions = if self.url == target.module_url {
+            ast.header_exposure_regions(&target.name)
+        } else {
+            ast.header_import_regions(&target.module_name, &target.name)
+        };
+
+        regions
+            .into_iter()
+            .filter_map(|region| {
+                identifier_region(&self.source, region, OccurrenceKind::Exact, &target.name)
+            })
+            .collect()
+    }
+
+    /// Whether the header's `imports` expose a value called `name` to this module unqualified.
+    fn imports_value(&self, name: &str) -> bool {
+        let arena = Bump::new();
+        let Ok(ast) = Ast::parse(&arena, &self.source) else {
+            return false;
+        };
+
+        ast.imported_value_names().contains(&name)
+    }
+
+    /// The edits renaming every occurrence of `target` in this document to `new_name`.
+    pub fn rename(
+        &self,
+        target: &GlobalSymbol,
+        new_name: &str,
+    ) -> Result<Vec<TextEdit>, RenameError> {
+        let occurrences = self.occurrences(target);
+
+        let Some(AnalyzedModule {
+            declarations,
+            interns,
+            ..
+        }) = self.module() else {
+            return Ok(vec![]);
+        };
+
+        let renamed: Vec<Symbol> = occurrences.iter().map(|(symbol, _, _)| *symbol).collect();
+        let is_new_name =
+            |symbol: &Symbol| !renamed.contains(symbol) && symbol.as_str(interns) == new_name;
+
+        // Uses like `Module.name` stay qualified after the rename, so nothing in this module can
+        // capture them.
+        let is_qualified =
+            |region: &Region| self.source[..region.start().offset as usize].ends_with('.');
+
+        let header_regions = if target.is_top_level {
+            self.header_regions(target)
+        } else {
+            vec![]
+        };
+
+        let unqualified = !header_regions.is_empty()
+            || occurrences
+                .iter()
+                .any(|(_, region, _)| !is_qualified(region));
+
+        let mut edits = Vec::with_capacity(occurrences.len());
+
+        for (_, region, kind) in occurrences.iter() {
+            // After the rename, the occurrence must not resolve to a different identifier.
+            let shadowed = !is_qualified(region)
+                && symbols_in_scope_at(region.start(), declarations)
+                    .into_iter()
+                    .any(|(symbol, _)| is_new_name(&symbol));
+
+            if shadowed {
+                return Err(RenameError::WouldShadow(new_name.to_string()));
+            }
+
+            let new_text = replacement_text(*kind, &target.name, new_name)?;
+            edits.push(TextEdit::new(region.to_range(self.line_info()), new_text));
+        }
+
+        // Definitions that come later in the renamed symbol's scope, which aren't in scope at any
+        // of its occurrences yet, would clash with it too. Symbols defined at the top level or
+        // imported are in scope throughout the module.
+        let bound_symbols = roc_can::traverse::find_bound_symbols(declarations);
+
+        for symbol in renamed.iter().copied().collect::<MutSet<_>>() {
+            let scope = roc_can::traverse::find_binding_scope(symbol, declarations);
+
+            let clashes = bound_symbols.iter().any(|(bound, region)| {
+                is_new_name(bound)
+                    && match scope {
+                        Some(scope) => scope.contains(region),
+                        None => unqualified,
+                    }
+            });
+
+            if clashes {
+                return Err(RenameError::WouldShadow(new_name.to_string()));
+            }
+        }
+
+        if unqualified && self.imports_value(new_name) {
+            return Err(RenameError::WouldShadow(new_name.to_string()));
+        }
+
+        edits.extend(
+            header_regions
+                .into_iter()
+                .map(|region| TextEdit::new(region.to_range(self.line_info()), new_name.into())),
+        );
+
+        Ok(edits)
+    }
+
+    pub fn code_actions(&mut self, range: Range) -> Option<CodeActionResponse> {
+        let mut actions: Vec<CodeActionOrCommand> = self
+            .fixes
+            .iter()
+            .filter(|(diagnostic, _)| ranges_overlap(diagnostic.range, range))
+            .filter_map(|(diagnostic, fix)| {
+                let edits = self.quick_fix_edits(fix)?;
+
+                Some(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: fix.title(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic.clone()]),
+                    edit: Some(self.workspace_edit(edits)),
+                    is_preferred: Some(true),
+                    ..CodeAction::default()
+                }))
+            })
+            .collect();
+
+        actions.extend(self.add_annotation_action(range.start));
+
+        Some(actions)
+    }
+
+    fn workspace_edit(&self, edits: Vec<TextEdit>) -> WorkspaceEdit {
+        WorkspaceEdit::new(HashMap::from([(self.url.clone(), edits)]))
+    }
+
+    fn quick_fix_edits(&self, fix: &QuickFix) -> Option<Vec<TextEdit>> {
+        let source = &self.source;
+
+        let (region, new_text) = match fix {
+            QuickFix::RemoveUnusedDef { symbol } => {
+                let AnalyzedModule { declarations, .. } = self.module()?;
+
+                let definition = roc_can::traverse::find_declaration(*symbol, declarations)?;
+                let annotations = roc_can::traverse::find_symbol_occurrences(*symbol, declarations)
+                    .into_iter()
+                    .filter(|(_, kind)| *kind == OccurrenceKind::Annotation)
+                    .map(|(region, _)| region);
+
+                let region = whole_lines(
+                    source,
+                    std::iter::once(definition.region()).chain(annotations),
+                )?;
+
+                (region, String::new())
+            }
+            QuickFix::RemoveUnusedImport { region } => {
+                (collection_item_removal(source, *region), String::new())
+            }
+            QuickFix::AddImport { module_name } => {
+                let arena = Bump::new();
+                let ast = Ast::parse(&arena, source).ok()?;
+                let entry = self.import_entry(&ast, module_name);
+
+                collection_append(
+                    &ast.import_entry_regions(),
+                    ast.imports_open_bracket()?,
+                    &entry,
+                )
+            }
+            QuickFix::AddWhenBranches {
+                when_region,
+                patterns,
+            } => when_branches_insertion(source, *when_region, patterns)?,
+        };
+
+        Some(vec![TextEdit::new(
+            region.to_range(self.line_info()),
+            new_text,
+        )])
+    }
+
+    /// How `module_name` is written in the `imports` of this document's header. Apps import
+    /// modules that are not part of the app itself through the platform they provide to.
+    fn import_entry(&self, ast: &Ast, module_name: &str) -> String {
+        let Some(shorthand) = ast.app_platform_shorthand() else {
+            return module_name.to_string();
+        };
+
+        let is_app_module = self
+            .url
+            .to_file_path()
+            .map(|path| {
+                let module_path = module_name.replace('.', "/") + ".roc";
+                find_src_dir(&path).join(module_path).exists()
+            })
+            .unwrap_or(true);
+
+        if is_app_module {
+            module_name.to_string()
+        } else {
+            format!("{shorthand}.{module_name}")
+        }
+    }
+
+    /// Offers to annotate the unannotated top-level value or function at `position` with its
+    /// inferred type.
+    fn add_annotation_action(&mut self, position: Position) -> Option<CodeActionOrCommand> {
+        let position = position.to_roc_position(&self.line_info);
+
+        let AnalyzedModule {
+            subs,
+            declarations,
+            module_id,
+            interns,
+            ..
+        } = self.module.as_mut()?;
+
+        let index = (0..declarations.len()).find(|index| {
+            let is_value = matches!(
+                declarations.declarations[*index],
+                DeclarationTag::Value
+                    | DeclarationTag::Function(_)
+                    | DeclarationTag::Recursive(_)
+                    | DeclarationTag::TailRecursive(_)
+            );
+            let region = Region::span_across(
+                &declarations.symbols[*index].region,
+                &declarations.expressions[*index].region,
+            );
+
+            is_value && declarations.annotations[*index].is_none() && region.contains_pos(position)
+        })?;
+
+        let loc_symbol = declarations.symbols[index];
+        let name = loc_symbol.value.as_str(interns).to_string();
+
+        let type_str = print_type(subs, declarations.variables[index], interns, *module_id);
+
+        let def_start = loc_symbol.region.start().byte_offset();
+        let indent = indentation_of_line_at(&self.source, def_start);
+        let insert_at = Region::from_pos(roc_region::all::Position::new(line_start(
+            &self.source,
+            def_start,
+        ) as u32));
+
+        let edit = TextEdit::new(
+            insert_at.to_range(&self.line_info),
+            format!("{indent}{name} : {type_str}\n"),
+        );
+
+        Some(CodeActionOrCommand::CodeAction(CodeAction {
+            title: format!("Add type annotation to `{name}`"),
+            kind: Some(CodeActionKind::REFACTOR_REWRITE),
+            edit: Some(self.workspace_edit(vec![edit])),
+            ..CodeAction::default()
+        }))
+    }
+
+    pub fn inlay_hints(&mut self, range: Range) -> Option<Vec<InlayHint>> {
+        let range = Region::new(
+            range.start.to_roc_position(&self.line_info),
+            range.end.to_roc_position(&self.line_info),
+        );
+
+        let AnalyzedModule {
+            subs,
+            declarations,
+            module_id,
+            interns,
+            ..
+        } = self.module.as_mut()?;
+
+        let hints = inlay_hint_positions(range, declarations)
+            .into_iter()
+            .map(|(position, var)| {
+                let type_str = print_type(subs, var, interns, *module_id);
+
+                InlayHint {
+                    position: Region::from_pos(position).to_range(&self.line_info).start,
+                    label: InlayHintLabel::String(format!(": {type_str}")),
+                    kind: Some(InlayHintKind::TYPE),
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: Some(true),
+                    padding_right: None,
+                    data: None,
+                }
+            })
+            .collect();
+
+        Some(hints)
+    }
+
+    /// The function applied at `position`, if it is referred to by name.
+    pub fn called_symbol(&self, position: Position) -> Option<Symbol> {
+        let position = position.to_roc_position(&self.line_info);
+        let AnalyzedModule { declarations, .. } = self.module()?;
+
+        enclosing_call(&self.source, position, declarations)?.symbol
+    }
+
+    /// The type of `symbol` as it is declared at the top level of this module.
+    pub fn declared_type(&mut self, symbol: Symbol) -> Option<String> {
+        let AnalyzedModule {
+            subs,
+            declarations,
+            module_id,
+            interns,
+            ..
+        } = self.module.as_mut()?;
+
+        let index = declarations
+            .symbols
+            .iter()
+            .position(|loc_symbol| loc_symbol.value == symbol)?;
+
+        let type_str = print_type(subs, declarations.variables[index], interns, *module_id);
+
+        Some(type_str)
+    }
+
+    /// The type of the function applied at `position`. Functions defined at the top level of a
+    /// module are shown with their declared type, which is passed in as `declared_type` for
+    /// those of other modules; other functions are shown with their type at the call site.
+    pub fn signature_help(
+        &mut self,
+        position: Position,
+        declared_type: Option<String>,
+    ) -> Option<SignatureHelp> {
+        let position = position.to_roc_position(&self.line_info);
+
+        let call = {
+            let AnalyzedModule { declarations, .. } = self.module()?;
+            enclosing_call(&self.source, position, declarations)?
+        };
+
+        let declared_type =
+            declared_type.or_else(|| call.symbol.and_then(|symbol| self.declared_type(symbol)));
+
+        let type_str = match declared_type {
+            Some(type_str) => type_str,
+            None => {
+                let AnalyzedModule {
+                    subs,
+                    module_id,
+                    interns,
+                    ..
+                } = self.module.as_mut()?;
+
+                let type_str = print_type(subs, call.fn_var, interns, *module_id);
+
+                type_str
+            }
+        };
+
+        let fn_name = self
+            .source
+            .get(call.fn_region.start().byte_offset()..call.fn_region.end().byte_offset())?;
+        let label = format!("{fn_name} : {type_str}");
+        let type_offset = label.len() - type_str.len();
+
+        let parameters: Vec<ParameterInformation> = argument_ranges(&type_str)
+            .into_iter()
+            .map(|(start, end)| ParameterInformation {
+                label: ParameterLabel::LabelOffsets([
+                    (type_offset + start) as u32,
+                    (type_offset + end) as u32,
+                ]),
+                documentation: None,
+            })
+            .collect();
+
+        let active_parameter =
+            (call.active_argument < parameters.len()).then_some(call.active_argument as u32);
+
+        Some(SignatureHelp {
+            signatures: vec![SignatureInformation {
+                label,
+                documentation: None,
+                parameters: Some(parameters),
+                active_parameter,
+            }],
+            active_signature: Some(0),
+            active_parameter,
+        })
+    }
+
+    pub fn document_symbols(&self) -> Option<DocumentSymbolResponse> {
+        let arena = Bump::new();
+        let ast = Ast::parse(&arena, &self.source).ok()?;
+
+        let symbols = document_symbols(ast.defs(), &self.source, self.line_info());
+
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+
+    pub fn workspace_symbols(&self, query: &str) -> Vec<SymbolInformation> {
+        parsed_workspace_symbols(&self.url, &self.source, query)
+    }
+
+    pub fn folding_ranges(&self) -> Option<Vec<FoldingRange>> {
+        let arena = Bump::new();
+        let ast = Ast::parse(&arena, &self.source).ok()?;
+
+        Some(folding_ranges(ast.defs(), &self.source, self.line_info()))
+    }
+}
+
+fn ranges_overlap(a: Range, b: Range) -> bool {
+    a.start <= b.end && b.start <= a.end
 }
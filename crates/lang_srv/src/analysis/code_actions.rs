@@ -0,0 +1,288 @@
+use roc_exhaustive::Context;
+use roc_problem::can::{Problem, RuntimeError};
+use roc_region::all::{Position, Region};
+use roc_reporting::report::RocDocAllocator;
+use roc_solve_problem::TypeError;
+
+/// A fix for a reported problem. Fixes are determined while building diagnostics, since the
+/// problems themselves are consumed when rendering their reports.
+#[derive(Debug)]
+pub(crate) enum QuickFix {
+    /// Add a module that is referenced but not imported to the header's `imports`.
+    AddImport { module_name: String },
+    /// Remove a definition that is never used, along with its annotation.
+    RemoveUnusedDef { symbol: roc_module::symbol::Symbol },
+    /// Remove an entry of the header's `imports` that is never used.
+    RemoveUnusedImport { region: Region },
+    /// Add a branch for each pattern a `when` does not cover.
+    AddWhenBranches {
+        when_region: Region,
+        patterns: Vec<String>,
+    },
+}
+
+impl QuickFix {
+    pub fn from_can_problem(problem: &Problem) -> Option<Self> {
+        match problem {
+            Problem::UnusedDef(symbol, _) => Some(QuickFix::RemoveUnusedDef { symbol: *symbol }),
+            Problem::UnusedImport(_, region) | Problem::UnusedModuleImport(_, region) => {
+                Some(QuickFix::RemoveUnusedImport { region: *region })
+            }
+            Problem::RuntimeError(RuntimeError::ModuleNotImported {
+                module_name,
+                module_exists: true,
+                ..
+            }) => Some(QuickFix::AddImport {
+                module_name: module_name.as_str().to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn from_type_problem<'a>(
+        problem: &TypeError,
+        alloc: &'a RocDocAllocator<'a>,
+    ) -> Option<Self> {
+        match problem {
+            TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(
+                region,
+                Context::BadCase,
+                missing,
+            )) => {
+                let patterns = missing
+                    .iter()
+                    .map(|pattern| {
+                        roc_reporting::report::unhandled_pattern_to_source(alloc, pattern.clone())
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+
+                Some(QuickFix::AddWhenBranches {
+                    when_region: *region,
+                    patterns,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            QuickFix::AddImport { module_name } => format!("Import `{module_name}`"),
+            QuickFix::RemoveUnusedDef { .. } => "Remove unused definition".to_string(),
+            QuickFix::RemoveUnusedImport { .. } => "Remove unused import".to_string(),
+            QuickFix::AddWhenBranches { patterns, .. } if patterns.len() == 1 => {
+                "Add missing branch".to_string()
+            }
+            QuickFix::AddWhenBranches { .. } => "Add missing branches".to_string(),
+        }
+    }
+}
+
+fn region_from_offsets(start: usize, end: usize) -> Region {
+    Region::new(Position::new(start as u32), Position::new(end as u32))
+}
+
+pub(crate) fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// The offset just past the newline ending the line `offset` is on.
+fn next_line_start(source: &str, offset: usize) -> usize {
+    source[offset..]
+        .find('\n')
+        .map_or(source.len(), |i| offset + i + 1)
+}
+
+pub(crate) fn indentation_of_line_at(source: &str, offset: usize) -> &str {
+    let start = line_start(source, offset);
+    let line = &source[start..];
+    let indent_len = line.len() - line.trim_start_matches(' ').len();
+
+    &line[..indent_len]
+}
+
+/// The whole lines spanned by `regions`, including the final newline.
+pub(crate) fn whole_lines(
+    source: &str,
+    regions: impl IntoIterator<Item = Region>,
+) -> Option<Region> {
+    let regions: Vec<Region> = regions.into_iter().collect();
+    let start = regions.iter().map(|r| r.start().byte_offset()).min()?;
+    let end = regions.iter().map(|r| r.end().byte_offset()).max()?;
+
+    Some(region_from_offsets(
+        line_start(source, start),
+        next_line_start(source, end),
+    ))
+}
+
+/// Extends the region of an item in a comma-separated collection to also cover one of its
+/// separators, so that removing it leaves a well-formed collection.
+pub(crate) fn collection_item_removal(source: &str, region: Region) -> Region {
+    let start = region.start().byte_offset();
+    let end = region.end().byte_offset();
+
+    // `item, next` - remove up to `next`.
+    let after = &source[end..];
+    if let Some(rest) = after.trim_start().strip_prefix(',') {
+        let removed = after.len() - rest.trim_start().len();
+        return region_from_offsets(start, end + removed);
+    }
+
+    // `previous, item` - remove from the end of `previous`.
+    if let Some(rest) = source[..start].trim_end().strip_suffix(',') {
+        return region_from_offsets(rest.trim_end().len(), end);
+    }
+
+    region
+}
+
+/// Where to insert `item` so that it becomes a new last item of the collection whose items
+/// span `items`, or its first item if it is empty.
+pub(crate) fn collection_append(
+    items: &[Region],
+    open_bracket: Region,
+    item: &str,
+) -> (Region, String) {
+    match items.last() {
+        Some(last) => {
+            let end = last.end().byte_offset();
+            (region_from_offsets(end, end), format!(", {item}"))
+        }
+        None => {
+            let insert_at = open_bracket.end().byte_offset();
+            (region_from_offsets(insert_at, insert_at), item.to_string())
+        }
+    }
+}
+
+/// The new branches for the `when` at `when_region`, inserted after its last branch.
+pub(crate) fn when_branches_insertion(
+    source: &str,
+    when_region: Region,
+    patterns: &[String],
+) -> Option<(Region, String)> {
+    let when_start = when_region.start().byte_offset();
+    let when_end = when_region.end().byte_offset();
+
+    // Branches are indented like the first line after `when ... is`.
+    let first_branch = next_line_start(source, when_start);
+    let first_branch_line = source.get(first_branch..when_end)?;
+    let first_branch_offset =
+        first_branch + (first_branch_line.len() - first_branch_line.trim_start().len());
+    let indent = indentation_of_line_at(source, first_branch_offset);
+
+    let branches: String = patterns
+        .iter()
+        .map(|pattern| format!("\n{indent}{pattern} -> crash \"TODO\""))
+        .collect();
+
+    Some((region_from_offsets(when_end, when_end), branches))
+}
+
+#[cfg(test)]
+mod tests {
+    use bumpalo::Bump;
+
+    use super::*;
+    use crate::analysis::parse_ast::Ast;
+
+    fn region_of(source: &str, text: &str) -> Region {
+        let start = source.find(text).unwrap();
+        region_from_offsets(start, start + text.len())
+    }
+
+    fn apply(source: &str, (region, new_text): (Region, String)) -> String {
+        let start = region.start().byte_offset();
+        let end = region.end().byte_offset();
+
+        format!("{}{}{}", &source[..start], new_text, &source[end..])
+    }
+
+    #[test]
+    fn remove_collection_items() {
+        let source = "imports [A, B, C]";
+        let remove = |item| {
+            apply(
+                source,
+                (
+                    collection_item_removal(source, region_of(source, item)),
+                    String::new(),
+                ),
+            )
+        };
+
+        assert_eq!(remove("A"), "imports [B, C]");
+        assert_eq!(remove("B"), "imports [A, C]");
+        assert_eq!(remove("C"), "imports [A, B]");
+
+        let only = "imports [A]";
+        assert_eq!(
+            apply(
+                only,
+                (
+                    collection_item_removal(only, region_of(only, "A")),
+                    String::new()
+                )
+            ),
+            "imports []"
+        );
+    }
+
+    #[test]
+    fn remove_multiline_collection_items() {
+        let source = "imports [\n    A,\n    B,\n]";
+
+        assert_eq!(
+            apply(
+                source,
+                (
+                    collection_item_removal(source, region_of(source, "A")),
+                    String::new()
+                )
+            ),
+            "imports [\n    B,\n]"
+        );
+    }
+
+    #[test]
+    fn append_to_header_imports() {
+        let append = |source: &str| {
+            let arena = Bump::new();
+            let ast = Ast::parse(&arena, source).unwrap();
+            let edit = collection_append(
+                &ast.import_entry_regions(),
+                ast.imports_open_bracket().unwrap(),
+                "Dict",
+            );
+
+            apply(source, edit)
+        };
+
+        assert_eq!(
+            append("interface A exposes [] imports [Set]\n"),
+            "interface A exposes [] imports [Set, Dict]\n"
+        );
+        // Mentions of `imports` before the header's own don't matter.
+        assert_eq!(
+            append("# no imports [] here\ninterface A exposes [imports] imports []\n"),
+            "# no imports [] here\ninterface A exposes [imports] imports [Dict]\n"
+        );
+        assert_eq!(
+            append("app \"imports\" packages { pf: \"platform/main.roc\" }\n    imports # []\n        []\n    provides [main] to pf\n"),
+            "app \"imports\" packages { pf: \"platform/main.roc\" }\n    imports # []\n        [Dict]\n    provides [main] to pf\n"
+        );
+    }
+
+    #[test]
+    fn insert_when_branches() {
+        let source = "x =\n    when y is\n        A -> 1\n        B -> 2\n";
+        let when_region = region_of(source, "when y is\n        A -> 1\n        B -> 2");
+        let patterns = ["C".to_string(), "D _".to_string()];
+
+        assert_eq!(
+            apply(source, when_branches_insertion(source, when_region, &patterns).unwrap()),
+            "x =\n    when y is\n        A -> 1\n        B -> 2\n        C -> crash \"TODO\"\n        D _ -> crash \"TODO\"\n"
+        );
+    }
+}
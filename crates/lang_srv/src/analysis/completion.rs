@@ -0,0 +1,437 @@
+use roc_can::{
+    def::Def,
+    expr::{Declarations, Expr},
+    module::TypeState,
+    pattern::{DestructType, Pattern, RecordDestruct, TupleDestruct},
+    traverse::{walk_decl, walk_expr, walk_pattern, walk_when_branch, DeclarationInfo, Visitor},
+};
+use roc_collections::{MutMap, MutSet};
+use roc_module::symbol::{IdentIds, Interns, ModuleId, ModuleIds, Symbol};
+use roc_region::all::{Position, Region};
+use roc_types::{
+    subs::{Content, FlatType, Subs, Variable},
+    types::AliasKind,
+};
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind};
+
+use super::print_type;
+
+/// What the user is in the middle of typing at the completion position.
+#[derive(Debug)]
+pub(crate) enum CompletionContext {
+    /// `fo|` - any symbol in scope.
+    Symbol { prefix: String },
+    /// `Module.fo|` - a member exposed by `Module`.
+    ModuleMember { module: String, prefix: String },
+    /// `rec.a.fo|` - a field of `rec.a`.
+    RecordField {
+        receiver: String,
+        path: Vec<String>,
+        prefix: String,
+    },
+}
+
+impl CompletionContext {
+    /// Determines the completion context from the text immediately preceding `offset`.
+    pub fn from_source(source: &str, offset: usize) -> Option<Self> {
+        let before = source.get(..offset)?;
+        let start = before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !(c.is_alphanumeric() || *c == '_' || *c == '.'))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+
+        let mut segments: Vec<&str> = before[start..].split('.').collect();
+        let prefix = segments.pop()?.to_string();
+
+        if segments.is_empty() {
+            return Some(CompletionContext::Symbol { prefix });
+        }
+
+        if segments.iter().any(|segment| segment.is_empty()) {
+            return None;
+        }
+
+        let is_module_name = |segment: &&str| segment.starts_with(char::is_uppercase);
+
+        if segments.iter().all(is_module_name) {
+            Some(CompletionContext::ModuleMember {
+                module: segments.join("."),
+                prefix,
+            })
+        } else if !segments.iter().any(is_module_name) {
+            let receiver = segments[0].to_string();
+            let path = segments[1..].iter().map(|s| s.to_string()).collect();
+
+            Some(CompletionContext::RecordField {
+                receiver,
+                path,
+                prefix,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Maps a byte offset in `current` to the corresponding offset in `previous`, assuming `current`
+/// was made by replacing a single span of `previous`. Offsets inside the replaced span map to
+/// where it starts in `previous`.
+pub(crate) fn offset_before_edit(current: &str, previous: &str, offset: usize) -> usize {
+    let common_prefix = current
+        .bytes()
+        .zip(previous.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let common_suffix = current
+        .bytes()
+        .rev()
+        .zip(previous.bytes().rev())
+        .take(current.len().min(previous.len()) - common_prefix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    if offset <= common_prefix {
+        offset
+    } else if offset >= current.len() - common_suffix {
+        offset + previous.len() - current.len()
+    } else {
+        common_prefix
+    }
+}
+
+/// Collects every symbol that is in scope at `position`, along with its type variable.
+pub(crate) fn symbols_in_scope_at(
+    position: Position,
+    decls: &Declarations,
+) -> Vec<(Symbol, Variable)> {
+    let mut visitor = InScopeVisitor {
+        position,
+        in_scope: Vec::new(),
+    };
+    visitor.visit_decls(decls);
+
+    let mut seen = MutSet::default();
+    let mut in_scope = visitor.in_scope;
+    in_scope.retain(|(symbol, _)| seen.insert(*symbol));
+    in_scope
+}
+
+struct InScopeVisitor {
+    position: Position,
+    in_scope: Vec<(Symbol, Variable)>,
+}
+
+impl InScopeVisitor {
+    fn add_def(&mut self, def: &Def) {
+        let opt_var = match def.loc_pattern.value {
+            Pattern::Identifier(..) | Pattern::AbilityMemberSpecialization { .. } => {
+                Some(def.expr_var)
+            }
+            _ => def.loc_pattern.value.opt_var(),
+        };
+
+        self.visit_pattern(&def.loc_pattern.value, def.loc_pattern.region, opt_var);
+    }
+}
+
+impl Visitor for InScopeVisitor {
+    fn should_visit(&mut self, region: Region) -> bool {
+        region.contains_pos(self.position)
+    }
+
+    fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+        // Top-level declarations are in scope everywhere in the module.
+        match &decl {
+            DeclarationInfo::Value {
+                loc_symbol,
+                expr_var,
+                ..
+            }
+            | DeclarationInfo::Function {
+                loc_symbol,
+                expr_var,
+                ..
+            } => self.in_scope.push((loc_symbol.value, *expr_var)),
+            DeclarationInfo::Destructure {
+                loc_pattern,
+                opt_pattern_var,
+                ..
+            } => self.visit_pattern(&loc_pattern.value, loc_pattern.region, *opt_pattern_var),
+            DeclarationInfo::Expectation { .. } => {}
+        }
+
+        if self.should_visit(decl.region()) {
+            walk_decl(self, decl);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        if !self.should_visit(region) {
+            return;
+        }
+
+        match expr {
+            Expr::LetNonRec(def, _) => {
+                self.add_def(def);
+                walk_expr(self, expr, var);
+            }
+            Expr::LetRec(defs, _, _) => {
+                defs.iter().for_each(|def| self.add_def(def));
+                walk_expr(self, expr, var);
+            }
+            Expr::When {
+                cond_var,
+                expr_var,
+                loc_cond,
+                branches,
+                ..
+            } => {
+                self.visit_expr(&loc_cond.value, loc_cond.region, *cond_var);
+
+                // Only the bindings of the branch we are in are in scope.
+                let position = self.position;
+                for branch in branches.iter() {
+                    let in_branch = branch.value.region.contains_pos(position)
+                        || (branch.guard.as_ref())
+                            .map_or(false, |guard| guard.region.contains_pos(position));
+
+                    if in_branch {
+                        walk_when_branch(self, branch, *expr_var);
+                    }
+                }
+            }
+            _ => walk_expr(self, expr, var),
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern, _region: Region, opt_var: Option<Variable>) {
+        match pattern {
+            Pattern::Identifier(symbol)
+            | Pattern::AbilityMemberSpecialization { ident: symbol, .. } => {
+                if let Some(var) = opt_var {
+                    self.in_scope.push((*symbol, var));
+                }
+            }
+            Pattern::As(subpattern, symbol) => {
+                if let Some(var) = opt_var {
+                    self.in_scope.push((*symbol, var));
+                }
+                self.visit_pattern(&subpattern.value, subpattern.region, opt_var);
+            }
+            _ => walk_pattern(self, pattern),
+        }
+    }
+
+    fn visit_record_destruct(&mut self, destruct: &RecordDestruct, _region: Region) {
+        match &destruct.typ {
+            DestructType::Guard(var, subpattern) => {
+                self.visit_pattern(&subpattern.value, subpattern.region, Some(*var))
+            }
+            DestructType::Required | DestructType::Optional(..) => {
+                self.in_scope.push((destruct.symbol, destruct.var))
+            }
+        }
+    }
+
+    fn visit_tuple_destruct(&mut self, destruct: &TupleDestruct, _region: Region) {
+        let (var, subpattern) = &destruct.typ;
+        self.visit_pattern(&subpattern.value, subpattern.region, Some(*var));
+    }
+}
+
+/// Follows structural aliases of `var` down to its underlying content.
+fn chase_aliases(subs: &Subs, mut var: Variable) -> &Content {
+    loop {
+        match subs.get_content_without_compacting(var) {
+            Content::Alias(_, _, real_var, AliasKind::Structural) => var = *real_var,
+            content => return content,
+        }
+    }
+}
+
+/// Returns the fields of the record type `var`, or nothing if it is not a record.
+pub(crate) fn record_fields(subs: &Subs, var: Variable) -> Vec<(String, Variable)> {
+    match chase_aliases(subs, var) {
+        Content::Structure(FlatType::Record(fields, ext)) => fields
+            .sorted_iterator(subs, *ext)
+            .map(|(label, field)| (label.as_str().to_string(), field.into_inner()))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Finds the type of the field at `path` on a record of type `var`.
+pub(crate) fn field_var_at_path(subs: &Subs, var: Variable, path: &[String]) -> Option<Variable> {
+    path.iter().try_fold(var, |var, field| {
+        record_fields(subs, var)
+            .into_iter()
+            .find(|(label, _)| label == field)
+            .map(|(_, field_var)| field_var)
+    })
+}
+
+fn completion_kind_of_var(subs: &Subs, var: Variable) -> CompletionItemKind {
+    match chase_aliases(subs, var) {
+        Content::Structure(FlatType::Func(..)) => CompletionItemKind::FUNCTION,
+        _ => CompletionItemKind::VARIABLE,
+    }
+}
+
+pub(crate) fn make_completion_item(
+    label: String,
+    kind: CompletionItemKind,
+    var: Variable,
+    subs: &mut Subs,
+    home: ModuleId,
+    interns: &Interns,
+) -> CompletionItem {
+    let type_str = print_type(subs, var, interns, home);
+
+    CompletionItem {
+        label,
+        kind: Some(kind),
+        detail: Some(type_str),
+        ..CompletionItem::default()
+    }
+}
+
+pub(crate) fn make_symbol_completion_item(
+    symbol: Symbol,
+    var: Variable,
+    subs: &mut Subs,
+    home: ModuleId,
+    interns: &Interns,
+) -> CompletionItem {
+    let kind = completion_kind_of_var(subs, var);
+    let label = symbol.as_str(interns).to_string();
+
+    make_completion_item(label, kind, var, subs, home, interns)
+}
+
+/// Completions for a builtin module whose definitions are not available as a document, using the
+/// types the builtins were solved to when the compiler was built.
+pub(crate) fn builtin_completions(
+    module_id: ModuleId,
+    prefix: &str,
+    builtin_types: &mut MutMap<ModuleId, TypeState>,
+) -> Vec<CompletionItem> {
+    let Some(TypeState {
+        subs,
+        exposed_vars_by_symbol,
+        ..
+    }) = builtin_types.get_mut(&module_id) else {
+        return vec![];
+    };
+
+    // The builtins only refer to each other, so their own names are all that's needed to print
+    // their types.
+    let interns = Interns {
+        module_ids: ModuleIds::default(),
+        all_ident_ids: IdentIds::exposed_builtins(0),
+    };
+
+    exposed_vars_by_symbol
+        .iter()
+        .filter(|(symbol, _)| {
+            let name = symbol.as_str(&interns);
+            is_completable_value(name) && name.starts_with(prefix)
+        })
+        .map(|(symbol, var)| make_symbol_completion_item(*symbol, *var, subs, module_id, &interns))
+        .collect()
+}
+
+/// Whether `name` is a user-facing value name, as opposed to a type or a compiler-generated name.
+pub(crate) fn is_completable_value(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(source_with_cursor: &str) -> Option<CompletionContext> {
+        let offset = source_with_cursor.find('|').unwrap();
+        let source = source_with_cursor.replace('|', "");
+
+        CompletionContext::from_source(&source, offset)
+    }
+
+    #[test]
+    fn symbol_context() {
+        assert!(matches!(
+            context("x = fo|"),
+            Some(CompletionContext::Symbol { prefix }) if prefix == "fo"
+        ));
+        assert!(matches!(
+            context("x = (|"),
+            Some(CompletionContext::Symbol { prefix }) if prefix.is_empty()
+        ));
+    }
+
+    #[test]
+    fn module_member_context() {
+        assert!(matches!(
+            context("List.ma|"),
+            Some(CompletionContext::ModuleMember { module, prefix })
+                if module == "List" && prefix == "ma"
+        ));
+        assert!(matches!(context("pf.Stdout.|"), None));
+        assert!(matches!(
+            context("Json.Decode.|"),
+            Some(CompletionContext::ModuleMember { module, prefix })
+                if module == "Json.Decode" && prefix.is_empty()
+        ));
+    }
+
+    #[test]
+    fn record_field_context() {
+        assert!(matches!(
+            context("rec.a.fo|"),
+            Some(CompletionContext::RecordField { receiver, path, prefix })
+                if receiver == "rec" && path == ["a"] && prefix == "fo"
+        ));
+        assert!(matches!(
+            context("rec.|"),
+            Some(CompletionContext::RecordField { receiver, path, prefix })
+                if receiver == "rec" && path.is_empty() && prefix.is_empty()
+        ));
+    }
+
+    #[test]
+    fn invalid_context() {
+        assert!(context("rec..|").is_none());
+        assert!(context(".|").is_none());
+    }
+
+    #[test]
+    fn offsets_around_an_insertion() {
+        let previous = "x = rec\ny = 1";
+        let current = "x = rec.\ny = 1";
+
+        // Before the edit, nothing moves.
+        assert_eq!(offset_before_edit(current, previous, 2), 2);
+        // At the end of the inserted text, which is where the cursor is while typing.
+        assert_eq!(offset_before_edit(current, previous, 8), 7);
+        // After the edit, offsets shift by the length of the inserted text.
+        assert_eq!(offset_before_edit(current, previous, 13), 12);
+    }
+
+    #[test]
+    fn offsets_inside_a_replacement() {
+        let previous = "x = foo + bar";
+        let current = "x = quux + bar";
+
+        assert_eq!(offset_before_edit(current, previous, 6), 4);
+        assert_eq!(offset_before_edit(current, previous, 13), 12);
+    }
+
+    #[test]
+    fn offsets_without_an_edit() {
+        let source = "x = 1";
+
+        assert_eq!(offset_before_edit(source, source, 3), 3);
+        assert_eq!(offset_before_edit(source, source, 5), 5);
+    }
+}
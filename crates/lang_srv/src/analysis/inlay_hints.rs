@@ -0,0 +1,178 @@
+use roc_can::{
+    def::Def,
+    expr::{AnnotatedMark, ClosureData, Declarations, Expr},
+    pattern::Pattern,
+    traverse::{walk_decl, walk_def, walk_expr, DeclarationInfo, Visitor},
+};
+use roc_collections::MutSet;
+use roc_module::symbol::Symbol;
+use roc_region::all::{Loc, Position, Region};
+use roc_types::subs::Variable;
+
+/// Finds the places within `range` where the type of an unannotated binding can be shown,
+/// along with the type variable to show.
+pub(crate) fn inlay_hint_positions(
+    range: Region,
+    decls: &Declarations,
+) -> Vec<(Position, Variable)> {
+    let annotated = decls
+        .annotations
+        .iter()
+        .zip(decls.symbols.iter())
+        .filter(|(annotation, _)| annotation.is_some())
+        .map(|(_, loc_symbol)| loc_symbol.value)
+        .collect();
+
+    let mut visitor = InlayHintVisitor {
+        range,
+        annotated,
+        hints: Vec::new(),
+    };
+    visitor.visit_decls(decls);
+
+    visitor.hints
+}
+
+struct InlayHintVisitor {
+    range: Region,
+    /// Top-level symbols that have an annotation.
+    annotated: MutSet<Symbol>,
+    hints: Vec<(Position, Variable)>,
+}
+
+impl InlayHintVisitor {
+    fn add_hint(&mut self, region: Region, var: Variable) {
+        if self.should_visit(region) {
+            self.hints.push((region.end(), var));
+        }
+    }
+
+    fn add_argument_hints(&mut self, arguments: &[(Variable, AnnotatedMark, Loc<Pattern>)]) {
+        for (var, _, loc_pattern) in arguments {
+            if let Pattern::Identifier(_) = loc_pattern.value {
+                self.add_hint(loc_pattern.region, *var);
+            }
+        }
+    }
+}
+
+impl Visitor for InlayHintVisitor {
+    fn should_visit(&mut self, region: Region) -> bool {
+        region.start() <= self.range.end() && self.range.start() <= region.end()
+    }
+
+    fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+        if !self.should_visit(decl.region()) {
+            return;
+        }
+
+        match &decl {
+            DeclarationInfo::Value {
+                loc_symbol,
+                expr_var,
+                annotation: None,
+                ..
+            } => self.add_hint(loc_symbol.region, *expr_var),
+            DeclarationInfo::Function {
+                loc_symbol,
+                expr_var,
+                function,
+                ..
+            } if !self.annotated.contains(&loc_symbol.value) => {
+                self.add_hint(loc_symbol.region, *expr_var);
+                self.add_argument_hints(&function.value.arguments);
+            }
+            _ => {}
+        }
+
+        walk_decl(self, decl);
+    }
+
+    fn visit_def(&mut self, def: &Def) {
+        if !self.should_visit(def.region()) {
+            return;
+        }
+
+        match (&def.annotation, &def.loc_expr.value) {
+            (None, _) => {
+                if let Pattern::Identifier(_) = def.loc_pattern.value {
+                    self.add_hint(def.loc_pattern.region, def.expr_var);
+                }
+
+                walk_def(self, def);
+            }
+            // The annotation already gives the types of the arguments.
+            (
+                Some(_),
+                Expr::Closure(ClosureData {
+                    loc_body,
+                    return_type,
+                    ..
+                }),
+            ) => self.visit_expr(&loc_body.value, loc_body.region, *return_type),
+            (Some(_), _) => walk_def(self, def),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        if !self.should_visit(region) {
+            return;
+        }
+
+        if let Expr::Closure(ClosureData { arguments, .. }) = expr {
+            self.add_argument_hints(arguments);
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp::lsp_types::{InlayHintLabel, Position, Range};
+
+    use crate::registry::Registry;
+
+    #[test]
+    fn unannotated_bindings() {
+        let dir = tempfile::tempdir().unwrap();
+        let source =
+            "interface Test exposes [f, g] imports []\n\nf = \\x -> x + 1\n\ng : Str\ng = \"hi\"\n";
+        let (mut registry, url) =
+            Registry::analyzed(dir.path(), &[("Test.roc", source)], "Test.roc");
+
+        let range = Range::new(Position::new(0, 0), Position::new(6, 0));
+        let hints: Vec<(Position, String)> = registry
+            .inlay_hints(&url, range)
+            .unwrap()
+            .into_iter()
+            .map(|hint| match hint.label {
+                InlayHintLabel::String(label) => (hint.position, label),
+                InlayHintLabel::LabelParts(_) => unreachable!(),
+            })
+            .collect();
+
+        // `g` is annotated, so it gets no hint.
+        assert_eq!(
+            hints,
+            [
+                (Position::new(2, 1), ": Num a -> Num a".to_string()),
+                (Position::new(2, 6), ": Num *".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_within_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = "interface Test exposes [a, b] imports []\n\na = 1\n\nb = 2\n";
+        let (mut registry, url) =
+            Registry::analyzed(dir.path(), &[("Test.roc", source)], "Test.roc");
+
+        let range = Range::new(Position::new(4, 0), Position::new(5, 0));
+        let hints = registry.inlay_hints(&url, range).unwrap();
+
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].position, Position::new(4, 1));
+    }
+}
@@ -0,0 +1,426 @@
+use roc_parse::ast::{Defs, Expr, Pattern, TypeDef, ValueDef};
+use roc_region::all::{LineInfo, Loc, Region};
+use tower_lsp::lsp_types::{
+    DocumentSymbol, FoldingRange, FoldingRangeKind, Location, SymbolInformation, SymbolKind, Url,
+};
+
+use crate::convert::ToRange;
+
+/// The outline of a module, built from its parse AST so that it is available even when the
+/// module does not type check.
+pub(crate) fn document_symbols(
+    defs: &Defs,
+    source: &str,
+    line_info: &LineInfo,
+) -> Vec<DocumentSymbol> {
+    defs.defs()
+        .zip(defs.regions.iter())
+        .filter_map(|(def, region)| match def {
+            Ok(type_def) => Some(type_def_symbol(type_def, *region, source, line_info)),
+            Err(value_def) => value_def_symbol(value_def, *region, source, line_info),
+        })
+        .collect()
+}
+
+/// The top-level symbols of a module whose names fuzzy-match `query`, along with the members of
+/// its abilities.
+pub(crate) fn workspace_symbols(
+    url: &Url,
+    defs: &Defs,
+    source: &str,
+    line_info: &LineInfo,
+    query: &str,
+) -> Vec<SymbolInformation> {
+    let container_name = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .map(|file_name| file_name.trim_end_matches(".roc").to_string());
+
+    let mut symbols = Vec::new();
+
+    for symbol in document_symbols(defs, source, line_info) {
+        if fuzzy_match(query, &symbol.name) {
+            symbols.push(symbol_information(url, &symbol, container_name.clone()));
+        }
+
+        if symbol.kind == SymbolKind::INTERFACE {
+            for member in symbol.children.iter().flatten() {
+                if fuzzy_match(query, &member.name) {
+                    symbols.push(symbol_information(url, member, Some(symbol.name.clone())));
+                }
+            }
+        }
+    }
+
+    symbols
+}
+
+#[allow(deprecated)]
+fn symbol_information(
+    url: &Url,
+    symbol: &DocumentSymbol,
+    container_name: Option<String>,
+) -> SymbolInformation {
+    SymbolInformation {
+        name: symbol.name.clone(),
+        kind: symbol.kind,
+        tags: None,
+        deprecated: None,
+        location: Location {
+            uri: url.clone(),
+            range: symbol.selection_range,
+        },
+        container_name,
+    }
+}
+
+/// Whether the characters of `query` appear in `name` in order, ignoring case.
+pub(crate) fn fuzzy_match(query: &str, name: &str) -> bool {
+    let mut name_chars = name.chars().flat_map(char::to_lowercase);
+
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| name_chars.any(|c| c == q))
+}
+
+#[allow(deprecated)]
+fn make_symbol(
+    name: &str,
+    kind: SymbolKind,
+    detail: Option<String>,
+    region: Region,
+    selection_region: Region,
+    children: Vec<DocumentSymbol>,
+    line_info: &LineInfo,
+) -> DocumentSymbol {
+    DocumentSymbol {
+        name: name.to_string(),
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: region.to_range(line_info),
+        selection_range: selection_region.to_range(line_info),
+        children: if children.is_empty() {
+            None
+        } else {
+            Some(children)
+        },
+    }
+}
+
+/// The source of a type annotation, on one line.
+fn annotation_detail(source: &str, region: Region) -> Option<String> {
+    let text = source.get(region.start().byte_offset()..region.end().byte_offset())?;
+
+    Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn type_def_symbol(
+    type_def: &TypeDef,
+    region: Region,
+    source: &str,
+    line_info: &LineInfo,
+) -> DocumentSymbol {
+    let (header, kind, detail, children) = match type_def {
+        TypeDef::Alias { header, ann } => (
+            header,
+            SymbolKind::STRUCT,
+            annotation_detail(source, ann.region),
+            vec![],
+        ),
+        TypeDef::Opaque { header, typ, .. } => (
+            header,
+            SymbolKind::CLASS,
+            annotation_detail(source, typ.region),
+            vec![],
+        ),
+        TypeDef::Ability {
+            header, members, ..
+        } => {
+            let members = members
+                .iter()
+                .map(|member| {
+                    let name = &member.name;
+                    make_symbol(
+                        name.value.item(),
+                        SymbolKind::METHOD,
+                        annotation_detail(source, member.typ.region),
+                        Region::span_across(&name.region, &member.typ.region),
+                        name.region,
+                        vec![],
+                        line_info,
+                    )
+                })
+                .collect();
+
+            (header, SymbolKind::INTERFACE, None, members)
+        }
+    };
+
+    make_symbol(
+        header.name.value,
+        kind,
+        detail,
+        region,
+        header.name.region,
+        children,
+        line_info,
+    )
+}
+
+fn value_def_symbol(
+    value_def: &ValueDef,
+    region: Region,
+    source: &str,
+    line_info: &LineInfo,
+) -> Option<DocumentSymbol> {
+    let (pattern, body, detail) = match value_def {
+        ValueDef::Body(pattern, body) => (pattern, body, None),
+        ValueDef::AnnotatedBody {
+            body_pattern,
+            body_expr,
+            ann_type,
+            ..
+        } => (
+            body_pattern,
+            body_expr,
+            annotation_detail(source, ann_type.region),
+        ),
+        ValueDef::Annotation(..)
+        | ValueDef::Dbg { .. }
+        | ValueDef::Expect { .. }
+        | ValueDef::ExpectFx { .. } => return None,
+    };
+
+    let name = identifier_name(&pattern.value)?;
+
+    let kind = match without_spaces(&body.value) {
+        Expr::Closure(..) => SymbolKind::FUNCTION,
+        _ => SymbolKind::CONSTANT,
+    };
+
+    let children = match nested_defs(&body.value) {
+        Some(defs) => document_symbols(defs, source, line_info),
+        None => vec![],
+    };
+
+    Some(make_symbol(
+        name,
+        kind,
+        detail,
+        region,
+        pattern.region,
+        children,
+        line_info,
+    ))
+}
+
+fn identifier_name<'a>(pattern: &Pattern<'a>) -> Option<&'a str> {
+    match pattern {
+        Pattern::Identifier(name) => Some(*name),
+        Pattern::SpaceBefore(pattern, _) | Pattern::SpaceAfter(pattern, _) => {
+            identifier_name(pattern)
+        }
+        _ => None,
+    }
+}
+
+fn without_spaces<'a, 'b>(expr: &'b Expr<'a>) -> &'b Expr<'a> {
+    match expr {
+        Expr::SpaceBefore(expr, _) | Expr::SpaceAfter(expr, _) | Expr::ParensAround(expr) => {
+            without_spaces(expr)
+        }
+        _ => expr,
+    }
+}
+
+/// The definitions at the start of a body, or of the body of a function.
+fn nested_defs<'a, 'b>(expr: &'b Expr<'a>) -> Option<&'b Defs<'a>> {
+    match without_spaces(expr) {
+        Expr::Defs(defs, _) => Some(*defs),
+        Expr::Closure(_, body) => nested_defs(&body.value),
+        _ => None,
+    }
+}
+
+pub(crate) fn folding_ranges(defs: &Defs, source: &str, line_info: &LineInfo) -> Vec<FoldingRange> {
+    let mut ranges = comment_folding_ranges(source);
+    defs_folding_ranges(defs, line_info, &mut ranges);
+
+    ranges
+}
+
+fn push_folding_range(region: Region, line_info: &LineInfo, ranges: &mut Vec<FoldingRange>) {
+    let range = region.to_range(line_info);
+
+    if range.end.line > range.start.line {
+        ranges.push(FoldingRange {
+            start_line: range.start.line,
+            start_character: None,
+            end_line: range.end.line,
+            end_character: None,
+            kind: None,
+        });
+    }
+}
+
+/// Runs of two or more lines that are only comments.
+fn comment_folding_ranges(source: &str) -> Vec<FoldingRange> {
+    let mut ranges = Vec::new();
+    let mut run_start = None;
+
+    let is_comment = |line: &str| line.trim_start().starts_with('#');
+    let lines: Vec<&str> = source.lines().collect();
+
+    for (index, line) in lines.iter().enumerate() {
+        match (is_comment(line), run_start) {
+            (true, None) => run_start = Some(index),
+            (false, Some(start)) => {
+                if index - 1 > start {
+                    ranges.push(comment_folding_range(start, index - 1));
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(start) = run_start {
+        if lines.len() - 1 > start {
+            ranges.push(comment_folding_range(start, lines.len() - 1));
+        }
+    }
+
+    ranges
+}
+
+fn comment_folding_range(start_line: usize, end_line: usize) -> FoldingRange {
+    FoldingRange {
+        start_line: start_line as u32,
+        start_character: None,
+        end_line: end_line as u32,
+        end_character: None,
+        kind: Some(FoldingRangeKind::Comment),
+    }
+}
+
+fn defs_folding_ranges(defs: &Defs, line_info: &LineInfo, ranges: &mut Vec<FoldingRange>) {
+    for (def, region) in defs.defs().zip(defs.regions.iter()) {
+        push_folding_range(*region, line_info, ranges);
+
+        match def {
+            Ok(_) => {}
+            Err(
+                ValueDef::Body(_, body)
+                | ValueDef::AnnotatedBody {
+                    body_expr: body, ..
+                },
+            ) => expr_folding_ranges(body, line_info, ranges),
+            Err(
+                ValueDef::Dbg { condition, .. }
+                | ValueDef::Expect { condition, .. }
+                | ValueDef::ExpectFx { condition, .. },
+            ) => expr_folding_ranges(condition, line_info, ranges),
+            Err(ValueDef::Annotation(..)) => {}
+        }
+    }
+}
+
+fn expr_folding_ranges(expr: &Loc<Expr>, line_info: &LineInfo, ranges: &mut Vec<FoldingRange>) {
+    match without_spaces(&expr.value) {
+        Expr::Defs(defs, final_expr) => {
+            defs_folding_ranges(defs, line_info, ranges);
+            expr_folding_ranges(final_expr, line_info, ranges);
+        }
+        Expr::Closure(_, body) => expr_folding_ranges(body, line_info, ranges),
+        Expr::When(_, branches) => {
+            for branch in branches.iter() {
+                let start = branch
+                    .patterns
+                    .first()
+                    .map_or(branch.value.region, |p| p.region);
+                let region = Region::span_across(&start, &branch.value.region);
+
+                push_folding_range(region, line_info, ranges);
+                expr_folding_ranges(&branch.value, line_info, ranges);
+            }
+        }
+        Expr::If(branches, final_else) => {
+            for (condition, then) in branches.iter() {
+                let region = Region::span_across(&condition.region, &then.region);
+
+                push_folding_range(region, line_info, ranges);
+                expr_folding_ranges(then, line_info, ranges);
+            }
+
+            push_folding_range(final_else.region, line_info, ranges);
+            expr_folding_ranges(final_else, line_info, ranges);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bumpalo::Bump;
+
+    use super::*;
+    use crate::analysis::parse_ast::Ast;
+
+    fn outline(source: &str) -> Vec<(String, SymbolKind, Vec<String>)> {
+        let arena = Bump::new();
+        let ast = Ast::parse(&arena, source).unwrap();
+
+        document_symbols(ast.defs(), source, &LineInfo::new(source))
+            .into_iter()
+            .map(|symbol| {
+                let children = (symbol.children.into_iter().flatten())
+                    .map(|child| child.name)
+                    .collect();
+                (symbol.name, symbol.kind, children)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn top_level_definitions() {
+        let source = "interface Test exposes [] imports []\n\nPoint : { x : I64, y : I64 }\n\norigin : Point\norigin = { x: 0, y: 0 }\n\nnorm = \\p ->\n    sq = p.x * p.x\n    sq\n";
+
+        assert_eq!(
+            outline(source),
+            [
+                ("Point".to_string(), SymbolKind::STRUCT, vec![]),
+                ("origin".to_string(), SymbolKind::CONSTANT, vec![]),
+                (
+                    "norm".to_string(),
+                    SymbolKind::FUNCTION,
+                    vec!["sq".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn ability_members() {
+        let source = "interface Test exposes [] imports []\n\nEq implements\n    isEq : a, a -> Bool where a implements Eq\n";
+
+        assert_eq!(
+            outline(source),
+            [(
+                "Eq".to_string(),
+                SymbolKind::INTERFACE,
+                vec!["isEq".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn fuzzy_matches() {
+        assert!(fuzzy_match("", "anything"));
+        assert!(fuzzy_match("lm", "List.map"));
+        assert!(fuzzy_match("WMB", "walkMaybeBackwards"));
+        assert!(!fuzzy_match("ml", "lam"));
+    }
+}
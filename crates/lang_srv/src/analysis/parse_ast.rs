@@ -1,10 +1,11 @@
 use bumpalo::Bump;
 use roc_fmt::Buf;
 use roc_parse::{
-    ast::{Defs, Module},
+    ast::{Defs, Header, Module, Spaced},
+    header::{ExposedName, ImportsEntry, To},
     parser::SyntaxError,
 };
-use roc_region::all::Loc;
+use roc_region::all::{Loc, Position, Region};
 
 use self::format::FormattedAst;
 
@@ -14,7 +15,10 @@ mod format;
 
 pub struct Ast<'a> {
     arena: &'a Bump,
+    src: &'a str,
     module: Module<'a>,
+    /// The offset where the module header ends and its definitions begin.
+    header_end: usize,
     defs: Defs<'a>,
 }
 
@@ -29,12 +33,16 @@ impl<'a> Ast<'a> {
         let (module, state) = parse_header(arena, State::new(src.as_bytes()))
             .map_err(|e| SyntaxError::Header(e.problem))?;
 
+        let header_end = state.pos().offset as usize;
+
         let (_, defs, _) = module_defs().parse(arena, state, 0).map_err(|(_, e)| e)?;
 
         Ok(Ast {
             module,
+            header_end,
             defs,
             arena,
+            src,
         })
     }
 
@@ -50,6 +58,173 @@ impl<'a> Ast<'a> {
         FormattedAst::new(buf)
     }
 
+    pub fn defs(&self) -> &Defs<'a> {
+        &self.defs
+    }
+
+    /// The names exposed by an interface or hosted module's header, or [None] if the module is
+    /// not of a kind whose values can be imported.
+    pub fn exposed_names(&self) -> Option<Vec<&'a str>> {
+        let exposes = match &self.module.header {
+            Header::Interface(header) => &header.exposes,
+            Header::Hosted(header) => &header.exposes,
+            Header::App(_) | Header::Package(_) | Header::Platform(_) => return None,
+        };
+
+        let names = exposes
+            .item
+            .items
+            .iter()
+            .map(|name| name.value.item().as_str())
+            .collect();
+
+        Some(names)
+    }
+
+    /// Regions where the header exposes or provides the value `name` defined in this module.
+    pub fn header_exposure_regions(&self, name: &str) -> Vec<Region> {
+        let entries: &[Loc<Spaced<ExposedName>>] = match &self.module.header {
+            Header::Interface(header) => header.exposes.item.items,
+            Header::Hosted(header) => header.exposes.item.items,
+            Header::App(header) => header.provides.entries.items,
+            Header::Platform(header) => header.provides.item.items,
+            Header::Package(_) => &[],
+        };
+
+        entries
+            .iter()
+            .filter(|entry| entry.value.item().as_str() == name)
+            .map(|entry| entry.region)
+            .collect()
+    }
+
+    fn imports(&self) -> &'a [Loc<Spaced<'a, ImportsEntry<'a>>>] {
+        match &self.module.header {
+            Header::Interface(header) => header.imports.item.items,
+            Header::Hosted(header) => header.imports.item.items,
+            Header::Platform(header) => header.imports.item.items,
+            Header::App(header) => match &header.imports {
+                Some(imports) => imports.item.items,
+                None => &[],
+            },
+            Header::Package(_) => &[],
+        }
+    }
+
+    /// Regions of the entries of the header's `imports`.
+    pub fn import_entry_regions(&self) -> Vec<Region> {
+        self.imports().iter().map(|entry| entry.region).collect()
+    }
+
+    /// The region of the opening bracket of the header's `imports`, where a first entry can be
+    /// inserted.
+    pub fn imports_open_bracket(&self) -> Option<Region> {
+        fn last_region<T>(items: &[Loc<T>]) -> Option<Region> {
+            items.last().map(|item| item.region)
+        }
+
+        // The parsed element that comes right before the `imports` keyword.
+        let preceding = match &self.module.header {
+            Header::Interface(header) => {
+                last_region(header.exposes.item.items).unwrap_or(header.name.region)
+            }
+            Header::Hosted(header) => {
+                last_region(header.exposes.item.items).unwrap_or(header.name.region)
+            }
+            Header::App(header) => {
+                header.imports.as_ref()?;
+
+                (header.packages.as_ref())
+                    .and_then(|packages| last_region(packages.item.items))
+                    .unwrap_or(header.name.region)
+            }
+            Header::Platform(header) => last_region(header.packages.item.items)
+                .or_else(|| last_region(header.exposes.item.items))
+                .unwrap_or(header.requires.item.signature.region),
+            Header::Package(_) => return None,
+        };
+
+        // Past that element, there are only keywords, brackets, spaces and comments until the
+        // collection of imports opens.
+        let header = &self.src[..self.header_end];
+        let mut offset = preceding.end().offset as usize;
+        let mut after_keyword = false;
+
+        while offset < header.len() {
+            let rest = &header[offset..];
+
+            if rest.starts_with('#') {
+                offset += rest.find('\n').unwrap_or(rest.len());
+            } else if rest.starts_with("imports") {
+                after_keyword = true;
+                offset += "imports".len();
+            } else if after_keyword && rest.starts_with('[') {
+                return Some(Region::new(
+                    Position::new(offset as u32),
+                    Position::new(offset as u32 + 1),
+                ));
+            } else {
+                offset += rest.chars().next()?.len_utf8();
+            }
+        }
+
+        None
+    }
+
+    /// The shorthand of the platform an app header provides to, as in `to pf`.
+    pub fn app_platform_shorthand(&self) -> Option<&'a str> {
+        match &self.module.header {
+            Header::App(header) => match header.provides.to.value {
+                To::ExistingPackage(shorthand) => Some(shorthand),
+                To::NewPackage(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Regions where the header imports the value `name` from `module_name`, as in
+    /// `imports [Module.{ name }]`.
+    pub fn header_import_regions(&self, module_name: &str, name: &str) -> Vec<Region> {
+        let imports = self.imports();
+
+        // Modules imported from packages are written without their package shorthand.
+        let unqualified_module_name = module_name.rsplit('.').next().unwrap_or(module_name);
+
+        imports
+            .iter()
+            .filter_map(|entry| match entry.value.item() {
+                ImportsEntry::Module(module, exposed)
+                | ImportsEntry::Package(_, module, exposed)
+                    if module.as_str() == unqualified_module_name =>
+                {
+                    Some(exposed.items)
+                }
+                _ => None,
+            })
+            .flatten()
+            .filter(|exposed| exposed.value.item().as_str() == name)
+            .map(|exposed| exposed.region)
+            .collect()
+    }
+
+    /// The names of the values the header's `imports` expose to this module unqualified, as in
+    /// `imports [Module.{ name }]` or `imports ["file.txt" as name : Str]`.
+    pub fn imported_value_names(&self) -> Vec<&'a str> {
+        self.imports()
+            .iter()
+            .flat_map(|entry| match entry.value.item() {
+                ImportsEntry::Module(_, exposed) | ImportsEntry::Package(_, _, exposed) => exposed
+                    .items
+                    .iter()
+                    .map(|exposed| exposed.value.item().as_str())
+                    .collect(),
+                ImportsEntry::IngestedFile(_, typed_ident) => {
+                    vec![typed_ident.item().ident.value]
+                }
+            })
+            .collect()
+    }
+
     pub fn semantic_tokens(&self) -> impl IntoIterator<Item = Loc<Token>> + '_ {
         let header_tokens = self.module.iter_tokens(self.arena);
         let body_tokens = self.defs.iter_tokens(self.arena);
@@ -0,0 +1,130 @@
+use std::fmt;
+
+use roc_can::traverse::OccurrenceKind;
+use roc_region::all::{Position, Region};
+
+/// Why a rename request was refused.
+#[derive(Debug)]
+pub(crate) enum RenameError {
+    /// The new name is not a valid name for a value.
+    InvalidName(String),
+    /// The symbol is not defined in any document we know of, e.g. because it is a builtin.
+    NotInWorkspace,
+    /// Renaming would make an occurrence refer to a different, existing identifier.
+    WouldShadow(String),
+    /// `{ x ? default }` cannot be expanded to give the binding a different name than the field.
+    OptionalRecordDestruct,
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameError::InvalidName(name) => write!(f, "`{name}` is not a valid name"),
+            RenameError::NotInWorkspace => {
+                write!(
+                    f,
+                    "Only definitions in the current workspace can be renamed"
+                )
+            }
+            RenameError::WouldShadow(name) => {
+                write!(f, "Renaming would shadow the existing identifier `{name}`")
+            }
+            RenameError::OptionalRecordDestruct => write!(
+                f,
+                "Cannot rename a binding introduced by an optional record field destructure"
+            ),
+        }
+    }
+}
+
+/// Checks that `name` can be used as the name of a value.
+pub(crate) fn validate_value_name(name: &str) -> Result<(), RenameError> {
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    let is_keyword = roc_parse::keyword::KEYWORDS.contains(&name);
+
+    if is_identifier && !is_keyword {
+        Ok(())
+    } else {
+        Err(RenameError::InvalidName(name.to_string()))
+    }
+}
+
+/// Narrows the region of an occurrence found by [roc_can::traverse::find_symbol_occurrences]
+/// down to just the identifier `name`.
+pub(crate) fn identifier_region(
+    source: &str,
+    region: Region,
+    kind: OccurrenceKind,
+    name: &str,
+) -> Option<Region> {
+    let start = region.start().byte_offset();
+    let end = region.end().byte_offset();
+
+    let (search_start, search_end, from_end) = match kind {
+        OccurrenceKind::Definition | OccurrenceKind::PunnedField => {
+            return Some(region);
+        }
+        OccurrenceKind::Exact | OccurrenceKind::AsPattern => (start, end, true),
+        OccurrenceKind::RecordDestruct { .. } | OccurrenceKind::RecordUpdate => (start, end, false),
+        OccurrenceKind::Annotation => {
+            // The name is on the same line as the start of the annotation, before the `:`.
+            let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+            (line_start, start, true)
+        }
+    };
+
+    let haystack = source.get(search_start..search_end)?;
+    let offset = find_word(haystack, name, from_end)?;
+    let ident_start = (search_start + offset) as u32;
+
+    Some(Region::new(
+        Position::new(ident_start),
+        Position::new(ident_start + name.len() as u32),
+    ))
+}
+
+fn find_word(haystack: &str, word: &str, from_end: bool) -> Option<usize> {
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    let is_whole_word = |index: usize| {
+        let before = haystack[..index].chars().next_back();
+        let after = haystack[index + word.len()..].chars().next();
+
+        !before.map_or(false, is_ident_char) && !after.map_or(false, is_ident_char)
+    };
+
+    if from_end {
+        haystack
+            .rmatch_indices(word)
+            .map(|(index, _)| index)
+            .find(|index| is_whole_word(*index))
+    } else {
+        haystack
+            .match_indices(word)
+            .map(|(index, _)| index)
+            .find(|index| is_whole_word(*index))
+    }
+}
+
+/// The text replacing the identifier at an occurrence of the given kind.
+pub(crate) fn replacement_text(
+    kind: OccurrenceKind,
+    old_name: &str,
+    new_name: &str,
+) -> Result<String, RenameError> {
+    match kind {
+        // The field keeps its name, only the binding changes.
+        OccurrenceKind::RecordDestruct { optional: false } | OccurrenceKind::PunnedField => {
+            Ok(format!("{old_name}: {new_name}"))
+        }
+        OccurrenceKind::RecordDestruct { optional: true } => {
+            Err(RenameError::OptionalRecordDestruct)
+        }
+        OccurrenceKind::Definition
+        | OccurrenceKind::Exact
+        | OccurrenceKind::AsPattern
+        | OccurrenceKind::RecordUpdate
+        | OccurrenceKind::Annotation => Ok(new_name.to_string()),
+    }
+}
@@ -0,0 +1,166 @@
+use roc_can::{
+    expr::{Declarations, Expr},
+    traverse::{walk_expr, Visitor},
+};
+use roc_module::{
+    called_via::{BinOp, CalledVia},
+    symbol::Symbol,
+};
+use roc_region::all::{Position, Region};
+use roc_types::subs::Variable;
+
+/// The innermost function application around a position.
+#[derive(Debug)]
+pub(crate) struct EnclosingCall {
+    /// The function being applied, if it is referred to by name.
+    pub symbol: Option<Symbol>,
+    pub fn_region: Region,
+    /// The type of the function at the call site.
+    pub fn_var: Variable,
+    /// The argument the position is at, counting from zero.
+    pub active_argument: usize,
+}
+
+/// Finds the innermost function application that `position` is in. The region of an application
+/// ends with its last argument, so the spaces after it (where the next argument is being typed)
+/// are considered part of it too.
+pub(crate) fn enclosing_call(
+    source: &str,
+    position: Position,
+    decls: &Declarations,
+) -> Option<EnclosingCall> {
+    let mut visitor = CallVisitor {
+        source,
+        position,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+
+    visitor.found
+}
+
+struct CallVisitor<'a> {
+    source: &'a str,
+    position: Position,
+    found: Option<EnclosingCall>,
+}
+
+impl Visitor for CallVisitor<'_> {
+    fn should_visit(&mut self, region: Region) -> bool {
+        let end = region.end().byte_offset();
+        let trailing_spaces = (self.source.get(end..))
+            .map_or(0, |rest| rest.len() - rest.trim_start_matches(' ').len());
+
+        region.start() <= self.position && self.position.byte_offset() <= end + trailing_spaces
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        if !self.should_visit(region) {
+            return;
+        }
+
+        if let Expr::Call(f, args, CalledVia::Space | CalledVia::BinOp(BinOp::Pizza)) = expr {
+            let (fn_var, loc_fn, _, _) = &**f;
+
+            let symbol = match loc_fn.value {
+                Expr::Var(symbol, _) | Expr::AbilityMember(symbol, _, _) => Some(symbol),
+                _ => None,
+            };
+
+            // Nested applications are visited later, and take precedence.
+            self.found = Some(EnclosingCall {
+                symbol,
+                fn_region: loc_fn.region,
+                fn_var: *fn_var,
+                active_argument: args
+                    .iter()
+                    .take_while(|(_, arg)| arg.region.end() < self.position)
+                    .count(),
+            });
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
+
+/// Splits a printed function type like `List a, (a -> b) -> List b` into the byte ranges of its
+/// arguments.
+pub(crate) fn argument_ranges(type_str: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (index, c) in type_str.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                ranges.push(trimmed_range(type_str, start, index));
+                start = index + 1;
+            }
+            '-' if depth == 0 && type_str[index..].starts_with("->") => {
+                ranges.push(trimmed_range(type_str, start, index));
+                return ranges;
+            }
+            _ => {}
+        }
+    }
+
+    // Not a function type.
+    vec![]
+}
+
+fn trimmed_range(s: &str, start: usize, end: usize) -> (usize, usize) {
+    let slice = &s[start..end];
+    let leading = slice.len() - slice.trim_start().len();
+
+    (start + leading, start + slice.trim_end().len())
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp::lsp_types::{ParameterLabel, Position};
+
+    use super::*;
+    use crate::registry::Registry;
+
+    fn arguments(type_str: &str) -> Vec<&str> {
+        argument_ranges(type_str)
+            .into_iter()
+            .map(|(start, end)| &type_str[start..end])
+            .collect()
+    }
+
+    #[test]
+    fn function_type_arguments() {
+        assert_eq!(
+            arguments("List a, (a -> b) -> List b"),
+            ["List a", "(a -> b)"]
+        );
+        assert_eq!(
+            arguments("{ x : Str, y : Str }, [A, B] -> Str"),
+            ["{ x : Str, y : Str }", "[A, B]"]
+        );
+        assert!(arguments("List Str").is_empty());
+    }
+
+    #[test]
+    fn imported_function_has_declared_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = "interface A exposes [twice] imports []\n\ntwice : Num a -> Num a\ntwice = \\n -> n * 2\n";
+        let b = "interface B exposes [x] imports [A.{ twice }]\n\nx = twice 5u8\n";
+        let (mut registry, url) =
+            Registry::analyzed(dir.path(), &[("A.roc", a), ("B.roc", b)], "B.roc");
+
+        let help = registry.signature_help(&url, Position::new(2, 10)).unwrap();
+        let signature = &help.signatures[0];
+
+        // Not `U8 -> U8`, which is its type at the call site.
+        assert_eq!(signature.label, "twice : Num a -> Num a");
+        assert_eq!(help.active_parameter, Some(0));
+        assert!(matches!(
+            signature.parameters.as_deref(),
+            Some([parameter]) if parameter.label == ParameterLabel::LabelOffsets([8, 13])
+        ));
+    }
+}
@@ -12,8 +12,8 @@ use roc_parse::{
     },
     header::{
         AppHeader, ExposedName, HostedHeader, ImportsEntry, InterfaceHeader, ModuleName,
-        PackageEntry, PackageHeader, PackageName, PlatformHeader, PlatformRequires, ProvidesTo, To,
-        TypedIdent,
+        PackageEntry, PackageHeader, PackageName, PackageVersion, PlatformHeader, PlatformRequires,
+        ProvidesTo, To, TypedIdent,
     },
     ident::{Accessor, UppercaseIdent},
 };
@@ -111,6 +111,12 @@ impl HasToken for PackageName<'_> {
     }
 }
 
+impl HasToken for PackageVersion<'_> {
+    fn token(&self) -> Token {
+        Token::String
+    }
+}
+
 impl HasToken for StrLiteral<'_> {
     fn token(&self) -> Token {
         Token::String
@@ -249,11 +255,13 @@ impl IterTokens for PackageHeader<'_> {
         let Self {
             before_name: _,
             name,
+            version,
             exposes,
             packages,
         } = self;
 
         (name.iter_tokens(arena).into_iter())
+            .chain(version.iter().flat_map(|v| v.item.iter_tokens(arena)))
             .chain(exposes.item.iter_tokens(arena))
             .chain(packages.item.iter_tokens(arena))
             .collect_in(arena)
@@ -75,9 +75,15 @@ pub(crate) mod diag {
     use roc_region::all::{LineInfo, Region};
     use roc_solve_problem::TypeError;
 
-    use roc_problem::Severity;
+    use roc_problem::{
+        can::{Problem, RuntimeError},
+        Severity,
+    };
     use roc_reporting::report::RocDocAllocator;
-    use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+    use tower_lsp::lsp_types::{
+        Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Position, Range,
+        Url,
+    };
 
     use super::ToRange;
 
@@ -151,6 +157,7 @@ pub(crate) mod diag {
                         roc_packaging::cache::roc_cache_dir().display()
                     )
                 }
+                LoadingProblem::Cancelled => "The analysis was cancelled".to_string(),
             };
 
             Some(Diagnostic {
@@ -171,6 +178,77 @@ pub(crate) mod diag {
         pub alloc: &'a RocDocAllocator<'a>,
         pub line_info: &'a LineInfo,
         pub path: &'a Path,
+        pub url: &'a Url,
+    }
+
+    impl ProblemFmt<'_> {
+        fn related_information(
+            &self,
+            related: Vec<(Region, &str)>,
+        ) -> Option<Vec<DiagnosticRelatedInformation>> {
+            if related.is_empty() {
+                return None;
+            }
+
+            let info = related
+                .into_iter()
+                .map(|(region, message)| DiagnosticRelatedInformation {
+                    location: Location {
+                        uri: self.url.clone(),
+                        range: region.to_range(self.line_info),
+                    },
+                    message: message.to_string(),
+                })
+                .collect();
+
+            Some(info)
+        }
+    }
+
+    /// Secondary regions of a problem that help explain it.
+    fn can_problem_related_regions(problem: &Problem) -> Vec<(Region, &'static str)> {
+        match problem {
+            Problem::Shadowing {
+                original_region, ..
+            }
+            | Problem::RuntimeError(RuntimeError::Shadowing {
+                original_region, ..
+            }) => vec![(*original_region, "The original definition")],
+            Problem::DuplicateRecordFieldValue {
+                replaced_region, ..
+            }
+            | Problem::DuplicateRecordFieldType {
+                replaced_region, ..
+            } => vec![(*replaced_region, "The field is first given here")],
+            Problem::DuplicateTag {
+                replaced_region, ..
+            } => vec![(*replaced_region, "The tag is first given here")],
+            Problem::SignatureDefMismatch {
+                annotation_pattern, ..
+            } => vec![(*annotation_pattern, "The annotation")],
+            Problem::DuplicateImpl { original, .. } => {
+                vec![(*original, "The first implementation")]
+            }
+            Problem::NestedDatatype { def_region, .. } => {
+                vec![(*def_region, "The type definition")]
+            }
+            Problem::RuntimeError(RuntimeError::LookupNotInScope {
+                underscored_suggestion_region: Some(region),
+                ..
+            }) => vec![(*region, "An ignored definition with a similar name")],
+            _ => vec![],
+        }
+    }
+
+    fn type_problem_related_regions(problem: &TypeError) -> Vec<(Region, &'static str)> {
+        use roc_exhaustive::Error;
+
+        match problem {
+            TypeError::Exhaustive(
+                Error::Redundant { overall_region, .. } | Error::Unmatchable { overall_region, .. },
+            ) => vec![(*overall_region, "The `when` containing the branch")],
+            _ => vec![],
+        }
     }
 
     impl<'a> IntoLspDiagnostic<'a> for roc_problem::can::Problem {
@@ -182,6 +260,8 @@ pub(crate) mod diag {
                 .unwrap_or_else(Region::zero)
                 .to_range(fmt.line_info);
 
+            let related_information = fmt.related_information(can_problem_related_regions(&self));
+
             let report = roc_reporting::report::can_problem(
                 fmt.alloc,
                 fmt.line_info,
@@ -201,7 +281,7 @@ pub(crate) mod diag {
                 code_description: None,
                 source: None,
                 message: msg,
-                related_information: None,
+                related_information,
                 tags: None,
                 data: None,
             })
@@ -217,6 +297,8 @@ pub(crate) mod diag {
                 .unwrap_or_else(Region::zero)
                 .to_range(fmt.line_info);
 
+            let related_information = fmt.related_information(type_problem_related_regions(&self));
+
             let report = roc_reporting::report::type_problem(
                 fmt.alloc,
                 fmt.line_info,
@@ -236,7 +318,7 @@ pub(crate) mod diag {
                 code_description: None,
                 source: None,
                 message: msg,
-                related_information: None,
+                related_information,
                 tags: None,
                 data: None,
             })
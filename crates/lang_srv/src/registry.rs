@@ -1,37 +1,137 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf, time::SystemTime};
 
+use roc_can::module::TypeState;
+use roc_collections::MutMap;
+use roc_load::CancelToken;
+use roc_module::symbol::ModuleId;
 use tower_lsp::lsp_types::{
-    Diagnostic, GotoDefinitionResponse, Hover, Position, SemanticTokensResult, TextEdit, Url,
+    CodeActionResponse, CompletionResponse, Diagnostic, DocumentSymbolResponse, FoldingRange,
+    GotoDefinitionResponse, Hover, InlayHint, Location, Position, Range, SemanticTokensResult,
+    SignatureHelp, SymbolInformation, TextEdit, Url, WorkspaceEdit,
 };
 
-use crate::analysis::{AnalyzedDocument, GlobalAnalysis};
+use crate::analysis::{
+    builtin_completions, fuzzy_match, parsed_workspace_symbols, unanalyzed_package_modules,
+    validate_value_name, AnalyzedDocument, CompletionContext, GlobalAnalysis, RenameError,
+};
 
 pub(crate) enum DocumentChange {
-    Modified(Url, String),
+    /// The module graph of an open document was analyzed.
+    Analyzed(Url, GlobalAnalysis),
     Closed(Url),
 }
 
+/// The latest contents of a document that is open in the editor.
+#[derive(Debug)]
+struct OpenDocument {
+    version: i32,
+    source: String,
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct Registry {
     documents: HashMap<Url, AnalyzedDocument>,
+    open_documents: HashMap<Url, OpenDocument>,
+    /// The documents the last analysis of each open document covered, i.e. its module graph.
+    module_graphs: HashMap<Url, Vec<Url>>,
+    /// The solved types of the builtin modules, loaded the first time they are needed.
+    builtin_types: Option<MutMap<ModuleId, TypeState>>,
+    /// The symbols of package modules that are not part of any analyzed module graph, along
+    /// with when their file was last modified.
+    parsed_modules: HashMap<PathBuf, (Option<SystemTime>, Vec<SymbolInformation>)>,
+    /// The token of the latest analysis started for each open document.
+    analyses: HashMap<Url, CancelToken>,
 }
 
 impl Registry {
+    /// Records the latest contents of an open document, before it is analyzed. An analysis of
+    /// its previous contents that is still running is cancelled.
+    pub fn open_document(&mut self, url: Url, source: String, version: i32) {
+        if let Some(cancel_token) = self.analyses.remove(&url) {
+            cancel_token.cancel();
+        }
+
+        self.open_documents
+            .insert(url, OpenDocument { version, source });
+    }
+
+    /// Returns the token for a new analysis of the document, cancelling the one that was
+    /// previously started for it.
+    pub fn start_analysis(&mut self, url: &Url) -> CancelToken {
+        let cancel_token = CancelToken::default();
+
+        if let Some(previous) = self.analyses.insert(url.clone(), cancel_token.clone()) {
+            previous.cancel();
+        }
+
+        cancel_token
+    }
+
+    /// Whether `version` is still the latest version of the document, so that an analysis of it
+    /// is not stale.
+    pub fn is_latest_version(&self, url: &Url, version: i32) -> bool {
+        self.open_documents
+            .get(url)
+            .map_or(false, |open| open.version == version)
+    }
+
+    /// Whether the document's module graph has not yet been analyzed with its latest contents.
+    pub fn needs_analysis(&self, url: &Url) -> bool {
+        match (self.open_documents.get(url), self.documents.get(url)) {
+            (Some(open), Some(document)) => {
+                !self.module_graphs.contains_key(url) || document.source() != open.source
+            }
+            _ => true,
+        }
+    }
+
+    /// The open documents whose module graph includes `url`, along with their latest contents
+    /// and version.
+    pub fn dependents(&self, url: &Url) -> Vec<(Url, String, i32)> {
+        self.module_graphs
+            .iter()
+            .filter(|(root, graph)| *root != url && graph.contains(url))
+            .filter_map(|(root, _)| {
+                let open = self.open_documents.get(root)?;
+                Some((root.clone(), open.source.clone(), open.version))
+            })
+            .collect()
+    }
+
     pub fn apply_change(&mut self, change: DocumentChange) {
         match change {
-            DocumentChange::Modified(url, source) => {
-                let GlobalAnalysis { documents } = GlobalAnalysis::new(url, source);
+            DocumentChange::Analyzed(root, GlobalAnalysis { documents }) => {
+                let graph = documents
+                    .iter()
+                    .map(|document| document.url().clone())
+                    .collect();
+                self.module_graphs.insert(root.clone(), graph);
 
-                // Only replace the set of documents and all dependencies that were re-analyzed.
-                // Note that this is actually the opposite of what we want - in truth we want to
-                // re-evaluate all dependents!
-                for document in documents {
+                for mut document in documents {
                     let url = document.url().clone();
-                    self.documents.insert(url.clone(), document);
+
+                    // Dependencies are loaded from disk, so keep the analysis of open documents
+                    // that have unsaved changes.
+                    let has_unsaved_changes = url != root
+                        && (self.open_documents.get(&url))
+                            .map_or(false, |open| open.source != document.source());
+                    if has_unsaved_changes {
+                        continue;
+                    }
+
+                    if let Some(previous) = self.documents.remove(&url) {
+                        document.inherit_stale_module(previous);
+                    }
+                    self.documents.insert(url, document);
                 }
             }
-            DocumentChange::Closed(_url) => {
-                // Do nothing.
+            DocumentChange::Closed(url) => {
+                // Keep the analysis around, the document may still be a dependency of others.
+                if let Some(cancel_token) = self.analyses.remove(&url) {
+                    cancel_token.cancel();
+                }
+                self.open_documents.remove(&url);
+                self.module_graphs.remove(&url);
             }
         }
     }
@@ -72,4 +172,359 @@ impl Registry {
         let document = self.document_by_url(url)?;
         document.semantic_tokens()
     }
+
+    pub fn completion(&mut self, url: &Url, position: Position) -> Option<CompletionResponse> {
+        let document = self.document_by_url(url)?;
+
+        let items = match document.completion_context(position)? {
+            CompletionContext::Symbol { prefix } => document.symbol_completions(position, &prefix),
+            CompletionContext::RecordField {
+                receiver,
+                path,
+                prefix,
+            } => document.field_completions(position, &receiver, &path, &prefix),
+            CompletionContext::ModuleMember { module, prefix } => {
+                match document.resolve_module_name(&module)? {
+                    (_, Some(module_url)) => self
+                        .document_by_url(&module_url)?
+                        .exposed_completions(&prefix),
+                    (module_id, None) if module_id.is_builtin() => {
+                        let builtin_types = self
+                            .builtin_types
+                            .get_or_insert_with(roc_load::cached_builtin_types);
+
+                        builtin_completions(module_id, &prefix, builtin_types)
+                    }
+                    (_, None) => vec![],
+                }
+            }
+        };
+
+        Some(CompletionResponse::Array(items))
+    }
+
+    pub fn references(
+        &mut self,
+        url: &Url,
+        position: Position,
+        include_declaration: bool,
+    ) -> Option<Vec<Location>> {
+        let target = self.document_by_url(url)?.global_symbol_at(position)?;
+
+        let locations = self
+            .documents
+            .values()
+            .flat_map(|document| document.references(&target, include_declaration))
+            .collect();
+
+        Some(locations)
+    }
+
+    /// Renames the symbol at `position` in every document of the loaded module graph.
+    pub fn rename(
+        &mut self,
+        url: &Url,
+        position: Position,
+        new_name: &str,
+    ) -> Result<Option<WorkspaceEdit>, RenameError> {
+        validate_value_name(new_name)?;
+
+        let Some(document) = self.document_by_url(url) else {
+            return Ok(None);
+        };
+        let Some(target) = document.global_symbol_at(position) else {
+            return Ok(None);
+        };
+
+        let mut changes = HashMap::default();
+
+        for document in self.documents.values() {
+            let edits = document.rename(&target, new_name)?;
+
+            if !edits.is_empty() {
+                changes.insert(document.url().clone(), edits);
+            }
+        }
+
+        if changes.is_empty() {
+            return Err(RenameError::NotInWorkspace);
+        }
+
+        Ok(Some(WorkspaceEdit::new(changes)))
+    }
+
+    pub fn code_actions(&mut self, url: &Url, range: Range) -> Option<CodeActionResponse> {
+        let document = self.document_by_url(url)?;
+        document.code_actions(range)
+    }
+
+    pub fn inlay_hints(&mut self, url: &Url, range: Range) -> Option<Vec<InlayHint>> {
+        let document = self.document_by_url(url)?;
+        document.inlay_hints(range)
+    }
+
+    pub fn signature_help(&mut self, url: &Url, position: Position) -> Option<SignatureHelp> {
+        let document = self.document_by_url(url)?;
+
+        // Functions defined in other modules are shown with the type they are declared with there.
+        let imported = document
+            .called_symbol(position)
+            .and_then(|symbol| Some((symbol, document.module_url(symbol.module_id())?)))
+            .filter(|(_, def_document_url)| def_document_url != url);
+        let declared_type = imported.and_then(|(symbol, def_document_url)| {
+            self.document_by_url(&def_document_url)?
+                .declared_type(symbol)
+        });
+
+        let document = self.document_by_url(url)?;
+        document.signature_help(position, declared_type)
+    }
+
+    pub fn document_symbols(&mut self, url: &Url) -> Option<DocumentSymbolResponse> {
+        let document = self.document_by_url(url)?;
+        document.document_symbols()
+    }
+
+    /// Searches the symbols of every analyzed document, as well as of the other modules in their
+    /// packages, which are not part of any analyzed module graph.
+    pub fn workspace_symbols(&mut self, query: &str) -> Option<Vec<SymbolInformation>> {
+        let mut symbols: Vec<SymbolInformation> = self
+            .documents
+            .values()
+            .flat_map(|document| document.workspace_symbols(query))
+            .collect();
+
+        for path in unanalyzed_package_modules(self.documents.values()) {
+            symbols.extend(
+                self.parsed_module_symbols(path)
+                    .iter()
+                    .filter(|symbol| fuzzy_match(query, &symbol.name))
+                    .cloned(),
+            );
+        }
+
+        Some(symbols)
+    }
+
+    /// All the symbols of a module that is not analyzed, parsed again only when the file changed.
+    fn parsed_module_symbols(&mut self, path: PathBuf) -> &[SymbolInformation] {
+        let modified = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        let is_stale = match self.parsed_modules.get(&path) {
+            Some((last_modified, _)) => modified.is_none() || *last_modified != modified,
+            None => true,
+        };
+
+        if is_stale {
+            let symbols = Url::from_file_path(&path)
+                .ok()
+                .zip(std::fs::read_to_string(&path).ok())
+                .map(|(url, source)| parsed_workspace_symbols(&url, &source, ""))
+                .unwrap_or_default();
+
+            self.parsed_modules
+                .insert(path.clone(), (modified, symbols));
+        }
+
+        &self.parsed_modules[&path].1
+    }
+
+    pub fn folding_ranges(&mut self, url: &Url) -> Option<Vec<FoldingRange>> {
+        let document = self.document_by_url(url)?;
+        document.folding_ranges()
+    }
+}
+
+#[cfg(test)]
+impl Registry {
+    /// Writes `modules` to `dir`, then analyzes the module graph of `root` like the server does.
+    pub(crate) fn analyzed(
+        dir: &std::path::Path,
+        modules: &[(&str, &str)],
+        root: &str,
+    ) -> (Registry, Url) {
+        for (file_name, source) in modules {
+            std::fs::write(dir.join(file_name), source).unwrap();
+        }
+
+        let path = dir.join(root);
+        let url = Url::from_file_path(&path).unwrap();
+        let source = std::fs::read_to_string(&path).unwrap();
+
+        let mut registry = Registry::default();
+        registry.open_document(url.clone(), source.clone(), 0);
+
+        let analysis = GlobalAnalysis::new(url.clone(), source, CancelToken::default()).unwrap();
+        registry.apply_change(DocumentChange::Analyzed(url.clone(), analysis));
+
+        (registry, url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn would_shadow(result: Result<Option<WorkspaceEdit>, RenameError>, new_name: &str) -> bool {
+        matches!(result, Err(RenameError::WouldShadow(name)) if name == new_name)
+    }
+
+    #[test]
+    fn rename_checks_defs_later_in_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = "interface Test exposes [f] imports []\n\nf = \\x ->\n    y = 1\n    y\n";
+        let (mut registry, url) =
+            Registry::analyzed(dir.path(), &[("Test.roc", source)], "Test.roc");
+
+        // The parameter `x` is unused, so `y` is never in scope where `x` occurs.
+        let x = Position::new(2, 5);
+
+        assert!(would_shadow(registry.rename(&url, x, "y"), "y"));
+        assert!(matches!(registry.rename(&url, x, "z"), Ok(Some(_))));
+    }
+
+    #[test]
+    fn rename_checks_imported_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = "interface A exposes [foo] imports []\n\nfoo = 1\n";
+        let b = "interface B exposes [bar] imports [A.{ foo }]\n\nbar = \\x -> x + foo\n";
+        let (mut registry, url) =
+            Registry::analyzed(dir.path(), &[("A.roc", a), ("B.roc", b)], "B.roc");
+
+        let x = Position::new(2, 7);
+
+        assert!(would_shadow(registry.rename(&url, x, "foo"), "foo"));
+        assert!(matches!(registry.rename(&url, x, "y"), Ok(Some(_))));
+    }
+
+    #[test]
+    fn rename_allows_qualified_uses() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = "interface A exposes [foo] imports []\n\nfoo = 1\n";
+        let b = "interface B exposes [bar] imports [A]\n\nbar = A.foo + 1\n";
+        let (mut registry, url) =
+            Registry::analyzed(dir.path(), &[("A.roc", a), ("B.roc", b)], "B.roc");
+
+        // `A.bar` can't be confused with the `bar` defined in B.
+        let foo = Position::new(2, 8);
+        let edit = registry.rename(&url, foo, "bar").unwrap().unwrap();
+        let changes = edit.changes.unwrap();
+
+        let b_url = Url::from_file_path(dir.path().join("B.roc")).unwrap();
+        let a_url = Url::from_file_path(dir.path().join("A.roc")).unwrap();
+
+        assert_eq!(changes[&b_url].len(), 1);
+        assert_eq!(changes[&b_url][0].new_text, "bar");
+        // The definition and the header's `exposes`.
+        assert_eq!(changes[&a_url].len(), 2);
+    }
+
+    #[test]
+    fn workspace_symbols_of_unanalyzed_package_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("Util")).unwrap();
+
+        let main = "interface Main exposes [main] imports []\n\nmain = 1\n";
+        let strings = "interface Util.Strings exposes [shout] imports []\n\nshout = \\s -> s\n";
+        let (mut registry, _) = Registry::analyzed(
+            dir.path(),
+            &[("Main.roc", main), ("Util/Strings.roc", strings)],
+            "Main.roc",
+        );
+
+        let names_in_dir = |registry: &mut Registry| {
+            let mut names: Vec<String> = registry
+                .workspace_symbols("")
+                .unwrap()
+                .into_iter()
+                .filter(|symbol| {
+                    let path = symbol.location.uri.to_file_path().unwrap();
+                    path.starts_with(dir.path())
+                })
+                .map(|symbol| symbol.name)
+                .collect();
+            names.sort();
+            names
+        };
+
+        // Nothing imports `Util.Strings`, but it is in the same package as `Main`.
+        assert_eq!(names_in_dir(&mut registry), ["main", "shout"]);
+
+        // Make sure the modification time changes even on file systems with a coarse one.
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        std::fs::write(
+            dir.path().join("Util/Strings.roc"),
+            "interface Util.Strings exposes [whisper] imports []\n\nwhisper = \\s -> s\n",
+        )
+        .unwrap();
+
+        assert_eq!(names_in_dir(&mut registry), ["main", "whisper"]);
+    }
+
+    #[test]
+    fn cancelled_analysis_is_discarded() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = "interface Test exposes [x] imports []\n\nx = 1\n";
+        let path = dir.path().join("Test.roc");
+        std::fs::write(&path, source).unwrap();
+        let url = Url::from_file_path(&path).unwrap();
+
+        let cancel_token = CancelToken::default();
+        cancel_token.cancel();
+
+        assert!(GlobalAnalysis::new(url, source.to_string(), cancel_token).is_none());
+    }
+
+    #[test]
+    fn changes_cancel_running_analyses() {
+        let url = Url::parse("file:///Test.roc").unwrap();
+        let mut registry = Registry::default();
+
+        registry.open_document(url.clone(), "x = 1".to_string(), 0);
+        let first = registry.start_analysis(&url);
+        let second = registry.start_analysis(&url);
+
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+
+        registry.open_document(url.clone(), "x = 2".to_string(), 1);
+
+        assert!(second.is_cancelled());
+        assert!(!registry.start_analysis(&url).is_cancelled());
+    }
+
+    #[test]
+    fn dependents_and_unsaved_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = "interface A exposes [foo] imports []\n\nfoo = 1\n";
+        let b = "interface B exposes [bar] imports [A.{ foo }]\n\nbar = foo + 1\n";
+        let a_url = Url::from_file_path(dir.path().join("A.roc")).unwrap();
+
+        let (mut registry, b_url) =
+            Registry::analyzed(dir.path(), &[("A.roc", a), ("B.roc", b)], "B.roc");
+
+        let dependents = registry.dependents(&a_url);
+        assert_eq!(dependents.len(), 1);
+        assert_eq!(dependents[0].0, b_url);
+        assert!(registry.dependents(&b_url).is_empty());
+
+        // A is open with unsaved changes, so B's analysis of A as it is on disk must not replace
+        // the analysis of A.
+        let unsaved = "interface A exposes [foo] imports []\n\nfoo = 2\n";
+        registry.open_document(a_url.clone(), unsaved.to_string(), 1);
+        std::fs::write(
+            dir.path().join("A.roc"),
+            "interface A exposes [foo] imports []\n\nfoo = 3\n",
+        )
+        .unwrap();
+
+        let analysis =
+            GlobalAnalysis::new(b_url.clone(), b.to_string(), CancelToken::default()).unwrap();
+        registry.apply_change(DocumentChange::Analyzed(b_url, analysis));
+
+        assert_eq!(registry.documents[&a_url].source(), a);
+        assert!(registry.needs_analysis(&a_url));
+    }
 }
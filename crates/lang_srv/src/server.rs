@@ -1,4 +1,6 @@
-use analysis::HIGHLIGHT_TOKENS_LEGEND;
+use std::time::Duration;
+
+use analysis::{GlobalAnalysis, HIGHLIGHT_TOKENS_LEGEND};
 use parking_lot::{Mutex, MutexGuard};
 use registry::{DocumentChange, Registry};
 use tower_lsp::jsonrpc::Result;
@@ -9,6 +11,10 @@ mod analysis;
 mod convert;
 mod registry;
 
+/// How long to wait for further changes to a document before analyzing it, so that typing does
+/// not start an analysis per keystroke.
+const ANALYSIS_DEBOUNCE: Duration = Duration::from_millis(100);
+
 #[derive(Debug)]
 struct RocLs {
     client: Client,
@@ -35,6 +41,7 @@ impl RocLs {
             TextDocumentSyncOptions {
                 open_close: Some(true),
                 change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
                 ..TextDocumentSyncOptions::default()
             },
         );
@@ -61,6 +68,21 @@ impl RocLs {
                 range: None,
                 full: Some(SemanticTokensFullOptions::Bool(true)),
             });
+        let completion_provider = CompletionOptions {
+            trigger_characters: Some(vec![".".to_string()]),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+            ..CompletionOptions::default()
+        };
+
+        let signature_help_provider = SignatureHelpOptions {
+            trigger_characters: Some(vec![" ".to_string(), "(".to_string()]),
+            retrigger_characters: None,
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        };
 
         ServerCapabilities {
             text_document_sync: Some(text_document_sync),
@@ -68,6 +90,15 @@ impl RocLs {
             definition_provider: Some(OneOf::Right(definition_provider)),
             document_formatting_provider: Some(OneOf::Right(document_formatting_provider)),
             semantic_tokens_provider: Some(semantic_tokens_provider),
+            completion_provider: Some(completion_provider),
+            references_provider: Some(OneOf::Left(true)),
+            rename_provider: Some(OneOf::Left(true)),
+            code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+            inlay_hint_provider: Some(OneOf::Left(true)),
+            signature_help_provider: Some(signature_help_provider),
+            document_symbol_provider: Some(OneOf::Left(true)),
+            workspace_symbol_provider: Some(OneOf::Left(true)),
+            folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
             ..ServerCapabilities::default()
         }
     }
@@ -75,8 +106,48 @@ impl RocLs {
     /// Records a document content change.
     async fn change(&self, fi: Url, text: String, version: i32) {
         self.registry()
-            .apply_change(DocumentChange::Modified(fi.clone(), text));
+            .open_document(fi.clone(), text.clone(), version);
+
+        tokio::time::sleep(ANALYSIS_DEBOUNCE).await;
+
+        // A newer change came in while we were waiting, and will be analyzed instead.
+        if !self.registry().is_latest_version(&fi, version) {
+            return;
+        }
 
+        if self.registry().needs_analysis(&fi) {
+            self.analyze(fi, text, version).await;
+        } else {
+            self.publish_diagnostics(fi, version).await;
+        }
+    }
+
+    /// Analyzes the module graph of an open document on a blocking thread, so that requests can
+    /// still be served in the meantime. The analysis is cancelled if the document changes while
+    /// it is running.
+    async fn analyze(&self, fi: Url, text: String, version: i32) {
+        let url = fi.clone();
+        let cancel_token = self.registry().start_analysis(&fi);
+        let analysis =
+            tokio::task::spawn_blocking(move || GlobalAnalysis::new(url, text, cancel_token));
+
+        let Ok(Some(analysis)) = analysis.await else {
+            // The analysis was cancelled or panicked.
+            return;
+        };
+
+        {
+            let mut registry = self.registry();
+            if !registry.is_latest_version(&fi, version) {
+                return;
+            }
+            registry.apply_change(DocumentChange::Analyzed(fi.clone(), analysis));
+        }
+
+        self.publish_diagnostics(fi, version).await;
+    }
+
+    async fn publish_diagnostics(&self, fi: Url, version: i32) {
         let diagnostics = match std::panic::catch_unwind(|| self.registry().diagnostics(&fi)) {
             Ok(ds) => ds,
             Err(_) => return,
@@ -87,6 +158,17 @@ impl RocLs {
             .await;
     }
 
+    /// Re-analyzes the open documents that import `fi`, since their analysis loaded it from disk.
+    async fn refresh_dependents(&self, fi: &Url) {
+        let dependents = self.registry().dependents(fi);
+
+        for (url, text, version) in dependents {
+            if self.registry().is_latest_version(&url, version) {
+                self.analyze(url, text, version).await;
+            }
+        }
+    }
+
     async fn close(&self, fi: Url) {
         self.registry().apply_change(DocumentChange::Closed(fi));
     }
@@ -125,6 +207,11 @@ impl LanguageServer for RocLs {
         self.change(uri, text, version).await;
     }
 
+    async fn did_save(&self, params: DidSaveTextDocumentParams) {
+        let TextDocumentIdentifier { uri } = params.text_document;
+        self.refresh_dependents(&uri).await;
+    }
+
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
         let TextDocumentIdentifier { uri } = params.text_document;
         self.close(uri).await;
@@ -189,6 +276,138 @@ impl LanguageServer for RocLs {
 
         panic_wrapper(|| self.registry().semantic_tokens(&text_document.uri))
     }
+
+    async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let CompletionParams {
+            text_document_position:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            work_done_progress_params: _,
+            partial_result_params: _,
+            context: _,
+        } = params;
+
+        panic_wrapper(|| self.registry().completion(&text_document.uri, position))
+    }
+
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let ReferenceParams {
+            text_document_position:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            context: ReferenceContext {
+                include_declaration,
+            },
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        panic_wrapper(|| {
+            self.registry()
+                .references(&text_document.uri, position, include_declaration)
+        })
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let RenameParams {
+            text_document_position:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            new_name,
+            work_done_progress_params: _,
+        } = params;
+
+        let result = std::panic::catch_unwind(|| {
+            self.registry()
+                .rename(&text_document.uri, position, &new_name)
+        });
+
+        match result {
+            Ok(Ok(edit)) => Ok(edit),
+            Ok(Err(rename_error)) => Err(tower_lsp::jsonrpc::Error::invalid_params(
+                rename_error.to_string(),
+            )),
+            Err(_) => Err(tower_lsp::jsonrpc::Error::internal_error()),
+        }
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let CodeActionParams {
+            text_document,
+            range,
+            context: _,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        panic_wrapper(|| self.registry().code_actions(&text_document.uri, range))
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        let InlayHintParams {
+            text_document,
+            range,
+            work_done_progress_params: _,
+        } = params;
+
+        panic_wrapper(|| self.registry().inlay_hints(&text_document.uri, range))
+    }
+
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let SignatureHelpParams {
+            text_document_position_params:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            context: _,
+            work_done_progress_params: _,
+        } = params;
+
+        panic_wrapper(|| self.registry().signature_help(&text_document.uri, position))
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let DocumentSymbolParams {
+            text_document,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        panic_wrapper(|| self.registry().document_symbols(&text_document.uri))
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let WorkspaceSymbolParams {
+            query,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        panic_wrapper(|| self.registry().workspace_symbols(&query))
+    }
+
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        let FoldingRangeParams {
+            text_document,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        panic_wrapper(|| self.registry().folding_ranges(&text_document.uri))
+    }
 }
 
 fn panic_wrapper<T>(f: impl FnOnce() -> Option<T> + std::panic::UnwindSafe) -> Result<Option<T>> {
@@ -5,16 +5,29 @@ use {
     std::fs,
 };
 #[cfg(not(target_family = "wasm"))]
-const MAX_DOWNLOAD_BYTES: u64 = 32 * 1_000_000_000; // GB
+pub(crate) const MAX_DOWNLOAD_BYTES: u64 = 32 * 1_000_000_000; // GB
 
+use crate::vendor::Lockfile;
 use std::path::{Path, PathBuf};
 
 #[derive(Copy, Clone, Debug)]
 pub enum RocCacheDir<'a> {
     /// Normal scenario: reading from the user's cache dir on disk
     Persistent(&'a Path),
+    /// Like `Persistent`, but before downloading a package from a https:// URL, look in this
+    /// directory of tarballs for one with the same filename as the one in the URL (that is, the
+    /// hash of its contents followed by .tar, .tar.gz, or .tar.br), e.g. for build machines
+    /// without internet access. Tarballs found there are verified against the hash in the URL
+    /// exactly like downloaded ones, so builds using a mirror are reproducible.
+    Mirrored {
+        cache_dir: &'a Path,
+        mirror_dir: &'a Path,
+    },
     /// For build.rs and tests where we never want to be downloading anything - yell loudly if we try!
     Disallowed,
+    /// The app has a lock file (see `roc vendor`), so packages are read from its vendor directory
+    /// and never downloaded. Packages which aren't in the lock file are an error.
+    Vendored(&'a Lockfile),
     /// For tests only; we don't want to write to the real cache during a test!
     #[cfg(test)]
     Temp(&'a tempfile::TempDir),
@@ -59,7 +72,10 @@ fn nixos_error_if_dynamic(url: &str, dest_dir: &Path) {
 /// into that dir. If the cache dir on the filesystem, then look into it to see if we already
 /// have an entry for the given URL. If we do, return its info. If we don't already have it, then:
 ///
-/// - Download and decompress the compressed tarball from the given URL
+/// - Decompress the tarball from the given URL. For file:// URLs this reads the tarball from the
+///   local filesystem. For https:// URLs, this first looks for a tarball with the same filename
+///   in the package mirror directory (see `RocCacheDir::Mirrored`), if there is one, and only
+///   downloads it if the mirror doesn't have it.
 /// - Verify its bytes against the hash in the URL
 /// - Extract the tarball's contents into the appropriate cache directory
 ///
@@ -78,8 +94,13 @@ pub fn install_package<'a>(
         root_module_filename,
     } = PackageMetadata::try_from(url).map_err(Problem::InvalidUrl)?;
 
+    let mirror_dir = match roc_cache_dir {
+        RocCacheDir::Mirrored { mirror_dir, .. } => Some(mirror_dir),
+        _ => None,
+    };
+
     match roc_cache_dir {
-        RocCacheDir::Persistent(cache_dir) => {
+        RocCacheDir::Persistent(cache_dir) | RocCacheDir::Mirrored { cache_dir, .. } => {
             // e.g. ~/.cache/roc/example.com/roc-packages/
             let parent_dir = cache_dir.join(cache_subdir);
             // e.g. ~/.cache/roc/example.com/roc-packages/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE
@@ -96,15 +117,28 @@ pub fn install_package<'a>(
 
                 Ok((dest_dir, root_module_filename))
             } else {
-                // Download into a tempdir; only move it to dest_dir if hash verification passes.
-                println!(
-                    "Downloading \u{001b}[36m{url}\u{001b}[0m\n    into {}\n",
-                    cache_dir.display()
-                );
+                // Unpack into a tempdir; only move it to dest_dir if hash verification passes.
                 let tempdir = tempfile::tempdir().map_err(Problem::IoErr)?;
                 let tempdir_path = tempdir.path();
-                let downloaded_hash =
-                    https::download_and_hash(url, tempdir_path, MAX_DOWNLOAD_BYTES)?;
+                let downloaded_hash = match file_url_path(url)
+                    .or_else(|| find_in_mirror(mirror_dir?, content_hash))
+                {
+                    Some(tarball_path) => {
+                        println!(
+                            "Unpacking \u{001b}[36m{}\u{001b}[0m\n    into {}\n",
+                            tarball_path.display(),
+                            cache_dir.display()
+                        );
+                        https::unpack_and_hash(&tarball_path, tempdir_path)?
+                    }
+                    None => {
+                        println!(
+                            "Downloading \u{001b}[36m{url}\u{001b}[0m\n    into {}\n",
+                            cache_dir.display()
+                        );
+                        https::download_and_hash(url, tempdir_path, MAX_DOWNLOAD_BYTES)?
+                    }
+                };
 
                 // Download the tarball into memory and verify it.
                 // The tarball name is the hash of its contents.
@@ -181,11 +215,83 @@ pub fn install_package<'a>(
                 url
             )
         }
+        RocCacheDir::Vendored(lockfile) => match lockfile.vendored_package_dir(url) {
+            Some(package_dir) if package_dir.is_dir() => {
+                // The vendored files could have been edited since `roc vendor` copied them,
+                // so check them against the hash it recorded.
+                let actual = crate::vendor::hash_dir(&package_dir).map_err(Problem::IoErr)?;
+                let expected = lockfile.vendored_hash(url).unwrap_or_default();
+
+                if actual == expected {
+                    Ok((package_dir, root_module_filename))
+                } else {
+                    Err(Problem::VendoredPackageModified {
+                        expected: expected.to_string(),
+                        actual,
+                    })
+                }
+            }
+            _ => Err(Problem::NotVendored),
+        },
         #[cfg(test)]
         RocCacheDir::Temp(temp_dir) => Ok((temp_dir.path().to_path_buf(), None)),
     }
 }
 
+/// Look for a tarball with the given content hash in the package mirror directory.
+#[cfg(not(target_family = "wasm"))]
+fn find_in_mirror(mirror_dir: &Path, content_hash: &str) -> Option<PathBuf> {
+    [".tar.br", ".tar.gz", ".tar"]
+        .iter()
+        .map(|ext| mirror_dir.join(format!("{content_hash}{ext}")))
+        .find(|path| path.is_file())
+}
+
+/// Tarballs can also be read from the local filesystem (e.g. on machines without internet access)
+/// using a URL like file:///home/me/packages/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE.tar.br
+pub const FILE_URL_PREFIX: &str = "file://";
+
+/// If the given URL is a file:// URL, returns the filesystem path it refers to, and the URL
+/// fragment if it has one, e.g. ("/home/me/packages/hash.tar.br", Some("main.roc"))
+pub fn split_file_url(url: &str) -> Option<(&str, Option<&str>)> {
+    let without_protocol = url.strip_prefix(FILE_URL_PREFIX)?;
+
+    match without_protocol.rsplit_once('#') {
+        Some((before_fragment, fragment)) => Some((before_fragment, Some(fragment))),
+        None => Some((without_protocol, None)),
+    }
+}
+
+/// If the given URL is a file:// URL, returns the filesystem path it refers to (without any fragment).
+pub fn file_url_path(url: &str) -> Option<PathBuf> {
+    split_file_url(url).map(|(path, _)| PathBuf::from(path))
+}
+
+/// Returns true if the given package source (e.g. from a `packages { ... }` header)
+/// refers to a tarball which must be installed into the cache dir before it can be loaded,
+/// as opposed to a path on the local filesystem.
+pub fn is_package_url(package_str: &str) -> bool {
+    package_str.starts_with("https://")
+        || file_url_path(package_str).map_or(false, |path| !path.is_dir())
+}
+
+/// Given a package source which is not a URL to a tarball (see `is_package_url`), returns the
+/// path to its root module. This can be either a path to the root module itself, or a path to
+/// a directory containing it - in which case the root module defaults to main.roc. Directories
+/// can also be given as file:// URLs, with the root module optionally specified via the fragment,
+/// e.g. file:///home/me/my-package#Package.roc
+pub fn local_package_root_module(src_dir: &Path, package_str: &str) -> PathBuf {
+    let (path_str, opt_root_module) = split_file_url(package_str).unwrap_or((package_str, None));
+
+    let path = src_dir.join(path_str);
+
+    if path.is_dir() {
+        path.join(opt_root_module.unwrap_or("main.roc"))
+    } else {
+        path
+    }
+}
+
 #[cfg(windows)]
 // e.g. the "Roc" in %APPDATA%\\Roc
 const ROC_CACHE_DIR_NAME: &str = "Roc";
@@ -260,3 +366,128 @@ pub fn roc_cache_dir() -> PathBuf {
 pub fn roc_cache_dir() -> PathBuf {
     PathBuf::from(".cache").join(ROC_CACHE_DIR_NAME)
 }
+
+/// Where roc_load caches the solved types of modules, so unchanged modules don't need to be
+/// type-checked again. This is a sibling of the given packages dir, e.g. ~/.cache/roc/modules
+pub fn roc_module_cache_dir(packages_dir: &Path) -> PathBuf {
+    packages_dir.with_file_name(MODULES_DIR_NAME)
+}
+
+const MODULES_DIR_NAME: &str = "modules";
+
+#[test]
+fn file_url_fragment() {
+    assert_eq!(
+        split_file_url("file:///home/me/packages/hash.tar.br#main.roc"),
+        Some(("/home/me/packages/hash.tar.br", Some("main.roc")))
+    );
+    assert_eq!(
+        file_url_path("file:///home/me/packages/hash.tar.br#main.roc"),
+        Some(PathBuf::from("/home/me/packages/hash.tar.br"))
+    );
+    assert_eq!(file_url_path("https://example.com/hash.tar.br"), None);
+}
+
+/// An uncompressed tarball containing just a main.roc, and its hash
+#[cfg(test)]
+fn test_tarball(main_roc: &str) -> (String, Vec<u8>) {
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(main_roc.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "main.roc", main_roc.as_bytes())
+        .unwrap();
+
+    let bytes = builder.into_inner().unwrap();
+    let hash = base64_url::encode(blake3::hash(&bytes).as_bytes());
+
+    (hash, bytes)
+}
+
+#[test]
+#[cfg(not(target_family = "wasm"))]
+fn install_from_file_url() {
+    let packages_dir = tempfile::tempdir().unwrap();
+    let cache_dir = tempfile::tempdir().unwrap();
+
+    let (hash, bytes) = test_tarball("package \"example\" exposes [] packages {}\n");
+    let tarball_path = packages_dir.path().join(format!("{hash}.tar"));
+    fs::write(&tarball_path, bytes).unwrap();
+
+    let url = format!("{FILE_URL_PREFIX}{}#main.roc", tarball_path.display());
+    let (installed_dir, root_module) =
+        install_package(RocCacheDir::Persistent(cache_dir.path()), &url).unwrap();
+
+    assert_eq!(root_module, Some("main.roc"));
+    assert!(installed_dir.starts_with(cache_dir.path()));
+    assert!(installed_dir.join("main.roc").is_file());
+
+    // A tarball whose name is not the hash of its contents is rejected
+    let (other_hash, other_bytes) = test_tarball("package \"other\" exposes [] packages {}\n");
+    let renamed_path = packages_dir.path().join(format!("{hash}-renamed.tar"));
+    fs::write(&renamed_path, other_bytes).unwrap();
+
+    let url = format!("{FILE_URL_PREFIX}{}", renamed_path.display());
+    match install_package(RocCacheDir::Persistent(cache_dir.path()), &url) {
+        Err(Problem::InvalidContentHash { expected, actual }) => {
+            assert_eq!(expected, format!("{hash}-renamed"));
+            assert_eq!(actual, other_hash);
+        }
+        other => panic!("expected a hash mismatch, got {other:?}"),
+    }
+}
+
+#[test]
+#[cfg(not(target_family = "wasm"))]
+fn install_from_mirror_without_downloading() {
+    let mirror_dir = tempfile::tempdir().unwrap();
+    let cache_dir = tempfile::tempdir().unwrap();
+
+    let (hash, bytes) = test_tarball("package \"example\" exposes [] packages {}\n");
+    fs::write(mirror_dir.path().join(format!("{hash}.tar")), bytes).unwrap();
+
+    // The mirror has a tarball with this name, but its contents have a different hash
+    let wrong_hash = "jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE";
+    let (tampered_hash, tampered_bytes) = test_tarball("package \"evil\" exposes [] packages {}\n");
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    std::io::Write::write_all(&mut encoder, &tampered_bytes).unwrap();
+    fs::write(
+        mirror_dir.path().join(format!("{wrong_hash}.tar.gz")),
+        encoder.finish().unwrap(),
+    )
+    .unwrap();
+
+    let roc_cache_dir = RocCacheDir::Mirrored {
+        cache_dir: cache_dir.path(),
+        mirror_dir: mirror_dir.path(),
+    };
+
+    // Nothing can be downloaded from example.invalid, so these must come from the mirror
+    let installed = install_package(
+        roc_cache_dir,
+        &format!("https://example.invalid/packages/{hash}.tar.br"),
+    );
+    let tampered = install_package(
+        roc_cache_dir,
+        &format!("https://example.invalid/packages/{wrong_hash}.tar.br"),
+    );
+
+    let (installed_dir, root_module) = installed.unwrap();
+    assert_eq!(root_module, None);
+    assert!(installed_dir.join("main.roc").is_file());
+
+    match tampered {
+        Err(Problem::InvalidContentHash { expected, actual }) => {
+            assert_eq!(expected, wrong_hash);
+            assert_eq!(actual, tampered_hash);
+        }
+        other => panic!("expected a hash mismatch, got {other:?}"),
+    }
+    assert!(!cache_dir
+        .path()
+        .join("example.invalid/packages")
+        .join(wrong_hash)
+        .exists());
+}
@@ -0,0 +1,353 @@
+//! Walking every package an app depends on, directly or through other packages and platforms.
+use crate::cache::{self, RocCacheDir};
+use crate::https::Problem;
+use crate::version::{Requirement, Resolver, Version, VersionProblem};
+use bumpalo::Bump;
+use roc_parse::ast::{Header, Spaced};
+use roc_parse::header::PackageEntry;
+use roc_parse::module::parse_header;
+use roc_parse::state::State;
+use roc_region::all::Loc;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum DependencyProblem {
+    /// Installing the package at this URL into the cache dir failed
+    Install(String, Problem),
+    /// The header of this module couldn't be parsed
+    InvalidHeader(PathBuf),
+    IoErr(PathBuf, io::Error),
+    FsExtraErr(fs_extra::error::Error),
+    Version(VersionProblem),
+}
+
+/// One module's dependency on a package, found by [walk_dependencies].
+#[derive(Debug)]
+pub struct Dependency<'a> {
+    /// The package's URL (or path), as written in the `packages` section of `required_by`
+    pub package_str: &'a str,
+    /// The module whose `packages` section has this dependency
+    pub required_by: &'a Path,
+    /// The directory the package was installed into in the cache dir.
+    /// This is None for local packages, which aren't installed.
+    pub installed_dir: Option<&'a Path>,
+    /// The package's `package` or `platform` module
+    pub root_module: &'a Path,
+    /// The name and version from the root module's header, if it's a `package` with a `version`
+    pub name_and_version: Option<(&'a str, &'a str)>,
+}
+
+/// What the dependency walk needs to know about a module's header.
+#[derive(Debug, Default)]
+struct ModuleDeps {
+    package_strs: Vec<String>,
+    name_and_version: Option<(String, String)>,
+}
+
+/// The URLs (or paths) in the `packages` section of the given module's header.
+pub fn read_package_strs(module_path: &Path) -> Result<Vec<String>, DependencyProblem> {
+    Ok(read_module_deps(module_path)?.package_strs)
+}
+
+/// Call `visit` for every dependency of every module reachable from the app at `app_path`,
+/// whose `packages` section is `app_packages`. URL packages are installed into the cache dir
+/// along the way. A package which several modules depend on is visited once per module,
+/// but its own dependencies are only walked once.
+pub fn walk_dependencies(
+    roc_cache_dir: RocCacheDir<'_>,
+    app_path: &Path,
+    app_packages: Vec<String>,
+    mut visit: impl FnMut(&Dependency) -> Result<(), DependencyProblem>,
+) -> Result<(), DependencyProblem> {
+    let mut walked: HashMap<PathBuf, ModuleDeps> = HashMap::new();
+    let mut stack = vec![(app_path.to_path_buf(), app_packages)];
+
+    while let Some((module_path, package_strs)) = stack.pop() {
+        let module_dir = module_path.parent().unwrap_or_else(|| Path::new("."));
+
+        for package_str in package_strs {
+            let (installed_dir, root_module) = if cache::is_package_url(&package_str) {
+                let (package_dir, opt_root_module) =
+                    cache::install_package(roc_cache_dir, &package_str).map_err(|problem| {
+                        DependencyProblem::Install(package_str.clone(), problem)
+                    })?;
+                let root_module = package_dir.join(opt_root_module.unwrap_or("main.roc"));
+
+                (Some(package_dir), root_module)
+            } else {
+                (
+                    None,
+                    cache::local_package_root_module(module_dir, &package_str),
+                )
+            };
+
+            let is_new = !walked.contains_key(&root_module);
+
+            if is_new {
+                walked.insert(root_module.clone(), read_module_deps(&root_module)?);
+            }
+
+            let deps = &walked[&root_module];
+
+            visit(&Dependency {
+                package_str: &package_str,
+                required_by: &module_path,
+                installed_dir: installed_dir.as_deref(),
+                root_module: &root_module,
+                name_and_version: deps
+                    .name_and_version
+                    .as_ref()
+                    .map(|(name, version)| (name.as_str(), version.as_str())),
+            })?;
+
+            if is_new {
+                stack.push((root_module.clone(), deps.package_strs.clone()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The app's dependencies, once their versions have been checked by [resolve_versions].
+#[derive(Debug)]
+pub struct Resolved {
+    pub resolver: Resolver,
+    /// The root module of each of the app's own packages, by its URL (or path)
+    pub root_modules: HashMap<String, PathBuf>,
+}
+
+/// Walk the app's dependencies and check that no two of them require incompatible versions
+/// of the same package, or a version that isn't valid.
+pub fn resolve_versions(
+    roc_cache_dir: RocCacheDir<'_>,
+    app_path: &Path,
+    app_packages: Vec<String>,
+) -> Result<Resolved, DependencyProblem> {
+    let mut resolver = Resolver::new();
+    let mut root_modules = HashMap::new();
+
+    walk_dependencies(roc_cache_dir, app_path, app_packages, |dependency| {
+        if dependency.required_by == app_path {
+            root_modules.insert(
+                dependency.package_str.to_string(),
+                dependency.root_module.to_path_buf(),
+            );
+        }
+
+        add_requirement(&mut resolver, dependency).map_err(DependencyProblem::Version)
+    })?;
+
+    Ok(Resolved {
+        resolver,
+        root_modules,
+    })
+}
+
+pub(crate) fn add_requirement(
+    resolver: &mut Resolver,
+    dependency: &Dependency,
+) -> Result<(), VersionProblem> {
+    match dependency.name_and_version {
+        Some((name, version_str)) => {
+            let version = version_str
+                .parse::<Version>()
+                .map_err(|_| VersionProblem::Invalid {
+                    package_str: dependency.package_str.to_string(),
+                    version: version_str.to_string(),
+                    required_by: dependency.required_by.to_path_buf(),
+                })?;
+
+            resolver.add(
+                name,
+                Requirement {
+                    package_str: dependency.package_str.to_string(),
+                    version,
+                    required_by: dependency.required_by.to_path_buf(),
+                },
+            )
+        }
+        None => Ok(()),
+    }
+}
+
+fn read_module_deps(path: &Path) -> Result<ModuleDeps, DependencyProblem> {
+    let bytes = fs::read(path).map_err(|err| DependencyProblem::IoErr(path.to_path_buf(), err))?;
+    let arena = Bump::new();
+    let (module, _) = parse_header(&arena, State::new(&bytes))
+        .map_err(|_| DependencyProblem::InvalidHeader(path.to_path_buf()))?;
+
+    let deps = match module.header {
+        Header::App(header) => ModuleDeps {
+            package_strs: match header.packages {
+                Some(packages) => package_strs(packages.item.items),
+                None => Vec::new(),
+            },
+            name_and_version: None,
+        },
+        Header::Package(header) => ModuleDeps {
+            package_strs: package_strs(header.packages.item.items),
+            name_and_version: header.version.map(|version| {
+                (
+                    header.name.value.as_str().to_string(),
+                    version.item.value.as_str().to_string(),
+                )
+            }),
+        },
+        Header::Platform(header) => ModuleDeps {
+            package_strs: package_strs(header.packages.item.items),
+            name_and_version: None,
+        },
+        Header::Interface(_) | Header::Hosted(_) => ModuleDeps::default(),
+    };
+
+    Ok(deps)
+}
+
+fn package_strs(packages: &[Loc<Spaced<PackageEntry>>]) -> Vec<String> {
+    packages
+        .iter()
+        .map(|entry| entry.value.item().package_name.value.as_str().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+fn write_modules(dir: &Path, modules: &[(&str, &str)]) {
+    for (path, src) in modules {
+        let path = dir.join(path);
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, src).unwrap();
+    }
+}
+
+#[cfg(test)]
+const PLATFORM_SRC: &str = r#"platform "pf"
+    requires {} { main : Str }
+    exposes []
+    packages { json: "../json2" }
+    imports []
+    provides [mainForHost]
+"#;
+
+#[test]
+fn walk_dependencies_visits_transitive_packages() {
+    let dir = tempfile::tempdir().unwrap();
+    let app_path = dir.path().join("app.roc");
+
+    write_modules(
+        dir.path(),
+        &[
+            ("platform/main.roc", PLATFORM_SRC),
+            (
+                "json/main.roc",
+                "package \"roc/json\"\n    version \"1.2.3\"\n    exposes []\n    packages {}\n",
+            ),
+            (
+                "json2/main.roc",
+                "package \"roc/json\"\n    version \"1.4.0\"\n    exposes []\n    packages {}\n",
+            ),
+        ],
+    );
+
+    let mut visited = Vec::new();
+
+    walk_dependencies(
+        RocCacheDir::Disallowed,
+        &app_path,
+        vec!["platform".to_string(), "json".to_string()],
+        |dependency| {
+            assert_eq!(dependency.installed_dir, None);
+            assert!(dependency.root_module.is_file());
+
+            visited.push((
+                dependency.required_by.to_path_buf(),
+                dependency.package_str.to_string(),
+                dependency
+                    .name_and_version
+                    .map(|(name, version)| format!("{name} {version}")),
+            ));
+
+            Ok(())
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        visited,
+        [
+            (app_path.clone(), "platform".to_string(), None),
+            (
+                app_path.clone(),
+                "json".to_string(),
+                Some("roc/json 1.2.3".to_string())
+            ),
+            (
+                dir.path().join("platform/main.roc"),
+                "../json2".to_string(),
+                Some("roc/json 1.4.0".to_string())
+            ),
+        ]
+    );
+
+    // Both are 1.x versions, so they're compatible and the newest one wins
+    let resolved = resolve_versions(
+        RocCacheDir::Disallowed,
+        &app_path,
+        vec!["platform".to_string(), "json".to_string()],
+    )
+    .unwrap();
+
+    assert_eq!(
+        resolved.resolver.resolved().collect::<Vec<_>>(),
+        [("roc/json", Version::new(1, 4, 0))]
+    );
+    assert_eq!(
+        resolved.root_modules.get("json"),
+        Some(&dir.path().join("json/main.roc"))
+    );
+    assert!(!resolved.root_modules.contains_key("../json2"));
+}
+
+#[test]
+fn resolve_versions_rejects_incompatible_transitive_versions() {
+    let dir = tempfile::tempdir().unwrap();
+    let app_path = dir.path().join("app.roc");
+
+    write_modules(
+        dir.path(),
+        &[
+            ("platform/main.roc", PLATFORM_SRC),
+            (
+                "json/main.roc",
+                "package \"roc/json\"\n    version \"1.2.3\"\n    exposes []\n    packages {}\n",
+            ),
+            (
+                "json2/main.roc",
+                "package \"roc/json\"\n    version \"2.0.0\"\n    exposes []\n    packages {}\n",
+            ),
+        ],
+    );
+
+    match resolve_versions(
+        RocCacheDir::Disallowed,
+        &app_path,
+        vec!["platform".to_string(), "json".to_string()],
+    ) {
+        Err(DependencyProblem::Version(VersionProblem::Conflict {
+            package_name,
+            existing,
+            new,
+        })) => {
+            assert_eq!(package_name, "roc/json");
+            assert_eq!(existing.version, Version::new(1, 2, 3));
+            assert_eq!(existing.required_by, app_path);
+            assert_eq!(new.version, Version::new(2, 0, 0));
+            assert_eq!(new.required_by, dir.path().join("platform/main.roc"));
+        }
+        other => panic!("expected a version conflict, got {other:?}"),
+    }
+}
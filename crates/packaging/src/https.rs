@@ -1,8 +1,10 @@
 use std::{
+    fs::File,
     io::{self, Read, Write},
     path::Path,
 };
 
+use crate::cache::FILE_URL_PREFIX;
 use crate::tarball::Compression;
 
 // gzip should be the most widely supported, and brotli offers the highest compression.
@@ -23,6 +25,13 @@ pub struct PackageMetadata<'a> {
     pub root_module_filename: Option<&'a str>,
 }
 
+const HTTPS_URL_PREFIX: &str = "https://";
+
+/// Tarballs installed from file:// URLs all go in this subfolder of the cache dir.
+/// Since the tarball's filename is the hash of its contents, there's no need to
+/// namespace them any further (and absolute paths can't be joined onto the cache dir anyway).
+const FILE_URL_CACHE_SUBDIR: &str = "file";
+
 /// Valid URLs must end in one of these:
 ///
 /// - .tar
@@ -65,12 +74,14 @@ impl<'a> TryFrom<&'a str> for PackageMetadata<'a> {
 
 impl<'a> PackageMetadata<'a> {
     fn new(url: &'a str) -> Result<Self, UrlProblem> {
-        // First, verify that the URL starts with https://
-        let without_protocol = match url.split_once("https://") {
-            Some((_, without_protocol)) => without_protocol,
-            None => {
-                return Err(UrlProblem::MissingHttps);
-            }
+        // First, verify that the URL starts with https:// (or file:// for local tarballs)
+        let (without_protocol, is_file_url) = if let Some(rest) = url.strip_prefix(HTTPS_URL_PREFIX)
+        {
+            (rest, false)
+        } else if let Some(rest) = url.strip_prefix(FILE_URL_PREFIX) {
+            (rest, true)
+        } else {
+            return Err(UrlProblem::MissingHttps);
         };
 
         // Next, check if there are misleading characters in the URL
@@ -120,7 +131,11 @@ impl<'a> PackageMetadata<'a> {
         };
 
         Ok(PackageMetadata {
-            cache_subdir: path,
+            cache_subdir: if is_file_url {
+                FILE_URL_CACHE_SUBDIR
+            } else {
+                path
+            },
             content_hash: tarball_name,
             root_module_filename: fragment,
         })
@@ -213,6 +228,19 @@ fn url_without_fragment() {
     );
 }
 
+#[test]
+fn file_url_without_fragment() {
+    let expected = Ok(PackageMetadata {
+        cache_subdir: "file",
+        content_hash: "hash",
+        root_module_filename: None,
+    });
+    assert_eq!(
+        PackageMetadata::try_from("file:///home/me/packages/hash.tar.br"),
+        expected
+    );
+}
+
 #[test]
 fn url_with_fragment() {
     let expected = Ok(PackageMetadata {
@@ -241,6 +269,13 @@ pub enum Problem {
     /// The Content-Length header of the response exceeded max_download_bytes
     DownloadTooBig(u64),
     NotFound,
+    /// The app has a lock file, but this package isn't in it (or is missing from the vendor dir)
+    NotVendored,
+    /// The package's files in the vendor dir don't have the hash recorded in the lock file
+    VendoredPackageModified {
+        expected: String,
+        actual: String,
+    },
 }
 
 pub fn download_and_hash(
@@ -288,6 +323,16 @@ pub fn download_and_hash(
     decompress_into(dest_dir, encoding, resp)
 }
 
+/// Decompress and unpack the tarball at the given path on the local filesystem into dest_dir,
+/// returning the base64url-encoded BLAKE3 hash of the (decompressed) tarball's contents.
+/// The compression format is inferred from the file extension.
+pub fn unpack_and_hash(tarball_path: &Path, dest_dir: &Path) -> Result<String, Problem> {
+    let file = File::open(tarball_path).map_err(Problem::IoErr)?;
+    let encoding = Encoding::new("", &tarball_path.to_string_lossy())?;
+
+    decompress_into(dest_dir, encoding, file)
+}
+
 /// The content encodings we support
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Encoding {
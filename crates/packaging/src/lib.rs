@@ -1,4 +1,8 @@
 pub mod cache;
 #[cfg(not(target_family = "wasm"))]
+pub mod dependencies;
+#[cfg(not(target_family = "wasm"))]
 pub mod https;
 pub mod tarball;
+pub mod vendor;
+pub mod version;
@@ -1,14 +1,20 @@
+#[cfg(not(target_family = "wasm"))]
+use crate::https::{self, PackageMetadata, Problem};
 use brotli::enc::BrotliEncoderParams;
 use bumpalo::Bump;
 use flate2::write::GzEncoder;
-use roc_parse::ast::{Header, Module};
-use roc_parse::header::PlatformHeader;
+use roc_module::ident::QualifiedModuleName;
+use roc_parse::ast::{Collection, Header, Module, Spaced};
+use roc_parse::header::{ExposedName, ImportsEntry, ModuleName};
 use roc_parse::module::parse_header;
 use roc_parse::state::State;
+use roc_region::all::Loc;
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
-use std::fs::File;
-use std::io::{self, Read, Write};
-use std::path::Path;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
 use tar;
 use walkdir::WalkDir;
 
@@ -54,6 +60,50 @@ impl<'a> TryFrom<&'a str> for Compression {
     }
 }
 
+/// Why a file was included in a bundle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleReason {
+    /// The package or platform module being bundled
+    RootModule,
+    /// Listed in the `exposes` section of the root module's header
+    Exposed,
+    /// Imported by the module at this path in the archive
+    ImportedBy(String),
+    /// A .roc file in the package's directory which no bundled module imports
+    InPackageDir,
+    /// A prebuilt host (or host metadata) file in the platform's directory
+    PrebuiltHost,
+}
+
+impl fmt::Display for BundleReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleReason::RootModule => write!(f, "root module"),
+            BundleReason::Exposed => write!(f, "exposed by the root module"),
+            BundleReason::ImportedBy(path) => write!(f, "imported by {path}"),
+            BundleReason::InPackageDir => write!(f, "in the package directory"),
+            BundleReason::PrebuiltHost => write!(f, "prebuilt host file"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundledFile {
+    /// The file's path inside the archive, always using / as the separator, e.g. "Json/Decode.roc"
+    pub archive_path: String,
+    pub reason: BundleReason,
+}
+
+/// The result of `build`
+#[derive(Debug)]
+pub struct Bundle {
+    /// The archive's filename: the hash of its contents, followed by the extension
+    /// for its compression (e.g. .tar.br)
+    pub filename: String,
+    /// Every file in the archive, in the order they appear in it (sorted by path)
+    pub files: Vec<BundledFile>,
+}
+
 /// Given a path to a .roc file, write a .tar file to disk.
 ///
 /// The .tar file will be in the same directory, and its filename
@@ -61,10 +111,14 @@ impl<'a> TryFrom<&'a str> for Compression {
 /// the name of that filename (including the .tar extension),
 /// so the caller can obtain the path to the file by calling
 /// Path::with_file_name(returned_string) on the Path argument it provided.
-pub fn build(path_to_main: &Path, compression: Compression) -> io::Result<String> {
+///
+/// Bundling is deterministic: the archive's entries are sorted by path, and the metadata
+/// which depends on the machine doing the bundling (mtimes, owners, and permissions)
+/// is normalized, so bundling the same files always produces the same hash.
+pub fn build(path_to_main: &Path, compression: Compression) -> io::Result<Bundle> {
     let mut archive_bytes = Vec::new();
 
-    write_archive(path_to_main, &mut archive_bytes)?;
+    let files = write_archive(path_to_main, &mut archive_bytes)?;
 
     // Now that we have our compressed archive, get its BLAKE3 hash
     // and base64url encode it. Use base64url encoding because:
@@ -99,6 +153,8 @@ pub fn build(path_to_main: &Path, compression: Compression) -> io::Result<String
                 )?;
             }
             Compression::Gzip => {
+                // GzEncoder::new writes an mtime of 0 into the gzip header,
+                // so the compressed output is deterministic too.
                 let mut encoder = GzEncoder::new(&mut file, flate2::Compression::fast());
                 encoder.write_all(&archive_bytes)?;
                 encoder.finish()?;
@@ -107,122 +163,152 @@ pub fn build(path_to_main: &Path, compression: Compression) -> io::Result<String
         };
     }
 
-    Ok(filename)
+    Ok(Bundle { filename, files })
 }
 
-/// Write an uncompressed tar archive to the given writer.
-fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<()> {
-    let root_dir = if let Some(parent) = path.parent() {
-        parent
-    } else {
-        eprintln!(
-            "{} is a directory, not a .roc file. Please specify a .roc file!",
-            path.to_string_lossy()
-        );
-        std::process::exit(1);
-    };
+/// Write an uncompressed tar archive to the given writer, returning the files that were written.
+fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<Vec<BundledFile>> {
+    let BundleContents { files, missing } = find_bundle_contents(path)?;
+
+    if let Some((archive_path, reason)) = missing.first() {
+        return Err(io::Error::new(
+            ErrorKind::NotFound,
+            format!(
+                "{archive_path} ({reason}) is not in the same directory as {}",
+                path.display()
+            ),
+        ));
+    }
+
     let mut builder = tar::Builder::new(writer);
+    let mut bundled_files = Vec::with_capacity(files.len());
+
+    // The BTreeMap iterates in sorted order, so the order of the entries doesn't depend on
+    // the order in which the filesystem happened to list them.
+    for (archive_path, (source_path, reason)) in files {
+        let bytes = fs::read(&source_path)?;
+
+        // Normalize all the metadata which could differ between machines (or between
+        // checkouts on the same machine), so that bundling is reproducible.
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(bytes.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        header.set_uid(0);
+        header.set_gid(0);
+
+        builder.append_data(&mut header, &archive_path, bytes.as_slice())?;
+
+        bundled_files.push(BundledFile {
+            archive_path,
+            reason,
+        });
+    }
+
+    builder.finish()?;
+
+    Ok(bundled_files)
+}
+
+struct BundleContents {
+    /// Archive path => (path on disk, why it's included)
+    files: BTreeMap<String, (PathBuf, BundleReason)>,
+    /// Modules which are exposed or imported by a bundled module, but which aren't
+    /// in the package's directory, along with why they were needed.
+    missing: Vec<(String, BundleReason)>,
+}
+
+/// Starting from the given package or platform module, find all the files which belong in its
+/// bundle: every module it exposes, and (transitively) every module those import. Other .roc
+/// files in the same directory tree are included too, as are a platform's prebuilt host files.
+fn find_bundle_contents(path: &Path) -> io::Result<BundleContents> {
+    let root_dir = match path.parent() {
+        Some(parent) => parent,
+        None => {
+            eprintln!(
+                "{} is a directory, not a .roc file. Please specify a .roc file!",
+                path.to_string_lossy()
+            );
+            std::process::exit(1);
+        }
+    };
     let arena = Bump::new();
-    let mut buf = Vec::new();
-
-    // TODO use this when finding .roc files by discovering them from the root module.
-    // let other_modules: &[Module<'_>] =
-    match read_header(&arena, &mut buf, path)?.header {
-        Header::Interface(_) => {
-            todo!();
-            // TODO report error
+    let mut files = BTreeMap::new();
+    let mut missing = Vec::new();
+    let root_archive_path = archive_path(root_dir, path);
+
+    // Modules still to be visited, along with why they're needed
+    let mut stack: Vec<(String, BundleReason)> = Vec::new();
+    let is_platform = match read_header(&arena, path)?.header {
+        Header::Package(header) => {
+            for name in exposed_module_names(header.exposes.item) {
+                stack.push((name, BundleReason::Exposed));
+            }
+
+            false
         }
-        Header::App(_) => {
-            todo!();
-            // TODO report error
+        Header::Platform(header) => {
+            for name in exposed_module_names(header.exposes.item) {
+                stack.push((name, BundleReason::Exposed));
+            }
+
+            for name in imported_module_names(header.imports.item) {
+                stack.push((name, BundleReason::ImportedBy(root_archive_path.clone())));
+            }
+
+            true
         }
-        Header::Hosted(_) => {
-            todo!();
-            // TODO report error
+        Header::Interface(_) | Header::App(_) | Header::Hosted(_) => {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} is not a package or platform module, so it can't be bundled.",
+                    path.display()
+                ),
+            ));
         }
-        Header::Package(_) => {
-            add_dot_roc_files(root_dir, &mut builder)?;
+    };
+
+    files.insert(
+        root_archive_path,
+        (path.to_path_buf(), BundleReason::RootModule),
+    );
+
+    while let Some((module_name, reason)) = stack.pop() {
+        let module_archive_path = format!("{}.roc", module_name.replace('.', "/"));
+
+        if files.contains_key(&module_archive_path) {
+            continue;
         }
-        Header::Platform(PlatformHeader { imports: _, .. }) => {
-            // Add all the prebuilt host files to the archive.
-            // These should all be in the same directory as the platform module.
-            for entry in std::fs::read_dir(root_dir)? {
-                let path = entry?.path();
-
-                if [
-                    // surgical linker format
-                    Some("rh"),
-                    // metadata file
-                    Some("rm"),
-                    // legacy linker formats
-                    Some("o"),
-                    Some("obj"),
-                    Some("wasm"),
-                    // optimized wasm builds compile to .zig for now,
-                    // because zig can't emit .bc for wasm yet.
-                    Some("zig"),
-                ]
-                .contains(&path.extension().and_then(OsStr::to_str))
-                {
-                    builder.append_path_with_name(
-                        &path,
-                        // Store it without the root path, so that (for example) we don't store
-                        // `examples/cli/main.roc` and therefore end up with the root of the tarball
-                        // being an `examples/cli/` dir instead of having `main.roc` in the root.
-                        path.strip_prefix(root_dir).unwrap(),
-                    )?;
-                }
+
+        let module_path = root_dir.join(&module_archive_path);
+
+        if !module_path.is_file() {
+            // Builtin modules like Str and List can be imported without being in the bundle.
+            let qualified_name = QualifiedModuleName {
+                opt_package: None,
+                module: module_name.as_str().into(),
+            };
+
+            if !qualified_name.is_builtin() {
+                missing.push((module_archive_path, reason));
             }
 
-            add_dot_roc_files(root_dir, &mut builder)?;
+            continue;
         }
-    };
 
-    // TODO: This will be necessary when bundling packages (not platforms, since platforms just
-    // slurp up the whole directory at the moment) and also platforms in a future where they
-    // have precompiled hosts, and we only need to grab the .roc files and the precompiled hostfiles!
-    // {
-    //     // Repeat this process on each of the root module's imports.
-    //     let mut stack = Vec::from_iter_in(other_modules, &arena);
-    //     let mut visited_paths = HashSet::from_iter([path]);
-
-    //     // We could do this all in parallel, but a simple stack seems fast enough for this use case.
-    //     while let Some(path) = stack.pop() {
-    //         let other_modules = match read_header(&arena, &mut buf, path) {
-    //             Module::Interface { .. } => {
-    //                 // TODO use header.imports
-    //                 builder.append_path(path)?;
-    //             }
-    //             Module::App { .. } => {
-    //                 // TODO report error
-    //             }
-    //             Module::Hosted { header } => {
-    //                 // TODO report error
-    //             }
-    //             Module::Platform { header } => {
-    //                 // TODO report error
-    //             }
-    //         };
-    //         let other_paths = todo!("infer from other_modules");
-
-    //         // Recurse on the other paths in the header
-    //         for other_path in other_paths {
-    //             if !visited_paths.contains(other_path) {
-    //                 stack.push(other_path);
-    //             }
-    //         }
-
-    //         paths_visited.insert(path);
-    //     }
-    // }
-
-    builder.finish()
-}
+        if let Header::Interface(header) = read_header(&arena, &module_path)?.header {
+            for name in imported_module_names(header.imports.item) {
+                stack.push((name, BundleReason::ImportedBy(module_archive_path.clone())));
+            }
+        }
+
+        files.insert(module_archive_path, (module_path, reason));
+    }
 
-fn add_dot_roc_files<W: Write>(
-    root_dir: &Path,
-    builder: &mut tar::Builder<W>,
-) -> Result<(), io::Error> {
+    // Include any other .roc files in the package's directory tree. They might not be imported,
+    // but (for example) they could be used by tests or examples in the package.
     for entry in WalkDir::new(root_dir).into_iter().filter_entry(|entry| {
         let path = entry.path();
 
@@ -237,40 +323,244 @@ fn add_dot_roc_files<W: Write>(
         // added based on the paths of the files inside anyway. (In fact, if we don't
         // filter out directories in this step, then empty ones can sometimes be added!)
         if path.is_file() {
-            builder.append_path_with_name(
-                path,
-                // Store it without the root path, so that (for example) we don't store
-                // `examples/cli/main.roc` and therefore end up with the root of the tarball
-                // being an `examples/cli/` dir instead of having `main.roc` in the root.
-                path.strip_prefix(root_dir).unwrap(),
-            )?;
+            files
+                .entry(archive_path(root_dir, path))
+                .or_insert_with(|| (path.to_path_buf(), BundleReason::InPackageDir));
         }
     }
 
-    Ok(())
-}
+    if is_platform {
+        // Add all the prebuilt host files to the archive.
+        // These should all be in the same directory as the platform module.
+        for entry in fs::read_dir(root_dir)? {
+            let path = entry?.path();
 
-fn read_header<'a>(
-    arena: &'a Bump,
-    buf: &'a mut Vec<u8>,
-    path: &'a Path,
-) -> io::Result<Module<'a>> {
-    // Read all the bytes into the buffer.
-    {
-        let mut file = File::open(path)?;
-        buf.clear();
-        file.read_to_end(buf)?;
+            if [
+                // surgical linker format
+                Some("rh"),
+                // metadata file
+                Some("rm"),
+                // legacy linker formats
+                Some("o"),
+                Some("obj"),
+                Some("wasm"),
+                // optimized wasm builds compile to .zig for now,
+                // because zig can't emit .bc for wasm yet.
+                Some("zig"),
+            ]
+            .contains(&path.extension().and_then(OsStr::to_str))
+            {
+                files.insert(
+                    archive_path(root_dir, &path),
+                    (path, BundleReason::PrebuiltHost),
+                );
+            }
+        }
     }
 
-    // TODO avoid copying the contents of the file into a Bumpalo arena by doing multiple
-    // https://doc.rust-lang.org/std/io/trait.Read.html#tymethod.read calls instead of
-    // using the much more convenient file.read_to_end - which requires a std::vec::Vec.
-    // (We can't use that for the parser state and still return Module<'a> unfortunately.)
-    let arena_buf = bumpalo::collections::Vec::from_iter_in(buf.iter().copied(), arena);
-    let parse_state = State::new(arena_buf.into_bump_slice());
-    let (module, _) = parse_header(arena, parse_state).unwrap_or_else(|_err| {
-        todo!(); // TODO report a nice error and exit 1 - or maybe just return Err, for better testability?
-    });
+    Ok(BundleContents { files, missing })
+}
+
+/// The path to store the given file at in the archive. This is relative to the root dir, so that
+/// (for example) we don't store `examples/cli/main.roc` and therefore end up with the root of
+/// the tarball being an `examples/cli/` dir instead of having `main.roc` in the root.
+/// It always uses / as the separator, so that bundles built on Windows have the same hash.
+fn archive_path(root_dir: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root_dir).unwrap();
+    let components: Vec<_> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+
+    components.join("/")
+}
+
+fn exposed_module_names(exposes: Collection<'_, Loc<Spaced<'_, ModuleName<'_>>>>) -> Vec<String> {
+    exposes
+        .iter()
+        .map(|name| name.value.item().as_str().to_string())
+        .collect()
+}
+
+/// The modules imported from within the same package (as opposed to from other packages
+/// via shorthands like `pf.Task`)
+fn imported_module_names(
+    imports: Collection<'_, Loc<Spaced<'_, ImportsEntry<'_>>>>,
+) -> Vec<String> {
+    imports
+        .iter()
+        .filter_map(|entry| match entry.value.item() {
+            ImportsEntry::Module(name, _) => Some(name.as_str().to_string()),
+            ImportsEntry::Package(..) | ImportsEntry::IngestedFile(..) => None,
+        })
+        .collect()
+}
+
+fn read_header<'a>(arena: &'a Bump, path: &Path) -> io::Result<Module<'a>> {
+    let bytes = arena.alloc_slice_copy(&fs::read(path)?);
+    let parse_state = State::new(bytes);
+    let (module, _) = parse_header(arena, parse_state).map_err(|_| {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "I couldn't parse the header of {}. Run `roc check` on it for details.",
+                path.display()
+            ),
+        )
+    })?;
 
     Ok(module)
 }
+
+/// What `verify` found out about a bundle
+#[derive(Debug)]
+pub struct Verification {
+    /// The hash in the bundle's URL or filename
+    pub expected_hash: String,
+    /// The hash of the bundle's actual contents
+    pub actual_hash: String,
+    /// Every file in the bundle which is needed by its root module, and why
+    pub files: Vec<BundledFile>,
+    /// Modules which are exposed or imported by a module in the bundle, but which are missing
+    /// from it, along with why they were needed
+    pub missing: Vec<BundledFile>,
+}
+
+impl Verification {
+    pub fn is_ok(&self) -> bool {
+        self.expected_hash == self.actual_hash && self.missing.is_empty()
+    }
+}
+
+/// Unpack the bundle at the given https:// URL, file:// URL, or filesystem path into a temporary
+/// directory. Then recompute its hash, and check that every module its root module exposes or
+/// (transitively) imports is included in it. The root module defaults to main.roc, but
+/// (as with package URLs) it can be specified using a URL fragment, e.g. #Package.roc
+#[cfg(not(target_family = "wasm"))]
+pub fn verify(url_or_path: &str) -> Result<Verification, Problem> {
+    let tempdir = tempfile::tempdir().map_err(Problem::IoErr)?;
+    let tempdir_path = tempdir.path();
+
+    let (expected_hash, opt_root_module, actual_hash) = if url_or_path.starts_with("https://") {
+        let metadata = PackageMetadata::try_from(url_or_path).map_err(Problem::InvalidUrl)?;
+        let actual_hash =
+            https::download_and_hash(url_or_path, tempdir_path, crate::cache::MAX_DOWNLOAD_BYTES)?;
+
+        (
+            metadata.content_hash.to_string(),
+            metadata.root_module_filename,
+            actual_hash,
+        )
+    } else {
+        let (path, opt_root_module) =
+            crate::cache::split_file_url(url_or_path).unwrap_or((url_or_path, None));
+        let path = PathBuf::from(path);
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let expected_hash = match filename.split_once(".tar") {
+            Some((hash, _)) => hash.to_string(),
+            None => return Err(Problem::InvalidUrl(crate::https::UrlProblem::MissingTarExt)),
+        };
+        let actual_hash = https::unpack_and_hash(&path, tempdir_path)?;
+
+        (expected_hash, opt_root_module, actual_hash)
+    };
+
+    let root_module = tempdir_path.join(opt_root_module.unwrap_or("main.roc"));
+    let BundleContents { files, missing } =
+        find_bundle_contents(&root_module).map_err(Problem::IoErr)?;
+
+    Ok(Verification {
+        expected_hash,
+        actual_hash,
+        files: files
+            .into_iter()
+            .map(|(archive_path, (_, reason))| BundledFile {
+                archive_path,
+                reason,
+            })
+            .collect(),
+        missing: missing
+            .into_iter()
+            .map(|(archive_path, reason)| BundledFile {
+                archive_path,
+                reason,
+            })
+            .collect(),
+    })
+}
+
+#[test]
+fn bundling_is_deterministic() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = dir.path().join("main.roc");
+
+    fs::write(
+        &main,
+        "package \"example\"\n    exposes [Foo]\n    packages {}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("Foo.roc"),
+        "interface Foo\n    exposes [foo]\n    imports [Bar, Str]\n\nfoo = Bar.bar\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("Bar.roc"),
+        "interface Bar\n    exposes [bar]\n    imports []\n\nbar = 1\n",
+    )
+    .unwrap();
+
+    let mut first = Vec::new();
+    let files = write_archive(&main, &mut first).unwrap();
+
+    // Rewriting a file with the same contents changes its mtime, but not the archive.
+    fs::write(
+        dir.path().join("Bar.roc"),
+        "interface Bar\n    exposes [bar]\n    imports []\n\nbar = 1\n",
+    )
+    .unwrap();
+
+    let mut second = Vec::new();
+    write_archive(&main, &mut second).unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(
+        files,
+        [
+            BundledFile {
+                archive_path: "Bar.roc".to_string(),
+                reason: BundleReason::ImportedBy("Foo.roc".to_string()),
+            },
+            BundledFile {
+                archive_path: "Foo.roc".to_string(),
+                reason: BundleReason::Exposed,
+            },
+            BundledFile {
+                archive_path: "main.roc".to_string(),
+                reason: BundleReason::RootModule,
+            },
+        ]
+    );
+}
+
+#[test]
+fn bundling_reports_missing_modules() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = dir.path().join("main.roc");
+
+    fs::write(
+        &main,
+        "package \"example\"\n    exposes [Foo]\n    packages {}\n",
+    )
+    .unwrap();
+
+    let err = write_archive(&main, Vec::new()).unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(err
+        .to_string()
+        .starts_with("Foo.roc (exposed by the root module)"));
+}
@@ -0,0 +1,330 @@
+//! Copying a Roc app's package dependencies into the app's own directory (`roc vendor`),
+//! and the lock file which records which packages were copied.
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+#[cfg(not(target_family = "wasm"))]
+use {
+    crate::cache::RocCacheDir,
+    crate::dependencies::{
+        add_requirement, read_package_strs, walk_dependencies, DependencyProblem,
+    },
+    crate::https::PackageMetadata,
+    crate::version::Resolver,
+};
+
+/// The name of the lock file, which lives in the same directory as the app module.
+pub const LOCKFILE_NAME: &str = "roc.lock";
+
+/// The name of the directory (next to the lock file) where vendored packages are stored.
+/// Inside it, packages are laid out the same way as in the Roc cache dir,
+/// e.g. vendor/example.com/roc-packages/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE
+pub const VENDOR_DIR_NAME: &str = "vendor";
+
+const LOCKFILE_HEADER: &str = "\
+# This file was generated by `roc vendor`. Don't edit it by hand!
+# Each line is the URL of a package this app depends on (directly or transitively),
+# followed by the hash of the package's files in the vendor/ directory next to this file.
+";
+
+/// The contents of a roc.lock file: every package URL an app depends on, directly or transitively.
+/// When an app's directory has a lock file, packages are loaded from its vendor directory instead
+/// of from the Roc cache dir, and nothing is downloaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lockfile {
+    /// e.g. /home/me/my-app/vendor
+    vendor_dir: PathBuf,
+    /// Package URLs, and the hashes of their vendored files (see `hash_dir`).
+    /// Sorted so that the lock file's contents are deterministic.
+    urls: BTreeMap<String, String>,
+}
+
+impl Lockfile {
+    pub fn new(app_dir: &Path) -> Self {
+        Self {
+            vendor_dir: app_dir.join(VENDOR_DIR_NAME),
+            urls: BTreeMap::new(),
+        }
+    }
+
+    /// Read the lock file in the given directory, if there is one.
+    pub fn read(app_dir: &Path) -> io::Result<Option<Self>> {
+        match fs::read_to_string(app_dir.join(LOCKFILE_NAME)) {
+            Ok(contents) => Ok(Some(Self::parse(app_dir, &contents))),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn parse(app_dir: &Path, contents: &str) -> Self {
+        let mut lockfile = Self::new(app_dir);
+
+        for line in contents.lines().map(str::trim) {
+            if !line.is_empty() && !line.starts_with('#') {
+                // A missing hash will never match, so the package has to be vendored again.
+                let (url, hash) = line.split_once(' ').unwrap_or((line, ""));
+                lockfile
+                    .urls
+                    .insert(url.to_string(), hash.trim().to_string());
+            }
+        }
+
+        lockfile
+    }
+
+    /// Write the lock file into the given directory.
+    pub fn write(&self, app_dir: &Path) -> io::Result<()> {
+        fs::write(app_dir.join(LOCKFILE_NAME), self.to_string())
+    }
+
+    pub fn vendor_dir(&self) -> &Path {
+        &self.vendor_dir
+    }
+
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        self.urls.keys().map(String::as_str)
+    }
+
+    pub fn contains(&self, url: &str) -> bool {
+        self.urls.contains_key(url)
+    }
+
+    /// The hash the given package's vendored files had when it was vendored
+    pub fn vendored_hash(&self, url: &str) -> Option<&str> {
+        self.urls.get(url).map(String::as_str)
+    }
+
+    /// The directory the given package was vendored into,
+    /// or None if the package isn't in this lock file.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn vendored_package_dir(&self, url: &str) -> Option<PathBuf> {
+        if !self.contains(url) {
+            return None;
+        }
+
+        let metadata = PackageMetadata::try_from(url).ok()?;
+
+        Some(
+            self.vendor_dir
+                .join(metadata.cache_subdir)
+                .join(metadata.content_hash),
+        )
+    }
+}
+
+impl std::fmt::Display for Lockfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(LOCKFILE_HEADER)?;
+
+        for (url, hash) in self.urls.iter() {
+            writeln!(f, "{url} {hash}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Find every package the given app depends on (directly, or through other packages and
+/// platforms), install each one into the cache dir, copy it into the vendor directory next to
+/// the app, and write a lock file listing them. Returns the lock file that was written.
+/// Nothing is written if two of the packages require incompatible versions of the same package.
+///
+/// Packages which are already vendored are not copied again, unless their files were changed.
+/// Vendored packages which are no longer depended on are left alone; delete the vendor directory
+/// to clean them up.
+#[cfg(not(target_family = "wasm"))]
+pub fn vendor_packages(
+    roc_cache_dir: RocCacheDir<'_>,
+    app_path: &Path,
+) -> Result<Lockfile, DependencyProblem> {
+    let app_dir = app_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut lockfile = Lockfile::new(app_dir);
+    let mut resolver = Resolver::new();
+    let mut to_copy = BTreeMap::new();
+
+    walk_dependencies(
+        roc_cache_dir,
+        app_path,
+        read_package_strs(app_path)?,
+        |dependency| {
+            add_requirement(&mut resolver, dependency).map_err(DependencyProblem::Version)?;
+
+            // Local packages aren't vendored, but the packages they depend on are.
+            if let Some(installed_dir) = dependency.installed_dir {
+                to_copy
+                    .entry(dependency.package_str.to_string())
+                    .or_insert_with(|| installed_dir.to_path_buf());
+            }
+
+            Ok(())
+        },
+    )?;
+
+    for (package_str, package_dir) in to_copy {
+        let hash = hash_dir(&package_dir)
+            .map_err(|err| DependencyProblem::IoErr(package_dir.clone(), err))?;
+        lockfile.urls.insert(package_str.clone(), hash.clone());
+
+        // Safe to unwrap; it was installed, so it's a valid package URL.
+        let vendored_dir = lockfile.vendored_package_dir(&package_str).unwrap();
+
+        if !vendored_dir.exists() || hash_dir(&vendored_dir).ok().as_ref() != Some(&hash) {
+            println!(
+                "Vendoring \u{001b}[36m{package_str}\u{001b}[0m\n    into {}\n",
+                vendored_dir.display()
+            );
+            remove_dir_if_exists(&vendored_dir)?;
+            copy_dir(&package_dir, &vendored_dir)?;
+        }
+    }
+
+    lockfile
+        .write(app_dir)
+        .map_err(|err| DependencyProblem::IoErr(app_dir.join(LOCKFILE_NAME), err))?;
+
+    Ok(lockfile)
+}
+
+/// Hash every file in the given directory, along with its path relative to the directory,
+/// returning the base64url-encoded BLAKE3 hash. The order files are found in doesn't matter.
+#[cfg(not(target_family = "wasm"))]
+pub fn hash_dir(dir: &Path) -> io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+
+    for entry in walkdir::WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry.map_err(io::Error::from)?;
+
+        if entry.file_type().is_file() {
+            let relative_path = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+            let contents = fs::read(entry.path())?;
+
+            // Use forward slashes so the hash is the same on every OS.
+            // Lengths are hashed too, so different files can't produce the same bytes.
+            let path_str = relative_path.to_string_lossy().replace('\\', "/");
+            hasher.update(&(path_str.len() as u64).to_le_bytes());
+            hasher.update(path_str.as_bytes());
+            hasher.update(&(contents.len() as u64).to_le_bytes());
+            hasher.update(&contents);
+        }
+    }
+
+    Ok(base64_url::encode(hasher.finalize().as_bytes()))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn remove_dir_if_exists(dir: &Path) -> Result<(), DependencyProblem> {
+    match fs::remove_dir_all(dir) {
+        Err(err) if err.kind() != ErrorKind::NotFound => {
+            Err(DependencyProblem::IoErr(dir.to_path_buf(), err))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn copy_dir(src: &Path, dest: &Path) -> Result<(), DependencyProblem> {
+    fs::create_dir_all(dest).map_err(|err| DependencyProblem::IoErr(dest.to_path_buf(), err))?;
+
+    fs_extra::dir::copy(
+        src,
+        dest,
+        &fs_extra::dir::CopyOptions {
+            content_only: true,
+            overwrite: true,
+            ..Default::default()
+        },
+    )
+    .map_err(DependencyProblem::FsExtraErr)?;
+
+    Ok(())
+}
+
+#[test]
+fn lockfile_round_trip() {
+    let app_dir = Path::new("/home/me/my-app");
+    let mut lockfile = Lockfile::new(app_dir);
+
+    lockfile.urls.insert(
+        "https://example.com/pkgs/hashB.tar.br".to_string(),
+        "dirHashB".to_string(),
+    );
+    lockfile.urls.insert(
+        "https://example.com/pkgs/hashA.tar.gz#Pkg.roc".to_string(),
+        "dirHashA".to_string(),
+    );
+
+    let contents = lockfile.to_string();
+
+    assert!(contents.starts_with(LOCKFILE_HEADER));
+    assert!(contents.ends_with("https://example.com/pkgs/hashB.tar.br dirHashB\n"));
+    assert_eq!(Lockfile::parse(app_dir, &contents), lockfile);
+    assert_eq!(
+        lockfile.vendored_hash("https://example.com/pkgs/hashA.tar.gz#Pkg.roc"),
+        Some("dirHashA")
+    );
+    assert_eq!(
+        lockfile.urls().collect::<Vec<_>>(),
+        [
+            "https://example.com/pkgs/hashA.tar.gz#Pkg.roc",
+            "https://example.com/pkgs/hashB.tar.br"
+        ]
+    );
+}
+
+#[test]
+fn vendored_package_dir_mirrors_cache_layout() {
+    let app_dir = Path::new("/home/me/my-app");
+    let url = "https://example.com/pkgs/hash.tar.br#Pkg.roc";
+    let mut lockfile = Lockfile::new(app_dir);
+
+    assert_eq!(lockfile.vendored_package_dir(url), None);
+
+    lockfile.urls.insert(url.to_string(), "dirHash".to_string());
+
+    assert_eq!(
+        lockfile.vendored_package_dir(url),
+        Some(app_dir.join("vendor").join("example.com/pkgs").join("hash"))
+    );
+}
+
+#[test]
+#[cfg(not(target_family = "wasm"))]
+fn vendored_packages_are_verified() {
+    use crate::cache::install_package;
+    use crate::https::Problem;
+
+    let app_dir = tempfile::tempdir().unwrap();
+    let url = "https://example.invalid/pkgs/hash.tar.br";
+    let mut lockfile = Lockfile::new(app_dir.path());
+    lockfile.urls.insert(url.to_string(), String::new());
+
+    let package_dir = lockfile.vendored_package_dir(url).unwrap();
+    fs::create_dir_all(&package_dir).unwrap();
+    fs::write(
+        package_dir.join("main.roc"),
+        "package \"pkg\" exposes [] packages {}\n",
+    )
+    .unwrap();
+
+    let hash = hash_dir(&package_dir).unwrap();
+    lockfile.urls.insert(url.to_string(), hash.clone());
+
+    let (installed_dir, _) = install_package(RocCacheDir::Vendored(&lockfile), url).unwrap();
+    assert_eq!(installed_dir, package_dir);
+
+    // Editing a vendored file is caught, even though the package is still in the lock file
+    fs::write(
+        package_dir.join("main.roc"),
+        "package \"evil\" exposes [] packages {}\n",
+    )
+    .unwrap();
+
+    match install_package(RocCacheDir::Vendored(&lockfile), url) {
+        Err(Problem::VendoredPackageModified { expected, actual }) => {
+            assert_eq!(expected, hash);
+            assert_eq!(actual, hash_dir(&package_dir).unwrap());
+        }
+        other => panic!("expected the vendored package to be rejected, got {other:?}"),
+    }
+}
@@ -0,0 +1,194 @@
+//! Semantic versions of packages, and detecting when an app's dependencies require
+//! incompatible versions of the same package.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// A semantic version, e.g. the 1.2.3 in `package "roc/json" version "1.2.3"`.
+/// Pre-release and build metadata suffixes aren't supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidVersion(pub String);
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Two versions are compatible if code written against one keeps working with the other.
+    /// Before 1.0.0 every minor release may be breaking, so 0.x.y versions are only
+    /// compatible when their minor versions match.
+    pub fn is_compatible_with(&self, other: &Version) -> bool {
+        if self.major == 0 || other.major == 0 {
+            self.major == other.major && self.minor == other.minor
+        } else {
+            self.major == other.major
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = InvalidVersion;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidVersion(string.to_string());
+        let mut parts = string.split('.').map(|part| {
+            // Reject things like "+1" and "01" which u64::from_str would otherwise accept.
+            if part.is_empty()
+                || !part.bytes().all(|byte| byte.is_ascii_digit())
+                || (part.len() > 1 && part.starts_with('0'))
+            {
+                Err(invalid())
+            } else {
+                part.parse::<u64>().map_err(|_| invalid())
+            }
+        });
+
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(major), Some(minor), Some(patch), None) => {
+                Ok(Version::new(major?, minor?, patch?))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// One module's dependency on a particular version of a package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requirement {
+    /// The package's URL (or path), as written in the `packages` section of the module
+    pub package_str: String,
+    pub version: Version,
+    /// The module whose `packages` section has this dependency
+    pub required_by: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionProblem {
+    /// The `version` in a package's header isn't a valid semantic version
+    Invalid {
+        package_str: String,
+        version: String,
+        required_by: PathBuf,
+    },
+    /// Two modules depend on versions of the same package which aren't compatible
+    Conflict {
+        package_name: String,
+        existing: Requirement,
+        new: Requirement,
+    },
+}
+
+/// Tracks which versions of each package (by the name in its `package` header) an app
+/// depends on, and rejects a version that's incompatible with one seen earlier. Packages
+/// without a `version` aren't tracked, since there's nothing to compare.
+#[derive(Debug, Default)]
+pub struct Resolver {
+    by_name: BTreeMap<String, Vec<Requirement>>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(
+        &mut self,
+        package_name: &str,
+        requirement: Requirement,
+    ) -> Result<(), VersionProblem> {
+        let requirements = self.by_name.entry(package_name.to_string()).or_default();
+
+        if let Some(existing) = requirements
+            .iter()
+            .find(|existing| !existing.version.is_compatible_with(&requirement.version))
+        {
+            return Err(VersionProblem::Conflict {
+                package_name: package_name.to_string(),
+                existing: existing.clone(),
+                new: requirement,
+            });
+        }
+
+        requirements.push(requirement);
+
+        Ok(())
+    }
+
+    /// The newest required version of each package, sorted by package name.
+    pub fn resolved(&self) -> impl Iterator<Item = (&str, Version)> {
+        self.by_name.iter().filter_map(|(name, requirements)| {
+            let newest = requirements.iter().map(|req| req.version).max()?;
+
+            Some((name.as_str(), newest))
+        })
+    }
+}
+
+#[test]
+fn parse_versions() {
+    assert_eq!("1.2.3".parse(), Ok(Version::new(1, 2, 3)));
+    assert_eq!("0.10.0".parse(), Ok(Version::new(0, 10, 0)));
+
+    for invalid in [
+        "", "1", "1.2", "1.2.3.4", "1.02.3", "1.-2.3", "1.+2.3", "v1.2.3",
+    ] {
+        assert_eq!(
+            invalid.parse::<Version>(),
+            Err(InvalidVersion(invalid.to_string()))
+        );
+    }
+}
+
+#[test]
+fn resolver_detects_conflicts() {
+    let requirement = |version: Version, required_by: &str| Requirement {
+        package_str: format!("https://example.com/json-{version}.tar.br"),
+        version,
+        required_by: PathBuf::from(required_by),
+    };
+    let mut resolver = Resolver::new();
+
+    resolver
+        .add("roc/json", requirement(Version::new(1, 2, 0), "main.roc"))
+        .unwrap();
+    resolver
+        .add("roc/json", requirement(Version::new(1, 4, 1), "Dep.roc"))
+        .unwrap();
+    resolver
+        .add("roc/http", requirement(Version::new(0, 3, 0), "main.roc"))
+        .unwrap();
+
+    assert_eq!(
+        resolver.resolved().collect::<Vec<_>>(),
+        [
+            ("roc/http", Version::new(0, 3, 0)),
+            ("roc/json", Version::new(1, 4, 1))
+        ]
+    );
+
+    assert!(matches!(
+        resolver.add("roc/json", requirement(Version::new(2, 0, 0), "Other.roc")),
+        Err(VersionProblem::Conflict { existing, .. }) if existing.version == Version::new(1, 2, 0)
+    ));
+    assert!(resolver
+        .add("roc/http", requirement(Version::new(0, 4, 0), "Other.roc"))
+        .is_err());
+}
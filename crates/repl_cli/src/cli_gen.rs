@@ -222,6 +222,7 @@ fn mono_module_to_dylib_llvm<'a>(
         mode: LlvmBackendMode::GenTest, // so roc_panic is generated
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        source_locations: None,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -316,6 +317,7 @@ fn mono_module_to_dylib_asm<'a>(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: roc_gen_dev::AssemblyBackendMode::Repl,
+        source_locations: None,
     };
 
     let target = target_lexicon::Triple::host();
@@ -352,6 +354,7 @@ fn mono_module_to_dylib_asm<'a>(
             builtins_host_tempfile.path().to_str().unwrap(),
         ],
         roc_build::link::LinkType::Dylib,
+        false,
     )
     .expect("failed to link dynamic library");
 
@@ -8,13 +8,17 @@ use roc_mono::ir::OptLevel;
 use roc_repl_eval::gen::Problems;
 use roc_repl_ui::colors::{BLUE, END_COL, PINK};
 use roc_repl_ui::repl_state::{ReplAction, ReplState};
-use roc_repl_ui::{format_output, is_incomplete, CONT_PROMPT, PROMPT, SHORT_INSTRUCTIONS, TIPS};
+use roc_repl_ui::{
+    format_output, format_types, is_incomplete, CONT_PROMPT, PROMPT, SHORT_INSTRUCTIONS, TIPS,
+};
 use roc_reporting::report::{ANSI_STYLE_CODES, DEFAULT_PALETTE};
 use roc_target::TargetInfo;
+use rustyline::completion::Completer;
 use rustyline::highlight::{Highlighter, PromptInfo};
 use rustyline::validate::{self, ValidationContext, ValidationResult, Validator};
-use rustyline_derive::{Completer, Helper, Hinter};
+use rustyline_derive::{Helper, Hinter};
 use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use target_lexicon::Triple;
 
 use crate::cli_gen::eval_llvm;
@@ -31,7 +35,7 @@ pub const WELCOME_MESSAGE: &str = concatcp!(
     "\n\n"
 );
 
-#[derive(Completer, Helper, Hinter, Default)]
+#[derive(Helper, Hinter, Default)]
 pub struct ReplHelper {
     validator: InputValidator,
     state: ReplState,
@@ -49,6 +53,14 @@ pub fn main() -> i32 {
     let mut editor = Editor::<ReplHelper>::new();
     let repl_helper = ReplHelper::default();
     editor.set_helper(Some(repl_helper));
+
+    let history_path = history_path();
+
+    if let Some(path) = &history_path {
+        // There's no history yet the first time the repl runs, which is fine.
+        let _ = editor.load_history(path);
+    }
+
     let target = Triple::host();
     let target_info = TargetInfo::from(&target);
     let mut arena = Bump::new();
@@ -60,6 +72,11 @@ pub fn main() -> i32 {
 
                 editor.add_history_entry(line);
 
+                // Save after every entry, so that history isn't lost if the repl crashes.
+                if let Some(path) = &history_path {
+                    save_history(&mut editor, path);
+                }
+
                 let repl_state = &mut editor
                     .helper_mut()
                     .expect("Editor helper was not set")
@@ -75,6 +92,19 @@ pub fn main() -> i32 {
                             println!("{output}");
                         }
                     }
+                    ReplAction::Types {
+                        type_defs,
+                        values,
+                        problems,
+                    } => {
+                        println!(
+                            "{}",
+                            format_types(ANSI_STYLE_CODES, type_defs, values, problems)
+                        );
+                    }
+                    ReplAction::Message(message) => {
+                        println!("{message}");
+                    }
                     ReplAction::Exit => {
                         return 0;
                     }
@@ -104,6 +134,62 @@ pub fn main() -> i32 {
     }
 }
 
+/// Where the repl remembers what was entered into it, so that it's available in later sessions.
+/// This follows the same conventions as the packages cache, but in the user's data dir:
+/// ~/.local/share/roc/repl_history will be typical on UNIX, and %APPDATA%\\Roc\\repl_history on Windows.
+///
+/// Returns None if none of those directories can be determined, in which case history
+/// only lasts for the current session.
+fn history_path() -> Option<PathBuf> {
+    use std::env;
+
+    const HISTORY_FILE_NAME: &str = "repl_history";
+
+    // Respect XDG, if the system appears to be using it.
+    // https://specifications.freedesktop.org/basedir-spec/basedir-spec-latest.html
+    if let Some(xdg_data_home) = env::var_os("XDG_DATA_HOME") {
+        return Some(
+            Path::new(&xdg_data_home)
+                .join("roc")
+                .join(HISTORY_FILE_NAME),
+        );
+    }
+
+    #[cfg(windows)]
+    {
+        // e.g. %APPDATA%\\Roc
+        env::var_os("APPDATA")
+            .or_else(|| env::var_os("CSIDL_APPDATA"))
+            .map(|appdata| Path::new(&appdata).join("Roc").join(HISTORY_FILE_NAME))
+    }
+
+    #[cfg(not(windows))]
+    {
+        // e.g. $HOME/.local/share/roc
+        env::var_os("HOME").map(|home| {
+            Path::new(&home)
+                .join(".local")
+                .join("share")
+                .join("roc")
+                .join(HISTORY_FILE_NAME)
+        })
+    }
+}
+
+/// Writes the editor's history to `path`, creating the directory it goes in if needed.
+pub fn save_history(editor: &mut rustyline::Editor<ReplHelper>, path: &Path) {
+    let result = match path.parent() {
+        Some(dir) => std::fs::create_dir_all(dir).map_err(rustyline::error::ReadlineError::from),
+        None => Ok(()),
+    }
+    .and_then(|()| editor.save_history(path));
+
+    // Not being able to save history shouldn't get in the way of using the repl.
+    if let Err(err) = result {
+        eprintln!("Unable to save repl history to {}: {err}", path.display());
+    }
+}
+
 pub fn evaluate(
     opt_mono: Option<MonomorphizedModule<'_>>,
     problems: Problems,
@@ -126,6 +212,27 @@ impl Validator for InputValidator {
     }
 }
 
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        // Complete the whole (possibly qualified) name the cursor is at the end of, e.g. `List.ma`
+        let start = line[..pos]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '.')
+            .last()
+            .map_or(pos, |(index, _)| index);
+
+        Ok((start, self.state.completions(&line[start..pos])))
+    }
+}
+
 impl Highlighter for ReplHelper {
     fn has_continuation_prompt(&self) -> bool {
         true
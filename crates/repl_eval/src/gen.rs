@@ -3,16 +3,23 @@ use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_problem::Severity;
 use roc_reporting::report::Palette;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use roc_can::expr::DeclarationTag;
+use roc_collections::MutMap;
 use roc_fmt::annotation::Formattable;
 use roc_fmt::annotation::{Newlines, Parens};
-use roc_load::{LoadingProblem, MonomorphizedModule};
+use roc_load::{LoadedModule, LoadingProblem, MonomorphizedModule};
+use roc_module::symbol::{Interns, ModuleId};
 use roc_parse::ast::Expr;
 use roc_region::all::LineInfo;
-use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
+use roc_reporting::report::{
+    can_problem, to_file_problem_report_string, type_problem, RocDocAllocator,
+};
 use roc_solve::FunctionKind;
+use roc_solve_problem::TypeError;
 use roc_target::TargetInfo;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
 
 #[derive(Debug)]
 pub struct ReplOutput {
@@ -47,14 +54,16 @@ impl Problems {
 
 pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
+    src_dir: Option<&Path>,
+    imports: &[String],
     defs: I,
     expr: &str,
     target_info: TargetInfo,
     palette: Palette,
 ) -> (Option<MonomorphizedModule<'a>>, Problems) {
     let filename = PathBuf::from("");
-    let src_dir = PathBuf::from("fake/test/path");
-    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, defs, expr);
+    let src_dir = src_dir.map_or_else(|| PathBuf::from(DEFAULT_SRC_DIR), Path::to_path_buf);
+    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, imports, defs, expr);
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
         filename,
@@ -79,17 +88,8 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
                 (m.can_problems, m.type_problems)
             );
         }
-        Err(LoadMonomorphizedError::LoadingProblem(LoadingProblem::FormattedReport(report))) => {
-            return (
-                None,
-                Problems {
-                    errors: vec![report],
-                    warnings: Vec::new(),
-                },
-            );
-        }
-        Err(e) => {
-            todo!("error while loading module: {:?}", e)
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return (None, loading_problems(problem));
         }
     };
 
@@ -101,6 +101,136 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         ..
     } = &mut loaded;
 
+    let problems = report_problems(
+        interns,
+        sources,
+        can_problems,
+        type_problems,
+        module_src,
+        bytes_before_expr,
+        palette,
+    );
+
+    (Some(loaded), problems)
+}
+
+/// Solves the types of the REPL's module without evaluating anything, and returns the
+/// type of each of its top-level values by name. The expression is named `replOutput`.
+pub fn solve_types<'a, 'i, I: Iterator<Item = &'i str>>(
+    arena: &'a Bump,
+    src_dir: Option<&Path>,
+    imports: &[String],
+    defs: I,
+    expr: &str,
+    target_info: TargetInfo,
+    palette: Palette,
+) -> (Option<MutMap<String, String>>, Problems) {
+    let filename = PathBuf::from("");
+    let src_dir = src_dir.map_or_else(|| PathBuf::from(DEFAULT_SRC_DIR), Path::to_path_buf);
+    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, imports, defs, expr);
+    let loaded = roc_load::load_and_typecheck_str(
+        arena,
+        filename,
+        module_src,
+        src_dir,
+        target_info,
+        FunctionKind::LambdaSet,
+        roc_reporting::report::RenderTarget::ColorTerminal,
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        palette,
+    );
+
+    let LoadedModule {
+        module_id,
+        interns,
+        solved,
+        sources,
+        mut can_problems,
+        mut type_problems,
+        mut declarations_by_id,
+        ..
+    } = match loaded {
+        Ok(v) => v,
+        Err(problem) => {
+            return (None, loading_problems(problem));
+        }
+    };
+
+    let problems = report_problems(
+        &interns,
+        &sources,
+        &mut can_problems,
+        &mut type_problems,
+        module_src,
+        bytes_before_expr,
+        palette,
+    );
+
+    let mut subs = solved.into_inner();
+    let mut types = MutMap::default();
+
+    if let Some(declarations) = declarations_by_id.remove(&module_id) {
+        for (index, tag) in declarations.declarations.iter().enumerate() {
+            match tag {
+                DeclarationTag::Value
+                | DeclarationTag::Function(_)
+                | DeclarationTag::Recursive(_)
+                | DeclarationTag::TailRecursive(_) => {
+                    let symbol = declarations.symbols[index].value;
+
+                    // Naming a type's variables changes subs, so undo that once it's printed;
+                    // otherwise, the next type would pick up where this one's names left off.
+                    let snapshot = subs.snapshot();
+                    let type_str = name_and_print_var(
+                        declarations.variables[index],
+                        &mut subs,
+                        module_id,
+                        &interns,
+                        DebugPrint::NOTHING,
+                    );
+                    subs.rollback_to(snapshot);
+
+                    types.insert(symbol.as_str(&interns).to_string(), type_str);
+                }
+                DeclarationTag::Expectation
+                | DeclarationTag::ExpectationFx
+                | DeclarationTag::Destructure(_)
+                | DeclarationTag::MutualRecursion { .. } => {}
+            }
+        }
+    }
+
+    (Some(types), problems)
+}
+
+/// The errors for a problem which kept the REPL's module from loading at all, like a
+/// module loaded with `:load` having been deleted since.
+fn loading_problems(problem: LoadingProblem) -> Problems {
+    let error = match problem {
+        LoadingProblem::FormattedReport(report) => report,
+        LoadingProblem::FileProblem { filename, error } => {
+            to_file_problem_report_string(&filename, error)
+        }
+        // TODO: tighten up the types here, we should always end up with a
+        // formatted report from load.
+        problem => format!("Failed with error: {problem:?}"),
+    };
+
+    Problems {
+        errors: vec![error],
+        warnings: Vec::new(),
+    }
+}
+
+fn report_problems(
+    interns: &Interns,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    module_src: &str,
+    bytes_before_expr: usize,
+    palette: Palette,
+) -> Problems {
     let mut problems = Problems::default();
 
     let errors = &mut problems.errors;
@@ -164,19 +294,31 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         }
     }
 
-    (Some(loaded), problems)
+    problems
 }
 
+/// Where the REPL's module looks for the modules it imports, when none have been loaded.
+const DEFAULT_SRC_DIR: &str = "fake/test/path";
+
 fn promote_expr_to_module<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
+    imports: &[String],
     defs: I,
     expr: &str,
 ) -> (usize, &'a str) {
-    const REPL_MODULE_HEADER: &str = "app \"app\" provides [replOutput] to \"./platform\"\n\n";
+    const REPL_MODULE_PROVIDES: &str = "provides [replOutput] to \"./platform\"\n\n";
     const REPL_MODULE_MAIN_DEF: &str = "replOutput =\n";
     const INDENT: &str = "    ";
 
-    let mut buffer = bumpalo::collections::string::String::from_str_in(REPL_MODULE_HEADER, arena);
+    let mut buffer = bumpalo::collections::string::String::from_str_in("app \"app\" ", arena);
+
+    if !imports.is_empty() {
+        buffer.push_str("imports [");
+        buffer.push_str(&imports.join(", "));
+        buffer.push_str("] ");
+    }
+
+    buffer.push_str(REPL_MODULE_PROVIDES);
 
     for line in defs {
         // don't indent the defs
@@ -0,0 +1,371 @@
+//! The processes that run top-level expects for `roc test`, and how they report back.
+//!
+//! Every expect runs in a child process of its own, which writes an [ExpectReport] to a file
+//! before it exits. The parent reads that file once the child is done, and renders the report
+//! itself. If there is no report, the child crashed or had to be stopped, and its exit status
+//! says why. How a child is started depends on the [ChildMode]; everything else is the same on
+//! every host.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+use crate::run::{run_expect_in_process, ExpectDylib, ExpectMemory};
+
+/// How to start the processes that run expects.
+#[derive(Debug, Clone)]
+pub enum ChildMode {
+    /// Fork the current process, which already has the expects loaded. This is the fastest way
+    /// to start a child, but is not available on Windows.
+    #[cfg(not(windows))]
+    Fork,
+    /// Run `program` with `args`, followed by the path of the dylib, the name of the expect and
+    /// the path to write the report to. The program is expected to pass those on to
+    /// [run_spawned_child]. This works on every host.
+    Spawn {
+        program: PathBuf,
+        args: Vec<OsString>,
+    },
+}
+
+impl ChildMode {
+    /// The name of the environment variable that makes [ChildMode::for_host] pick the portable
+    /// mode, even on hosts that can fork.
+    pub const ENV_VAR: &'static str = "ROC_EXPECT_CHILD";
+
+    /// Forks where possible, and spawns `program` otherwise, or when `ROC_EXPECT_CHILD=spawn`.
+    pub fn for_host(program: PathBuf, args: Vec<OsString>) -> Self {
+        #[cfg(not(windows))]
+        if std::env::var_os(Self::ENV_VAR).map_or(true, |mode| mode != "spawn") {
+            return ChildMode::Fork;
+        }
+
+        ChildMode::Spawn { program, args }
+    }
+}
+
+/// What running an expect reported.
+#[derive(Debug)]
+pub(crate) enum ExpectReport {
+    Passed,
+    /// The expect failed `failures` times, and the failures were written to `buffer`.
+    Failed {
+        failures: usize,
+        buffer: Vec<u8>,
+    },
+    Panicked(String),
+}
+
+impl ExpectReport {
+    const PASSED: u8 = 0;
+    const FAILED: u8 = 1;
+    const PANICKED: u8 = 2;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        match self {
+            ExpectReport::Passed => bytes.push(Self::PASSED),
+            ExpectReport::Failed { failures, buffer } => {
+                bytes.push(Self::FAILED);
+                bytes.extend((*failures as u64).to_le_bytes());
+                bytes.extend((buffer.len() as u64).to_le_bytes());
+                bytes.extend(buffer);
+            }
+            ExpectReport::Panicked(message) => {
+                bytes.push(Self::PANICKED);
+                bytes.extend((message.len() as u64).to_le_bytes());
+                bytes.extend(message.as_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    /// Returns [None] if `bytes` is not a complete report, e.g. because the child crashed while
+    /// writing it.
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        fn read_u64(bytes: &mut &[u8]) -> Option<u64> {
+            if bytes.len() < 8 {
+                return None;
+            }
+
+            let (int, rest) = bytes.split_at(8);
+            *bytes = rest;
+
+            Some(u64::from_le_bytes(int.try_into().unwrap()))
+        }
+
+        let (&tag, mut rest) = bytes.split_first()?;
+
+        let report = match tag {
+            Self::PASSED => ExpectReport::Passed,
+            Self::FAILED => {
+                let failures = read_u64(&mut rest)? as usize;
+                let len = read_u64(&mut rest)? as usize;
+
+                ExpectReport::Failed {
+                    failures,
+                    buffer: rest.get(..len)?.to_vec(),
+                }
+            }
+            Self::PANICKED => {
+                let len = read_u64(&mut rest)? as usize;
+                let message = std::str::from_utf8(rest.get(..len)?).ok()?;
+
+                ExpectReport::Panicked(message.to_string())
+            }
+            _ => return None,
+        };
+
+        Some(report)
+    }
+
+    fn write_to(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+}
+
+/// Runs the expect named `expect_name` from the dylib at `dylib_path`, and writes its report to
+/// `report_path`. This is what a child started in [ChildMode::Spawn] mode should do.
+pub fn run_spawned_child(
+    dylib_path: &Path,
+    expect_name: &str,
+    report_path: &Path,
+) -> std::io::Result<()> {
+    let lib = unsafe { libloading::Library::new(dylib_path) }
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error))?;
+
+    run_child(&lib, expect_name, report_path)
+}
+
+fn run_child(
+    lib: &libloading::Library,
+    expect_name: &str,
+    report_path: &Path,
+) -> std::io::Result<()> {
+    // Expect frames are read with the alignment they were written with, so back the buffer
+    // with words rather than bytes.
+    let mut words = vec![0u64; ExpectMemory::SHM_SIZE / 8];
+    let buffer = unsafe {
+        std::slice::from_raw_parts_mut(words.as_mut_ptr().cast::<u8>(), ExpectMemory::SHM_SIZE)
+    };
+
+    let mut memory = ExpectMemory::from_slice(buffer);
+    memory.set_shared_buffer(lib);
+
+    run_expect_in_process(lib, &mut memory, expect_name).write_to(report_path)
+}
+
+/// A running child process, from the parent's side.
+enum ChildProcess {
+    #[cfg(not(windows))]
+    Forked(libc::pid_t),
+    Spawned(std::process::Child),
+}
+
+impl ChildProcess {
+    /// Returns a function that blocks until the process has exited, without reaping it. Until
+    /// [ChildProcess::try_wait] or [ChildProcess::kill] reap it, its pid can't be reused for
+    /// another process.
+    #[cfg(not(windows))]
+    fn exit_waiter(&self) -> impl FnOnce() + Send + 'static {
+        let pid = match self {
+            ChildProcess::Forked(pid) => *pid,
+            ChildProcess::Spawned(child) => child.id() as libc::pid_t,
+        };
+
+        move || {
+            let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+
+            unsafe {
+                libc::waitid(
+                    libc::P_PID,
+                    pid as libc::id_t,
+                    &mut info,
+                    libc::WEXITED | libc::WNOWAIT,
+                )
+            };
+        }
+    }
+
+    /// Returns a function that blocks until the process has exited. The process handle stays
+    /// valid until the [std::process::Child] is dropped.
+    #[cfg(windows)]
+    fn exit_waiter(&self) -> impl FnOnce() + Send + 'static {
+        use std::os::windows::io::AsRawHandle;
+        use winapi::um::{synchapi::WaitForSingleObject, winbase::INFINITE};
+
+        let ChildProcess::Spawned(child) = self;
+
+        // Raw pointers aren't Send.
+        let handle = child.as_raw_handle() as usize;
+
+        move || unsafe {
+            WaitForSingleObject(handle as _, INFINITE);
+        }
+    }
+
+    fn try_wait(&mut self) -> std::io::Result<Option<ExitStatus>> {
+        match self {
+            #[cfg(not(windows))]
+            ChildProcess::Forked(pid) => {
+                use std::os::unix::process::ExitStatusExt;
+
+                let mut status = 0;
+
+                match unsafe { libc::waitpid(*pid, &mut status, libc::WNOHANG) } {
+                    -1 => Err(std::io::Error::last_os_error()),
+                    0 => Ok(None),
+                    _ => Ok(Some(ExitStatus::from_raw(status))),
+                }
+            }
+            ChildProcess::Spawned(child) => child.try_wait(),
+        }
+    }
+
+    fn kill(&mut self) -> std::io::Result<()> {
+        match self {
+            #[cfg(not(windows))]
+            ChildProcess::Forked(pid) => {
+                let mut status = 0;
+
+                unsafe {
+                    libc::kill(*pid, libc::SIGKILL);
+                    libc::waitpid(*pid, &mut status, 0);
+                }
+
+                Ok(())
+            }
+            ChildProcess::Spawned(child) => {
+                child.kill()?;
+                child.wait().map(|_| ())
+            }
+        }
+    }
+}
+
+/// A child process running a single expect.
+pub(crate) struct ExpectChild {
+    process: ChildProcess,
+    report_path: PathBuf,
+    start: Instant,
+}
+
+impl ExpectChild {
+    pub fn start(
+        mode: &ChildMode,
+        dylib: &ExpectDylib,
+        expect_name: &str,
+        report_path: PathBuf,
+    ) -> std::io::Result<Self> {
+        let start = Instant::now();
+
+        let process = match mode {
+            #[cfg(not(windows))]
+            ChildMode::Fork => match unsafe { libc::fork() } {
+                0 => {
+                    // we are the child
+                    let code = match run_child(&dylib.lib, expect_name, &report_path) {
+                        Ok(()) => 0,
+                        Err(_) => 1,
+                    };
+
+                    std::process::exit(code)
+                }
+                -1 => return Err(std::io::Error::last_os_error()),
+                pid => ChildProcess::Forked(pid),
+            },
+            ChildMode::Spawn { program, args } => {
+                let child = Command::new(program)
+                    .args(args)
+                    .arg(&dylib.path)
+                    .arg(expect_name)
+                    .arg(&report_path)
+                    .stdin(Stdio::null())
+                    .spawn()?;
+
+                ChildProcess::Spawned(child)
+            }
+        };
+
+        Ok(Self {
+            process,
+            report_path,
+            start,
+        })
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Sends a message on `exited` once the child has exited, so the parent can block until
+    /// there is a child to finish. The child still has to be finished with
+    /// [ExpectChild::try_finish] afterwards.
+    pub fn notify_exit(&self, exited: Sender<()>) {
+        let wait_for_exit = self.process.exit_waiter();
+
+        std::thread::spawn(move || {
+            wait_for_exit();
+
+            // The parent is gone if it no longer listens, and no one cares about the exit.
+            let _ = exited.send(());
+        });
+    }
+
+    /// Returns the child's report once it has exited, or why it has no report, and stops the
+    /// child if it has been running for longer than `timeout`.
+    pub fn try_finish(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> std::io::Result<Option<Result<ExpectReport, String>>> {
+        match self.process.try_wait()? {
+            Some(status) => Ok(Some(self.read_report(status))),
+            None => match timeout {
+                Some(timeout) if self.elapsed() >= timeout => {
+                    self.process.kill()?;
+
+                    Ok(Some(Err(format!(
+                        "It was stopped after running for longer than the timeout of {timeout:?}."
+                    ))))
+                }
+                _ => Ok(None),
+            },
+        }
+    }
+
+    fn read_report(&self, status: ExitStatus) -> Result<ExpectReport, String> {
+        let report = std::fs::read(&self.report_path)
+            .ok()
+            .and_then(|bytes| ExpectReport::from_bytes(&bytes));
+
+        match report {
+            Some(report) if status.success() => Ok(report),
+            _ => Err(describe_exit(status)),
+        }
+    }
+}
+
+fn describe_exit(status: ExitStatus) -> String {
+    #[cfg(not(windows))]
+    {
+        use std::os::unix::process::ExitStatusExt;
+
+        if let Some(signal) = status.signal() {
+            let description = unsafe { std::ffi::CStr::from_ptr(libc::strsignal(signal)) };
+
+            return format!(
+                "The process running it crashed: {} (signal {signal}).",
+                description.to_string_lossy()
+            );
+        }
+    }
+
+    match status.code() {
+        Some(code) => format!("The process running it exited unexpectedly, with code {code}."),
+        None => "The process running it exited unexpectedly.".to_string(),
+    }
+}
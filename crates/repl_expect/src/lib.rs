@@ -1,5 +1,4 @@
 //! Supports evaluating `expect` and printing contextual information when they fail.
-#[cfg(not(windows))]
 use {
     roc_module::symbol::Interns,
     roc_mono::{
@@ -12,15 +11,12 @@ use {
     roc_types::subs::{Subs, Variable},
 };
 
-#[cfg(not(windows))]
 mod app;
-#[cfg(not(windows))]
+pub mod child;
 pub mod run;
 
-#[cfg(not(windows))]
 use app::{ExpectMemory, ExpectReplApp};
 
-#[cfg(not(windows))]
 #[allow(clippy::too_many_arguments)]
 pub fn get_values<'a>(
     target_info: TargetInfo,
@@ -104,20 +100,16 @@ mod test {
 
     use super::*;
 
-    fn run_expect_test(source: &str, expected: &str) {
-        let arena = bumpalo::Bump::new();
-        let arena = &arena;
-
-        let triple = Triple::host();
-        let target = &triple;
-
-        let opt_level = roc_mono::ir::OptLevel::Normal;
-        let target_info = TargetInfo::from(target);
+    /// Loads `source` from `Test.roc` in `src_dir`, which is also where failures are rendered from.
+    fn load_test_module<'a>(
+        arena: &'a bumpalo::Bump,
+        src_dir: &std::path::Path,
+        source: &'a str,
+    ) -> roc_load::MonomorphizedModule<'a> {
+        let target_info = TargetInfo::from(&Triple::host());
         let function_kind = FunctionKind::LambdaSet;
 
-        // Step 1: compile the app and generate the .o file
-        let src_dir = tempfile::tempdir().unwrap();
-        let filename = src_dir.path().join("Test.roc");
+        let filename = src_dir.join("Test.roc");
 
         std::fs::write(&filename, source).unwrap();
 
@@ -129,11 +121,11 @@ mod test {
             threading: Threading::Single,
             exec_mode: ExecutionMode::Test,
         };
-        let loaded = match roc_load::load_and_monomorphize_from_str(
+        match roc_load::load_and_monomorphize_from_str(
             arena,
             filename,
             source,
-            src_dir.path().to_path_buf(),
+            src_dir.to_path_buf(),
             RocCacheDir::Disallowed,
             load_config,
         ) {
@@ -142,7 +134,21 @@ mod test {
                 internal_error!("{:?}", (m.can_problems, m.type_problems))
             }
             Err(e) => internal_error!("{e:?}"),
-        };
+        }
+    }
+
+    fn run_expect_test(source: &str, expected: &str) {
+        let arena = bumpalo::Bump::new();
+        let arena = &arena;
+
+        let triple = Triple::host();
+        let target = &triple;
+
+        let opt_level = roc_mono::ir::OptLevel::Normal;
+
+        // Step 1: compile the app and generate the .o file
+        let src_dir = tempfile::tempdir().unwrap();
+        let loaded = load_test_module(arena, src_dir.path(), source);
 
         let mut loaded = loaded;
         let mut expectations = std::mem::take(&mut loaded.expectations);
@@ -150,7 +156,7 @@ mod test {
 
         let interns = loaded.interns.clone();
 
-        let (lib, expects, layout_interner) = expect_mono_module_to_dylib(
+        let (dylib, expects, layout_interner) = expect_mono_module_to_dylib(
             arena,
             target.clone(),
             loaded,
@@ -168,18 +174,19 @@ mod test {
         let mut memory = crate::run::ExpectMemory::from_slice(&mut shared_buffer);
 
         // communicate the mmapped name to zig/roc
-        let set_shared_buffer = run_roc_dylib!(lib, "set_shared_buffer", (*mut u8, usize), ());
+        let set_shared_buffer =
+            run_roc_dylib!(dylib.lib, "set_shared_buffer", (*mut u8, usize), ());
         let mut result = RocCallResult::default();
         unsafe { set_shared_buffer((shared_buffer.as_mut_ptr(), BUFFER_SIZE), &mut result) };
 
         let mut writer = Vec::with_capacity(1024);
-        let (_failed, _passed) = crate::run::run_expects_with_memory(
+        let _outcomes = crate::run::run_expects_with_memory(
             &mut writer,
             RenderTarget::ColorTerminal,
             arena,
             interns,
             &layout_interner.into_global(),
-            &lib,
+            &dylib.lib,
             &mut expectations,
             expects,
             &mut memory,
@@ -1253,4 +1260,139 @@ mod test {
             ),
         );
     }
+
+    /// Runs the expects in `source` in child processes, like `roc test` does.
+    fn run_isolated_expects(
+        source: &str,
+        options: crate::run::ExpectRunOptions,
+    ) -> Vec<crate::run::ExpectOutcome> {
+        let arena = &bumpalo::Bump::new();
+        let src_dir = tempfile::tempdir().unwrap();
+        let mut loaded = load_test_module(arena, src_dir.path(), source);
+
+        let mut expectations = std::mem::take(&mut loaded.expectations);
+        let interns = arena.alloc(loaded.interns.clone());
+
+        let (dylib, expects, layout_interner) = expect_mono_module_to_dylib(
+            arena,
+            Triple::host(),
+            loaded,
+            roc_mono::ir::OptLevel::Normal,
+            LlvmBackendMode::CliTest,
+        )
+        .unwrap();
+
+        crate::run::run_toplevel_expects(
+            &mut std::io::sink(),
+            RenderTarget::Generic,
+            arena,
+            interns,
+            &layout_interner.into_global(),
+            &dylib,
+            &mut expectations,
+            expects,
+            options,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn hanging_expect_times_out() {
+        let start = std::time::Instant::now();
+
+        let outcomes = run_isolated_expects(
+            indoc!(
+                r#"
+                interface Test exposes [] imports []
+
+                spin : U64 -> U64
+                spin = \n -> spin (n + 1)
+
+                expect spin 0 == 0
+
+                expect 1 == 1
+                "#
+            ),
+            crate::run::ExpectRunOptions {
+                max_workers: 2,
+                timeout: Some(std::time::Duration::from_millis(500)),
+                child_mode: crate::child::ChildMode::Fork,
+            },
+        );
+
+        let (failed, passed): (Vec<_>, Vec<_>) =
+            outcomes.iter().partition(|outcome| !outcome.passed());
+        assert_eq!((failed.len(), passed.len()), (1, 1));
+
+        let failure = failed[0].failure.as_deref().unwrap();
+        assert!(failure.contains("longer than the timeout"), "{failure}");
+
+        // The hanging child is stopped, and the passing one does not have to wait for it.
+        assert!(start.elapsed() < std::time::Duration::from_secs(30));
+        assert!(passed[0].duration < std::time::Duration::from_millis(500));
+    }
+
+    #[test]
+    fn crashing_expect_fails_alone() {
+        // Stand in for a child that crashes before it can write its report.
+        let crash = crate::child::ChildMode::Spawn {
+            program: "sh".into(),
+            args: vec!["-c".into(), "kill -SEGV $$".into(), "sh".into()],
+        };
+
+        let outcomes = run_isolated_expects(
+            indoc!(
+                r#"
+                interface Test exposes [] imports []
+
+                expect 1 == 1
+
+                expect 1 == 2
+                "#
+            ),
+            crate::run::ExpectRunOptions {
+                max_workers: 1,
+                timeout: None,
+                child_mode: crash,
+            },
+        );
+
+        assert_eq!(outcomes.len(), 2);
+
+        for outcome in outcomes {
+            let failure = outcome.failure.unwrap();
+            assert!(
+                failure.contains("crashed") && failure.contains("signal 11"),
+                "{failure}"
+            );
+        }
+    }
+
+    #[test]
+    fn expects_run_in_forked_children() {
+        let outcomes = run_isolated_expects(
+            indoc!(
+                r#"
+                interface Test exposes [] imports []
+
+                expect 1 == 1
+
+                expect 1 == 2
+
+                expect crash "boom"
+                "#
+            ),
+            crate::run::ExpectRunOptions {
+                max_workers: 3,
+                timeout: None,
+                child_mode: crate::child::ChildMode::Fork,
+            },
+        );
+
+        let failures: Vec<&str> = (outcomes.iter())
+            .filter_map(|outcome| outcome.failure.as_deref())
+            .collect();
+        assert_eq!((outcomes.len(), failures.len()), (3, 2));
+        assert!(failures.iter().any(|failure| failure.contains("boom")));
+    }
 }
@@ -1,15 +1,19 @@
 use std::{
-    os::unix::process::parent_id,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, AtomicU32},
         Arc,
     },
+    time::Duration,
 };
 
+#[cfg(not(windows))]
+use std::os::unix::process::parent_id;
+
 use bumpalo::collections::Vec as BumpVec;
 use bumpalo::Bump;
 use inkwell::context::Context;
-use roc_build::link::llvm_module_to_dylib;
+use roc_build::link::llvm_module_to_dylib_in;
 use roc_can::expr::ExpectLookup;
 use roc_collections::{MutSet, VecMap};
 use roc_error_macros::internal_error;
@@ -30,6 +34,8 @@ use roc_target::TargetInfo;
 use roc_types::subs::Subs;
 use target_lexicon::Triple;
 
+use crate::child::{ChildMode, ExpectChild, ExpectReport};
+
 pub struct ExpectMemory<'a> {
     ptr: *mut u8,
     length: usize,
@@ -38,9 +44,8 @@ pub struct ExpectMemory<'a> {
 }
 
 impl<'a> ExpectMemory<'a> {
-    const SHM_SIZE: usize = 1024;
+    pub(crate) const SHM_SIZE: usize = 1024;
 
-    #[cfg(test)]
     pub(crate) fn from_slice(slice: &mut [u8]) -> Self {
         Self {
             ptr: slice.as_mut_ptr(),
@@ -50,16 +55,19 @@ impl<'a> ExpectMemory<'a> {
         }
     }
 
+    #[cfg(not(windows))]
     pub fn create_or_reuse_mmap(shm_name: &str) -> Self {
         let cstring = std::ffi::CString::new(shm_name).unwrap();
         Self::mmap_help(cstring, libc::O_RDWR | libc::O_CREAT)
     }
 
+    #[cfg(not(windows))]
     fn reuse_mmap(&mut self) -> Option<Self> {
         let shm_name = self.shm_name.as_ref()?.clone();
         Some(Self::mmap_help(shm_name, libc::O_RDWR))
     }
 
+    #[cfg(not(windows))]
     fn mmap_help(cstring: std::ffi::CString, shm_flags: i32) -> Self {
         let ptr = unsafe {
             let shared_fd = libc::shm_open(cstring.as_ptr().cast(), shm_flags, 0o666);
@@ -108,7 +116,7 @@ impl<'a> ExpectMemory<'a> {
         }
     }
 
-    fn set_shared_buffer(&mut self, lib: &libloading::Library) {
+    pub(crate) fn set_shared_buffer(&mut self, lib: &libloading::Library) {
         let set_shared_buffer = run_roc_dylib!(lib, "set_shared_buffer", (*mut u8, usize), ());
         let mut result = RocCallResult::default();
         unsafe { set_shared_buffer((self.ptr, self.length), &mut result) };
@@ -123,8 +131,45 @@ impl<'a> ExpectMemory<'a> {
         let mut sequence = ExpectSequence { ptr: self.ptr };
         sequence.reset();
     }
+
+    fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.length) }
+    }
+}
+
+/// The result of running a single top-level `expect`.
+#[derive(Debug)]
+pub struct ExpectOutcome {
+    pub symbol: Symbol,
+    pub region: Region,
+    pub duration: Duration,
+    /// The rendered failure, or [None] if the expect passed.
+    pub failure: Option<String>,
+}
+
+impl ExpectOutcome {
+    fn new(expect: ToplevelExpect<'_>, duration: Duration, passed: bool, failure: Vec<u8>) -> Self {
+        Self {
+            symbol: expect.symbol,
+            region: expect.region,
+            duration,
+            failure: (!passed).then(|| String::from_utf8_lossy(&failure).into_owned()),
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
 }
 
+/// Counts the failed and passed expects.
+pub fn count_outcomes(outcomes: &[ExpectOutcome]) -> (usize, usize) {
+    let passed = outcomes.iter().filter(|outcome| outcome.passed()).count();
+
+    (outcomes.len() - passed, passed)
+}
+
+#[cfg(not(windows))]
 #[allow(clippy::too_many_arguments)]
 pub fn run_inline_expects<'a, W: std::io::Write>(
     writer: &mut W,
@@ -139,7 +184,7 @@ pub fn run_inline_expects<'a, W: std::io::Write>(
     let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
     let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
 
-    run_expects_with_memory(
+    let outcomes = run_expects_with_memory(
         writer,
         render_target,
         arena,
@@ -149,9 +194,24 @@ pub fn run_inline_expects<'a, W: std::io::Write>(
         expectations,
         expects,
         &mut memory,
-    )
+    )?;
+
+    Ok(count_outcomes(&outcomes))
+}
+
+/// How [run_toplevel_expects] runs expects.
+#[derive(Debug, Clone)]
+pub struct ExpectRunOptions {
+    /// The most expects to run at the same time.
+    pub max_workers: usize,
+    /// How long a single expect may run before it is stopped and counted as failed.
+    pub timeout: Option<Duration>,
+    pub child_mode: ChildMode,
 }
 
+/// Runs each expect in a process of its own, so that an expect that crashes or never finishes
+/// is reported as a failure of that expect, rather than taking the whole run down with it.
+/// Failures are written to `writer`, and outcomes returned, in the order of `expects`.
 #[allow(clippy::too_many_arguments)]
 pub fn run_toplevel_expects<'a, W: std::io::Write>(
     writer: &mut W,
@@ -159,26 +219,120 @@ pub fn run_toplevel_expects<'a, W: std::io::Write>(
     arena: &'a Bump,
     interns: &'a Interns,
     layout_interner: &GlobalLayoutInterner<'a>,
-    lib: &libloading::Library,
+    dylib: &ExpectDylib,
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
-) -> std::io::Result<(usize, usize)> {
-    let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
-    let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+    options: ExpectRunOptions,
+) -> std::io::Result<Vec<ExpectOutcome>> {
+    let reports_dir = tempfile::tempdir()?;
 
-    run_expects_with_memory(
-        writer,
-        render_target,
-        arena,
-        interns,
-        layout_interner,
-        lib,
-        expectations,
-        expects,
-        &mut memory,
-    )
+    let mut pending = (expects.fx.iter())
+        .chain(expects.pure.iter())
+        .copied()
+        .enumerate();
+    let mut outcomes: Vec<Option<ExpectOutcome>> = Vec::new();
+    outcomes.resize_with(expects.fx.len() + expects.pure.len(), || None);
+
+    let mut printed = 0;
+
+    let max_workers = options.max_workers.max(1);
+    let mut children: Vec<(usize, ToplevelExpect, ExpectChild)> = Vec::with_capacity(max_workers);
+
+    // Every child sends a message here when it exits.
+    let (exited_sender, exited) = std::sync::mpsc::channel();
+
+    loop {
+        while children.len() < max_workers {
+            let (index, expect) = match pending.next() {
+                Some(next) => next,
+                None => break,
+            };
+
+            let report_path = reports_dir.path().join(format!("{index}.report"));
+            let child = ExpectChild::start(&options.child_mode, dylib, expect.name, report_path)?;
+            child.notify_exit(exited_sender.clone());
+
+            children.push((index, expect, child));
+        }
+
+        if children.is_empty() {
+            break;
+        }
+
+        // Block until a child exits, or the longest running one reaches the timeout.
+        let until_timeout = options.timeout.and_then(|timeout| {
+            (children.iter())
+                .map(|(_, _, child)| timeout.saturating_sub(child.elapsed()))
+                .min()
+        });
+        match until_timeout {
+            Some(until_timeout) => {
+                let _ = exited.recv_timeout(until_timeout);
+            }
+            None => {
+                // We hold a sender ourselves, so this can't fail.
+                let _ = exited.recv();
+            }
+        }
+
+        let mut i = 0;
+
+        while i < children.len() {
+            let result = match children[i].2.try_finish(options.timeout)? {
+                Some(result) => result,
+                None => {
+                    i += 1;
+                    continue;
+                }
+            };
+
+            let (index, expect, child) = children.swap_remove(i);
+            let duration = child.elapsed();
+
+            let mut failure = Vec::new();
+            let passed = match result {
+                Ok(report) => render_report(
+                    &mut failure,
+                    render_target,
+                    arena,
+                    interns,
+                    layout_interner,
+                    expectations,
+                    expect,
+                    &report,
+                )?,
+                Err(reason) => {
+                    render_aborted(
+                        &mut failure,
+                        render_target,
+                        arena,
+                        interns,
+                        expectations,
+                        expect,
+                        &reason,
+                    )?;
+
+                    false
+                }
+            };
+
+            outcomes[index] = Some(ExpectOutcome::new(expect, duration, passed, failure));
+
+            // Print failures in the order of the expects, regardless of which finish first.
+            while let Some(Some(outcome)) = outcomes.get(printed) {
+                if let Some(failure) = &outcome.failure {
+                    writer.write_all(failure.as_bytes())?;
+                }
+
+                printed += 1;
+            }
+        }
+    }
+
+    Ok(outcomes.into_iter().map(Option::unwrap).collect())
 }
 
+#[cfg(not(windows))]
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     writer: &mut W,
@@ -190,13 +344,15 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
     memory: &mut ExpectMemory,
-) -> std::io::Result<(usize, usize)> {
-    let mut failed = 0;
-    let mut passed = 0;
+) -> std::io::Result<Vec<ExpectOutcome>> {
+    let mut outcomes = Vec::with_capacity(expects.fx.len() + expects.pure.len());
 
     for expect in expects.fx {
-        let result = run_expect_fx(
-            writer,
+        let start = std::time::Instant::now();
+        let mut failure = Vec::new();
+
+        let passed = run_expect_fx(
+            &mut failure,
             render_target,
             arena,
             interns,
@@ -207,17 +363,18 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expect,
         )?;
 
-        match result {
-            true => passed += 1,
-            false => failed += 1,
-        }
+        writer.write_all(&failure)?;
+        outcomes.push(ExpectOutcome::new(expect, start.elapsed(), passed, failure));
     }
 
     memory.set_shared_buffer(lib);
 
     for expect in expects.pure {
-        let result = run_expect_pure(
-            writer,
+        let start = std::time::Instant::now();
+        let mut failure = Vec::new();
+
+        let passed = run_expect_pure(
+            &mut failure,
             render_target,
             arena,
             interns,
@@ -228,15 +385,14 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expect,
         )?;
 
-        match result {
-            true => passed += 1,
-            false => failed += 1,
-        }
+        writer.write_all(&failure)?;
+        outcomes.push(ExpectOutcome::new(expect, start.elapsed(), passed, failure));
     }
 
-    Ok((failed, passed))
+    Ok(outcomes)
 }
 
+#[cfg(not(windows))]
 #[allow(clippy::too_many_arguments)]
 fn run_expect_pure<'a, W: std::io::Write>(
     writer: &mut W,
@@ -249,30 +405,85 @@ fn run_expect_pure<'a, W: std::io::Write>(
     shared_memory: &mut ExpectMemory,
     expect: ToplevelExpect<'_>,
 ) -> std::io::Result<bool> {
+    let report = run_expect_in_process(lib, shared_memory, expect.name);
+
+    render_report(
+        writer,
+        render_target,
+        arena,
+        interns,
+        layout_interner,
+        expectations,
+        expect,
+        &report,
+    )
+}
+
+/// Runs an expect in the current process, with `shared_memory` as the buffer that failures are
+/// written to. The buffer must already be registered with `set_shared_buffer`.
+pub(crate) fn run_expect_in_process(
+    lib: &libloading::Library,
+    shared_memory: &mut ExpectMemory,
+    expect_name: &str,
+) -> ExpectReport {
     use roc_gen_llvm::try_run_jit_function;
 
     let sequence = ExpectSequence::new(shared_memory.ptr.cast());
 
-    let result: Result<(), (String, _)> = try_run_jit_function!(lib, expect.name, (), |v: ()| v);
+    let result: Result<(), (String, _)> = try_run_jit_function!(lib, expect_name, (), |v: ()| v);
 
-    let shared_memory_ptr: *const u8 = shared_memory.ptr.cast();
+    match result {
+        Err((roc_panic_message, _roc_panic_tag)) => ExpectReport::Panicked(roc_panic_message),
+        Ok(()) if sequence.count_failures() == 0 => ExpectReport::Passed,
+        Ok(()) => ExpectReport::Failed {
+            failures: sequence.count_failures(),
+            buffer: shared_memory.as_slice().to_vec(),
+        },
+    }
+}
 
-    if result.is_err() || sequence.count_failures() > 0 {
-        let module_id = expect.symbol.module_id();
-        let data = expectations.get_mut(&module_id).unwrap();
+/// Renders what running `expect` reported, and returns whether it passed.
+#[allow(clippy::too_many_arguments)]
+fn render_report<'a, W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    expect: ToplevelExpect<'_>,
+    report: &ExpectReport,
+) -> std::io::Result<bool> {
+    if let ExpectReport::Passed = report {
+        return Ok(true);
+    }
+
+    let module_id = expect.symbol.module_id();
+    let data = expectations.get_mut(&module_id).unwrap();
+
+    let path = &data.path;
+    let filename = data.path.to_owned();
+    let source = std::fs::read_to_string(path).unwrap();
 
-        let path = &data.path;
-        let filename = data.path.to_owned();
-        let source = std::fs::read_to_string(path).unwrap();
+    let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
 
-        let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
+    match report {
+        ExpectReport::Passed => unreachable!(),
+        ExpectReport::Panicked(roc_panic_message) => {
+            renderer.render_panic(writer, roc_panic_message, expect.region)?;
+        }
+        ExpectReport::Failed { failures, buffer } => {
+            // The buffer may have been copied from another process; frames are read with the
+            // alignment they were written with.
+            let mut words = vec![0u64; (buffer.len() + 7) / 8];
+            let aligned = unsafe {
+                std::slice::from_raw_parts_mut(words.as_mut_ptr().cast::<u8>(), buffer.len())
+            };
+            aligned.copy_from_slice(buffer);
 
-        if let Err((roc_panic_message, _roc_panic_tag)) = result {
-            renderer.render_panic(writer, &roc_panic_message, expect.region)?;
-        } else {
             let mut offset = ExpectSequence::START_OFFSET;
 
-            for _ in 0..sequence.count_failures() {
+            for _ in 0..*failures {
                 offset += render_expect_failure(
                     writer,
                     &renderer,
@@ -281,20 +492,41 @@ fn run_expect_pure<'a, W: std::io::Write>(
                     expectations,
                     interns,
                     layout_interner,
-                    shared_memory_ptr,
+                    aligned.as_ptr(),
                     offset,
                 )?;
             }
         }
+    }
 
-        writeln!(writer)?;
+    writeln!(writer)?;
 
-        Ok(false)
-    } else {
-        Ok(true)
-    }
+    Ok(false)
 }
 
+/// Renders an expect that did not run to completion, e.g. because its process crashed.
+fn render_aborted<'a, W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    expect: ToplevelExpect<'_>,
+    reason: &str,
+) -> std::io::Result<()> {
+    let module_id = expect.symbol.module_id();
+    let data = expectations.get_mut(&module_id).unwrap();
+    let filename = data.path.to_owned();
+    let source = std::fs::read_to_string(&data.path).unwrap();
+
+    let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
+
+    renderer.render_aborted(writer, reason, expect.region)?;
+
+    writeln!(writer)
+}
+
+#[cfg(not(windows))]
 #[allow(clippy::too_many_arguments)]
 fn run_expect_fx<'a, W: std::io::Write>(
     writer: &mut W,
@@ -611,20 +843,21 @@ pub struct ExpectFunctions<'a> {
     pub fx: BumpVec<'a, ToplevelExpect<'a>>,
 }
 
+/// The dylib that a module's expects were compiled into.
+pub struct ExpectDylib {
+    pub lib: libloading::Library,
+    /// Where the dylib is, so that child processes can load it too.
+    pub path: PathBuf,
+    _dir: tempfile::TempDir,
+}
+
 pub fn expect_mono_module_to_dylib<'a>(
     arena: &'a Bump,
     target: Triple,
     loaded: MonomorphizedModule<'a>,
     opt_level: OptLevel,
     mode: LlvmBackendMode,
-) -> Result<
-    (
-        libloading::Library,
-        ExpectFunctions<'a>,
-        STLayoutInterner<'a>,
-    ),
-    libloading::Error,
-> {
+) -> Result<(ExpectDylib, ExpectFunctions<'a>, STLayoutInterner<'a>), libloading::Error> {
     let target_info = TargetInfo::from(&target);
 
     let MonomorphizedModule {
@@ -660,6 +893,7 @@ pub fn expect_mono_module_to_dylib<'a>(
         mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        source_locations: None,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -731,5 +965,14 @@ pub fn expect_mono_module_to_dylib<'a>(
         );
     }
 
-    llvm_module_to_dylib(env.module, &target, opt_level).map(|lib| (lib, expects, layout_interner))
+    let dir = tempfile::tempdir().unwrap();
+
+    let (lib, path) = llvm_module_to_dylib_in(env.module, &target, opt_level, dir.path())?;
+    let dylib = ExpectDylib {
+        lib,
+        path,
+        _dir: dir,
+    };
+
+    Ok((dylib, expects, layout_interner))
 }
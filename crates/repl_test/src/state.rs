@@ -1,9 +1,9 @@
 use bumpalo::Bump;
 use indoc::indoc;
-use roc_repl_cli::{evaluate, ReplHelper};
-use roc_repl_ui::is_incomplete;
+use roc_repl_cli::{evaluate, save_history, ReplHelper};
 use roc_repl_ui::repl_state::{ReplAction, ReplState};
-use roc_reporting::report::DEFAULT_PALETTE;
+use roc_repl_ui::{format_types, is_incomplete};
+use roc_reporting::report::{ANSI_STYLE_CODES, DEFAULT_PALETTE};
 use roc_target::TargetInfo;
 use rustyline::Editor;
 use target_lexicon::Triple;
@@ -148,6 +148,214 @@ fn standalone_annotation() {
     assert!(matches!(action, ReplAction::Nothing));
 }
 
+#[test]
+fn type_without_evaluating() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, "5 : Num *");
+    types(":type x + 1", &mut state, "x + 1 : Num *");
+    types(
+        ":type Str.concat",
+        &mut state,
+        "Str.concat : Str, Str -> Str",
+    );
+}
+
+#[test]
+fn defs_with_types() {
+    let mut state = ReplState::new();
+
+    message(
+        ":defs",
+        &mut state,
+        "There are no defs in this session yet.",
+    );
+
+    complete("x = 5", &mut state, "5 : Num *");
+    complete("greeting = \"hi\"", &mut state, "\"hi\" : Str");
+
+    types(":defs", &mut state, "x : Num *\ngreeting : Str");
+}
+
+#[test]
+fn reset_forgets_defs() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, "5 : Num *");
+    message(
+        ":reset",
+        &mut state,
+        "Forgot every def and loaded module from this session.",
+    );
+    message(
+        ":defs",
+        &mut state,
+        "There are no defs in this session yet.",
+    );
+}
+
+#[test]
+fn load_interface_module() {
+    let tmp = format!("tmp/repl_load_{}", std::process::id());
+    let dir = roc_test_utils::TmpDir::new(&tmp);
+    let module_path = dir.path().join("Greeting.roc");
+
+    std::fs::write(
+        &module_path,
+        indoc!(
+            r#"
+            interface Greeting
+                exposes [greet]
+                imports []
+
+            greet : Str -> Str
+            greet = \name -> "Hello, \(name)!"
+            "#
+        ),
+    )
+    .unwrap();
+
+    let mut state = ReplState::new();
+
+    message(
+        &format!(":load {}", module_path.display()),
+        &mut state,
+        "Loaded Greeting, which exposes greet.",
+    );
+    types(":type greet", &mut state, "greet : Str -> Str");
+    complete("greet \"Roc\"", &mut state, "\"Hello, Roc!\" : Str");
+}
+
+#[test]
+fn deleted_module_is_an_error() {
+    let tmp = format!("tmp/repl_deleted_{}", std::process::id());
+    let dir = roc_test_utils::TmpDir::new(&tmp);
+    let module_path = dir.path().join("Gone.roc");
+
+    std::fs::write(
+        &module_path,
+        indoc!(
+            r#"
+            interface Gone
+                exposes [answer]
+                imports []
+
+            answer = 42
+            "#
+        ),
+    )
+    .unwrap();
+
+    let mut state = ReplState::new();
+
+    message(
+        &format!(":load {}", module_path.display()),
+        &mut state,
+        "Loaded Gone, which exposes answer.",
+    );
+
+    std::fs::remove_file(&module_path).unwrap();
+
+    let arena = Bump::new();
+    let target_info = TargetInfo::from(&Triple::host());
+
+    // Every later input imports the module, so each of them reports it's missing.
+    for input in [":type 1", "1"] {
+        let problems = match state.step(&arena, input, target_info, DEFAULT_PALETTE) {
+            ReplAction::Types { problems, .. } | ReplAction::Eval { problems, .. } => problems,
+            action => panic!("Unexpected action: {:?}", action),
+        };
+
+        assert_eq!(problems.errors.len(), 1);
+        assert!(
+            problems.errors[0].contains("Gone.roc"),
+            "{}",
+            problems.errors[0]
+        );
+    }
+}
+
+#[test]
+fn unclosed_brackets_continue() {
+    let mut input = "[1, 2,".to_string();
+    incomplete(&mut input);
+
+    input.push_str("3]");
+    complete(&input, &mut ReplState::new(), "[1, 2, 3] : List (Num *)");
+}
+
+#[test]
+fn brackets_in_strings_and_comments_dont_close() {
+    assert!(is_incomplete("[1, \"]\""));
+    assert!(is_incomplete("[1, # ]"));
+}
+
+#[test]
+fn trailing_operator_continues() {
+    let mut input = "1 +".to_string();
+    incomplete(&mut input);
+
+    input.push('2');
+    complete(&input, &mut ReplState::new(), "3 : Num *");
+}
+
+#[test]
+fn unfinished_if_continues() {
+    let mut input = "if 1 == 1 then \"yes\"".to_string();
+    incomplete(&mut input);
+
+    input.push_str("    else \"no\"");
+    complete(&input, &mut ReplState::new(), "\"yes\" : Str");
+}
+
+#[test]
+fn unclosed_multiline_string_continues() {
+    assert!(is_incomplete("\"\"\"first line"));
+}
+
+#[test]
+fn syntax_error_before_the_end_does_not_continue() {
+    assert!(!is_incomplete("if 1 == 1 then ) else 2"));
+    assert!(!is_incomplete("[1, 2) + 3"));
+}
+
+#[test]
+fn history_persists() {
+    let tmp = format!("tmp/repl_history_{}", std::process::id());
+    let dir = roc_test_utils::TmpDir::new(&tmp);
+    // Like the first time the repl runs, the directory the history goes in doesn't exist yet.
+    let path = dir.path().join("roc").join("repl_history");
+
+    let mut editor = Editor::<ReplHelper>::new();
+    editor.add_history_entry("x = 1");
+    editor.add_history_entry("x + 1");
+    save_history(&mut editor, &path);
+
+    let mut next_session = Editor::<ReplHelper>::new();
+    next_session.load_history(&path).unwrap();
+
+    let history = next_session.history();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).map(String::as_str), Some("x = 1"));
+    assert_eq!(history.get(1).map(String::as_str), Some("x + 1"));
+}
+
+#[test]
+fn completions() {
+    let mut state = ReplState::new();
+
+    complete("greeting = \"hi\"", &mut state, "\"hi\" : Str");
+
+    assert_eq!(state.completions("gree"), vec!["greeting".to_string()]);
+    assert!(state
+        .completions("List.ma")
+        .contains(&"List.map".to_string()));
+    assert!(state
+        .completions("List.")
+        .iter()
+        .all(|name| name.starts_with("List.")));
+}
+
 /// validate and step the given input, then check the Result vs the output
 /// with ANSI escape codes stripped.
 fn complete(input: &str, state: &mut ReplState, expected_start: &str) {
@@ -210,3 +418,47 @@ fn error(input: &str, state: &mut ReplState, expected_step_result: String) {
         }
     }
 }
+
+/// step the given meta-command, then check the types it shows
+/// with ANSI escape codes stripped.
+fn types(input: &str, state: &mut ReplState, expected: &str) {
+    assert!(!is_incomplete(input));
+    let arena = Bump::new();
+    let target = Triple::host();
+    let target_info = TargetInfo::from(&target);
+    let action = state.step(&arena, input, target_info, DEFAULT_PALETTE);
+
+    match action {
+        ReplAction::Types {
+            type_defs,
+            values,
+            problems,
+        } => {
+            let string = format_types(ANSI_STYLE_CODES, type_defs, values, problems);
+            let escaped =
+                std::string::String::from_utf8(strip_ansi_escapes::strip(string.trim()).unwrap())
+                    .unwrap();
+
+            assert_eq!(expected, escaped);
+        }
+        _ => {
+            panic!("Unexpected action: {:?}", action);
+        }
+    }
+}
+
+/// step the given meta-command, then check the message it shows.
+fn message(input: &str, state: &mut ReplState, expected: &str) {
+    assert!(!is_incomplete(input));
+    let arena = Bump::new();
+    let target = Triple::host();
+    let target_info = TargetInfo::from(&target);
+    let action = state.step(&arena, input, target_info, DEFAULT_PALETTE);
+
+    match action {
+        ReplAction::Message(message) => assert_eq!(expected, message),
+        _ => {
+            panic!("Unexpected action: {:?}", action);
+        }
+    }
+}
@@ -39,6 +39,17 @@ pub const TIPS: &str = concatcp!(
             BLUE,
             "  - ",
             END_COL,
+            PINK,
+            "tab",
+            END_COL,
+            " completes names of defs and builtins, like ",
+            BLUE,
+            "List.ma",
+            END_COL,
+            "\n",
+            BLUE,
+            "  - ",
+            END_COL,
             GREEN,
             ":q",
             END_COL,
@@ -50,6 +61,34 @@ pub const TIPS: &str = concatcp!(
             ":help",
             END_COL,
             " shows this text again\n",
+            BLUE,
+            "  - ",
+            END_COL,
+            GREEN,
+            ":type",
+            END_COL,
+            " followed by an expression shows its type without evaluating it\n",
+            BLUE,
+            "  - ",
+            END_COL,
+            GREEN,
+            ":defs",
+            END_COL,
+            " lists the defs entered so far, with their types\n",
+            BLUE,
+            "  - ",
+            END_COL,
+            GREEN,
+            ":load",
+            END_COL,
+            " followed by the path of an interface module lets you use what it exposes\n",
+            BLUE,
+            "  - ",
+            END_COL,
+            GREEN,
+            ":reset",
+            END_COL,
+            " forgets every def and loaded module\n",
         )
     }
 );
@@ -59,6 +98,7 @@ pub const TIPS: &str = concatcp!(
 pub const SHORT_INSTRUCTIONS: &str = "Enter an expression, or :help, or :q to quit.\n\n";
 pub const PROMPT: &str = concatcp!(BLUE, "»", END_COL, " ");
 pub const CONT_PROMPT: &str = concatcp!(BLUE, "…", END_COL, " ");
+const EXPR_TYPE_SEPARATOR: &str = " : "; // e.g. in "5 : Num *"
 
 pub fn is_incomplete(input: &str) -> bool {
     let arena = Bump::new();
@@ -79,6 +119,10 @@ pub fn is_incomplete(input: &str) -> bool {
         ParseOutcome::Empty
         | ParseOutcome::Help
         | ParseOutcome::Exit
+        | ParseOutcome::Type(_)
+        | ParseOutcome::Load(_)
+        | ParseOutcome::Reset
+        | ParseOutcome::Defs
         | ParseOutcome::ValueDef(_)
         | ParseOutcome::TypeDef(_)
         | ParseOutcome::SyntaxErr
@@ -110,8 +154,6 @@ pub fn format_output(
         // Also, for now we also don't print anything if there was a compile-time error.
         // In the future, it would be great to run anyway and print useful output here!
         if !expr.is_empty() && problems.errors.is_empty() {
-            const EXPR_TYPE_SEPARATOR: &str = " : "; // e.g. in "5 : Num *"
-
             // Print the expr and its type
             {
                 buf.push('\n');
@@ -126,3 +168,35 @@ pub fn format_output(
 
     buf
 }
+
+/// Formats the types which `:type` and `:defs` show, e.g. `x : Num *`.
+pub fn format_types(
+    style_codes: StyleCodes,
+    type_defs: Vec<String>,
+    values: Vec<(String, String)>,
+    problems: Problems,
+) -> String {
+    // Like when evaluating, don't show anything besides the errors if there were any.
+    let has_errors = !problems.errors.is_empty();
+    let mut buf = format_output(style_codes, None, problems);
+
+    if has_errors {
+        return buf;
+    }
+
+    for type_def in type_defs {
+        buf.push('\n');
+        buf.push_str(&type_def);
+    }
+
+    for (name, name_type) in values {
+        buf.push('\n');
+        buf.push_str(&name);
+        buf.push_str(style_codes.magenta); // Color for the type separator
+        buf.push_str(EXPR_TYPE_SEPARATOR);
+        buf.push_str(style_codes.reset);
+        buf.push_str(&name_type);
+    }
+
+    buf
+}
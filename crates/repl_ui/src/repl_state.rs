@@ -1,17 +1,20 @@
 use bumpalo::Bump;
 use roc_collections::MutSet;
 use roc_load::MonomorphizedModule;
-use roc_parse::ast::{Expr, Pattern, TypeDef, TypeHeader, ValueDef};
+use roc_module::symbol::{IdentIds, ModuleIds};
+use roc_parse::ast::{Expr, Header, Pattern, TypeDef, TypeHeader, ValueDef};
 use roc_parse::expr::{parse_single_def, ExprParseOptions, SingleDef};
+use roc_parse::module::parse_header;
 use roc_parse::parser::Parser;
-use roc_parse::parser::{EClosure, EExpr, EPattern};
-use roc_parse::parser::{EWhen, Either};
+use roc_parse::parser::{EClosure, EExpect, EExpr, EIf, EInParens, EList, EPattern, ERecord};
+use roc_parse::parser::{EString, EWhen, Either};
 use roc_parse::state::State;
 use roc_parse::{join_alias_to_body, join_ann_to_body};
-use roc_region::all::Loc;
-use roc_repl_eval::gen::{compile_to_mono, Problems};
+use roc_region::all::{Loc, Position};
+use roc_repl_eval::gen::{compile_to_mono, solve_types, Problems};
 use roc_reporting::report::Palette;
 use roc_target::TargetInfo;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, PartialEq)]
 struct PastDef {
@@ -19,9 +22,19 @@ struct PastDef {
     src: String,
 }
 
+/// An interface module which was brought into the session with `:load`.
+#[derive(Debug, Clone, PartialEq)]
+struct LoadedModule {
+    name: String,
+    exposes: Vec<String>,
+}
+
 pub struct ReplState {
     past_defs: Vec<PastDef>,
     past_def_idents: MutSet<String>,
+    loaded_modules: Vec<LoadedModule>,
+    /// The directory the loaded modules are in, which is where the REPL's module imports them from.
+    src_dir: Option<PathBuf>,
 }
 
 impl Default for ReplState {
@@ -37,6 +50,14 @@ pub enum ReplAction<'a> {
         opt_mono: Option<MonomorphizedModule<'a>>,
         problems: Problems,
     },
+    /// The types of these values, which were solved without evaluating anything.
+    /// Type definitions don't have a type of their own, so their source is shown instead.
+    Types {
+        type_defs: Vec<String>,
+        values: Vec<(String, String)>,
+        problems: Problems,
+    },
+    Message(String),
     Exit,
     Help,
     Nothing,
@@ -47,6 +68,8 @@ impl ReplState {
         Self {
             past_defs: Default::default(),
             past_def_idents: Default::default(),
+            loaded_modules: Default::default(),
+            src_dir: None,
         }
     }
 
@@ -61,6 +84,16 @@ impl ReplState {
         let src: &str = match parse_src(arena, line) {
             ParseOutcome::Empty | ParseOutcome::Help => return ReplAction::Help,
             ParseOutcome::Exit => return ReplAction::Exit,
+            ParseOutcome::Type(expr) => return self.type_of(arena, expr, target_info, palette),
+            ParseOutcome::Defs => return self.defs(arena, target_info, palette),
+            ParseOutcome::Load(path) => return self.load(arena, path),
+            ParseOutcome::Reset => {
+                *self = Self::new();
+
+                return ReplAction::Message(
+                    "Forgot every def and loaded module from this session.".to_string(),
+                );
+            }
             ParseOutcome::Expr(_) | ParseOutcome::Incomplete | ParseOutcome::SyntaxErr => {
                 pending_past_def = None;
 
@@ -168,6 +201,8 @@ impl ReplState {
 
         let (opt_mono, problems) = compile_to_mono(
             arena,
+            self.src_dir.as_deref(),
+            &self.imports(),
             self.past_defs.iter().map(|def| def.src.as_str()),
             src,
             target_info,
@@ -188,8 +223,240 @@ impl ReplState {
 
         self.past_defs.push(PastDef { ident, src });
     }
+
+    /// Handles `:type`, which shows an expression's type without evaluating it.
+    fn type_of<'a>(
+        &self,
+        arena: &'a Bump,
+        expr: &str,
+        target_info: TargetInfo,
+        palette: Palette,
+    ) -> ReplAction<'a> {
+        if expr.is_empty() {
+            return ReplAction::Message(
+                "Enter an expression after :type to see its type, like :type 1 + 1".to_string(),
+            );
+        }
+
+        let (opt_types, problems) = solve_types(
+            arena,
+            self.src_dir.as_deref(),
+            &self.imports(),
+            self.past_defs.iter().map(|def| def.src.as_str()),
+            expr,
+            target_info,
+            palette,
+        );
+
+        let values = opt_types
+            .and_then(|mut types| types.remove(REPL_OUTPUT_IDENT))
+            .map(|expr_type| vec![(expr.to_string(), expr_type)])
+            .unwrap_or_default();
+
+        ReplAction::Types {
+            type_defs: Vec::new(),
+            values,
+            problems,
+        }
+    }
+
+    /// Handles `:defs`, which lists this session's defs along with their types.
+    fn defs<'a>(
+        &self,
+        arena: &'a Bump,
+        target_info: TargetInfo,
+        palette: Palette,
+    ) -> ReplAction<'a> {
+        if self.past_defs.is_empty() {
+            return ReplAction::Message("There are no defs in this session yet.".to_string());
+        }
+
+        // The expression doesn't matter, since only the defs' types are shown.
+        let (opt_types, problems) = solve_types(
+            arena,
+            self.src_dir.as_deref(),
+            &self.imports(),
+            self.past_defs.iter().map(|def| def.src.as_str()),
+            "{}",
+            target_info,
+            palette,
+        );
+        let types = opt_types.unwrap_or_default();
+
+        let mut type_defs = Vec::new();
+        let mut values = Vec::new();
+        let mut listed_idents = MutSet::default();
+
+        // A def may have been entered more than once, e.g. as an annotation and then again
+        // with its body, so only list each one once, in the order they were first entered.
+        for PastDef { ident, src } in self.past_defs.iter() {
+            if !listed_idents.insert(ident.as_str()) {
+                continue;
+            }
+
+            match types.get(ident) {
+                Some(ident_type) => values.push((ident.clone(), ident_type.clone())),
+                None => type_defs.push(src.trim().to_string()),
+            }
+        }
+
+        ReplAction::Types {
+            type_defs,
+            values,
+            problems,
+        }
+    }
+
+    /// Handles `:load`, which imports everything an interface module exposes into the session.
+    fn load<'a>(&mut self, arena: &'a Bump, path: &str) -> ReplAction<'a> {
+        if path.is_empty() {
+            return ReplAction::Message(
+                "Enter the path of an interface module after :load, like :load Parser.roc"
+                    .to_string(),
+            );
+        }
+
+        let path = Path::new(path);
+        let src = match std::fs::read_to_string(path) {
+            Ok(src) => arena.alloc_str(&src),
+            Err(err) => {
+                return ReplAction::Message(format!("I could not read {}: {err}", path.display()));
+            }
+        };
+
+        let (name, exposes) = match parse_header(arena, State::new(src.as_bytes())) {
+            Ok((module, _)) => match module.header {
+                Header::Interface(header) => (
+                    header.name.value.as_str().to_string(),
+                    header
+                        .exposes
+                        .item
+                        .items
+                        .iter()
+                        .map(|exposed| exposed.value.item().as_str().to_string())
+                        .collect::<Vec<_>>(),
+                ),
+                _ => {
+                    return ReplAction::Message(format!(
+                        "{} is not an interface module, so there is nothing to load from it.",
+                        path.display()
+                    ));
+                }
+            },
+            Err(_) => {
+                return ReplAction::Message(format!(
+                    "I could not parse the header of {}.",
+                    path.display()
+                ));
+            }
+        };
+
+        // Modules are looked up relative to the directory the root module's in,
+        // e.g. `Parser.Json` would be in `Parser/Json.roc` inside of it.
+        let mut src_dir = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        for _ in name.split('.') {
+            src_dir.pop();
+        }
+
+        match &self.src_dir {
+            Some(existing_dir) if *existing_dir != src_dir => {
+                return ReplAction::Message(format!(
+                    "{name} is not in {}, where the modules loaded so far are. Use :reset to load modules from a different directory.",
+                    existing_dir.display()
+                ));
+            }
+            _ => {
+                self.src_dir = Some(src_dir);
+            }
+        }
+
+        let message = if exposes.is_empty() {
+            format!("Loaded {name}, which does not expose anything.")
+        } else {
+            format!("Loaded {name}, which exposes {}.", exposes.join(", "))
+        };
+
+        // Loading a module again picks up any changes to what it exposes.
+        self.loaded_modules.retain(|module| module.name != name);
+        self.loaded_modules.push(LoadedModule { name, exposes });
+
+        ReplAction::Message(message)
+    }
+
+    /// The entries for the `imports` of the REPL's module header, e.g. `Parser.{ parse }`.
+    fn imports(&self) -> Vec<String> {
+        self.loaded_modules
+            .iter()
+            .map(|LoadedModule { name, exposes }| {
+                if exposes.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{name}.{{ {} }}", exposes.join(", "))
+                }
+            })
+            .collect()
+    }
+
+    /// The names which could complete `prefix`: the defs from this session, what the loaded
+    /// modules expose (both on their own and qualified), and the members of builtin modules.
+    pub fn completions(&self, prefix: &str) -> Vec<String> {
+        let defined = self.past_def_idents.iter().cloned();
+        let loaded = self
+            .loaded_modules
+            .iter()
+            .flat_map(|LoadedModule { name, exposes }| {
+                exposes
+                    .iter()
+                    .flat_map(move |exposed| [exposed.clone(), format!("{name}.{exposed}")])
+            });
+
+        let mut completions: Vec<String> = defined
+            .chain(loaded)
+            .chain(builtin_members())
+            .filter(|name| name.starts_with(prefix))
+            .collect();
+
+        completions.sort();
+        completions.dedup();
+
+        completions
+    }
+}
+
+/// Every value the builtin modules expose, qualified by its module's name, e.g. `List.map`.
+fn builtin_members() -> Vec<String> {
+    let module_ids = ModuleIds::default();
+    let builtins = IdentIds::exposed_builtins(0);
+
+    builtins
+        .keys()
+        .filter_map(|module_id| Some((module_ids.get_name(*module_id)?, builtins.get(module_id)?)))
+        // Some builtin modules are only used internally by the compiler, and can't be imported.
+        .filter(|(module_name, _)| {
+            module_name
+                .as_str()
+                .starts_with(|c: char| c.is_ascii_uppercase())
+        })
+        .flat_map(|(module_name, ident_ids)| {
+            ident_ids
+                .ident_strs()
+                .map(|(_, name)| name)
+                .filter(|name| is_completable_value(name))
+                .map(move |name| format!("{}.{name}", module_name.as_str()))
+        })
+        .collect()
 }
 
+/// Whether `name` is a user-facing value name, as opposed to a type or a compiler-generated name.
+fn is_completable_value(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// The name of the def which the REPL evaluates its input as.
+const REPL_OUTPUT_IDENT: &str = "replOutput";
+
 #[derive(Debug, PartialEq)]
 pub enum ParseOutcome<'a> {
     ValueDef(ValueDef<'a>),
@@ -200,12 +467,24 @@ pub enum ParseOutcome<'a> {
     Empty,
     Help,
     Exit,
+    Type(&'a str),
+    Load(&'a str),
+    Reset,
+    Defs,
 }
 
 pub fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
     match line.trim().to_lowercase().as_str() {
         "" => ParseOutcome::Empty,
         ":help" => ParseOutcome::Help,
+        ":reset" => ParseOutcome::Reset,
+        ":defs" => ParseOutcome::Defs,
+        command if is_meta_command(command, ":type") => {
+            ParseOutcome::Type(meta_command_argument(line, ":type"))
+        }
+        command if is_meta_command(command, ":load") => {
+            ParseOutcome::Load(meta_command_argument(line, ":load"))
+        }
         // These are all common things beginners try.
         // Let people exit the repl easily!
         // If you really need to evaluate `exit` for some reason,
@@ -363,8 +642,211 @@ pub fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
                         Err(_) => ParseOutcome::SyntaxErr,
                     }
                 }
+                // The parser ran out of input partway through something, like a list with
+                // no `]` yet or an `if` with no `else`, which the next line might finish.
+                Err((_, problem)) if reached_end(&problem, line) => ParseOutcome::Incomplete,
                 Err(_) => ParseOutcome::SyntaxErr,
             }
         }
     }
 }
+
+/// Whether the parser gave up on `src` because it got to the end of it, rather than because
+/// of something it found before then. Every problem's own position is where the expression
+/// it's about starts, so this goes by the innermost problem, which is where parsing stopped.
+fn reached_end(problem: &EExpr<'_>, src: &str) -> bool {
+    let pos = match problem {
+        EExpr::DefMissingFinalExpr2(problem, _)
+        | EExpr::List(EList::Expr(problem, _), _)
+        | EExpr::InParens(EInParens::Expr(problem, _), _)
+        | EExpr::Record(ERecord::Expr(problem, _), _)
+        | EExpr::Str(EString::Format(problem, _), _)
+        | EExpr::Closure(EClosure::Body(problem, _), _)
+        | EExpr::When(
+            EWhen::Condition(problem, _) | EWhen::Branch(problem, _) | EWhen::IfGuard(problem, _),
+            _,
+        )
+        | EExpr::If(
+            EIf::Condition(problem, _) | EIf::ThenBranch(problem, _) | EIf::ElseBranch(problem, _),
+            _,
+        )
+        | EExpr::Expect(EExpect::Condition(problem, _) | EExpect::Continuation(problem, _), _)
+        | EExpr::Dbg(EExpect::Condition(problem, _) | EExpect::Continuation(problem, _), _) => {
+            return reached_end(problem, src);
+        }
+        // Unclosed strings are reported where they start, and only a multiline one can be
+        // closed on a later line.
+        EExpr::Str(EString::EndlessMultiLine(_), _) => return true,
+        EExpr::List(problem, _) => list_position(problem),
+        EExpr::InParens(problem, _) => in_parens_position(problem),
+        EExpr::Record(problem, _) => record_position(problem),
+        EExpr::Str(problem, _) => string_position(problem),
+        EExpr::Closure(problem, _) => closure_position(problem),
+        EExpr::When(problem, _) => when_position(problem),
+        EExpr::If(problem, _) => if_position(problem),
+        EExpr::Expect(problem, _) | EExpr::Dbg(problem, _) => expect_position(problem),
+        EExpr::OptionalValueInRecordBuilder(region) | EExpr::RecordUpdateBuilder(region) => {
+            region.end()
+        }
+        EExpr::TrailingOperator(pos)
+        | EExpr::Start(pos)
+        | EExpr::End(pos)
+        | EExpr::BadExprEnd(pos)
+        | EExpr::Space(_, pos)
+        | EExpr::Dot(pos)
+        | EExpr::Access(pos)
+        | EExpr::UnaryNot(pos)
+        | EExpr::UnaryNegate(pos)
+        | EExpr::BadOperator(_, pos)
+        | EExpr::DefMissingFinalExpr(pos)
+        | EExpr::Type(_, pos)
+        | EExpr::Pattern(_, pos)
+        | EExpr::Ability(_, pos)
+        | EExpr::IndentDefBody(pos)
+        | EExpr::IndentEquals(pos)
+        | EExpr::IndentAnnotation(pos)
+        | EExpr::Equals(pos)
+        | EExpr::Colon(pos)
+        | EExpr::DoubleColon(pos)
+        | EExpr::Ident(pos)
+        | EExpr::ElmStyleFunction(_, pos)
+        | EExpr::MalformedPattern(pos)
+        | EExpr::QualifiedTag(pos)
+        | EExpr::BackpassComma(pos)
+        | EExpr::BackpassArrow(pos)
+        | EExpr::Underscore(pos)
+        | EExpr::Crash(pos)
+        | EExpr::Number(_, pos)
+        | EExpr::IndentStart(pos)
+        | EExpr::IndentEnd(pos) => *pos,
+    };
+
+    // Trailing whitespace doesn't count, since the parser skips it before giving up.
+    pos.offset as usize >= src.trim_end().len()
+}
+
+fn list_position(problem: &EList<'_>) -> Position {
+    match problem {
+        EList::Open(pos) | EList::End(pos) | EList::Space(_, pos) | EList::Expr(_, pos) => *pos,
+    }
+}
+
+fn in_parens_position(problem: &EInParens<'_>) -> Position {
+    match problem {
+        EInParens::End(pos)
+        | EInParens::Open(pos)
+        | EInParens::Empty(pos)
+        | EInParens::Expr(_, pos)
+        | EInParens::Space(_, pos) => *pos,
+    }
+}
+
+fn record_position(problem: &ERecord<'_>) -> Position {
+    match problem {
+        ERecord::End(pos)
+        | ERecord::Open(pos)
+        | ERecord::Updateable(pos)
+        | ERecord::Field(pos)
+        | ERecord::Colon(pos)
+        | ERecord::QuestionMark(pos)
+        | ERecord::Arrow(pos)
+        | ERecord::Ampersand(pos)
+        | ERecord::Expr(_, pos)
+        | ERecord::Space(_, pos) => *pos,
+    }
+}
+
+fn string_position(problem: &EString<'_>) -> Position {
+    match problem {
+        EString::Open(pos)
+        | EString::CodePtOpen(pos)
+        | EString::CodePtEnd(pos)
+        | EString::InvalidSingleQuote(_, pos)
+        | EString::Space(_, pos)
+        | EString::EndlessSingleLine(pos)
+        | EString::EndlessMultiLine(pos)
+        | EString::EndlessSingleQuote(pos)
+        | EString::UnknownEscape(pos)
+        | EString::Format(_, pos)
+        | EString::FormatEnd(pos)
+        | EString::MultilineInsufficientIndent(pos)
+        | EString::ExpectedDoubleQuoteGotSingleQuote(pos) => *pos,
+    }
+}
+
+fn closure_position(problem: &EClosure<'_>) -> Position {
+    match problem {
+        EClosure::Space(_, pos)
+        | EClosure::Start(pos)
+        | EClosure::Arrow(pos)
+        | EClosure::Comma(pos)
+        | EClosure::Arg(pos)
+        | EClosure::Pattern(_, pos)
+        | EClosure::Body(_, pos)
+        | EClosure::IndentArrow(pos)
+        | EClosure::IndentBody(pos)
+        | EClosure::IndentArg(pos) => *pos,
+    }
+}
+
+fn when_position(problem: &EWhen<'_>) -> Position {
+    match problem {
+        EWhen::Space(_, pos)
+        | EWhen::When(pos)
+        | EWhen::Is(pos)
+        | EWhen::Pattern(_, pos)
+        | EWhen::Arrow(pos)
+        | EWhen::Bar(pos)
+        | EWhen::IfToken(pos)
+        | EWhen::IfGuard(_, pos)
+        | EWhen::Condition(_, pos)
+        | EWhen::Branch(_, pos)
+        | EWhen::IndentCondition(pos)
+        | EWhen::IndentPattern(pos)
+        | EWhen::IndentArrow(pos)
+        | EWhen::IndentBranch(pos)
+        | EWhen::IndentIfGuard(pos)
+        | EWhen::PatternAlignment(_, pos) => *pos,
+    }
+}
+
+fn if_position(problem: &EIf<'_>) -> Position {
+    match problem {
+        EIf::Space(_, pos)
+        | EIf::If(pos)
+        | EIf::Then(pos)
+        | EIf::Else(pos)
+        | EIf::Condition(_, pos)
+        | EIf::ThenBranch(_, pos)
+        | EIf::ElseBranch(_, pos)
+        | EIf::IndentCondition(pos)
+        | EIf::IndentIf(pos)
+        | EIf::IndentThenToken(pos)
+        | EIf::IndentElseToken(pos)
+        | EIf::IndentThenBranch(pos)
+        | EIf::IndentElseBranch(pos) => *pos,
+    }
+}
+
+fn expect_position(problem: &EExpect<'_>) -> Position {
+    match problem {
+        EExpect::Space(_, pos)
+        | EExpect::Dbg(pos)
+        | EExpect::Expect(pos)
+        | EExpect::Condition(_, pos)
+        | EExpect::Continuation(_, pos)
+        | EExpect::IndentCondition(pos) => *pos,
+    }
+}
+
+/// Whether the input is this meta-command, with or without an argument after it.
+fn is_meta_command(input: &str, command: &str) -> bool {
+    match input.strip_prefix(command) {
+        Some(rest) => rest.is_empty() || rest.starts_with(char::is_whitespace),
+        None => false,
+    }
+}
+
+fn meta_command_argument<'a>(line: &'a str, command: &str) -> &'a str {
+    line.trim().get(command.len()..).unwrap_or_default().trim()
+}
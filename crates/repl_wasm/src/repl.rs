@@ -12,7 +12,7 @@ use roc_repl_eval::{
     ReplApp, ReplAppMemory,
 };
 use roc_repl_ui::{
-    format_output,
+    format_output, format_types,
     repl_state::{ReplAction, ReplState},
     TIPS,
 };
@@ -204,6 +204,12 @@ pub async fn entrypoint_from_js(src: String) -> String {
             "To exit the web version of the REPL, just close the browser tab!".to_string()
         }
         ReplAction::Nothing => String::new(),
+        ReplAction::Message(message) => message,
+        ReplAction::Types {
+            type_defs,
+            values,
+            problems,
+        } => format_types(HTML_STYLE_CODES, type_defs, values, problems),
         ReplAction::Eval { opt_mono, problems } => {
             let opt_output = match opt_mono {
                 Some(mono) => eval_wasm(arena, target_info, mono).await,
@@ -256,6 +262,7 @@ async fn eval_wasm<'a>(
                 .keys()
                 .copied()
                 .collect::<MutSet<_>>(),
+            source_locations: None,
         };
 
         let (mut module, mut called_fns, main_fn_index) = {
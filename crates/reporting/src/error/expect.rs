@@ -146,28 +146,10 @@ impl<'a> Renderer<'a> {
     where
         W: std::io::Write,
     {
-        use crate::report::Report;
-
         let line_col_region = self.to_line_col_region(expect_region, failure_region);
         let doc = self.render_lookups(subs, line_col_region, symbols, variables, expressions);
 
-        let report = Report {
-            title: "EXPECT FAILED".into(),
-            doc,
-            filename: self.filename.clone(),
-            severity: Severity::RuntimeError,
-        };
-
-        let mut buf = String::new();
-
-        report.render(
-            self.render_target,
-            &mut buf,
-            &self.alloc,
-            &crate::report::DEFAULT_PALETTE,
-        );
-
-        write!(writer, "{buf}")
+        self.write_report(writer, "EXPECT FAILED", doc)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -210,7 +192,6 @@ impl<'a> Renderer<'a> {
     where
         W: std::io::Write,
     {
-        use crate::report::Report;
         use ven_pretty::DocAllocator;
 
         let line_col_region = self.line_info.convert_region(expect_region);
@@ -222,8 +203,46 @@ impl<'a> Renderer<'a> {
             self.alloc.text(message),
         ]);
 
+        self.write_report(writer, "EXPECT PANICKED", doc)
+    }
+
+    /// Renders an expectation that did not run to completion, for example because it had to be
+    /// stopped after running for too long.
+    pub fn render_aborted<W>(
+        &self,
+        writer: &mut W,
+        reason: &str,
+        expect_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(expect_region);
+
+        let doc = self.alloc.stack([
+            self.alloc.text("This expectation did not finish running:"),
+            self.alloc.region(line_col_region),
+            self.alloc.text(reason.to_string()),
+        ]);
+
+        self.write_report(writer, "EXPECT ABORTED", doc)
+    }
+
+    fn write_report<W>(
+        &self,
+        writer: &mut W,
+        title: &str,
+        doc: RocDocBuilder<'a>,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::report::Report;
+
         let report = Report {
-            title: "EXPECT PANICKED".into(),
+            title: title.into(),
             doc,
             filename: self.filename.clone(),
             severity: Severity::RuntimeError,
@@ -3336,6 +3336,28 @@ fn to_header_report<'a>(
             }
         }
 
+        EHeader::Version(_, pos) => {
+            let surroundings = Region::new(start, *pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(*pos));
+
+            let doc = alloc.stack([
+                alloc.reflow(r"I am partway through parsing a package header, but got stuck here:"),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region),
+                alloc.concat([
+                    alloc.reflow("I am expecting a version next, like "),
+                    alloc.parser_suggestion("version \"1.2.3\""),
+                    alloc.reflow(". Versions must be quoted."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "WEIRD PACKAGE VERSION".to_string(),
+                severity: Severity::RuntimeError,
+            }
+        }
+
         EHeader::PlatformName(_, pos) => {
             let surroundings = Region::new(start, *pos);
             let region = LineColumnRegion::from_pos(lines.convert_pos(*pos));
@@ -1,7 +1,7 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::error::canonicalize::{to_circular_def_doc, CIRCULAR_DEF};
-use crate::report::{Annotation, Report, RocDocAllocator, RocDocBuilder};
+use crate::report::{Annotation, CiWrite, Report, RocDocAllocator, RocDocBuilder};
 use itertools::EitherOrBoth;
 use itertools::Itertools;
 use roc_can::expected::{Expected, PExpected};
@@ -5042,6 +5042,28 @@ pub fn unhandled_patterns_to_doc_block<'b>(
         .annotate(Annotation::TypeBlock)
 }
 
+/// Renders a pattern that a `when` is missing as Roc source code, so that editors can offer to
+/// add the missing branch. Returns [None] for patterns that are only missing an `if` guard.
+pub fn unhandled_pattern_to_source<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    pattern: roc_exhaustive::Pattern,
+) -> Option<String> {
+    if let roc_exhaustive::Pattern::Ctor(union, _, _) = &pattern {
+        if union.render_as == roc_exhaustive::RenderAs::Guard {
+            return None;
+        }
+    }
+
+    let mut buf = String::new();
+    exhaustive_pattern_to_doc(alloc, pattern)
+        .annotate(Annotation::TypeBlock)
+        .1
+        .render_raw(200, &mut CiWrite::new(&mut buf))
+        .ok()?;
+
+    Some(buf)
+}
+
 fn exhaustive_pattern_to_doc<'b>(
     alloc: &'b RocDocAllocator<'b>,
     pattern: roc_exhaustive::Pattern,
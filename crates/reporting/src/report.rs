@@ -1,6 +1,7 @@
 use roc_module::ident::Ident;
 use roc_module::ident::{Lowercase, ModuleName, TagName, Uppercase};
 use roc_module::symbol::{Interns, ModuleId, ModuleIds, PQModuleName, PackageQualified, Symbol};
+use roc_packaging::version::{Requirement, VersionProblem};
 use roc_problem::Severity;
 use roc_region::all::LineColumnRegion;
 use std::path::{Path, PathBuf};
@@ -14,7 +15,7 @@ use roc_packaging::https::Problem;
 
 pub use crate::error::canonicalize::can_problem;
 pub use crate::error::parse::parse_problem;
-pub use crate::error::r#type::type_problem;
+pub use crate::error::r#type::{type_problem, unhandled_pattern_to_source};
 
 #[cfg(windows)]
 const CYCLE_ELEMENTS: [&str; 4] = ["+-----+", "|     ", "|     |", "+-<---+"];
@@ -1247,6 +1248,69 @@ pub fn to_https_problem_report<'b>(
                 severity: Severity::Fatal,
             }
         }
+        Problem::NotVendored => {
+            let doc = alloc.stack([
+                alloc.reflow(r"This app has a lock file, so I only load packages from its vendor directory. But this package isn't vendored:"),
+                alloc
+                    .string((&url).to_string())
+                    .annotate(Annotation::Url)
+                    .indent(4),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow(r"Run "),
+                    alloc.keyword(r"roc vendor"),
+                    alloc.reflow(r" to add this package to the lock file and the vendor directory."),
+                ]),
+            ]);
+            Report {
+                filename: "UNKNOWN.roc".into(),
+                doc,
+                title: "PACKAGE NOT VENDORED".to_string(),
+                severity: Severity::Fatal,
+            }
+        }
+        Problem::VendoredPackageModified { expected, actual } => {
+            let doc = alloc.stack([
+                alloc.reflow(
+                    r"This app has a lock file, so I load this package from its vendor directory:",
+                ),
+                alloc
+                    .string((&url).to_string())
+                    .annotate(Annotation::Url)
+                    .indent(4),
+                alloc.concat([
+                    alloc.reflow(r"But its files were changed after they were vendored. "),
+                    alloc.reflow(r"This is the content signature I was "),
+                    alloc.reflow(r"expecting").annotate(Annotation::Emphasized),
+                    alloc.reflow(r":"),
+                ]),
+                alloc
+                    .string(expected)
+                    .annotate(Annotation::PlainText)
+                    .indent(4),
+                alloc.concat([
+                    alloc.reflow(r"However, this is the content signature I "),
+                    alloc.reflow(r"obtained").annotate(Annotation::Emphasized),
+                    alloc.reflow(r":"),
+                ]),
+                alloc
+                    .string(actual)
+                    .annotate(Annotation::PlainText)
+                    .indent(4),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow(r"Run "),
+                    alloc.keyword(r"roc vendor"),
+                    alloc.reflow(r" to copy the package into the vendor directory again."),
+                ]),
+            ]);
+            Report {
+                filename: "UNKNOWN.roc".into(),
+                doc,
+                title: "VENDORED PACKAGE MODIFIED".to_string(),
+                severity: Severity::Fatal,
+            }
+        }
         // TODO: The reporting text for IoErr and FsExtraErr could probably be unified
         Problem::IoErr(io_error) => {
             let doc = alloc.stack([
@@ -1491,7 +1555,10 @@ pub fn to_https_problem_report<'b>(
                     alloc.reflow(r"For your security, I will only attempt to download "),
                     alloc.reflow(r"files from servers which use the "),
                     alloc.keyword(r"https"),
-                    alloc.reflow(r" protocol."),
+                    alloc.reflow(r" protocol. Packages on the local filesystem can be specified "),
+                    alloc.reflow(r"with a "),
+                    alloc.keyword(r"file://"),
+                    alloc.reflow(r" URL or a relative path instead."),
                 ]),
                 alloc.concat([
                     alloc.tip(),
@@ -1582,6 +1649,118 @@ pub fn to_https_problem_report<'b>(
     }
 }
 
+pub fn to_version_problem_report_string(version_problem: VersionProblem) -> String {
+    let src_lines: Vec<&str> = Vec::new();
+
+    let mut module_ids = ModuleIds::default();
+
+    let module_id = module_ids.get_or_insert(&"find module name somehow?".into());
+
+    let interns = Interns::default();
+
+    let alloc = RocDocAllocator::new(&src_lines, module_id, &interns);
+
+    let mut buf = String::new();
+    let palette = DEFAULT_PALETTE;
+    let report = to_version_problem_report(&alloc, version_problem);
+    report.render_color_terminal(&mut buf, &alloc, &palette);
+
+    buf
+}
+
+pub fn to_version_problem_report<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    version_problem: VersionProblem,
+) -> Report<'b> {
+    match version_problem {
+        VersionProblem::Invalid {
+            package_str,
+            version,
+            required_by,
+        } => {
+            let doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow(r"This package, which "),
+                    alloc.string(required_by.display().to_string()),
+                    alloc.reflow(r" depends on:"),
+                ]),
+                alloc
+                    .string(package_str)
+                    .annotate(Annotation::Url)
+                    .indent(4),
+                alloc.concat([
+                    alloc.reflow(
+                        r"has a version in its header which isn't a valid semantic version: ",
+                    ),
+                    alloc
+                        .string(format!("\"{version}\""))
+                        .annotate(Annotation::Emphasized),
+                ]),
+                alloc.concat([
+                    alloc.reflow(r"Versions must have a major, minor and patch number, like "),
+                    alloc.parser_suggestion("version \"1.2.3\""),
+                    alloc.reflow(r"."),
+                ]),
+            ]);
+
+            Report {
+                filename: required_by,
+                doc,
+                title: "INVALID PACKAGE VERSION".to_string(),
+                severity: Severity::Fatal,
+            }
+        }
+        VersionProblem::Conflict {
+            package_name,
+            existing,
+            new,
+        } => {
+            let requirement_doc = |requirement: Requirement| {
+                alloc.stack([
+                    alloc.concat([
+                        alloc.string(requirement.required_by.display().to_string()),
+                        alloc.reflow(r" depends on version "),
+                        alloc
+                            .string(requirement.version.to_string())
+                            .annotate(Annotation::Emphasized),
+                        alloc.reflow(r":"),
+                    ]),
+                    alloc
+                        .string(requirement.package_str)
+                        .annotate(Annotation::Url)
+                        .indent(4),
+                ])
+            };
+
+            let filename = new.required_by.clone();
+            let doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow(r"This app depends on two incompatible versions of the "),
+                    alloc.string(package_name).annotate(Annotation::Emphasized),
+                    alloc.reflow(r" package."),
+                ]),
+                requirement_doc(existing),
+                requirement_doc(new),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow(r"Versions are compatible when their major versions match, "),
+                    alloc.reflow(r"or, before 1.0.0, when their minor versions match. "),
+                    alloc.reflow(
+                        r"Update one of these dependencies so they both use compatible versions.",
+                    ),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "INCOMPATIBLE PACKAGE VERSIONS".to_string(),
+                severity: Severity::Fatal,
+            }
+        }
+    }
+}
+
 pub fn to_file_problem_report_string(filename: &Path, error: io::ErrorKind) -> String {
     let src_lines: Vec<&str> = Vec::new();
 
@@ -13,6 +13,7 @@ use core::str;
 
 mod roc_box;
 mod roc_dict;
+mod roc_hash;
 mod roc_list;
 mod roc_set;
 mod roc_str;
@@ -20,6 +21,7 @@ mod storage;
 
 pub use roc_box::RocBox;
 pub use roc_dict::RocDict;
+pub use roc_hash::{RocHash, RocHasher};
 pub use roc_list::{RocList, SendSafeRocList};
 pub use roc_set::RocSet;
 pub use roc_str::{InteriorNulError, RocStr, SendSafeRocStr};
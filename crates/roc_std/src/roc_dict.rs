@@ -1,12 +1,34 @@
+use crate::roc_hash::{RocHash, RocHasher};
 use crate::roc_list::RocList;
 use core::{
+    cmp::Ordering,
     fmt::{self, Debug},
-    hash::Hash,
-    mem::{align_of, ManuallyDrop},
+    hash::{Hash, Hasher},
+    iter,
+    mem::{self, align_of, ManuallyDrop},
 };
 
-/// At the moment, Roc's Dict is just an association list. Its lookups are O(n) but
-/// we haven't grown such big programs that it's a problem yet!
+/// Marks a slot in `metadata` that has never held an entry.
+const EMPTY_SLOT: i8 = -128;
+
+/// Roc's builtin `Dict`, which is a hash map along the lines of
+/// [absl::flat_hash_map](https://abseil.io/docs/cpp/guides/container) that keeps
+/// its entries in insertion order, like [IndexMap](https://docs.rs/indexmap).
+///
+/// The fields mirror the opaque record in `Dict.roc`, in the order Roc lays
+/// them out in memory (by alignment, then alphabetically):
+///
+/// - `data` holds the key-value pairs, in insertion order.
+/// - `data_indices` maps each slot of the table to an index into `data`.
+/// - `metadata` holds, for each slot, the low 7 bits of its key's hash. Slots
+///   without an entry are negative: [`EMPTY_SLOT`], or `-2` once Roc has
+///   removed the entry that was there.
+/// - `seed` is what the keys are hashed with. Roc picks it when the dictionary
+///   is created, so it is different for every build (and usually every run).
+///
+/// Lookups and insertions hash keys with [`RocHash`], which matches `Hash.roc`,
+/// and the dictionary's own seed, so they find and place entries in exactly the
+/// slots Roc would.
 ///
 /// We do some things in this data structure that only make sense because the
 /// memory is managed in Roc:
@@ -15,57 +37,316 @@ use core::{
 ///    since Roc owns the memory, not rust.
 /// 2. We use a union for [`RocDictItem`] instead of just a struct. See the
 ///    comment on that data structure for why.
-#[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[repr(transparent)]
-pub struct RocDict<K, V>(RocList<RocDictItem<K, V>>);
+#[derive(Clone)]
+#[repr(C)]
+pub struct RocDict<K, V> {
+    // Where a u64 is more aligned than a pointer, Roc puts the seed first.
+    #[cfg(all(target_pointer_width = "32", not(target_arch = "x86")))]
+    seed: u64,
+    data: RocList<RocDictItem<K, V>>,
+    data_indices: RocList<usize>,
+    metadata: RocList<i8>,
+    #[cfg(not(all(target_pointer_width = "32", not(target_arch = "x86"))))]
+    seed: u64,
+}
 
 impl<K, V> RocDict<K, V> {
+    /// The same as `Dict.empty {}` in Roc.
+    pub fn empty() -> Self {
+        Self {
+            data: RocList::empty(),
+            data_indices: RocList::from_slice(&[0; 8]),
+            metadata: RocList::from_slice(&[EMPTY_SLOT; 8]),
+            seed: pseudo_seed(),
+        }
+    }
+
+    /// The same as `Dict.withCapacity` in Roc: enough room for `capacity`
+    /// entries to be inserted without a rehash.
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity == 0 {
+            return Self::empty();
+        }
+
+        // The max load is 7/8, so grow by 8/7, then round up to a power of 2.
+        let slots = (capacity * 8 / 7).next_power_of_two().max(8);
+
+        Self {
+            data: RocList::with_capacity(slots),
+            data_indices: RocList::from_iter(iter::repeat(0).take(slots)),
+            metadata: RocList::from_iter(iter::repeat(EMPTY_SLOT).take(slots)),
+            seed: pseudo_seed(),
+        }
+    }
+
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.data.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.data.is_empty()
     }
 
-    pub fn with_capacity(capacity: usize) -> Self {
-        Self(RocList::with_capacity(capacity))
+    /// The same as `Dict.capacity` in Roc: how many entries fit before a rehash.
+    pub fn capacity(&self) -> usize {
+        let slots = self.data_indices.len();
+
+        slots - (slots >> 3)
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
-        self.0.iter().map(|item| (item.key(), item.value()))
+        self.data.iter().map(|item| (item.key(), item.value()))
     }
 
     pub fn iter_keys(&self) -> impl Iterator<Item = &K> {
-        self.0.iter().map(|item| item.key())
+        self.data.iter().map(|item| item.key())
     }
 
     pub fn iter_values(&self) -> impl Iterator<Item = &V> {
-        self.0.iter().map(|item| item.value())
+        self.data.iter().map(|item| item.value())
+    }
+}
+
+impl<K: RocHash + PartialEq, V> RocDict<K, V> {
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let index = self.find_index(key, &Slot::of(key, self.seed))?;
+        let data_index = self.data_indices[index];
+
+        Some(self.data[data_index].value())
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find_index(key, &Slot::of(key, self.seed)).is_some()
+    }
+
+    /// Returns the index into `metadata` of the slot holding `key`, searching
+    /// past deleted slots the way `findIndexHelper` in `Dict.roc` does.
+    fn find_index(&self, key: &K, slot: &Slot) -> Option<usize> {
+        let metadata = self.metadata.as_slice();
+        let mut probe = Probe::new(slot.h1, metadata.len())?;
+
+        loop {
+            for offset in 0..8 {
+                let index = probe.slot_index * 8 + offset;
+                let md = metadata[index];
+
+                if md == EMPTY_SLOT {
+                    return None;
+                } else if md == slot.h2 {
+                    let data_index = self.data_indices[index];
+
+                    if self.data[data_index].key() == key {
+                        return Some(index);
+                    }
+                }
+            }
+
+            probe.next();
+        }
+    }
+}
+
+impl<K: RocHash + PartialEq + Clone, V: Clone> RocDict<K, V> {
+    /// The same as `Dict.insert` in Roc. If the key was already present, its
+    /// value is replaced and the old value is returned.
+    ///
+    /// Any of the underlying lists that are shared with other values are copied
+    /// before being modified, like Roc would.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let slot = Slot::of(&key, self.seed);
+
+        if let Some(index) = self.find_index(&key, &slot) {
+            let data_index = self.data_indices[index];
+            let data = self.unique_data();
+
+            return Some(mem::replace(data[data_index].value_mut(), value));
+        }
+
+        self.maybe_rehash();
+
+        let index = next_empty_or_deleted(&self.metadata, slot.h1);
+        let data_index = self.data.len();
+
+        make_unique(&mut self.metadata)[index] = slot.h2;
+        make_unique(&mut self.data_indices)[index] = data_index;
+
+        self.unique_data();
+
+        if self.data.len() == self.data.capacity() {
+            self.data.reserve(self.data.len().max(8));
+        }
+
+        self.data.extend_from_slice(&[RocDictItem::new(key, value)]);
+
+        None
+    }
+
+    /// Grow the table if inserting one more entry would exceed the max load
+    /// factor of 7/8, the same way `maybeRehash` in `Dict.roc` does.
+    fn maybe_rehash(&mut self) {
+        if self.data.len() < self.capacity() {
+            return;
+        }
+
+        let slots = (self.data_indices.len() * 2).max(8);
+        let mut metadata = RocList::from_iter(iter::repeat(EMPTY_SLOT).take(slots));
+        let mut data_indices = RocList::from_iter(iter::repeat(0).take(slots));
+
+        for (index, &md) in self.metadata.iter().enumerate() {
+            if md >= 0 {
+                let data_index = self.data_indices[index];
+                let slot = Slot::of(self.data[data_index].key(), self.seed);
+                let new_index = next_empty_or_deleted(&metadata, slot.h1);
+
+                metadata[new_index] = slot.h2;
+                data_indices[new_index] = data_index;
+            }
+        }
+
+        self.metadata = metadata;
+        self.data_indices = data_indices;
+    }
+
+    fn unique_data(&mut self) -> &mut [RocDictItem<K, V>] {
+        if !self.data.is_unique() || self.data.is_seamless_slice() {
+            self.data = self.data.iter().cloned().collect();
+        }
+
+        self.data.as_mut_slice()
+    }
+}
+
+/// The seed for dictionaries created in Rust. Like `dictPseudoSeed` in the builtins'
+/// `utils.zig`, this is the address of this function, so it is hard to predict.
+fn pseudo_seed() -> u64 {
+    pseudo_seed as usize as u64
+}
+
+/// Copy the list's elements into a fresh allocation if anything else refers to them.
+fn make_unique<T: Clone>(list: &mut RocList<T>) -> &mut [T] {
+    if !list.is_unique() {
+        *list = RocList::from_slice(list.as_slice());
+    }
+
+    list.as_mut_slice()
+}
+
+/// The index of the first empty or deleted slot along the key's probe sequence.
+fn next_empty_or_deleted(metadata: &[i8], h1: u64) -> usize {
+    let mut probe = Probe::new(h1, metadata.len()).expect("a Roc Dict always has slots");
+
+    loop {
+        for offset in 0..8 {
+            let index = probe.slot_index * 8 + offset;
+
+            if metadata[index] < 0 {
+                return index;
+            }
+        }
+
+        probe.next();
+    }
+}
+
+/// A key's hash, split the way `h1` and `h2` in `Dict.roc` split it.
+struct Slot {
+    /// Picks the group of 8 slots to start probing from.
+    h1: u64,
+    /// Stored in `metadata` to rule out most non-matching keys without comparing them.
+    h2: i8,
+}
+
+impl Slot {
+    fn of<K: RocHash>(key: &K, seed: u64) -> Self {
+        let mut hasher = RocHasher::with_seed(seed);
+        key.roc_hash(&mut hasher);
+        let hash = hasher.complete();
+
+        Self {
+            h1: hash >> 7,
+            h2: (hash & 0b0111_1111) as i8,
+        }
     }
 }
 
-impl<K: Hash, V> RocDict<K, V> {
-    unsafe fn insert_unchecked(&mut self, _key: K, _val: V) {
-        todo!();
+/// Quadratic probing over groups of 8 slots, like `Probe` in `Dict.roc`.
+struct Probe {
+    slot_index: usize,
+    probe_i: usize,
+    mask: usize,
+}
+
+impl Probe {
+    fn new(h1: u64, metadata_len: usize) -> Option<Self> {
+        let slots = metadata_len >> 3;
+
+        if slots == 0 {
+            return None;
+        }
+
+        let mask = slots - 1;
+
+        Some(Self {
+            slot_index: h1 as usize & mask,
+            probe_i: 1,
+            mask,
+        })
+    }
+
+    fn next(&mut self) {
+        self.slot_index = (self.slot_index + self.probe_i) & self.mask;
+        self.probe_i += 1;
     }
 }
 
-impl<K: Hash, V> FromIterator<(K, V)> for RocDict<K, V> {
+impl<K, V> Default for RocDict<K, V> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<K: RocHash + PartialEq + Clone, V: Clone> FromIterator<(K, V)> for RocDict<K, V> {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(into_iter: T) -> Self {
         let src = into_iter.into_iter();
         let mut ret = Self::with_capacity(src.size_hint().0);
 
         for (key, val) in src {
-            unsafe {
-                ret.insert_unchecked(key, val);
-            }
+            ret.insert(key, val);
         }
 
         ret
     }
 }
 
+// Comparisons and hashing only look at the entries, in insertion order. The
+// rest of the table depends on the dictionary's capacity and history.
+
+impl<K: PartialEq, V: PartialEq> PartialEq for RocDict<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for RocDict<K, V> {}
+
+impl<K: PartialOrd, V: PartialOrd> PartialOrd for RocDict<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.data.partial_cmp(&other.data)
+    }
+}
+
+impl<K: Ord, V: Ord> Ord for RocDict<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.data.cmp(&other.data)
+    }
+}
+
+impl<K: Hash, V: Hash> Hash for RocDict<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state);
+    }
+}
+
 impl<'a, K, V> IntoIterator for &'a RocDict<K, V> {
     type Item = (&'a K, &'a V);
     type IntoIter = IntoIter<'a, K, V>;
@@ -73,7 +354,7 @@ impl<'a, K, V> IntoIterator for &'a RocDict<K, V> {
     fn into_iter(self) -> Self::IntoIter {
         IntoIter {
             index: 0,
-            items: self.0.as_slice(),
+            items: self.data.as_slice(),
         }
     }
 }
@@ -150,6 +431,18 @@ struct ValueFirst<K, V> {
 }
 
 impl<K, V> RocDictItem<K, V> {
+    fn new(key: K, value: V) -> Self {
+        if align_of::<K>() >= align_of::<V>() {
+            Self {
+                key_first: ManuallyDrop::new(KeyFirst { key, value }),
+            }
+        } else {
+            Self {
+                value_first: ManuallyDrop::new(ValueFirst { value, key }),
+            }
+        }
+    }
+
     fn key(&self) -> &K {
         if align_of::<K>() >= align_of::<V>() {
             unsafe { &self.key_first.key }
@@ -165,6 +458,20 @@ impl<K, V> RocDictItem<K, V> {
             unsafe { &self.value_first.value }
         }
     }
+
+    fn value_mut(&mut self) -> &mut V {
+        if align_of::<K>() >= align_of::<V>() {
+            unsafe { &mut self.key_first.value }
+        } else {
+            unsafe { &mut self.value_first.value }
+        }
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for RocDictItem<K, V> {
+    fn clone(&self) -> Self {
+        Self::new(self.key().clone(), self.value().clone())
+    }
 }
 
 impl<K, V> Drop for RocDictItem<K, V> {
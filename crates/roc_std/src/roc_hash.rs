@@ -0,0 +1,320 @@
+//! A Rust port of the hasher that Roc's builtin `Dict` uses (`LowLevelHasher` in
+//! `Dict.roc`), along with [`RocHash`], which feeds values into it the same way
+//! the `Hash` ability does in `Hash.roc`.
+//!
+//! Rust's own [`core::hash::Hash`] can't be used for this, because it hashes
+//! things differently than Roc does (for example, slices and strings get their
+//! lengths hashed too). Roc's hashes have to be reproduced bit-for-bit, or else
+//! lookups from Rust would look in the wrong slots of a dictionary Roc built.
+use crate::{RocDec, RocList, RocStr, I128, U128};
+
+const WYP0: u64 = 0xa0761d6478bd642f;
+const WYP1: u64 = 0xe7037ed1a0b428db;
+const WYP2: u64 = 0x8ebc6af09c88c6e3;
+const WYP3: u64 = 0x589965cc75374cc3;
+
+/// The same wyhash-based hasher as `LowLevelHasher` in `Dict.roc`.
+#[derive(Clone, Copy, Debug)]
+pub struct RocHasher {
+    initialized_seed: u64,
+    state: u64,
+}
+
+impl RocHasher {
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            initialized_seed: wymix(seed ^ WYP0, WYP1) ^ seed,
+            state: seed,
+        }
+    }
+
+    pub fn complete(&self) -> u64 {
+        self.state
+    }
+
+    pub fn add_u8(&mut self, u8: u8) {
+        let p0 = u8 as u64;
+        let a = (p0 << 16) | (p0 << 8) | p0;
+
+        self.combine(a, 0, self.initialized_seed, 1);
+    }
+
+    pub fn add_u16(&mut self, u16: u16) {
+        let p0 = (u16 & 0xFF) as u64;
+        let p1 = (u16 >> 8) as u64;
+        let a = (p0 << 16) | (p1 << 8) | p1;
+
+        self.combine(a, 0, self.initialized_seed, 2);
+    }
+
+    pub fn add_u32(&mut self, u32: u32) {
+        let p0 = u32 as u64;
+        let a = (p0 << 32) | p0;
+
+        self.combine(a, a, self.initialized_seed, 4);
+    }
+
+    pub fn add_u64(&mut self, u64: u64) {
+        let p0 = u64 & 0xFFFF_FFFF;
+        let p1 = u64 >> 32;
+        let a = (p0 << 32) | p1;
+        let b = (p1 << 32) | p0;
+
+        self.combine(a, b, self.initialized_seed, 8);
+    }
+
+    pub fn add_u128(&mut self, u128: u128) {
+        let lower = u128 as u64;
+        let upper = (u128 >> 64) as u64;
+        let p0 = lower & 0xFFFF_FFFF;
+        let p1 = lower >> 32;
+        let p2 = upper & 0xFFFF_FFFF;
+        let p3 = upper >> 32;
+        let a = (p0 << 32) | p2;
+        let b = (p3 << 32) | p1;
+
+        self.combine(a, b, self.initialized_seed, 16);
+    }
+
+    pub fn add_bytes(&mut self, bytes: &[u8]) {
+        let length = bytes.len();
+        let seed = self.initialized_seed;
+
+        let (a, b, seed) = if length <= 16 {
+            if length >= 4 {
+                let x = (length >> 3) << 2;
+                let a = (wyr4(bytes, 0) << 32) | wyr4(bytes, x);
+                let b = (wyr4(bytes, length - 4) << 32) | wyr4(bytes, length - 4 - x);
+
+                (a, b, seed)
+            } else if length > 0 {
+                (wyr3(bytes, 0, length), 0, seed)
+            } else {
+                (0, 0, seed)
+            }
+        } else if length <= 48 {
+            hash_bytes_helper_16(seed, bytes, 0, length)
+        } else {
+            hash_bytes_helper_48(seed, seed, seed, bytes, 0, length)
+        };
+
+        self.combine(a, b, seed, length as u64);
+    }
+
+    fn combine(&mut self, a: u64, b: u64, seed: u64, length: u64) {
+        let (lower, upper) = wymum(a ^ WYP1, b ^ seed);
+        let hash = wymix(lower ^ WYP0 ^ length, upper ^ WYP1);
+
+        self.state = wymix(self.state, hash);
+    }
+}
+
+fn hash_bytes_helper_48(
+    seed: u64,
+    see1: u64,
+    see2: u64,
+    bytes: &[u8],
+    index: usize,
+    remaining: usize,
+) -> (u64, u64, u64) {
+    let new_seed = wymix(wyr8(bytes, index) ^ WYP1, wyr8(bytes, index + 8) ^ seed);
+    let new_see1 = wymix(
+        wyr8(bytes, index + 16) ^ WYP2,
+        wyr8(bytes, index + 24) ^ see1,
+    );
+    let new_see2 = wymix(
+        wyr8(bytes, index + 32) ^ WYP3,
+        wyr8(bytes, index + 40) ^ see2,
+    );
+    let new_remaining = remaining - 48;
+    let new_index = index + 48;
+
+    if new_remaining > 48 {
+        hash_bytes_helper_48(
+            new_seed,
+            new_see1,
+            new_see2,
+            bytes,
+            new_index,
+            new_remaining,
+        )
+    } else if new_remaining > 16 {
+        let final_seed = new_see2 ^ new_see1 ^ new_seed;
+
+        hash_bytes_helper_16(final_seed, bytes, new_index, new_remaining)
+    } else {
+        let final_seed = new_see2 ^ new_see1 ^ new_seed;
+
+        (
+            wyr8(bytes, new_index + new_remaining - 16),
+            wyr8(bytes, new_index + new_remaining - 8),
+            final_seed,
+        )
+    }
+}
+
+fn hash_bytes_helper_16(
+    seed: u64,
+    bytes: &[u8],
+    index: usize,
+    remaining: usize,
+) -> (u64, u64, u64) {
+    let new_seed = wymix(wyr8(bytes, index) ^ WYP1, wyr8(bytes, index + 8) ^ seed);
+    let new_remaining = remaining - 16;
+    let new_index = index + 16;
+
+    if new_remaining <= 16 {
+        (
+            wyr8(bytes, new_index + new_remaining - 16),
+            wyr8(bytes, new_index + new_remaining - 8),
+            new_seed,
+        )
+    } else {
+        hash_bytes_helper_16(new_seed, bytes, new_index, new_remaining)
+    }
+}
+
+fn wymix(a: u64, b: u64) -> u64 {
+    let (lower, upper) = wymum(a, b);
+
+    lower ^ upper
+}
+
+fn wymum(a: u64, b: u64) -> (u64, u64) {
+    let r = a as u128 * b as u128;
+
+    (r as u64, (r >> 64) as u64)
+}
+
+fn wyr8(bytes: &[u8], index: usize) -> u64 {
+    let mut buf = [0; 8];
+    buf.copy_from_slice(&bytes[index..index + 8]);
+
+    u64::from_le_bytes(buf)
+}
+
+fn wyr4(bytes: &[u8], index: usize) -> u64 {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(&bytes[index..index + 4]);
+
+    u32::from_le_bytes(buf) as u64
+}
+
+fn wyr3(bytes: &[u8], index: usize, k: usize) -> u64 {
+    let p1 = bytes[index] as u64;
+    let p2 = bytes[index + (k >> 1)] as u64;
+    let p3 = bytes[index + k - 1] as u64;
+
+    (p1 << 16) | (p2 << 8) | p3
+}
+
+/// Values that can be hashed the same way Roc's `Hash` ability hashes them.
+///
+/// This is what [`crate::RocDict`] requires of its keys, so that Rust and Roc
+/// agree on where in the dictionary each key lives. For records, tuples and tag
+/// unions, implementations should hash their fields in the same order Roc's
+/// derived `Hash` implementation would.
+pub trait RocHash {
+    fn roc_hash(&self, hasher: &mut RocHasher);
+}
+
+macro_rules! roc_hash_int {
+    ($($ty:ty => $method:ident as $as_ty:ty),* $(,)?) => {
+        $(
+            impl RocHash for $ty {
+                fn roc_hash(&self, hasher: &mut RocHasher) {
+                    hasher.$method(*self as $as_ty);
+                }
+            }
+        )*
+    };
+}
+
+roc_hash_int! {
+    u8 => add_u8 as u8,
+    u16 => add_u16 as u16,
+    u32 => add_u32 as u32,
+    u64 => add_u64 as u64,
+    u128 => add_u128 as u128,
+    i8 => add_u8 as u8,
+    i16 => add_u16 as u16,
+    i32 => add_u32 as u32,
+    i64 => add_u64 as u64,
+    i128 => add_u128 as u128,
+}
+
+/// Like `Nat` in Roc, this hashes as a `U32` on 32-bit targets and a `U64` otherwise.
+impl RocHash for usize {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        #[cfg(target_pointer_width = "32")]
+        hasher.add_u32(*self as u32);
+
+        #[cfg(not(target_pointer_width = "32"))]
+        hasher.add_u64(*self as u64);
+    }
+}
+
+impl RocHash for bool {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        hasher.add_u8(*self as u8);
+    }
+}
+
+impl RocHash for () {
+    fn roc_hash(&self, _hasher: &mut RocHasher) {}
+}
+
+impl RocHash for I128 {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        hasher.add_u128(i128::from(*self) as u128);
+    }
+}
+
+impl RocHash for U128 {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        hasher.add_u128(u128::from(*self));
+    }
+}
+
+impl RocHash for RocDec {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        hasher.add_u128(self.as_i128() as u128);
+    }
+}
+
+impl RocHash for RocStr {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        hasher.add_bytes(self.as_str().as_bytes());
+    }
+}
+
+impl<T: RocHash> RocHash for RocList<T> {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        for elem in self.iter() {
+            elem.roc_hash(hasher);
+        }
+    }
+}
+
+impl<T: RocHash + ?Sized> RocHash for &T {
+    fn roc_hash(&self, hasher: &mut RocHasher) {
+        (**self).roc_hash(hasher);
+    }
+}
+
+macro_rules! roc_hash_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: RocHash),+> RocHash for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn roc_hash(&self, hasher: &mut RocHasher) {
+                let ($($name,)+) = self;
+
+                $($name.roc_hash(hasher);)+
+            }
+        }
+    };
+}
+
+roc_hash_tuple! { A B }
+roc_hash_tuple! { A B C }
+roc_hash_tuple! { A B C D }
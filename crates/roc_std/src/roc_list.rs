@@ -256,7 +256,7 @@ where
 
             if is_unique {
                 // If we have enough capacity, we can add to the existing elements in-place.
-                if self.capacity() >= slice.len() {
+                if self.capacity() >= new_len {
                     elements
                 } else {
                     // There wasn't enough capacity, so we need a new allocation.
@@ -283,8 +283,8 @@ where
                 }
 
                 // Allocate new memory.
-                self.capacity_or_ref_ptr = slice.len();
-                let new_elements = Self::elems_with_capacity(slice.len());
+                self.capacity_or_ref_ptr = new_len;
+                let new_elements = Self::elems_with_capacity(new_len);
 
                 // Copy the old elements to the new allocation.
                 unsafe {
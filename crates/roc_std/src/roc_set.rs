@@ -1,13 +1,20 @@
 use crate::roc_dict::RocDict;
+use crate::roc_hash::RocHash;
 use core::{
     fmt::{self, Debug},
     hash::Hash,
 };
 
+/// Roc's builtin `Set`, which is a `Dict` whose values are all `{}`.
 #[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
 pub struct RocSet<T>(RocDict<T, ()>);
 
 impl<T> RocSet<T> {
+    pub fn empty() -> Self {
+        Self(RocDict::empty())
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -16,18 +23,29 @@ impl<T> RocSet<T> {
         self.0.is_empty()
     }
 
-    #[allow(unused)]
     pub fn with_capacity(capacity: usize) -> Self {
         Self(RocDict::with_capacity(capacity))
     }
 
-    #[allow(unused)]
     pub fn iter(&self) -> impl Iterator<Item = &T> {
         self.0.iter_keys()
     }
 }
 
-impl<T: Hash> FromIterator<T> for RocSet<T> {
+impl<T: RocHash + PartialEq> RocSet<T> {
+    pub fn contains(&self, elem: &T) -> bool {
+        self.0.contains_key(elem)
+    }
+}
+
+impl<T: RocHash + PartialEq + Clone> RocSet<T> {
+    /// The same as `Set.insert` in Roc. Returns whether the element was newly added.
+    pub fn insert(&mut self, elem: T) -> bool {
+        self.0.insert(elem, ()).is_none()
+    }
+}
+
+impl<T: RocHash + PartialEq + Clone> FromIterator<T> for RocSet<T> {
     fn from_iter<I: IntoIterator<Item = T>>(into_iter: I) -> Self {
         Self(RocDict::from_iter(
             into_iter.into_iter().map(|elem| (elem, ())),
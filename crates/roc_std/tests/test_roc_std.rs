@@ -234,6 +234,28 @@ mod test_roc_std {
         assert_eq!(from_array.capacity(), from_slice.capacity());
     }
 
+    #[test]
+    fn extend_unique_list_past_capacity() {
+        // The slice fits in the capacity, but the list plus the slice doesn't
+        let mut list = RocList::with_capacity(2);
+        list.extend_from_slice(&[1i64, 2]);
+        list.extend_from_slice(&[3, 4]);
+
+        assert_eq!(list.as_slice(), &[1, 2, 3, 4]);
+        assert!(list.capacity() >= list.len());
+    }
+
+    #[test]
+    fn extend_shared_list() {
+        let original = RocList::from_slice(&[1i64, 2, 3]);
+        let mut copy = original.clone();
+        copy.extend_from_slice(&[4]);
+
+        assert_eq!(original.as_slice(), &[1, 2, 3]);
+        assert_eq!(copy.as_slice(), &[1, 2, 3, 4]);
+        assert!(copy.capacity() >= copy.len());
+    }
+
     #[test]
     fn roc_result_to_rust_result() {
         let greeting = "Hello, World!";
@@ -360,6 +382,162 @@ mod test_roc_std {
     }
 }
 
+#[cfg(test)]
+mod roc_dict {
+    use quickcheck_macros::quickcheck;
+    use roc_std::{RocDict, RocHasher, RocList, RocSet, RocStr};
+
+    // The same seed and expected hashes as the `expect`s in Dict.roc
+    const TEST_SEED: u64 = 0x526F_6352_616E_643F;
+
+    fn hash_bytes(bytes: &[u8]) -> u64 {
+        let mut hasher = RocHasher::with_seed(TEST_SEED);
+        hasher.add_bytes(bytes);
+        hasher.complete()
+    }
+
+    #[test]
+    fn hasher_matches_dict_roc() {
+        assert_eq!(hash_bytes(&[]), 0xD59C59757DBBE6B3);
+        assert_eq!(hash_bytes(&[0x42]), 0x38CE03D0E61AF963);
+        assert_eq!(hash_bytes(&[0xFF, 0xFF]), 0xE1CB2FA0D6A64113);
+        assert_eq!(hash_bytes(&[0x36, 0xA7]), 0x26B8319EDAF81B15);
+        assert_eq!(hash_bytes(&[0x00, 0x00, 0x00, 0x00]), 0xA187D7CA074F9EE7);
+        assert_eq!(hash_bytes(&[0xA9, 0x2F, 0xEE, 0x21]), 0xA499EFE4C1454D09);
+        assert_eq!(
+            hash_bytes(&[
+                0x5D, 0x66, 0xB1, 0x8F, 0x68, 0x44, 0xC7, 0x03, 0xE1, 0xDD, 0x23, 0x34, 0xBB, 0x9A,
+                0x42, 0xA7
+            ]),
+            0xDD39A206AED64C73
+        );
+        assert_eq!(
+            hash_bytes(b"abcdefghijklmnopqrstuvwxyz"),
+            0x51C59DF5B1D15F40
+        );
+        assert_eq!(
+            hash_bytes(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"),
+            0xD8D0A129D97A4E95
+        );
+        assert_eq!(
+            hash_bytes(b"1234567890123456789012345678901234567890123456789012345678901234567890"),
+            0x8188065B44FB4AAA
+        );
+        assert_eq!(hash_bytes(&[0x77; 100]), 0x47A2A606EADF3378);
+    }
+
+    #[test]
+    fn hasher_ints_match_bytes() {
+        let mut hasher = RocHasher::with_seed(TEST_SEED);
+        hasher.add_u8(0x42);
+        assert_eq!(hasher.complete(), hash_bytes(&[0x42]));
+
+        let mut hasher = RocHasher::with_seed(TEST_SEED);
+        hasher.add_u16(0xA736);
+        assert_eq!(hasher.complete(), hash_bytes(&[0x36, 0xA7]));
+
+        let mut hasher = RocHasher::with_seed(TEST_SEED);
+        hasher.add_u32(0x21EE_2FA9);
+        assert_eq!(hasher.complete(), hash_bytes(&[0xA9, 0x2F, 0xEE, 0x21]));
+
+        let mut hasher = RocHasher::with_seed(TEST_SEED);
+        hasher.add_u128(0xA742_9ABB_3423_DDE1_03C7_4468_8FB1_665D);
+        assert_eq!(hasher.complete(), 0xDD39A206AED64C73);
+    }
+
+    #[test]
+    fn empty_dict() {
+        let dict = RocDict::<u64, u64>::empty();
+
+        assert_eq!(dict.len(), 0);
+        assert_eq!(dict.capacity(), 7);
+        assert_eq!(dict.get(&1), None);
+    }
+
+    #[test]
+    fn insert_replaces_value() {
+        let mut dict = RocDict::empty();
+
+        assert_eq!(dict.insert(RocStr::from("foo"), 1u8), None);
+        assert_eq!(dict.insert(RocStr::from("foo"), 2u8), Some(1));
+        assert_eq!(dict.get(&RocStr::from("foo")), Some(&2));
+        assert_eq!(dict.len(), 1);
+    }
+
+    #[test]
+    fn insert_rehashes() {
+        let mut dict = RocDict::empty();
+
+        for (i, key) in ["a", "b", "c", "d", "e", "f", "g"].into_iter().enumerate() {
+            dict.insert(RocStr::from(key), i);
+        }
+
+        assert_eq!(dict.capacity(), 7);
+
+        dict.insert(RocStr::from("h"), 7);
+
+        assert_eq!(dict.capacity(), 14);
+
+        for (i, key) in ["a", "b", "c", "d", "e", "f", "g", "h"]
+            .into_iter()
+            .enumerate()
+        {
+            assert_eq!(dict.get(&RocStr::from(key)), Some(&i));
+        }
+    }
+
+    #[test]
+    fn insert_into_shared_dict() {
+        let original: RocDict<u32, RocList<u8>> =
+            [(1, RocList::from_slice(&[1]))].into_iter().collect();
+        let mut copy = original.clone();
+
+        copy.insert(1, RocList::from_slice(&[2]));
+        copy.insert(2, RocList::from_slice(&[3]));
+
+        assert_eq!(original.get(&1), Some(&RocList::from_slice(&[1])));
+        assert_eq!(original.get(&2), None);
+        assert_eq!(copy.get(&1), Some(&RocList::from_slice(&[2])));
+        assert_eq!(copy.get(&2), Some(&RocList::from_slice(&[3])));
+    }
+
+    #[test]
+    fn set_insert() {
+        let mut set = RocSet::empty();
+
+        assert!(set.insert(1i64));
+        assert!(!set.insert(1i64));
+        assert!(set.contains(&1));
+        assert!(!set.contains(&2));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[quickcheck]
+    fn dict_from_iter_finds_every_key(pairs: Vec<(i64, u16)>) -> bool {
+        let dict: RocDict<i64, u16> = pairs.iter().copied().collect();
+        let expected: std::collections::HashMap<i64, u16> = pairs.iter().copied().collect();
+
+        dict.len() == expected.len()
+            && expected
+                .iter()
+                .all(|(key, value)| dict.get(key) == Some(value))
+    }
+
+    #[quickcheck]
+    fn dict_keeps_insertion_order(keys: Vec<u32>) -> bool {
+        let dict: RocDict<u32, ()> = keys.iter().map(|&key| (key, ())).collect();
+        let mut expected = Vec::new();
+
+        for key in keys {
+            if !expected.contains(&key) {
+                expected.push(key);
+            }
+        }
+
+        dict.iter_keys().copied().eq(expected)
+    }
+}
+
 #[cfg(test)]
 mod with_terminator {
     use core::slice;
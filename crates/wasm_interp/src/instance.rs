@@ -5,6 +5,7 @@ use std::iter::{self, once, Iterator};
 use roc_wasm_module::opcodes::{MemoryInstruction, OpCode};
 use roc_wasm_module::parse::{Parse, SkipBytes};
 use roc_wasm_module::sections::{ImportDesc, MemorySection, SignatureParamsIter};
+use roc_wasm_module::source_map::SourceMap;
 use roc_wasm_module::{ExportType, WasmModule};
 use roc_wasm_module::{Value, ValueType};
 
@@ -66,6 +67,8 @@ pub struct Instance<'a, I: ImportDispatcher> {
     import_arguments: Vec<'a, Value>,
     /// temporary storage for output using the --debug option
     debug_string: Option<String>,
+    /// Source map embedded in the module, used to show Roc source locations with --debug
+    source_map: Option<SourceMap>,
 }
 
 impl<'a, I: ImportDispatcher> Instance<'a, I> {
@@ -95,6 +98,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             import_dispatcher,
             import_arguments: Vec::new_in(arena),
             debug_string: Some(String::new()),
+            source_map: None,
         }
     }
 
@@ -142,6 +146,18 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             None
         };
 
+        let source_map = if is_debug_mode && !module.source_mapping_url.url.is_empty() {
+            match SourceMap::from_data_url(module.source_mapping_url.url) {
+                Ok(source_map) => Some(source_map),
+                Err(e) => {
+                    eprintln!("Ignoring source map: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         let import_count = module.import.imports.len();
         let branch_cache = {
             let num_functions = import_count + module.code.function_count as usize;
@@ -163,6 +179,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             import_dispatcher,
             import_arguments: Vec::new_in(arena),
             debug_string,
+            source_map,
         })
     }
 
@@ -278,12 +295,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         };
 
         if self.debug_string.is_some() {
+            let file_offset = self.program_counter + module.code.section_offset as usize;
             println!(
                 "Calling export func[{}] '{}' at address {:#x}",
-                fn_index,
-                fn_name,
-                self.program_counter + module.code.section_offset as usize
+                fn_index, fn_name, file_offset
             );
+            if let Some(location) = self.debug_source_location(file_offset) {
+                println!("Roc source location: {location}");
+            }
         }
 
         Ok((fn_index, param_type_iter, return_type))
@@ -1864,7 +1883,21 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
 
             // Function and address match wasm-objdump formatting, for easy copy & find
             writeln!(buffer, "func[{fn_index}]  {fn_name}")?;
-            writeln!(buffer, "  address  {:06x}", execution_addrs.next().unwrap())?;
+            let execution_addr = execution_addrs.next().unwrap();
+            writeln!(buffer, "  address  {execution_addr:06x}")?;
+            let file_offset = execution_addr + self.module.code.section_offset as usize;
+            if let Some(location) = self.debug_source_location(file_offset) {
+                writeln!(buffer, "  source   {location}")?;
+            }
+
+            let local_names = self
+                .module
+                .names
+                .local_names
+                .iter()
+                .find(|(idx, _)| *idx == *fn_index as u32)
+                .map(|(_, locals)| locals.as_slice())
+                .unwrap_or(&[]);
 
             write!(buffer, "  args     ")?;
             for local_index in 0..*locals_count {
@@ -1874,7 +1907,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 } else if local_index != 0 {
                     write!(buffer, ", ")?;
                 }
-                write!(buffer, "{local_index}: {value:?}")?;
+                let local_name = local_names
+                    .iter()
+                    .find(|(idx, _)| *idx == local_index as u32)
+                    .map(|(_, name)| *name);
+                match local_name {
+                    Some(name) => write!(buffer, "{local_index} ({name}): {value:?}")?,
+                    None => write!(buffer, "{local_index}: {value:?}")?,
+                }
             }
 
             write!(buffer, "\n  stack    [")?;
@@ -1896,6 +1936,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         Ok(())
     }
 
+    /// Find the Roc source location of a file offset in the code section, if the module has a source map.
+    /// Mappings are zero-based, but we display them one-based like an editor would.
+    fn debug_source_location(&self, file_offset: usize) -> Option<String> {
+        let source_map = self.source_map.as_ref()?;
+        let (path, line, column) = source_map.lookup(file_offset as u32)?;
+        Some(format!("{}:{}:{}", path, line + 1, column + 1))
+    }
+
     // Call address is more intuitive than the return address in the stack trace. Search backward for it.
     fn debug_return_addr_to_call_addr(&self, return_addr: usize) -> usize {
         // return_addr is pointing at the next instruction after the CALL/CALLINDIRECT.
@@ -6,7 +6,7 @@ pub mod wasi;
 
 // Main external interface
 pub use instance::Instance;
-pub use wasi::{WasiDispatcher, WasiFile};
+pub use wasi::{WasiDir, WasiDispatcher, WasiFile};
 
 pub use roc_wasm_module::Value;
 use roc_wasm_module::ValueType;
@@ -12,6 +12,7 @@ use roc_wasm_module::WasmModule;
 pub const FLAG_FUNCTION: &str = "function";
 pub const FLAG_DEBUG: &str = "debug";
 pub const FLAG_HEX: &str = "hex";
+pub const FLAG_DIR: &str = "dir";
 pub const WASM_FILE: &str = "WASM_FILE";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
 
@@ -38,6 +39,13 @@ fn main() -> io::Result<()> {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_dir = Arg::new(FLAG_DIR)
+        .long(FLAG_DIR)
+        .help("Give the app access to a directory on the host. It can be given a different name inside the app, e.g. `--dir ./data::/app/data`. Can be repeated.")
+        .value_name("HOST_DIR[::GUEST_DIR]")
+        .action(ArgAction::Append)
+        .required(false);
+
     let wasm_file_to_run = Arg::new(WASM_FILE)
         .help("The .wasm file to run")
         .required(true);
@@ -51,6 +59,7 @@ fn main() -> io::Result<()> {
         .arg(flag_function)
         .arg(flag_debug)
         .arg(flag_hex)
+        .arg(flag_dir)
         .arg(wasm_file_to_run)
         .arg(args_for_app);
 
@@ -87,7 +96,17 @@ fn main() -> io::Result<()> {
 
     // Create an execution instance
 
-    let dispatcher = DefaultImportDispatcher::new(&wasi_argv);
+    let mut dispatcher = DefaultImportDispatcher::new(&wasi_argv);
+
+    for dir in matches.get_many::<String>(FLAG_DIR).unwrap_or_default() {
+        let (host_dir, guest_dir) = dir.split_once("::").unwrap_or((dir, dir));
+
+        if let Err(e) = dispatcher.wasi.preopen_dir(guest_dir, host_dir) {
+            eprintln!("I couldn't give the app access to the directory {host_dir}: {e}");
+            process::exit(1);
+        }
+    }
+
     let mut inst =
         Instance::for_module(&arena, &module, dispatcher, is_debug_mode).unwrap_or_else(|e| {
             eprintln!("{e}");
@@ -0,0 +1,513 @@
+use crate::wasi::{Errno, WasiDispatcher};
+use roc_wasm_module::Value;
+use std::fs;
+
+const PREOPEN_FD: i32 = 3;
+
+// Memory layout for the tests
+const PTR_OUT: usize = 0;
+const PTR_IOVS: usize = 64;
+const PTR_PATH: usize = 128;
+const PTR_DATA: usize = 256;
+const MEMORY_SIZE: usize = 1024;
+
+fn call(wasi: &mut WasiDispatcher, memory: &mut [u8], name: &str, args: &[Value]) -> i32 {
+    match wasi.dispatch(name, args, memory) {
+        Some(Value::I32(errno)) => errno,
+        other => panic!("{name} returned {other:?}"),
+    }
+}
+
+fn read_u32(memory: &[u8], addr: usize) -> u32 {
+    u32::from_le_bytes(memory[addr..][..4].try_into().unwrap())
+}
+
+fn read_u64(memory: &[u8], addr: usize) -> u64 {
+    u64::from_le_bytes(memory[addr..][..8].try_into().unwrap())
+}
+
+fn set_path(memory: &mut [u8], path: &str) -> [Value; 2] {
+    memory[PTR_PATH..][..path.len()].copy_from_slice(path.as_bytes());
+    [Value::I32(PTR_PATH as i32), Value::I32(path.len() as i32)]
+}
+
+fn set_iov(memory: &mut [u8], data: &[u8], len: usize) {
+    memory[PTR_DATA..][..data.len()].copy_from_slice(data);
+    memory[PTR_IOVS..][..4].copy_from_slice(&(PTR_DATA as u32).to_le_bytes());
+    memory[PTR_IOVS + 4..][..4].copy_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn open(
+    wasi: &mut WasiDispatcher,
+    memory: &mut [u8],
+    path: &str,
+    oflags: i32,
+    rights: i64,
+) -> Result<i32, i32> {
+    let [ptr_path, path_len] = set_path(memory, path);
+    let args = [
+        Value::I32(PREOPEN_FD),
+        Value::I32(LOOKUPFLAGS_SYMLINK_FOLLOW),
+        ptr_path,
+        path_len,
+        Value::I32(oflags),
+        Value::I64(rights),
+        Value::I64(rights),
+        Value::I32(0),
+        Value::I32(PTR_OUT as i32),
+    ];
+
+    match call(wasi, memory, "path_open", &args) {
+        0 => Ok(read_u32(memory, PTR_OUT) as i32),
+        errno => Err(errno),
+    }
+}
+
+const OFLAGS_CREAT: i32 = 1;
+const OFLAGS_DIRECTORY: i32 = 2;
+const LOOKUPFLAGS_SYMLINK_FOLLOW: i32 = 1;
+const RIGHTS_READ_WRITE: i64 = (1 << 1) | (1 << 6);
+
+#[test]
+fn test_preopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut wasi = WasiDispatcher::default();
+    wasi.preopen_dir("/data", dir.path()).unwrap();
+    let mut memory = vec![0; MEMORY_SIZE];
+
+    let args = [Value::I32(PREOPEN_FD), Value::I32(PTR_OUT as i32)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_prestat_get", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u32(&memory, PTR_OUT), 0);
+    assert_eq!(read_u32(&memory, PTR_OUT + 4), 5);
+
+    let args = [
+        Value::I32(PREOPEN_FD),
+        Value::I32(PTR_PATH as i32),
+        Value::I32(5),
+    ];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_prestat_dir_name", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(&memory[PTR_PATH..][..5], b"/data");
+
+    // stdio is not preopened, and neither is anything after the last preopen
+    for fd in [1, 4] {
+        let args = [Value::I32(fd), Value::I32(PTR_OUT as i32)];
+        assert_eq!(
+            call(&mut wasi, &mut memory, "fd_prestat_get", &args),
+            Errno::Badf as i32
+        );
+    }
+}
+
+#[test]
+fn test_file_roundtrip() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut wasi = WasiDispatcher::default();
+    wasi.preopen_dir("/", dir.path()).unwrap();
+    let mut memory = vec![0; MEMORY_SIZE];
+
+    assert_eq!(
+        open(&mut wasi, &mut memory, "hello.txt", 0, RIGHTS_READ_WRITE),
+        Err(Errno::Noent as i32)
+    );
+
+    let fd = open(
+        &mut wasi,
+        &mut memory,
+        "hello.txt",
+        OFLAGS_CREAT,
+        RIGHTS_READ_WRITE,
+    )
+    .unwrap();
+    assert_eq!(fd, 4);
+
+    set_iov(&mut memory, b"Hello, World!", 13);
+    let args = [
+        Value::I32(fd),
+        Value::I32(PTR_IOVS as i32),
+        Value::I32(1),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_write", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u32(&memory, PTR_OUT), 13);
+    assert_eq!(
+        fs::read(dir.path().join("hello.txt")).unwrap(),
+        b"Hello, World!"
+    );
+
+    let args = [
+        Value::I32(fd),
+        Value::I64(7),
+        Value::I32(0),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_seek", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u64(&memory, PTR_OUT), 7);
+
+    set_iov(&mut memory, &[0; 16], 16);
+    let args = [
+        Value::I32(fd),
+        Value::I32(PTR_IOVS as i32),
+        Value::I32(1),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_read", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u32(&memory, PTR_OUT), 6);
+    assert_eq!(&memory[PTR_DATA..][..6], b"World!");
+
+    let args = [Value::I32(fd), Value::I32(PTR_DATA as i32)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_filestat_get", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(memory[PTR_DATA + 16], 4); // regular file
+    assert_eq!(read_u64(&memory, PTR_DATA + 32), 13);
+
+    let args = [Value::I32(fd)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_close", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_close", &args),
+        Errno::Badf as i32
+    );
+
+    // The closed file descriptor gets reused
+    let fd = open(&mut wasi, &mut memory, "hello.txt", 0, RIGHTS_READ_WRITE).unwrap();
+    assert_eq!(fd, 4);
+}
+
+#[test]
+fn test_sandbox() {
+    let parent = tempfile::tempdir().unwrap();
+    let dir = parent.path().join("sandbox");
+    fs::create_dir(&dir).unwrap();
+    fs::write(parent.path().join("secret.txt"), "secret").unwrap();
+
+    let mut wasi = WasiDispatcher::default();
+    wasi.preopen_dir("/", &dir).unwrap();
+    let mut memory = vec![0; MEMORY_SIZE];
+
+    for path in ["../secret.txt", "a/../../secret.txt", "/secret.txt"] {
+        assert_eq!(
+            open(&mut wasi, &mut memory, path, 0, RIGHTS_READ_WRITE),
+            Err(Errno::Notcapable as i32),
+            "{path}"
+        );
+    }
+
+    // Going up is fine as long as it stays inside
+    fs::write(dir.join("inside.txt"), "").unwrap();
+    assert!(open(
+        &mut wasi,
+        &mut memory,
+        "sub/../inside.txt",
+        0,
+        RIGHTS_READ_WRITE
+    )
+    .is_ok());
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(parent.path().join("secret.txt"), dir.join("link")).unwrap();
+        assert_eq!(
+            open(&mut wasi, &mut memory, "link", 0, RIGHTS_READ_WRITE),
+            Err(Errno::Notcapable as i32)
+        );
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn test_sandbox_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let parent = tempfile::tempdir().unwrap();
+    let dir = parent.path().join("sandbox");
+    fs::create_dir(&dir).unwrap();
+    fs::write(parent.path().join("secret.txt"), "secret").unwrap();
+
+    let mut wasi = WasiDispatcher::default();
+    wasi.preopen_dir("/", &dir).unwrap();
+    let mut memory = vec![0; MEMORY_SIZE];
+
+    // A dangling symlink can't be used to create a file outside
+    symlink(parent.path().join("created.txt"), dir.join("dangling")).unwrap();
+    assert_eq!(
+        open(
+            &mut wasi,
+            &mut memory,
+            "dangling",
+            OFLAGS_CREAT,
+            RIGHTS_READ_WRITE
+        ),
+        Err(Errno::Notcapable as i32)
+    );
+    assert!(!parent.path().join("created.txt").exists());
+
+    // A symlink to a directory outside can't be used anywhere in a path
+    symlink(parent.path(), dir.join("up")).unwrap();
+    for path in ["up/secret.txt", "up/sandbox/../secret.txt", "up/new.txt"] {
+        assert_eq!(
+            open(
+                &mut wasi,
+                &mut memory,
+                path,
+                OFLAGS_CREAT,
+                RIGHTS_READ_WRITE
+            ),
+            Err(Errno::Notcapable as i32),
+            "{path}"
+        );
+    }
+    assert!(!parent.path().join("new.txt").exists());
+
+    // Symlinks that stay inside are fine
+    fs::create_dir(dir.join("real")).unwrap();
+    symlink(dir.join("real"), dir.join("alias")).unwrap();
+    assert!(open(
+        &mut wasi,
+        &mut memory,
+        "alias/file.txt",
+        OFLAGS_CREAT,
+        RIGHTS_READ_WRITE
+    )
+    .is_ok());
+    assert!(dir.join("real/file.txt").exists());
+
+    // Operations on the symlink itself don't follow it, so the dangling one can be read and
+    // removed, but not opened without following it.
+    let [ptr_path, path_len] = set_path(&mut memory, "dangling");
+    let args = [
+        Value::I32(PREOPEN_FD),
+        ptr_path,
+        path_len,
+        Value::I32(PTR_DATA as i32),
+        Value::I32(256),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_readlink", &args),
+        Errno::Success as i32
+    );
+    let target = parent.path().join("created.txt");
+    let target = target.to_str().unwrap().as_bytes();
+    assert_eq!(read_u32(&memory, PTR_OUT) as usize, target.len());
+    assert_eq!(&memory[PTR_DATA..][..target.len()], target);
+
+    let args = [
+        Value::I32(PREOPEN_FD),
+        Value::I32(0),
+        ptr_path,
+        path_len,
+        Value::I32(0),
+        Value::I64(RIGHTS_READ_WRITE),
+        Value::I64(RIGHTS_READ_WRITE),
+        Value::I32(0),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_open", &args),
+        Errno::Loop as i32
+    );
+
+    let args = [Value::I32(PREOPEN_FD), ptr_path, path_len];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_unlink_file", &args),
+        Errno::Success as i32
+    );
+    assert!(fs::symlink_metadata(dir.join("dangling")).is_err());
+}
+
+#[test]
+fn test_file_descriptor_operations() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut wasi = WasiDispatcher::default();
+    wasi.preopen_dir("/", dir.path()).unwrap();
+    let mut memory = vec![0; MEMORY_SIZE];
+
+    let a = open(
+        &mut wasi,
+        &mut memory,
+        "a.txt",
+        OFLAGS_CREAT,
+        RIGHTS_READ_WRITE,
+    )
+    .unwrap();
+    let b = open(
+        &mut wasi,
+        &mut memory,
+        "b.txt",
+        OFLAGS_CREAT,
+        RIGHTS_READ_WRITE,
+    )
+    .unwrap();
+
+    // Allocating only ever grows the file
+    let args = [Value::I32(a), Value::I64(4), Value::I64(6)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_allocate", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(fs::metadata(dir.path().join("a.txt")).unwrap().len(), 10);
+
+    let args = [Value::I32(a), Value::I64(0), Value::I64(2)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_allocate", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(fs::metadata(dir.path().join("a.txt")).unwrap().len(), 10);
+
+    let args = [Value::I32(a), Value::I64(0), Value::I64(10), Value::I32(0)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_advise", &args),
+        Errno::Success as i32
+    );
+
+    // `a` takes the place of `b`, which is closed
+    let args = [Value::I32(a), Value::I32(b)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_renumber", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_renumber", &args),
+        Errno::Badf as i32
+    );
+
+    let args = [Value::I32(b), Value::I32(PTR_DATA as i32)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_filestat_get", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u64(&memory, PTR_DATA + 32), 10);
+
+    // Hard links share their contents
+    let old_path = "a.txt";
+    let new_path = "c.txt";
+    memory[PTR_PATH..][..old_path.len()].copy_from_slice(old_path.as_bytes());
+    memory[PTR_DATA..][..new_path.len()].copy_from_slice(new_path.as_bytes());
+    let args = [
+        Value::I32(PREOPEN_FD),
+        Value::I32(0),
+        Value::I32(PTR_PATH as i32),
+        Value::I32(old_path.len() as i32),
+        Value::I32(PREOPEN_FD),
+        Value::I32(PTR_DATA as i32),
+        Value::I32(new_path.len() as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_link", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(fs::metadata(dir.path().join("c.txt")).unwrap().len(), 10);
+
+    // What std can't do is reported as not implemented, rather than crashing the interpreter
+    let args = [Value::I32(b), Value::I64(0), Value::I64(0), Value::I32(0)];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_filestat_set_times", &args),
+        Errno::Nosys as i32
+    );
+}
+
+#[test]
+fn test_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut wasi = WasiDispatcher::default();
+    wasi.preopen_dir("/", dir.path()).unwrap();
+    let mut memory = vec![0; MEMORY_SIZE];
+
+    let [ptr_path, path_len] = set_path(&mut memory, "sub");
+    let args = [Value::I32(PREOPEN_FD), ptr_path, path_len];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_create_directory", &args),
+        Errno::Success as i32
+    );
+    assert!(dir.path().join("sub").is_dir());
+
+    fs::write(dir.path().join("sub/b.txt"), "").unwrap();
+    fs::write(dir.path().join("sub/a.txt"), "").unwrap();
+
+    let fd = open(&mut wasi, &mut memory, "sub", OFLAGS_DIRECTORY, 0).unwrap();
+
+    let args = [
+        Value::I32(fd),
+        Value::I32(PTR_DATA as i32),
+        Value::I32(512),
+        Value::I64(0),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_readdir", &args),
+        Errno::Success as i32
+    );
+
+    let bufused = read_u32(&memory, PTR_OUT) as usize;
+    let mut names = Vec::new();
+    let mut offset = 0;
+    while offset < bufused {
+        let dirent = PTR_DATA + offset;
+        let namlen = read_u32(&memory, dirent + 16) as usize;
+        names.push(String::from_utf8(memory[dirent + 24..][..namlen].to_vec()).unwrap());
+        offset += 24 + namlen;
+    }
+    assert_eq!(names, [".", "..", "a.txt", "b.txt"]);
+
+    // Resuming from a cookie skips the entries before it
+    let args = [
+        Value::I32(fd),
+        Value::I32(PTR_DATA as i32),
+        Value::I32(512),
+        Value::I64(3),
+        Value::I32(PTR_OUT as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "fd_readdir", &args),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u32(&memory, PTR_OUT), 24 + 5);
+    assert_eq!(&memory[PTR_DATA + 24..][..5], b"b.txt");
+
+    let [ptr_path, path_len] = set_path(&mut memory, "sub");
+    let args = [Value::I32(PREOPEN_FD), ptr_path, path_len];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_remove_directory", &args),
+        Errno::Notempty as i32
+    );
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_unlink_file", &args),
+        Errno::Isdir as i32
+    );
+
+    for file in ["sub/a.txt", "sub/b.txt"] {
+        let [ptr_path, path_len] = set_path(&mut memory, file);
+        let args = [Value::I32(PREOPEN_FD), ptr_path, path_len];
+        assert_eq!(
+            call(&mut wasi, &mut memory, "path_unlink_file", &args),
+            Errno::Success as i32
+        );
+    }
+
+    let [ptr_path, path_len] = set_path(&mut memory, "sub");
+    let args = [Value::I32(PREOPEN_FD), ptr_path, path_len];
+    assert_eq!(
+        call(&mut wasi, &mut memory, "path_remove_directory", &args),
+        Errno::Success as i32
+    );
+    assert!(!dir.path().join("sub").exists());
+}
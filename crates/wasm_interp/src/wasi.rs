@@ -1,7 +1,10 @@
 use rand::prelude::*;
 use roc_wasm_module::Value;
-use std::io::{self, Read, StderrLock, StdoutLock, Write};
+use std::fs::{self, File, Metadata, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, StderrLock, StdoutLock, Write};
+use std::path::{Component, Path, PathBuf};
 use std::process::exit;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub const MODULE_NAME: &str = "wasi_snapshot_preview1";
 
@@ -22,14 +25,54 @@ pub enum WasiFile {
     WriteOnly(Vec<u8>),
     ReadWrite(Vec<u8>),
     HostSystemFile,
+    /// A file on the host filesystem, opened by the app with `path_open`
+    HostFile(File),
+    /// A directory on the host filesystem, either preopened or opened by the app with `path_open`
+    Directory(WasiDir),
+    /// A file descriptor the app has closed. It will be reused by the next `path_open`.
+    Closed,
+}
+
+pub struct WasiDir {
+    /// The canonical path of the preopened directory this one is in. The app can't access
+    /// anything outside of it.
+    pub sandbox: PathBuf,
+    pub host_path: PathBuf,
+    /// The path the app knows this directory by, if it was preopened
+    pub preopen_name: Option<String>,
 }
 
 enum WriteLock<'a> {
     StdOut(StdoutLock<'a>),
     Stderr(StderrLock<'a>),
     RegularFile(&'a mut Vec<u8>),
+    HostFile(&'a File),
 }
 
+// https://github.com/WebAssembly/WASI/blob/snapshot-01/phases/snapshot/docs.md
+const FILETYPE_UNKNOWN: u8 = 0;
+const FILETYPE_CHARACTER_DEVICE: u8 = 2;
+const FILETYPE_DIRECTORY: u8 = 3;
+const FILETYPE_REGULAR_FILE: u8 = 4;
+const FILETYPE_SYMBOLIC_LINK: u8 = 7;
+
+const OFLAGS_CREAT: i32 = 1 << 0;
+const OFLAGS_DIRECTORY: i32 = 1 << 1;
+const OFLAGS_EXCL: i32 = 1 << 2;
+const OFLAGS_TRUNC: i32 = 1 << 3;
+
+const FDFLAGS_APPEND: i32 = 1 << 0;
+
+const LOOKUPFLAGS_SYMLINK_FOLLOW: i32 = 1 << 0;
+
+const RIGHTS_FD_READ: i64 = 1 << 1;
+const RIGHTS_FD_WRITE: i64 = 1 << 6;
+/// We don't restrict what the app can do with the files it opens, other than keeping it in the
+/// sandbox, so we grant every right there is.
+const RIGHTS_ALL: u64 = (1 << 29) - 1;
+
+const PREOPENTYPE_DIR: u32 = 0;
+
 /// Implementation of WASI syscalls
 /// References for other engines:
 /// https://github.com/wasmerio/wasmer/blob/ef8d2f651ed29b4b06fdc2070eb8189922c54d82/lib/wasi/src/syscalls/mod.rs
@@ -47,6 +90,28 @@ impl<'a> WasiDispatcher<'a> {
         }
     }
 
+    /// Give the app access to the host directory `host_path`, which it will see as `guest_path`.
+    /// Call this before running the app, since WASI libc looks for preopened directories when it
+    /// starts up.
+    pub fn preopen_dir(&mut self, guest_path: &str, host_path: impl AsRef<Path>) -> io::Result<()> {
+        let sandbox = fs::canonicalize(host_path)?;
+
+        if !sandbox.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a directory", sandbox.display()),
+            ));
+        }
+
+        self.files.push(WasiFile::Directory(WasiDir {
+            host_path: sandbox.clone(),
+            sandbox,
+            preopen_name: Some(guest_path.to_string()),
+        }));
+
+        Ok(())
+    }
+
     pub fn dispatch(
         &mut self,
         function_name: &str,
@@ -109,10 +174,45 @@ impl<'a> WasiDispatcher<'a> {
             }
             "clock_res_get" => success_code, // this dummy implementation seems to be good enough for some functions
             "clock_time_get" => success_code,
-            "fd_advise" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_allocate" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_close" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_datasync" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_advise" => {
+                // (fd, offset, len, advice)
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+
+                // The advice is only a hint, so it's fine to ignore it
+                errno_value(self.host_file(fd).map(|_| ()))
+            }
+            "fd_allocate" => {
+                // (fd, offset, len)
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let offset = arguments[1].expect_i64().unwrap() as u64;
+                let len = arguments[2].expect_i64().unwrap() as u64;
+
+                let result = self.host_file(fd).and_then(|file| {
+                    let end = offset.checked_add(len).ok_or(Errno::Fbig)?;
+                    if file.metadata()?.len() < end {
+                        file.set_len(end)?;
+                    }
+                    Ok(())
+                });
+
+                errno_value(result)
+            }
+            "fd_close" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+
+                match self.files.get_mut(fd) {
+                    Some(WasiFile::Closed) | None => Some(Value::I32(Errno::Badf as i32)),
+                    Some(file) => {
+                        *file = WasiFile::Closed;
+                        success_code
+                    }
+                }
+            }
+            "fd_datasync" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+
+                errno_value(self.host_file(fd).and_then(|file| Ok(file.sync_data()?)))
+            }
             "fd_fdstat_get" => {
                 // (i32, i32) -> i32
 
@@ -121,9 +221,14 @@ impl<'a> WasiDispatcher<'a> {
                 // ptr to a wasi_fdstat_t
                 let stat_mut_ptr = arguments[1].expect_i32().unwrap() as usize;
 
-                match fd {
-                    1 => {
-                        // Tell WASI that stdout is a tty (no seek or tell)
+                let (filetype, rights) = match self.files.get(fd) {
+                    Some(
+                        WasiFile::HostSystemFile
+                        | WasiFile::ReadOnly(_)
+                        | WasiFile::WriteOnly(_)
+                        | WasiFile::ReadWrite(_),
+                    ) => {
+                        // Tell WASI that stdio is a tty (no seek or tell)
                         // https://github.com/WebAssembly/wasi-libc/blob/659ff414560721b1660a19685110e484a081c3d4/libc-bottom-half/sources/isatty.c
                         // *Not* a tty if:
                         //     (statbuf.fs_filetype != __WASI_FILETYPE_CHARACTER_DEVICE ||
@@ -131,25 +236,77 @@ impl<'a> WasiDispatcher<'a> {
                         // So it's sufficient to set:
                         //     .fs_filetype = __WASI_FILETYPE_CHARACTER_DEVICE
                         //     .fs_rights_base = 0
+                        (FILETYPE_CHARACTER_DEVICE, 0)
+                    }
+                    Some(WasiFile::HostFile(_)) => (FILETYPE_REGULAR_FILE, RIGHTS_ALL),
+                    Some(WasiFile::Directory(_)) => (FILETYPE_DIRECTORY, RIGHTS_ALL),
+                    Some(WasiFile::Closed) | None => return Some(Value::I32(Errno::Badf as i32)),
+                };
 
-                        const WASI_FILETYPE_CHARACTER_DEVICE: u8 = 2;
-                        memory[stat_mut_ptr] = WASI_FILETYPE_CHARACTER_DEVICE;
+                // fs_filetype: u8, fs_flags: u16, fs_rights_base: u64, fs_rights_inheriting: u64
+                memory[stat_mut_ptr..][..24].fill(0);
+                memory[stat_mut_ptr] = filetype;
+                write_u64(memory, stat_mut_ptr + 8, rights);
+                write_u64(memory, stat_mut_ptr + 16, rights);
 
-                        for b in memory[stat_mut_ptr + 1..stat_mut_ptr + 24].iter_mut() {
-                            *b = 0;
-                        }
+                success_code
+            }
+            // The host can't change the flags of a file that is already open, and we don't keep
+            // track of rights.
+            "fd_fdstat_set_flags" | "fd_fdstat_set_rights" => Some(Value::I32(Errno::Nosys as i32)),
+            "fd_filestat_get" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                // ptr to a wasi_filestat_t
+                let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
+
+                let metadata = match self.files.get(fd) {
+                    Some(WasiFile::HostFile(file)) => file.metadata(),
+                    Some(WasiFile::Directory(dir)) => fs::metadata(&dir.host_path),
+                    Some(
+                        WasiFile::HostSystemFile
+                        | WasiFile::ReadOnly(_)
+                        | WasiFile::WriteOnly(_)
+                        | WasiFile::ReadWrite(_),
+                    ) => {
+                        memory[ptr_buf..][..64].fill(0);
+                        memory[ptr_buf + 16] = FILETYPE_CHARACTER_DEVICE;
+                        return success_code;
                     }
-                    _ => todo!("WASI {}({:?})", function_name, arguments),
-                }
+                    Some(WasiFile::Closed) | None => return Some(Value::I32(Errno::Badf as i32)),
+                };
 
-                success_code
+                errno_value(
+                    metadata
+                        .map(|metadata| write_filestat(memory, ptr_buf, &metadata))
+                        .map_err(Errno::from),
+                )
+            }
+            "fd_filestat_set_size" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let size = arguments[1].expect_i64().unwrap() as u64;
+
+                errno_value(self.host_file(fd).and_then(|file| Ok(file.set_len(size)?)))
+            }
+            // std can't set the access and modification times of a file
+            "fd_filestat_set_times" => Some(Value::I32(Errno::Nosys as i32)),
+            "fd_pread" => {
+                // (fd, iovs, iovs_len, offset, ptr_nread)
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_iovs = arguments[1].expect_i32().unwrap() as usize;
+                let iovs_len = arguments[2].expect_i32().unwrap() as usize;
+                let offset = arguments[3].expect_i64().unwrap() as u64;
+                let ptr_nread = arguments[4].expect_i32().unwrap() as usize;
+
+                let result = self.host_file(fd).and_then(|file| {
+                    let n_read = at_offset(file, offset, |mut file| {
+                        read_iovs(&mut file, memory, ptr_iovs, iovs_len)
+                    })?;
+                    write_u32(memory, ptr_nread, n_read as u32);
+                    Ok(())
+                });
+
+                errno_value(result)
             }
-            "fd_fdstat_set_flags" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_fdstat_set_rights" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_size" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_pread" => todo!("WASI {}({:?})", function_name, arguments),
             "fd_prestat_get" => {
                 // The preopened file descriptor to query
                 let fd = arguments[0].expect_i32().unwrap() as usize;
@@ -157,19 +314,57 @@ impl<'a> WasiDispatcher<'a> {
                 //  preopen type: 4 bytes, where 0=dir is the only one supported, it seems
                 //  preopen name length: 4 bytes
                 let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
-                memory[ptr_buf..][..8].copy_from_slice(&0u64.to_le_bytes());
-                if fd < self.files.len() {
-                    success_code
-                } else {
-                    println!("WASI warning: file descriptor {fd} does not exist");
-                    Some(Value::I32(Errno::Badf as i32))
+
+                // WASI libc asks about every file descriptor after stdio until it gets an error,
+                // so not finding one is expected.
+                match self.files.get(fd) {
+                    Some(WasiFile::Directory(WasiDir {
+                        preopen_name: Some(name),
+                        ..
+                    })) => {
+                        write_u32(memory, ptr_buf, PREOPENTYPE_DIR);
+                        write_u32(memory, ptr_buf + 4, name.len() as u32);
+                        success_code
+                    }
+                    _ => Some(Value::I32(Errno::Badf as i32)),
                 }
             }
             "fd_prestat_dir_name" => {
-                // We're not giving names to any of our files so just return success
-                success_code
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_path = arguments[1].expect_i32().unwrap() as usize;
+                let path_len = arguments[2].expect_i32().unwrap() as usize;
+
+                match self.files.get(fd) {
+                    Some(WasiFile::Directory(WasiDir {
+                        preopen_name: Some(name),
+                        ..
+                    })) => {
+                        // The name is not zero-terminated
+                        let len = name.len().min(path_len);
+                        memory[ptr_path..][..len].copy_from_slice(&name.as_bytes()[..len]);
+                        success_code
+                    }
+                    _ => Some(Value::I32(Errno::Badf as i32)),
+                }
+            }
+            "fd_pwrite" => {
+                // (fd, iovs, iovs_len, offset, ptr_nwritten)
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_iovs = arguments[1].expect_i32().unwrap() as usize;
+                let iovs_len = arguments[2].expect_i32().unwrap() as usize;
+                let offset = arguments[3].expect_i64().unwrap() as u64;
+                let ptr_nwritten = arguments[4].expect_i32().unwrap() as usize;
+
+                let result = self.host_file(fd).and_then(|file| {
+                    let n_written = at_offset(file, offset, |mut file| {
+                        write_iovs(&mut file, memory, ptr_iovs, iovs_len)
+                    })?;
+                    write_u32(memory, ptr_nwritten, n_written as u32);
+                    Ok(())
+                });
+
+                errno_value(result)
             }
-            "fd_pwrite" => todo!("WASI {}({:?})", function_name, arguments),
             "fd_read" => {
                 use WasiFile::*;
 
@@ -203,6 +398,14 @@ impl<'a> WasiDispatcher<'a> {
                             n_read += len;
                         }
                     }
+                    Some(HostFile(file)) => {
+                        let mut file: &File = file;
+                        match read_iovs(&mut file, memory, ptr_iovs, iovs_len as usize) {
+                            Ok(n) => n_read = n,
+                            Err(error) => return Some(Value::I32(Errno::from(error) as i32)),
+                        }
+                    }
+                    Some(Directory(_)) => return Some(Value::I32(Errno::Isdir as i32)),
                     Some(HostSystemFile) if fd == 0 => {
                         let mut stdin = io::stdin();
                         for _ in 0..iovs_len {
@@ -224,11 +427,82 @@ impl<'a> WasiDispatcher<'a> {
                 memory[ptr_nread..][..4].copy_from_slice(&(n_read as u32).to_le_bytes());
                 success_code
             }
-            "fd_readdir" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_renumber" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_seek" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_sync" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_tell" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_readdir" => {
+                // (fd, buf, buf_len, cookie, ptr_bufused)
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
+                let buf_len = arguments[2].expect_i32().unwrap() as usize;
+                let cookie = arguments[3].expect_i64().unwrap() as u64;
+                let ptr_bufused = arguments[4].expect_i32().unwrap() as usize;
+
+                let result = self.dir(fd).and_then(|dir| {
+                    let buf = &mut memory[ptr_buf..][..buf_len];
+                    let bufused = write_dirents(&dir.host_path, cookie, buf)?;
+                    write_u32(memory, ptr_bufused, bufused as u32);
+                    Ok(())
+                });
+
+                errno_value(result)
+            }
+            "fd_renumber" => {
+                // (fd, to)
+                let from = arguments[0].expect_i32().unwrap() as usize;
+                let to = arguments[1].expect_i32().unwrap() as usize;
+
+                // Both file descriptors must be open. `to` is closed, and `from` takes its place.
+                match (self.files.get(from), self.files.get(to)) {
+                    (Some(WasiFile::Closed) | None, _) | (_, Some(WasiFile::Closed) | None) => {
+                        Some(Value::I32(Errno::Badf as i32))
+                    }
+                    _ if from == to => success_code,
+                    _ => {
+                        self.files[to] = std::mem::replace(&mut self.files[from], WasiFile::Closed);
+                        success_code
+                    }
+                }
+            }
+            "fd_seek" => {
+                // (fd, offset, whence, ptr_newoffset)
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let offset = arguments[1].expect_i64().unwrap();
+                let whence = arguments[2].expect_i32().unwrap();
+                let ptr_newoffset = arguments[3].expect_i32().unwrap() as usize;
+
+                let position = match whence {
+                    0 => match u64::try_from(offset) {
+                        Ok(offset) => SeekFrom::Start(offset),
+                        Err(_) => return Some(Value::I32(Errno::Inval as i32)),
+                    },
+                    1 => SeekFrom::Current(offset),
+                    2 => SeekFrom::End(offset),
+                    _ => return Some(Value::I32(Errno::Inval as i32)),
+                };
+
+                let result = self.seekable_file(fd).and_then(|mut file| {
+                    let new_offset = file.seek(position)?;
+                    write_u64(memory, ptr_newoffset, new_offset);
+                    Ok(())
+                });
+
+                errno_value(result)
+            }
+            "fd_sync" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+
+                errno_value(self.host_file(fd).and_then(|file| Ok(file.sync_all()?)))
+            }
+            "fd_tell" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_offset = arguments[1].expect_i32().unwrap() as usize;
+
+                let result = self.seekable_file(fd).and_then(|mut file| {
+                    let offset = file.stream_position()?;
+                    write_u64(memory, ptr_offset, offset);
+                    Ok(())
+                });
+
+                errno_value(result)
+            }
             "fd_write" => {
                 use WasiFile::*;
 
@@ -252,6 +526,8 @@ impl<'a> WasiDispatcher<'a> {
                     Some(WriteOnly(content) | ReadWrite(content)) => {
                         WriteLock::RegularFile(content)
                     }
+                    Some(HostFile(file)) => WriteLock::HostFile(file),
+                    Some(Directory(_)) => return Some(Value::I32(Errno::Isdir as i32)),
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
@@ -280,6 +556,7 @@ impl<'a> WasiDispatcher<'a> {
                         WriteLock::StdOut(stdout) => stdout.write_all(bytes),
                         WriteLock::Stderr(stderr) => stderr.write_all(bytes),
                         WriteLock::RegularFile(content) => content.write_all(bytes),
+                        WriteLock::HostFile(file) => file.write_all(bytes),
                     };
                     if write_result.is_err() {
                         break;
@@ -300,16 +577,182 @@ impl<'a> WasiDispatcher<'a> {
                     Err(_) => Some(Value::I32(Errno::Io as i32)),
                 }
             }
-            "path_create_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_link" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_open" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_readlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_remove_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_rename" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_symlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_unlink_file" => todo!("WASI {}({:?})", function_name, arguments),
+            "path_create_directory" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_path = arguments[1].expect_i32().unwrap() as usize;
+                let path_len = arguments[2].expect_i32().unwrap() as usize;
+
+                let result = read_path(memory, ptr_path, path_len)
+                    .and_then(|path| self.resolve_path(fd, path, false))
+                    .and_then(|host_path| Ok(fs::create_dir(host_path)?));
+
+                errno_value(result)
+            }
+            "path_filestat_get" => {
+                // (fd, lookupflags, path, path_len, ptr_buf)
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let lookupflags = arguments[1].expect_i32().unwrap();
+                let ptr_path = arguments[2].expect_i32().unwrap() as usize;
+                let path_len = arguments[3].expect_i32().unwrap() as usize;
+                // ptr to a wasi_filestat_t
+                let ptr_buf = arguments[4].expect_i32().unwrap() as usize;
+
+                let follow_symlinks = lookupflags & LOOKUPFLAGS_SYMLINK_FOLLOW != 0;
+
+                let result = read_path(memory, ptr_path, path_len)
+                    .and_then(|path| self.resolve_path(fd, path, follow_symlinks))
+                    .and_then(|host_path| {
+                        let metadata = if follow_symlinks {
+                            fs::metadata(host_path)?
+                        } else {
+                            fs::symlink_metadata(host_path)?
+                        };
+                        write_filestat(memory, ptr_buf, &metadata);
+                        Ok(())
+                    });
+
+                errno_value(result)
+            }
+            // std can't set the access and modification times of a file
+            "path_filestat_set_times" => Some(Value::I32(Errno::Nosys as i32)),
+            "path_link" => {
+                // (old_fd, old_lookupflags, old_path, old_path_len, new_fd, new_path, new_path_len)
+                let old_fd = arguments[0].expect_i32().unwrap() as usize;
+                let old_lookupflags = arguments[1].expect_i32().unwrap();
+                let ptr_old_path = arguments[2].expect_i32().unwrap() as usize;
+                let old_path_len = arguments[3].expect_i32().unwrap() as usize;
+                let new_fd = arguments[4].expect_i32().unwrap() as usize;
+                let ptr_new_path = arguments[5].expect_i32().unwrap() as usize;
+                let new_path_len = arguments[6].expect_i32().unwrap() as usize;
+
+                let follow_symlinks = old_lookupflags & LOOKUPFLAGS_SYMLINK_FOLLOW != 0;
+
+                let result = read_path(memory, ptr_old_path, old_path_len)
+                    .and_then(|path| self.resolve_path(old_fd, path, follow_symlinks))
+                    .and_then(|old_host_path| {
+                        let new_path = read_path(memory, ptr_new_path, new_path_len)?;
+                        let new_host_path = self.resolve_path(new_fd, new_path, false)?;
+
+                        // Hard links to a symlink are links to the symlink itself, so resolve it
+                        // first if we were asked to follow it.
+                        let old_host_path = if follow_symlinks {
+                            fs::canonicalize(old_host_path)?
+                        } else {
+                            old_host_path
+                        };
+
+                        Ok(fs::hard_link(old_host_path, new_host_path)?)
+                    });
+
+                errno_value(result)
+            }
+            "path_open" => {
+                // (fd, lookupflags, path, path_len, oflags, rights_base, rights_inheriting, fdflags, ptr_fd)
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let lookupflags = arguments[1].expect_i32().unwrap();
+                let ptr_path = arguments[2].expect_i32().unwrap() as usize;
+                let path_len = arguments[3].expect_i32().unwrap() as usize;
+                let oflags = arguments[4].expect_i32().unwrap();
+                let rights = arguments[5].expect_i64().unwrap();
+                let fdflags = arguments[7].expect_i32().unwrap();
+                let ptr_opened_fd = arguments[8].expect_i32().unwrap() as usize;
+
+                let follow_symlinks = lookupflags & LOOKUPFLAGS_SYMLINK_FOLLOW != 0;
+
+                let result = read_path(memory, ptr_path, path_len).and_then(|path| {
+                    self.path_open(fd, path, follow_symlinks, oflags, rights, fdflags)
+                });
+
+                match result {
+                    Ok(opened_fd) => {
+                        write_u32(memory, ptr_opened_fd, opened_fd as u32);
+                        success_code
+                    }
+                    Err(errno) => Some(Value::I32(errno as i32)),
+                }
+            }
+            "path_readlink" => {
+                // (fd, path, path_len, buf, buf_len, ptr_bufused)
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_path = arguments[1].expect_i32().unwrap() as usize;
+                let path_len = arguments[2].expect_i32().unwrap() as usize;
+                let ptr_buf = arguments[3].expect_i32().unwrap() as usize;
+                let buf_len = arguments[4].expect_i32().unwrap() as usize;
+                let ptr_bufused = arguments[5].expect_i32().unwrap() as usize;
+
+                let result = read_path(memory, ptr_path, path_len)
+                    .and_then(|path| self.resolve_path(fd, path, false))
+                    .and_then(|host_path| {
+                        let target = fs::read_link(host_path)?;
+                        let target = target.to_str().ok_or(Errno::Ilseq)?.as_bytes();
+
+                        // Like readlink(2), truncate the target if it doesn't fit, and don't
+                        // zero-terminate it.
+                        let len = target.len().min(buf_len);
+                        memory[ptr_buf..][..len].copy_from_slice(&target[..len]);
+                        write_u32(memory, ptr_bufused, len as u32);
+                        Ok(())
+                    });
+
+                errno_value(result)
+            }
+            "path_remove_directory" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_path = arguments[1].expect_i32().unwrap() as usize;
+                let path_len = arguments[2].expect_i32().unwrap() as usize;
+
+                let result = read_path(memory, ptr_path, path_len)
+                    .and_then(|path| self.resolve_path(fd, path, false))
+                    .and_then(|host_path| {
+                        if !fs::symlink_metadata(&host_path)?.is_dir() {
+                            return Err(Errno::Notdir);
+                        }
+                        if fs::read_dir(&host_path)?.next().is_some() {
+                            return Err(Errno::Notempty);
+                        }
+                        Ok(fs::remove_dir(host_path)?)
+                    });
+
+                errno_value(result)
+            }
+            "path_rename" => {
+                // (fd, old_path, old_path_len, new_fd, new_path, new_path_len)
+                let old_fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_old_path = arguments[1].expect_i32().unwrap() as usize;
+                let old_path_len = arguments[2].expect_i32().unwrap() as usize;
+                let new_fd = arguments[3].expect_i32().unwrap() as usize;
+                let ptr_new_path = arguments[4].expect_i32().unwrap() as usize;
+                let new_path_len = arguments[5].expect_i32().unwrap() as usize;
+
+                let result = read_path(memory, ptr_old_path, old_path_len)
+                    .and_then(|path| self.resolve_path(old_fd, path, false))
+                    .and_then(|old_host_path| {
+                        let new_path = read_path(memory, ptr_new_path, new_path_len)?;
+                        let new_host_path = self.resolve_path(new_fd, new_path, false)?;
+                        Ok(fs::rename(old_host_path, new_host_path)?)
+                    });
+
+                errno_value(result)
+            }
+            // Symlinks that the app creates could point anywhere, and creating them portably
+            // needs to know whether the target is a directory, so we don't support it.
+            "path_symlink" => Some(Value::I32(Errno::Nosys as i32)),
+            "path_unlink_file" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let ptr_path = arguments[1].expect_i32().unwrap() as usize;
+                let path_len = arguments[2].expect_i32().unwrap() as usize;
+
+                let result = read_path(memory, ptr_path, path_len)
+                    .and_then(|path| self.resolve_path(fd, path, false))
+                    .and_then(|host_path| {
+                        if fs::symlink_metadata(&host_path)?.is_dir() {
+                            return Err(Errno::Isdir);
+                        }
+                        Ok(fs::remove_file(host_path)?)
+                    });
+
+                errno_value(result)
+            }
             "poll_oneoff" => todo!("WASI {}({:?})", function_name, arguments),
             "proc_exit" => {
                 let exit_code = arguments[0].expect_i32().unwrap();
@@ -333,6 +776,294 @@ impl<'a> WasiDispatcher<'a> {
             _ => panic!("Unknown WASI function {function_name}({arguments:?})"),
         }
     }
+
+    fn path_open(
+        &mut self,
+        fd: usize,
+        path: &str,
+        follow_symlinks: bool,
+        oflags: i32,
+        rights: i64,
+        fdflags: i32,
+    ) -> Result<usize, Errno> {
+        let host_path = self.resolve_path(fd, path, follow_symlinks)?;
+        let sandbox = self.dir(fd)?.sandbox.clone();
+
+        // Like O_NOFOLLOW, since the host would follow it
+        if !follow_symlinks && fs::symlink_metadata(&host_path).map_or(false, |m| m.is_symlink()) {
+            return Err(Errno::Loop);
+        }
+
+        let read = rights & RIGHTS_FD_READ != 0;
+        let write = rights & RIGHTS_FD_WRITE != 0;
+        let is_dir = host_path.is_dir();
+
+        let opened = if oflags & OFLAGS_DIRECTORY != 0 || (is_dir && !write) {
+            if !is_dir {
+                return Err(if host_path.exists() {
+                    Errno::Notdir
+                } else {
+                    Errno::Noent
+                });
+            }
+
+            WasiFile::Directory(WasiDir {
+                sandbox,
+                host_path,
+                preopen_name: None,
+            })
+        } else if is_dir {
+            return Err(Errno::Isdir);
+        } else {
+            let file = OpenOptions::new()
+                // The host insists on one of these, and reading is the harmless one
+                .read(read || !write)
+                .write(write)
+                .append(fdflags & FDFLAGS_APPEND != 0)
+                .create(oflags & OFLAGS_CREAT != 0)
+                .create_new(oflags & OFLAGS_CREAT != 0 && oflags & OFLAGS_EXCL != 0)
+                .truncate(oflags & OFLAGS_TRUNC != 0)
+                .open(host_path)?;
+
+            WasiFile::HostFile(file)
+        };
+
+        // Like POSIX, reuse the lowest free file descriptor
+        let opened_fd = match self
+            .files
+            .iter()
+            .position(|f| matches!(f, WasiFile::Closed))
+        {
+            Some(free_fd) => {
+                self.files[free_fd] = opened;
+                free_fd
+            }
+            None => {
+                self.files.push(opened);
+                self.files.len() - 1
+            }
+        };
+
+        Ok(opened_fd)
+    }
+
+    fn dir(&self, fd: usize) -> Result<&WasiDir, Errno> {
+        match self.files.get(fd) {
+            Some(WasiFile::Directory(dir)) => Ok(dir),
+            Some(WasiFile::Closed) | None => Err(Errno::Badf),
+            Some(_) => Err(Errno::Notdir),
+        }
+    }
+
+    fn host_file(&self, fd: usize) -> Result<&File, Errno> {
+        match self.files.get(fd) {
+            Some(WasiFile::HostFile(file)) => Ok(file),
+            Some(WasiFile::Directory(_)) => Err(Errno::Isdir),
+            Some(WasiFile::Closed) | None => Err(Errno::Badf),
+            Some(_) => Err(Errno::Inval),
+        }
+    }
+
+    fn seekable_file(&self, fd: usize) -> Result<&File, Errno> {
+        match self.host_file(fd) {
+            // stdio and in-memory files are streams
+            Err(Errno::Inval) => Err(Errno::Spipe),
+            result => result,
+        }
+    }
+
+    /// Find the host path for `path`, relative to the directory `fd`, making sure it stays
+    /// inside the sandbox. Absolute paths are not allowed, since WASI libc resolves them against
+    /// the preopened directories before calling us. A symlink at the end of the path is only
+    /// checked if `follow_symlinks` is set, for operations that act on the symlink itself.
+    fn resolve_path(&self, fd: usize, path: &str, follow_symlinks: bool) -> Result<PathBuf, Errno> {
+        let dir = self.dir(fd)?;
+        let mut host_path = dir.host_path.clone();
+
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(name) => host_path.push(name),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    host_path.pop();
+                }
+                Component::RootDir | Component::Prefix(_) => return Err(Errno::Notcapable),
+            }
+
+            if !host_path.starts_with(&dir.sandbox) {
+                return Err(Errno::Notcapable);
+            }
+        }
+
+        // The path could still escape through a symlink in any of its components. Check where
+        // each one that is a symlink really leads. A dangling symlink could lead anywhere once
+        // its target is created, e.g. by opening it with O_CREAT, so it isn't allowed either.
+        let mut checked = dir.sandbox.clone();
+        let components: Vec<_> = host_path
+            .strip_prefix(&dir.sandbox)
+            .map_err(|_| Errno::Notcapable)?
+            .components()
+            .collect();
+
+        for (index, component) in components.iter().enumerate() {
+            checked.push(component);
+
+            if index == components.len() - 1 && !follow_symlinks {
+                break;
+            }
+
+            match fs::symlink_metadata(&checked) {
+                Ok(metadata) if metadata.is_symlink() => match fs::canonicalize(&checked) {
+                    Ok(real_path) if real_path.starts_with(&dir.sandbox) => {}
+                    _ => return Err(Errno::Notcapable),
+                },
+                Ok(_) => {}
+                // Nothing below a path that doesn't exist can be a symlink
+                Err(_) => break,
+            }
+        }
+
+        Ok(host_path)
+    }
+}
+
+fn errno_value(result: Result<(), Errno>) -> Option<Value> {
+    let errno = match result {
+        Ok(()) => Errno::Success,
+        Err(errno) => errno,
+    };
+
+    Some(Value::I32(errno as i32))
+}
+
+fn read_path(memory: &[u8], ptr_path: usize, path_len: usize) -> Result<&str, Errno> {
+    std::str::from_utf8(&memory[ptr_path..][..path_len]).map_err(|_| Errno::Ilseq)
+}
+
+/// Read into an array of `iovs_len` IO vectors, until one of them is not filled completely
+fn read_iovs(
+    reader: &mut impl Read,
+    memory: &mut [u8],
+    ptr_iovs: usize,
+    iovs_len: usize,
+) -> io::Result<usize> {
+    let mut n_read = 0;
+
+    for i in 0..iovs_len {
+        let ptr_iov = ptr_iovs + 8 * i;
+        let iov_base = read_u32(memory, ptr_iov) as usize;
+        let iov_len = read_u32(memory, ptr_iov + 4) as usize;
+
+        let n = reader.read(&mut memory[iov_base..][..iov_len])?;
+        n_read += n;
+        if n < iov_len {
+            break;
+        }
+    }
+
+    Ok(n_read)
+}
+
+fn write_iovs(
+    writer: &mut impl Write,
+    memory: &[u8],
+    ptr_iovs: usize,
+    iovs_len: usize,
+) -> io::Result<usize> {
+    let mut n_written = 0;
+
+    for i in 0..iovs_len {
+        let ptr_iov = ptr_iovs + 8 * i;
+        let iov_base = read_u32(memory, ptr_iov) as usize;
+        let iov_len = read_u32(memory, ptr_iov + 4) as usize;
+
+        writer.write_all(&memory[iov_base..][..iov_len])?;
+        n_written += iov_len;
+    }
+
+    Ok(n_written)
+}
+
+/// Do something at `offset` in `file`, without moving the file's own position
+fn at_offset<T>(
+    mut file: &File,
+    offset: u64,
+    f: impl FnOnce(&File) -> io::Result<T>,
+) -> Result<T, Errno> {
+    let position = file.stream_position()?;
+    file.seek(SeekFrom::Start(offset))?;
+    let result = f(file);
+    file.seek(SeekFrom::Start(position))?;
+
+    Ok(result?)
+}
+
+/// Write as many `wasi_dirent_t`s (each followed by its name) as fit into `buf`, starting at
+/// entry number `cookie`. If the last one doesn't fit, it's cut off. Returns the number of
+/// bytes written, which is less than the length of `buf` once all entries have been written.
+fn write_dirents(dir: &Path, cookie: u64, buf: &mut [u8]) -> Result<usize, Errno> {
+    let mut entries = vec![
+        (".".to_string(), FILETYPE_DIRECTORY),
+        ("..".to_string(), FILETYPE_DIRECTORY),
+    ];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let filetype = filetype(&entry.file_type()?);
+        entries.push((entry.file_name().to_string_lossy().into_owned(), filetype));
+    }
+    // The cookie is an index into this list, so it has to be the same from one call to the next
+    entries[2..].sort();
+
+    let mut used = 0;
+    let start = cookie.min(entries.len() as u64) as usize;
+
+    for (index, (name, filetype)) in entries.iter().enumerate().skip(start) {
+        // d_next: u64, d_ino: u64, d_namlen: u32, d_type: u8, then padding
+        let mut dirent = [0u8; 24];
+        dirent[0..8].copy_from_slice(&(index as u64 + 1).to_le_bytes());
+        dirent[16..20].copy_from_slice(&(name.len() as u32).to_le_bytes());
+        dirent[20] = *filetype;
+
+        for byte in dirent.iter().chain(name.as_bytes()) {
+            if used == buf.len() {
+                return Ok(used);
+            }
+            buf[used] = *byte;
+            used += 1;
+        }
+    }
+
+    Ok(used)
+}
+
+fn filetype(file_type: &fs::FileType) -> u8 {
+    if file_type.is_dir() {
+        FILETYPE_DIRECTORY
+    } else if file_type.is_file() {
+        FILETYPE_REGULAR_FILE
+    } else if file_type.is_symlink() {
+        FILETYPE_SYMBOLIC_LINK
+    } else {
+        FILETYPE_UNKNOWN
+    }
+}
+
+/// Write a `wasi_filestat_t`
+fn write_filestat(memory: &mut [u8], ptr_buf: usize, metadata: &Metadata) {
+    let nanos = |time: io::Result<SystemTime>| {
+        time.ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_nanos() as u64)
+    };
+
+    // dev: u64, ino: u64, filetype: u8, nlink: u64, size: u64, atim: u64, mtim: u64, ctim: u64
+    memory[ptr_buf..][..64].fill(0);
+    memory[ptr_buf + 16] = filetype(&metadata.file_type());
+    write_u64(memory, ptr_buf + 24, 1);
+    write_u64(memory, ptr_buf + 32, metadata.len());
+    write_u64(memory, ptr_buf + 40, nanos(metadata.accessed()));
+    write_u64(memory, ptr_buf + 48, nanos(metadata.modified()));
+    write_u64(memory, ptr_buf + 56, nanos(metadata.created()));
 }
 
 fn read_u32(memory: &[u8], addr: usize) -> u32 {
@@ -355,12 +1086,16 @@ fn write_i32(memory: &mut [u8], addr: usize, value: i32) {
     memory[addr..][..4].copy_from_slice(&value.to_le_bytes());
 }
 
+fn write_u64(memory: &mut [u8], addr: usize, value: u64) {
+    memory[addr..][..8].copy_from_slice(&value.to_le_bytes());
+}
+
 /// Error codes returned by functions.
 /// Not all of these error codes are returned by the functions provided by this
 /// API; some are used in higher-level library layers, and others are provided
 /// merely for alignment with POSIX.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Errno {
     /// No error occurred. System call completed successfully.
     Success,
@@ -517,3 +1252,18 @@ pub enum Errno {
     /// Extension: Capabilities insufficient.
     Notcapable,
 }
+
+impl From<io::Error> for Errno {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => Errno::Noent,
+            io::ErrorKind::PermissionDenied => Errno::Access,
+            io::ErrorKind::AlreadyExists => Errno::Exist,
+            io::ErrorKind::InvalidInput => Errno::Inval,
+            io::ErrorKind::Interrupted => Errno::Intr,
+            io::ErrorKind::WouldBlock => Errno::Again,
+            io::ErrorKind::Unsupported => Errno::Notsup,
+            _ => Errno::Io,
+        }
+    }
+}
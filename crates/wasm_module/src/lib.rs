@@ -3,6 +3,7 @@ pub mod opcodes;
 pub mod parse;
 pub mod sections;
 pub mod serialize;
+pub mod source_map;
 
 use std::iter::repeat;
 
@@ -19,7 +20,7 @@ use self::parse::{Parse, ParseError};
 use self::sections::{
     CodeSection, DataSection, ElementSection, ExportSection, FunctionSection, GlobalSection,
     ImportDesc, ImportSection, MemorySection, NameSection, OpaqueSection, Section, SectionId,
-    TableSection, TypeSection,
+    SourceMappingUrlSection, TableSection, TypeSection,
 };
 pub use self::serialize::{SerialBuffer, Serialize};
 
@@ -45,6 +46,7 @@ pub struct WasmModule<'a> {
     pub reloc_code: RelocationSection<'a>,
     pub reloc_data: RelocationSection<'a>,
     pub names: NameSection<'a>,
+    pub source_mapping_url: SourceMappingUrlSection<'a>,
 }
 
 impl<'a> WasmModule<'a> {
@@ -67,6 +69,7 @@ impl<'a> WasmModule<'a> {
             reloc_code: RelocationSection::new(arena, "reloc.CODE"),
             reloc_data: RelocationSection::new(arena, "reloc.DATA"),
             names: NameSection::new(arena),
+            source_mapping_url: SourceMappingUrlSection::new(),
         }
     }
 
@@ -96,6 +99,7 @@ impl<'a> WasmModule<'a> {
         self.code.serialize(buffer);
         self.data.serialize(buffer);
         self.names.serialize(buffer);
+        self.source_mapping_url.serialize(buffer);
     }
 
     /// Module size in bytes (assuming no linker data)
@@ -113,6 +117,7 @@ impl<'a> WasmModule<'a> {
             + self.code.size()
             + self.data.size()
             + self.names.size()
+            + self.source_mapping_url.size()
     }
 
     pub fn preload(
@@ -150,6 +155,7 @@ impl<'a> WasmModule<'a> {
         let mut reloc_code = RelocationSection::new(arena, "reloc.CODE");
         let mut reloc_data = RelocationSection::new(arena, "reloc.DATA");
         let mut names = NameSection::new(arena);
+        let mut source_mapping_url = SourceMappingUrlSection::new();
 
         // Consume all remaining Custom sections
         while let Ok((section_name, section_end)) = Self::peek_custom_section(arena, bytes, cursor)
@@ -169,6 +175,9 @@ impl<'a> WasmModule<'a> {
                 "name" => {
                     names = NameSection::parse(arena, bytes, &mut cursor)?;
                 }
+                "sourceMappingURL" => {
+                    source_mapping_url = SourceMappingUrlSection::parse(arena, bytes, &mut cursor)?;
+                }
                 _ => {
                     cursor = section_end;
                 }
@@ -230,6 +239,7 @@ impl<'a> WasmModule<'a> {
             reloc_code,
             reloc_data,
             names,
+            source_mapping_url,
         })
     }
 
@@ -1712,6 +1712,8 @@ enum NameSubSections {
 
 pub struct NameSection<'a> {
     pub function_names: Vec<'a, (u32, &'a str)>,
+    /// Names of the locals of each function, sorted by function index and then local index
+    pub local_names: Vec<'a, (u32, Vec<'a, (u32, &'a str)>)>,
 }
 
 impl<'a> NameSection<'a> {
@@ -1719,19 +1721,49 @@ impl<'a> NameSection<'a> {
     const NAME: &'static str = "name";
 
     pub fn size(&self) -> usize {
-        self.function_names
+        let function_names_size: usize = self
+            .function_names
             .iter()
             .map(|(_, s)| MAX_SIZE_ENCODED_U32 + s.len())
-            .sum()
+            .sum();
+
+        let local_names_size: usize = self
+            .local_names
+            .iter()
+            .map(|(_, locals)| {
+                2 * MAX_SIZE_ENCODED_U32
+                    + locals
+                        .iter()
+                        .map(|(_, s)| MAX_SIZE_ENCODED_U32 + s.len())
+                        .sum::<usize>()
+            })
+            .sum();
+
+        function_names_size + local_names_size
     }
 
     pub fn append_function(&mut self, index: u32, name: &'a str) {
         self.function_names.push((index, name));
     }
 
+    /// Name the locals of a function. `locals` must be sorted by local index,
+    /// and functions must be appended in order of their index.
+    pub fn append_locals(&mut self, fn_index: u32, locals: Vec<'a, (u32, &'a str)>) {
+        debug_assert!(self
+            .local_names
+            .last()
+            .map(|(prev, _)| *prev < fn_index)
+            .unwrap_or(true));
+
+        if !locals.is_empty() {
+            self.local_names.push((fn_index, locals));
+        }
+    }
+
     pub fn new(arena: &'a Bump) -> Self {
         NameSection {
             function_names: bumpalo::vec![in arena],
+            local_names: bumpalo::vec![in arena],
         }
     }
 
@@ -1757,7 +1789,10 @@ impl<'a> NameSection<'a> {
         let mut function_names = Vec::from_iter_in(names, arena);
         function_names.sort_by_key(|(idx, _name)| *idx);
 
-        NameSection { function_names }
+        NameSection {
+            function_names,
+            local_names: bumpalo::vec![in arena],
+        }
     }
 }
 
@@ -1816,6 +1851,7 @@ impl<'a> Parse<&'a Bump> for NameSection<'a> {
         let count = u32::parse((), module_bytes, cursor)?;
         let mut section = NameSection {
             function_names: Vec::with_capacity_in(count as usize, arena),
+            local_names: bumpalo::vec![in arena],
         };
 
         // Function names
@@ -1825,6 +1861,26 @@ impl<'a> Parse<&'a Bump> for NameSection<'a> {
             section.function_names.push((index, name));
         }
 
+        // Local names are optional, but if they're present, they come next
+        if *cursor < section_end && module_bytes[*cursor] == NameSubSections::LocalNames as u8 {
+            *cursor += 1;
+            let _subsection_size = u32::parse((), module_bytes, cursor)?;
+
+            let fn_count = u32::parse((), module_bytes, cursor)?;
+            section.local_names.reserve(fn_count as usize);
+            for _ in 0..fn_count {
+                let fn_index = u32::parse((), module_bytes, cursor)?;
+                let local_count = u32::parse((), module_bytes, cursor)?;
+                let mut locals = Vec::with_capacity_in(local_count as usize, arena);
+                for _ in 0..local_count {
+                    let local_index = u32::parse((), module_bytes, cursor)?;
+                    let name = <&'a str>::parse(arena, module_bytes, cursor)?;
+                    locals.push((local_index, name));
+                }
+                section.local_names.push((fn_index, locals));
+            }
+        }
+
         *cursor = section_end;
 
         Ok(section)
@@ -1849,6 +1905,25 @@ impl<'a> Serialize for NameSection<'a> {
                 (buffer.size() - subsection_start) as u32,
             );
 
+            if !self.local_names.is_empty() {
+                let subsection_id = NameSubSections::LocalNames as u8;
+                subsection_id.serialize(buffer);
+
+                let subsection_size_index = buffer.encode_padded_u32(0);
+                let subsection_start = buffer.size();
+
+                buffer.encode_u32(self.local_names.len() as u32);
+                for (fn_index, locals) in self.local_names.iter() {
+                    buffer.encode_u32(*fn_index);
+                    locals.serialize(buffer);
+                }
+
+                buffer.overwrite_padded_u32(
+                    subsection_size_index,
+                    (buffer.size() - subsection_start) as u32,
+                );
+            }
+
             update_section_size(buffer, header_indices);
         }
     }
@@ -1862,10 +1937,89 @@ impl<'a> Debug for NameSection<'a> {
             writeln!(f, "  {index:4}: {name}")?;
         }
 
+        for (fn_index, locals) in self.local_names.iter() {
+            writeln!(f, "  locals of {fn_index}")?;
+            for (local_index, name) in locals.iter() {
+                writeln!(f, "    {local_index:4}: {name}")?;
+            }
+        }
+
         Ok(())
     }
 }
 
+/*******************************************************************
+ *
+ * Source mapping URL section
+ * https://github.com/WebAssembly/tool-conventions/blob/main/Debugging.md#source-maps
+ *
+ *******************************************************************/
+
+/// Tells debuggers where to find the source map of the module (see [crate::source_map])
+#[derive(Debug, Default)]
+pub struct SourceMappingUrlSection<'a> {
+    pub url: &'a str,
+}
+
+impl<'a> SourceMappingUrlSection<'a> {
+    const ID: SectionId = SectionId::Custom;
+    const NAME: &'static str = "sourceMappingURL";
+
+    pub fn new() -> Self {
+        SourceMappingUrlSection { url: "" }
+    }
+
+    pub fn size(&self) -> usize {
+        if self.url.is_empty() {
+            0
+        } else {
+            MAX_SIZE_SECTION_HEADER + Self::NAME.len() + MAX_SIZE_ENCODED_U32 + self.url.len()
+        }
+    }
+}
+
+impl<'a> Parse<&'a Bump> for SourceMappingUrlSection<'a> {
+    fn parse(arena: &'a Bump, module_bytes: &[u8], cursor: &mut usize) -> Result<Self, ParseError> {
+        if module_bytes[*cursor] != Self::ID as u8 {
+            return Err(ParseError {
+                message: format!("Expected {} custom section", Self::NAME),
+                offset: *cursor,
+            });
+        }
+        *cursor += 1;
+
+        let section_size = u32::parse((), module_bytes, cursor)? as usize;
+        let section_end = *cursor + section_size;
+
+        let section_name = <&'a str>::parse(arena, module_bytes, cursor)?;
+        if section_name != Self::NAME {
+            return Err(ParseError {
+                message: format!(
+                    "Expected {} custom section, found {}",
+                    Self::NAME,
+                    section_name
+                ),
+                offset: *cursor,
+            });
+        }
+
+        let url = <&'a str>::parse(arena, module_bytes, cursor)?;
+        *cursor = section_end;
+
+        Ok(SourceMappingUrlSection { url })
+    }
+}
+
+impl<'a> Serialize for SourceMappingUrlSection<'a> {
+    fn serialize<T: SerialBuffer>(&self, buffer: &mut T) {
+        if !self.url.is_empty() {
+            let header_indices = write_custom_section_header(buffer, Self::NAME);
+            self.url.serialize(buffer);
+            update_section_size(buffer, header_indices);
+        }
+    }
+}
+
 /*******************************************************************
  *
  * Unit tests
@@ -0,0 +1,294 @@
+//! Source maps link byte offsets in a Wasm binary back to the source files it was compiled from.
+//!
+//! We follow the conventions for Wasm source maps that browser devtools understand:
+//! https://github.com/WebAssembly/tool-conventions/blob/main/Debugging.md#source-maps
+//! The map is a version 3 source map where everything is on "line" 0 of the generated code,
+//! and each "column" is a byte offset from the start of the Wasm file.
+//!
+//! Rather than writing a separate `.map` file, we embed the whole map in a `data:` URL in the
+//! `sourceMappingURL` custom section. That way there's only one file to serve, and tools like
+//! `roc_wasm_interp --debug` can read the map straight out of the module.
+
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+const DATA_URL_PREFIX: &str = "data:application/json;base64,";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    pub sources: Vec<String>,
+    /// Sorted by code offset
+    pub mappings: Vec<SourceMapping>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceMapping {
+    /// Byte offset from the start of the Wasm file
+    pub code_offset: u32,
+    /// Index into `sources`
+    pub source: u32,
+    /// Zero-based line number
+    pub line: u32,
+    /// Zero-based column number
+    pub column: u32,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    /// Get the index of a source file path, adding it if it's not there yet
+    pub fn add_source(&mut self, path: &str) -> u32 {
+        match self.sources.iter().position(|s| s == path) {
+            Some(index) => index as u32,
+            None => {
+                self.sources.push(path.to_string());
+                self.sources.len() as u32 - 1
+            }
+        }
+    }
+
+    pub fn add_mapping(&mut self, mapping: SourceMapping) {
+        let index = self
+            .mappings
+            .partition_point(|m| m.code_offset <= mapping.code_offset);
+        self.mappings.insert(index, mapping);
+    }
+
+    /// Find the source location of the code at a byte offset in the Wasm file.
+    /// Returns the path, and the zero-based line and column.
+    pub fn lookup(&self, code_offset: u32) -> Option<(&str, u32, u32)> {
+        let index = self
+            .mappings
+            .partition_point(|m| m.code_offset <= code_offset);
+        let mapping = self.mappings.get(index.checked_sub(1)?)?;
+        let path = self.sources.get(mapping.source as usize)?;
+
+        Some((path, mapping.line, mapping.column))
+    }
+
+    pub fn to_json(&self) -> String {
+        // Each field of a segment is relative to the same field in the previous segment
+        let mut mappings = String::new();
+        let mut prev = SourceMapping {
+            code_offset: 0,
+            source: 0,
+            line: 0,
+            column: 0,
+        };
+        for (i, mapping) in self.mappings.iter().enumerate() {
+            if i > 0 {
+                mappings.push(',');
+            }
+            encode_vlq(
+                &mut mappings,
+                mapping.code_offset as i64 - prev.code_offset as i64,
+            );
+            encode_vlq(&mut mappings, mapping.source as i64 - prev.source as i64);
+            encode_vlq(&mut mappings, mapping.line as i64 - prev.line as i64);
+            encode_vlq(&mut mappings, mapping.column as i64 - prev.column as i64);
+            prev = *mapping;
+        }
+
+        let json_map = JsonSourceMap {
+            version: 3,
+            sources: self.sources.clone(),
+            names: Vec::new(),
+            mappings,
+        };
+
+        serde_json::to_string(&json_map).expect("a source map is always valid JSON")
+    }
+
+    /// Parse a source map that has the Wasm layout described above.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let json_map: JsonSourceMap = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        if json_map.version != 3 {
+            return Err(format!(
+                "Source map version {} is not supported",
+                json_map.version
+            ));
+        }
+
+        let mut mappings = Vec::new();
+        let mut prev = SourceMapping {
+            code_offset: 0,
+            source: 0,
+            line: 0,
+            column: 0,
+        };
+        for segment in json_map.mappings.split(',').filter(|s| !s.is_empty()) {
+            if segment.contains(';') {
+                return Err("Wasm source maps should only have one line of generated code".into());
+            }
+            let fields = decode_vlq_segment(segment)?;
+            if fields.len() < 4 {
+                // A segment with only a code offset has no source location
+                continue;
+            }
+            let add = |base: u32, delta: i64| -> Result<u32, String> {
+                u32::try_from(base as i64 + delta)
+                    .map_err(|_| format!("Invalid source map segment {segment}"))
+            };
+            let mapping = SourceMapping {
+                code_offset: add(prev.code_offset, fields[0])?,
+                source: add(prev.source, fields[1])?,
+                line: add(prev.line, fields[2])?,
+                column: add(prev.column, fields[3])?,
+            };
+            mappings.push(mapping);
+            prev = mapping;
+        }
+        mappings.sort_by_key(|m| m.code_offset);
+
+        Ok(SourceMap {
+            sources: json_map.sources,
+            mappings,
+        })
+    }
+
+    /// A URL for the `sourceMappingURL` section, containing the whole map
+    pub fn to_data_url(&self) -> String {
+        let mut url = String::from(DATA_URL_PREFIX);
+        BASE64_STANDARD.encode_string(self.to_json(), &mut url);
+        url
+    }
+
+    /// Read a source map from a `data:` URL like the ones we emit.
+    /// Maps in separate files are not supported.
+    pub fn from_data_url(url: &str) -> Result<Self, String> {
+        let encoded = url
+            .strip_prefix(DATA_URL_PREFIX)
+            .ok_or_else(|| format!("Source map URL is not an embedded JSON source map: {url}"))?;
+        let bytes = BASE64_STANDARD.decode(encoded).map_err(|e| e.to_string())?;
+        let json = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+        Self::from_json(&json)
+    }
+}
+
+/// The fields of a version 3 source map that we use
+#[derive(Serialize, Deserialize)]
+struct JsonSourceMap {
+    version: u32,
+    sources: Vec<String>,
+    #[serde(default)]
+    names: Vec<String>,
+    mappings: String,
+}
+
+/*******************************************************************
+ *
+ * Base64 VLQ, for the "mappings" field
+ *
+ *******************************************************************/
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_value(c: u8) -> Option<u32> {
+    BASE64_CHARS.iter().position(|&b| b == c).map(|i| i as u32)
+}
+
+fn encode_vlq(out: &mut String, value: i64) {
+    // The sign goes in the least significant bit
+    let mut vlq: u64 = if value < 0 {
+        ((value.unsigned_abs()) << 1) | 1
+    } else {
+        (value as u64) << 1
+    };
+
+    loop {
+        let mut digit = (vlq & 0b1_1111) as usize;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b10_0000; // continuation bit
+        }
+        out.push(BASE64_CHARS[digit] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+fn decode_vlq_segment(segment: &str) -> Result<Vec<i64>, String> {
+    let mut fields = Vec::with_capacity(4);
+    let mut value: u64 = 0;
+    let mut shift = 0;
+    for c in segment.bytes() {
+        let digit = base64_value(c)
+            .ok_or_else(|| format!("Invalid character in source map segment {segment}"))?
+            as u64;
+        value |= (digit & 0b1_1111) << shift;
+        if digit & 0b10_0000 != 0 {
+            shift += 5;
+            if shift > 60 {
+                return Err(format!("Source map segment {segment} is too large"));
+            }
+        } else {
+            let magnitude = (value >> 1) as i64;
+            fields.push(if value & 1 == 1 {
+                -magnitude
+            } else {
+                magnitude
+            });
+            value = 0;
+            shift = 0;
+        }
+    }
+    if shift != 0 {
+        return Err(format!("Unterminated source map segment {segment}"));
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vlq_round_trip() {
+        for value in [0, 1, -1, 15, -16, 16, 1000, -123456, i32::MAX as i64] {
+            let mut encoded = String::new();
+            encode_vlq(&mut encoded, value);
+            assert_eq!(decode_vlq_segment(&encoded), Ok(vec![value]));
+        }
+    }
+
+    #[test]
+    fn source_map_round_trip() {
+        let mut map = SourceMap::new();
+        let main = map.add_source("examples/main.roc");
+        let util = map.add_source("examples/Util \"quoted\".roc");
+        assert_eq!(map.add_source("examples/main.roc"), main);
+
+        map.add_mapping(SourceMapping {
+            code_offset: 0x230,
+            source: util,
+            line: 3,
+            column: 0,
+        });
+        map.add_mapping(SourceMapping {
+            code_offset: 0x1a4,
+            source: main,
+            line: 10,
+            column: 4,
+        });
+
+        let parsed = SourceMap::from_data_url(&map.to_data_url()).unwrap();
+        assert_eq!(parsed, map);
+
+        assert_eq!(parsed.lookup(0x100), None);
+        assert_eq!(parsed.lookup(0x1a4), Some(("examples/main.roc", 10, 4)));
+        assert_eq!(parsed.lookup(0x22f), Some(("examples/main.roc", 10, 4)));
+        assert_eq!(
+            parsed.lookup(0x300),
+            Some(("examples/Util \"quoted\".roc", 3, 0))
+        );
+    }
+}